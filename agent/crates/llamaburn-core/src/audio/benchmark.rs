@@ -134,6 +134,7 @@ impl AudioBenchmark {
             AudioSourceMode::LiveStream => AudioSource::LiveStream {
                 device_id: "default".to_string(),
             },
+            AudioSourceMode::Dataset => AudioSource::Dataset,
         };
 
         AudioBenchmarkConfig {
@@ -144,6 +145,7 @@ impl AudioBenchmark {
             language: None,
             iterations: self.iterations,
             warmup_runs: self.warmup,
+            reference_text: None,
         }
     }
 }
@@ -13,6 +13,9 @@ pub struct AudioBenchmarkConfig {
     pub language: Option<String>,
     pub iterations: u32,
     pub warmup_runs: u32,
+    /// Expected transcript for WER/CER scoring
+    #[serde(default)]
+    pub reference_text: Option<String>,
 }
 
 impl Default for AudioBenchmarkConfig {
@@ -25,6 +28,7 @@ impl Default for AudioBenchmarkConfig {
             language: None,
             iterations: 3,
             warmup_runs: 1,
+            reference_text: None,
         }
     }
 }
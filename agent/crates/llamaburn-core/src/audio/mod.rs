@@ -3,6 +3,7 @@ mod benchmark_config;
 mod effect_tool;
 mod mode;
 mod types;
+mod wer;
 mod whisper_model;
 
 pub use benchmark::AudioBenchmark;
@@ -11,9 +12,10 @@ pub use effect_tool::EffectDetectionTool;
 pub use mode::AudioMode;
 pub use types::{
     AppliedEffect, AudioBenchmarkMetrics, AudioBenchmarkResult, AudioBenchmarkSummary,
-    AudioCaptureConfig, AudioDevice, AudioSampleFormat, AudioSource, AudioSourceMode,
-    DetectedEffect, DeviceType, EffectDetectionConfig, EffectDetectionResult, Segment,
-    SignalAnalysis, TranscriptionResult, TranscriptionSegment, WhisperEvent, CHANNEL_OPTIONS,
-    SAMPLE_RATES,
+    AudioCaptureConfig, AudioDevice, AudioSampleFormat, AudioSource, AudioSourceMode, DatasetClip,
+    DetectedEffect, DeviceType, EffectDetectionConfig, EffectDetectionResult, FailedClip, Segment,
+    SignalAnalysis, TranscriptAccuracy, TranscriptionResult, TranscriptionSegment, WhisperEvent,
+    CHANNEL_OPTIONS, SAMPLE_RATES,
 };
+pub use wer::{normalize_transcript, score_transcript, word_error_rate};
 pub use whisper_model::WhisperModel;
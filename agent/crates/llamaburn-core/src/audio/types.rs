@@ -24,6 +24,78 @@ pub struct AudioBenchmarkMetrics {
     pub audio_duration_ms: f64,
     pub transcription: String,
     pub word_count: u32,
+    /// Scored against the reference transcript, when one was given
+    #[serde(default)]
+    pub accuracy: Option<TranscriptAccuracy>,
+    /// The dataset clip this iteration transcribed; `None` for single-file runs
+    #[serde(default)]
+    pub clip: Option<DatasetClip>,
+}
+
+/// One utterance of an STT dataset
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct DatasetClip {
+    pub audio_path: PathBuf,
+    /// Expected transcript; empty when the dataset has none for this clip
+    pub reference: String,
+    pub speaker: Option<String>,
+    pub language: Option<String>,
+}
+
+/// A dataset clip that couldn't be transcribed, kept with the run so it counts against it
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct FailedClip {
+    pub clip: DatasetClip,
+    pub error: String,
+}
+
+/// Edit counts of a transcript against its reference, after normalization
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct TranscriptAccuracy {
+    pub reference_words: u32,
+    pub substitutions: u32,
+    pub insertions: u32,
+    pub deletions: u32,
+    pub reference_chars: u32,
+    pub char_errors: u32,
+}
+
+impl TranscriptAccuracy {
+    /// Word-level edits: substitutions + insertions + deletions
+    pub fn errors(&self) -> u32 {
+        self.substitutions + self.insertions + self.deletions
+    }
+
+    /// Word error rate, (S + D + I) / N
+    pub fn wer(&self) -> f64 {
+        match self.reference_words {
+            0 => 0.0,
+            n => self.errors() as f64 / n as f64,
+        }
+    }
+
+    /// Character error rate
+    pub fn cer(&self) -> f64 {
+        match self.reference_chars {
+            0 => 0.0,
+            n => self.char_errors as f64 / n as f64,
+        }
+    }
+
+    /// Pool edit counts, so WER is weighted by reference length. `None` when empty.
+    pub fn combine<'a>(scores: impl IntoIterator<Item = &'a TranscriptAccuracy>) -> Option<Self> {
+        scores.into_iter().fold(None, |total: Option<Self>, s| {
+            let t = total.unwrap_or_default();
+            Some(Self {
+                reference_words: t.reference_words + s.reference_words,
+                substitutions: t.substitutions + s.substitutions,
+                insertions: t.insertions + s.insertions,
+                deletions: t.deletions + s.deletions,
+                reference_chars: t.reference_chars + s.reference_chars,
+                char_errors: t.char_errors + s.char_errors,
+            })
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -42,6 +114,12 @@ pub struct AudioBenchmarkSummary {
     pub max_rtf: f64,
     pub avg_processing_ms: f64,
     pub iterations: u32,
+    /// Edit counts pooled over the scored iterations; corpus-level for dataset runs
+    #[serde(default)]
+    pub accuracy: Option<TranscriptAccuracy>,
+    /// Dataset clips that failed to transcribe; they have no metrics
+    #[serde(default)]
+    pub failed_clips: Vec<FailedClip>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
@@ -50,6 +128,7 @@ pub enum AudioSourceMode {
     File,
     Capture,
     LiveStream,
+    Dataset,
 }
 
 impl AudioSourceMode {
@@ -58,6 +137,7 @@ impl AudioSourceMode {
             AudioSourceMode::File => "File",
             AudioSourceMode::Capture => "Capture",
             AudioSourceMode::LiveStream => "Live",
+            AudioSourceMode::Dataset => "Dataset",
         }
     }
 }
@@ -73,6 +153,8 @@ pub enum AudioSource {
     LiveStream {
         device_id: String,
     },
+    /// Every clip of a dataset directory or manifest, once each
+    Dataset,
 }
 
 impl AudioSource {
@@ -81,11 +163,12 @@ impl AudioSource {
             AudioSource::File => "File",
             AudioSource::Capture { .. } => "Capture",
             AudioSource::LiveStream { .. } => "Live Stream",
+            AudioSource::Dataset => "Dataset",
         }
     }
 
     pub fn is_recording(&self) -> bool {
-        !matches!(self, AudioSource::File)
+        matches!(self, AudioSource::Capture { .. } | AudioSource::LiveStream { .. })
     }
 }
 
@@ -305,6 +388,8 @@ impl AudioBenchmarkResult {
             max_rtf,
             avg_processing_ms,
             iterations: metrics.len() as u32,
+            accuracy: TranscriptAccuracy::combine(metrics.iter().filter_map(|m| m.accuracy.as_ref())),
+            failed_clips: Vec::new(),
         }
     }
 }
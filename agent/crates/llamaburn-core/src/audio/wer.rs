@@ -0,0 +1,291 @@
+//! Transcript accuracy
+
+use super::TranscriptAccuracy;
+
+/// Word error rate of `hypothesis` against `reference` after normalization (see
+/// [`normalize_transcript`]). `None` when the reference has no words.
+pub fn word_error_rate(reference: &str, hypothesis: &str) -> Option<f64> {
+    score_transcript(reference, hypothesis).map(|a| a.wer())
+}
+
+/// Align the normalized transcripts word by word and character by character. `None` when
+/// the reference has no words.
+pub fn score_transcript(reference: &str, hypothesis: &str) -> Option<TranscriptAccuracy> {
+    let reference = normalize_transcript(reference);
+    let hypothesis = normalize_transcript(hypothesis);
+    let ref_words: Vec<&str> = reference.split(' ').filter(|w| !w.is_empty()).collect();
+    let hyp_words: Vec<&str> = hypothesis.split(' ').filter(|w| !w.is_empty()).collect();
+    if ref_words.is_empty() {
+        return None;
+    }
+
+    let (substitutions, insertions, deletions) = align_words(&ref_words, &hyp_words);
+    let ref_chars: Vec<char> = reference.chars().collect();
+    let hyp_chars: Vec<char> = hypothesis.chars().collect();
+
+    Some(TranscriptAccuracy {
+        reference_words: ref_words.len() as u32,
+        substitutions,
+        insertions,
+        deletions,
+        reference_chars: ref_chars.len() as u32,
+        char_errors: edit_distance(&ref_chars, &hyp_chars) as u32,
+    })
+}
+
+/// Lowercase, spell out numbers ("21st" → "twenty first", "3.5%" → "three point five
+/// percent"), drop punctuation (apostrophes inside words stay) and collapse whitespace
+pub fn normalize_transcript(text: &str) -> String {
+    let lowered = text.to_lowercase();
+    // Hyphens and slashes separate words ("twenty-one", "and/or")
+    let spaced: String = lowered
+        .chars()
+        .map(|c| match c {
+            '-' | '–' | '—' | '/' => ' ',
+            c => c,
+        })
+        .collect();
+
+    let mut words = Vec::new();
+    for token in spaced.split_whitespace() {
+        let token = token.trim_matches(|c: char| !c.is_alphanumeric() && c != '%');
+        if let Some(spoken) = spell_number(token) {
+            words.push(spoken);
+            continue;
+        }
+        let word: String = token
+            .chars()
+            .filter(|c| c.is_alphanumeric() || *c == '\'')
+            .collect();
+        let word = word.trim_matches('\'');
+        if !word.is_empty() {
+            words.push(word.to_string());
+        }
+    }
+    words.join(" ")
+}
+
+/// (substitutions, insertions, deletions) of the minimum edit alignment
+fn align_words(reference: &[&str], hypothesis: &[&str]) -> (u32, u32, u32) {
+    let (n, m) = (reference.len(), hypothesis.len());
+    // cost[i][j]: edits turning reference[..i] into hypothesis[..j]
+    let mut cost = vec![vec![0u32; m + 1]; n + 1];
+    for (i, row) in cost.iter_mut().enumerate() {
+        row[0] = i as u32;
+    }
+    for (j, c) in cost[0].iter_mut().enumerate() {
+        *c = j as u32;
+    }
+    for i in 1..=n {
+        for j in 1..=m {
+            let substitution =
+                cost[i - 1][j - 1] + u32::from(reference[i - 1] != hypothesis[j - 1]);
+            cost[i][j] = substitution.min(cost[i - 1][j] + 1).min(cost[i][j - 1] + 1);
+        }
+    }
+
+    // Walk back, preferring matches and substitutions over insertions and deletions
+    let (mut substitutions, mut insertions, mut deletions) = (0, 0, 0);
+    let (mut i, mut j) = (n, m);
+    while i > 0 || j > 0 {
+        if i > 0 && j > 0 {
+            let differs = reference[i - 1] != hypothesis[j - 1];
+            if cost[i][j] == cost[i - 1][j - 1] + u32::from(differs) {
+                substitutions += u32::from(differs);
+                i -= 1;
+                j -= 1;
+                continue;
+            }
+        }
+        if i > 0 && cost[i][j] == cost[i - 1][j] + 1 {
+            deletions += 1;
+            i -= 1;
+        } else {
+            insertions += 1;
+            j -= 1;
+        }
+    }
+    (substitutions, insertions, deletions)
+}
+
+/// Levenshtein distance with a single row
+fn edit_distance<T: PartialEq>(reference: &[T], hypothesis: &[T]) -> usize {
+    let mut row: Vec<usize> = (0..=hypothesis.len()).collect();
+    for (i, r) in reference.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, h) in hypothesis.iter().enumerate() {
+            let substitution = diagonal + usize::from(r != h);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[hypothesis.len()]
+}
+
+const ONES: [&str; 20] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+    "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen", "seventeen", "eighteen",
+    "nineteen",
+];
+const TENS: [&str; 10] = [
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+const SCALES: [(u64, &str); 4] = [
+    (1_000_000_000_000, "trillion"),
+    (1_000_000_000, "billion"),
+    (1_000_000, "million"),
+    (1_000, "thousand"),
+];
+
+/// Spoken form of a numeric token: "1,200", "3.14", "21st", "50%". `None` for other text.
+fn spell_number(token: &str) -> Option<String> {
+    let (token, percent) = match token.strip_suffix('%') {
+        Some(rest) => (rest, true),
+        None => (token, false),
+    };
+    let (digits, ordinal) = match ["st", "nd", "rd", "th"]
+        .iter()
+        .find_map(|s| token.strip_suffix(s))
+    {
+        Some(rest) if !percent => (rest, true),
+        _ => (token, false),
+    };
+    let digits = digits.replace(',', "");
+    let (whole, fraction) = match digits.split_once('.') {
+        // A trailing dot ends the sentence, it isn't a decimal point ("3." → "three")
+        Some((whole, fraction)) if !ordinal => (whole, Some(fraction).filter(|f| !f.is_empty())),
+        Some(_) => return None,
+        None => (digits.as_str(), None),
+    };
+    let all_digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    if !all_digits(whole) || fraction.is_some_and(|f| !all_digits(f)) {
+        return None;
+    }
+
+    let mut spoken = cardinal(whole.parse().ok()?);
+    if ordinal {
+        spoken = to_ordinal(&spoken);
+    }
+    if let Some(fraction) = fraction {
+        spoken.push_str(" point");
+        for digit in fraction.chars() {
+            spoken.push(' ');
+            spoken.push_str(ONES[digit.to_digit(10)? as usize]);
+        }
+    }
+    if percent {
+        spoken.push_str(" percent");
+    }
+    Some(spoken)
+}
+
+/// "one hundred twenty three thousand four hundred five"
+fn cardinal(n: u64) -> String {
+    if n < 20 {
+        return ONES[n as usize].to_string();
+    }
+    if n < 100 {
+        return match n % 10 {
+            0 => TENS[(n / 10) as usize].to_string(),
+            ones => format!("{} {}", TENS[(n / 10) as usize], ONES[ones as usize]),
+        };
+    }
+    if n < 1_000 {
+        return match n % 100 {
+            0 => format!("{} hundred", ONES[(n / 100) as usize]),
+            rest => format!("{} hundred {}", ONES[(n / 100) as usize], cardinal(rest)),
+        };
+    }
+    let (scale, name) = SCALES
+        .iter()
+        .copied()
+        .find(|(scale, _)| n >= *scale)
+        .unwrap_or((1_000, "thousand"));
+    match n % scale {
+        0 => format!("{} {}", cardinal(n / scale), name),
+        rest => format!("{} {} {}", cardinal(n / scale), name, cardinal(rest)),
+    }
+}
+
+/// Turn the last word of a cardinal into its ordinal ("twenty one" → "twenty first")
+fn to_ordinal(cardinal: &str) -> String {
+    let (head, last) = match cardinal.rsplit_once(' ') {
+        Some((head, last)) => (format!("{} ", head), last),
+        None => (String::new(), cardinal),
+    };
+    let last = match last {
+        "one" => "first".to_string(),
+        "two" => "second".to_string(),
+        "three" => "third".to_string(),
+        "five" => "fifth".to_string(),
+        "eight" => "eighth".to_string(),
+        "nine" => "ninth".to_string(),
+        "twelve" => "twelfth".to_string(),
+        tens if tens.ends_with('y') => format!("{}ieth", &tens[..tens.len() - 1]),
+        other => format!("{}th", other),
+    };
+    head + &last
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_error_counts() {
+        let a = score_transcript("the cat sat on the mat", "the bat sat the mat today").unwrap();
+        assert_eq!((a.substitutions, a.insertions, a.deletions), (1, 1, 1));
+        assert_eq!(a.reference_words, 6);
+        assert!((a.wer() - 0.5).abs() < 1e-9);
+
+        // Casing and punctuation aren't errors
+        assert_eq!(word_error_rate("Hello, world!", "hello world"), Some(0.0));
+        assert_eq!(word_error_rate("don't stop", "Don't STOP."), Some(0.0));
+    }
+
+    #[test]
+    fn test_empty_transcripts() {
+        assert_eq!(score_transcript("", "anything"), None);
+        assert_eq!(score_transcript(" ... ", ""), None);
+
+        let missed = score_transcript("a b c d", "").unwrap();
+        assert_eq!((missed.substitutions, missed.insertions, missed.deletions), (0, 0, 4));
+        assert_eq!(missed.wer(), 1.0);
+        assert_eq!(missed.cer(), 1.0);
+    }
+
+    #[test]
+    fn test_number_normalization() {
+        assert_eq!(
+            normalize_transcript("On the 21st, 3.5% rose by 1,200!"),
+            "on the twenty first three point five percent rose by one thousand two hundred"
+        );
+        assert_eq!(
+            normalize_transcript("100th 12th 2nd 40th 3rd"),
+            "one hundredth twelfth second fortieth third"
+        );
+        assert_eq!(normalize_transcript("2,000,005 90 0"), "two million five ninety zero");
+        assert_eq!(normalize_transcript("Twenty-one and/or"), "twenty one and or");
+        assert_eq!(spell_number("3.").as_deref(), Some("three"));
+        assert_eq!(normalize_transcript("Up 7.% from 3."), "up seven percent from three");
+        // Digits and spoken numbers score the same
+        assert_eq!(word_error_rate("It costs 5 dollars", "it costs five dollars"), Some(0.0));
+        assert_eq!(word_error_rate("the 21st floor", "the twenty first floor"), Some(0.0));
+    }
+
+    #[test]
+    fn test_character_error_rate() {
+        // "the cat" → "the bat" is one edit in seven characters
+        let a = score_transcript("the cat", "the bat").unwrap();
+        assert_eq!((a.reference_chars, a.char_errors), (7, 1));
+        assert!((a.cer() - 1.0 / 7.0).abs() < 1e-9);
+
+        let exact = score_transcript("Same words.", "same words").unwrap();
+        assert_eq!(exact.cer(), 0.0);
+
+        let kitten: Vec<char> = "kitten".chars().collect();
+        let sitting: Vec<char> = "sitting".chars().collect();
+        assert_eq!(edit_distance(&kitten, &sitting), 3);
+    }
+}
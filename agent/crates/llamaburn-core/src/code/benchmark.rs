@@ -1,8 +1,8 @@
 use serde::{Deserialize, Serialize};
 
 use super::{
-    BenchmarkCombo, CodeBenchmarkConfig, CodeBenchmarkMetrics, CodeBenchmarkSummary, ErrorLogEntry,
-    Language, Preset,
+    BenchmarkCombo, CodeBenchmarkConfig, CodeBenchmarkMetrics, CodeBenchmarkSummary, CodeTask,
+    ErrorLogEntry, GenerationMode, Language, Preset,
 };
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -11,11 +11,15 @@ pub struct CodeBenchmark {
     pub selected_languages: Vec<Language>,
     pub selected_temperatures: Vec<f32>,
     pub selected_max_tokens: Vec<u32>,
+    pub selected_generation_modes: Vec<GenerationMode>,
+    pub selected_tasks: Vec<CodeTask>,
     pub selected_problem_ids: Vec<String>,
 
     pub warmup_runs: u32,
     pub auto_run_tests: bool,
     pub skip_on_error: bool,
+    pub repair_attempts: u32,
+    pub analyze_code: bool,
 
     pub combo_queue: Vec<BenchmarkCombo>,
     pub current_combo: Option<BenchmarkCombo>,
@@ -60,6 +64,8 @@ impl CodeBenchmark {
             skip_on_error: true,
             selected_temperatures: vec![0.0],
             selected_max_tokens: vec![2048],
+            selected_generation_modes: vec![GenerationMode::Structured],
+            selected_tasks: vec![CodeTask::Generate],
             ..Default::default()
         }
     }
@@ -72,6 +78,14 @@ impl CodeBenchmark {
         self.auto_run_tests = enabled;
     }
 
+    pub fn set_repair_attempts(&mut self, n: u32) {
+        self.repair_attempts = n;
+    }
+
+    pub fn set_analyze_code(&mut self, enabled: bool) {
+        self.analyze_code = enabled;
+    }
+
     pub fn set_skip_on_error(&mut self, enabled: bool) {
         self.skip_on_error = enabled;
     }
@@ -146,9 +160,15 @@ impl CodeBenchmark {
             max_tokens: combo.max_tokens,
             warmup_runs: self.warmup_runs,
             run_tests: self.auto_run_tests,
+            max_repair_attempts: self.repair_attempts,
+            generation_mode: combo.generation_mode,
+            analyze_code: self.analyze_code,
+            task: combo.task,
         })
     }
 
+    /// Every selected model × language × temperature × max tokens × mode × task.
+    /// Translations into their own source language are skipped.
     pub fn generate_combos(&self) -> Vec<BenchmarkCombo> {
         let mut combos = Vec::new();
 
@@ -156,12 +176,21 @@ impl CodeBenchmark {
             for &language in &self.selected_languages {
                 for &temperature in &self.selected_temperatures {
                     for &max_tokens in &self.selected_max_tokens {
-                        combos.push(BenchmarkCombo {
-                            model: model.clone(),
-                            language,
-                            temperature,
-                            max_tokens: Some(max_tokens),
-                        });
+                        for &generation_mode in &self.selected_generation_modes {
+                            for &task in &self.selected_tasks {
+                                if task == (CodeTask::Translate { from: language }) {
+                                    continue;
+                                }
+                                combos.push(BenchmarkCombo {
+                                    model: model.clone(),
+                                    language,
+                                    temperature,
+                                    max_tokens: Some(max_tokens),
+                                    generation_mode,
+                                    task,
+                                });
+                            }
+                        }
                     }
                 }
             }
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use super::Language;
+use super::{CodeTask, GenerationMode, Language};
 
 fn default_run_tests() -> bool {
     true
@@ -27,6 +27,17 @@ pub struct CodeBenchmarkConfig {
     pub warmup_runs: u32,
     #[serde(default = "default_run_tests")]
     pub run_tests: bool,
+    /// Follow-up turns that feed test failures back to the model (0 = off)
+    #[serde(default)]
+    pub max_repair_attempts: u32,
+    #[serde(default)]
+    pub generation_mode: GenerationMode,
+    /// Run linters/formatters and complexity metrics on the final code
+    #[serde(default)]
+    pub analyze_code: bool,
+    /// Write from scratch, fix a buggy solution or translate one
+    #[serde(default)]
+    pub task: CodeTask,
 }
 
 impl Default for CodeBenchmarkConfig {
@@ -39,6 +50,10 @@ impl Default for CodeBenchmarkConfig {
             max_tokens: None,
             warmup_runs: default_warmup(),
             run_tests: default_run_tests(),
+            max_repair_attempts: 0,
+            generation_mode: GenerationMode::default(),
+            analyze_code: false,
+            task: CodeTask::default(),
         }
     }
 }
@@ -7,6 +7,8 @@ pub use benchmark::CodeBenchmark;
 pub use benchmark_config::CodeBenchmarkConfig;
 pub use language::Language;
 pub use types::{
-    BenchmarkCombo, CodeBenchmarkMetrics, CodeBenchmarkResult, CodeBenchmarkSummary, CodeProblem,
-    Difficulty, ErrorLogEntry, EvaluationMode, Preset, ProblemSet, TestCase,
+    all_tests_passed, BenchmarkCombo, CodeBenchmarkMetrics, CodeBenchmarkResult,
+    CodeBenchmarkSummary, CodeProblem, CodeQuality, CodeTask, Comparator, Difficulty,
+    ErrorLogEntry, EvaluationMode, GenerationMode, PatchStats, Preset, ProblemKind, ProblemSet,
+    RepairAttempt, RepoFixture, TagStats, TestCase, TestCaseResult,
 };
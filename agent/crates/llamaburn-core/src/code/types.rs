@@ -1,17 +1,64 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use serde::{Deserialize, Serialize};
 
 use super::{CodeBenchmarkConfig, Language};
+use crate::{BatchCombo, CodeBatchCombo};
 
 // =============================================================================
 // Simple Types (no internal dependencies)
 // =============================================================================
 
+/// How a test's actual output is compared against the expected output
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Comparator {
+    /// Whitespace-insensitive string match, numbers normalized (2 == 2.0)
+    #[default]
+    Normalized,
+    /// Byte-for-byte match; only trailing newlines are ignored
+    Exact,
+    /// Parse both sides as JSON and compare structurally
+    Json,
+    /// JSON compare where numbers match within an absolute or relative tolerance
+    Float {
+        #[serde(default)]
+        abs_tol: f64,
+        #[serde(default)]
+        rel_tol: f64,
+    },
+    /// Top-level JSON array compared ignoring element order
+    UnorderedList,
+    /// List of lists compared ignoring both outer and inner order
+    SetOfLists,
+    /// External checker invoked as `<script> <input> <expected> <actual>`; exit 0 passes
+    Custom { script: String },
+}
+
+impl Comparator {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Comparator::Normalized => "Normalized",
+            Comparator::Exact => "Exact",
+            Comparator::Json => "JSON",
+            Comparator::Float { .. } => "Float",
+            Comparator::UnorderedList => "Unordered List",
+            Comparator::SetOfLists => "Set of Lists",
+            Comparator::Custom { .. } => "Custom",
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TestCase {
     pub input: String,
     pub expected: String,
+    /// Overrides the problem-level comparator for this test
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comparator: Option<Comparator>,
+    /// Large-input case that also fails when its CPU time exceeds the problem's time limit
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub performance: bool,
 }
 
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
@@ -33,6 +80,120 @@ impl Difficulty {
     }
 }
 
+/// Shape of a problem's solution and how its test cases are executed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProblemKind {
+    /// Single function; input is a JSON array of args, output the JSON return value
+    #[default]
+    Function,
+    /// Complete program; input is fed to stdin and stdout is compared
+    Stdio,
+    /// Class driven by an operation sequence, input `[["Cls","op",..],[[ctor args],[op args],..]]`;
+    /// output is a JSON array of results with null for the constructor and void methods
+    ClassOperations,
+    /// Repository fixture; the solution is a unified diff applied to a fresh checkout.
+    /// Each test input is a shell command run there, passing on exit 0 with matching stdout
+    RepoPatch,
+}
+
+impl ProblemKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProblemKind::Function => "Function",
+            ProblemKind::Stdio => "Stdin/Stdout",
+            ProblemKind::ClassOperations => "Class Operations",
+            ProblemKind::RepoPatch => "Repo Patch",
+        }
+    }
+}
+
+/// How code is requested from the model
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GenerationMode {
+    /// JSON schema output with function name, imports and code
+    #[default]
+    Structured,
+    /// Normal chat; code is extracted from the fenced block in the reply
+    FreeForm,
+}
+
+impl GenerationMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            GenerationMode::Structured => "Structured",
+            GenerationMode::FreeForm => "Free-form",
+        }
+    }
+
+    pub fn all() -> &'static [GenerationMode] {
+        &[GenerationMode::Structured, GenerationMode::FreeForm]
+    }
+}
+
+/// What the model is asked to do with a problem
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CodeTask {
+    /// Write a solution from the description
+    #[default]
+    Generate,
+    /// Fix the problem's buggy solution in the target language
+    BugFix,
+    /// Port the problem's reference solution from another language
+    Translate { from: Language },
+}
+
+impl CodeTask {
+    pub fn label(&self) -> String {
+        match self {
+            CodeTask::Generate => "Generate".to_string(),
+            CodeTask::BugFix => "Bug Fix".to_string(),
+            CodeTask::Translate { from } => format!("Translate from {}", from.label()),
+        }
+    }
+
+    pub fn all() -> Vec<CodeTask> {
+        let translations = Language::all().iter().map(|&from| CodeTask::Translate { from });
+        [CodeTask::Generate, CodeTask::BugFix].into_iter().chain(translations).collect()
+    }
+
+    /// Code the prompt starts from: the buggy or source solution.
+    /// None for Generate, or when the problem has no such solution.
+    pub fn source_code<'a>(&self, problem: &'a CodeProblem, language: Language) -> Option<&'a str> {
+        match self {
+            CodeTask::Generate => None,
+            CodeTask::BugFix => problem.buggy_solutions.get(&language).map(String::as_str),
+            CodeTask::Translate { from } => {
+                problem.reference_solutions.get(from).map(String::as_str)
+            }
+        }
+    }
+
+    /// Whether `problem` can be posed as this task in `language`.
+    /// Repo patches are diffs against a fixture, so they only support Generate.
+    pub fn supports(&self, problem: &CodeProblem, language: Language) -> bool {
+        match (self, problem.problem_kind) {
+            (CodeTask::Generate, _) => true,
+            (_, ProblemKind::RepoPatch) => false,
+            (CodeTask::Translate { from }, _) if *from == language => false,
+            _ => self.source_code(problem, language).is_some(),
+        }
+    }
+
+    /// Why `problem` can't be posed as this task in `language`
+    pub fn unsupported_reason(&self, problem: &CodeProblem, language: Language) -> String {
+        match (self, problem.problem_kind) {
+            (_, ProblemKind::RepoPatch) => format!("{} doesn't apply to repo patches", self.label()),
+            (CodeTask::Translate { from }, _) if *from == language => {
+                format!("already written in {}", language.label())
+            }
+            _ => format!("no {} source for {}", self.label(), language.label()),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum EvaluationMode {
@@ -54,6 +215,12 @@ pub struct CodeBenchmarkSummary {
     pub avg_tps: f64,
     pub avg_execution_time_ms: f64,
     #[serde(default)]
+    pub avg_ttft_ms: f64,
+    #[serde(default)]
+    pub avg_test_execution_time_ms: f64,
+    #[serde(default)]
+    pub total_output_tokens: u64,
+    #[serde(default)]
     pub easy_solved: u32,
     #[serde(default)]
     pub easy_total: u32,
@@ -65,6 +232,156 @@ pub struct CodeBenchmarkSummary {
     pub hard_solved: u32,
     #[serde(default)]
     pub hard_total: u32,
+    /// Cumulative pass rate by attempt: index k = solved within k+1 attempts
+    #[serde(default)]
+    pub pass_at_attempt: Vec<f64>,
+    /// Solved/total per problem tag (a problem counts toward each of its tags)
+    #[serde(default)]
+    pub tag_stats: BTreeMap<String, TagStats>,
+    /// Problems with code-quality results; the averages below cover only these
+    #[serde(default)]
+    pub problems_analyzed: u32,
+    #[serde(default)]
+    pub avg_lint_warnings: f64,
+    #[serde(default)]
+    pub avg_lines_of_code: f64,
+    #[serde(default)]
+    pub avg_cyclomatic_complexity: f64,
+    /// Geometric mean slowdown over problems with a reference solution
+    #[serde(default)]
+    pub avg_slowdown: Option<f64>,
+    #[serde(default)]
+    pub problems_inefficient: u32,
+    #[serde(default)]
+    pub max_peak_rss_kb: u64,
+    /// Repo-patch problems run, how many of their diffs applied, and mean diff size over them
+    #[serde(default)]
+    pub patch_problems: u32,
+    #[serde(default)]
+    pub patches_applied: u32,
+    #[serde(default)]
+    pub avg_diff_lines: f64,
+    /// Problems the task couldn't be posed for; excluded from `problems_total` and the pass rate
+    #[serde(default)]
+    pub skipped_problems: Vec<String>,
+}
+
+impl CodeBenchmarkSummary {
+    /// "solved/total", noting problems left out of the total
+    pub fn solved_label(&self) -> String {
+        match self.skipped_problems.len() {
+            0 => format!("{}/{}", self.problems_solved, self.problems_total),
+            n => format!("{}/{} ({} skipped)", self.problems_solved, self.problems_total, n),
+        }
+    }
+}
+
+/// Solved/total counts for one problem tag
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TagStats {
+    pub solved: u32,
+    pub total: u32,
+}
+
+impl TagStats {
+    pub fn pass_rate(&self) -> f64 {
+        match self.total {
+            0 => 0.0,
+            _ => self.solved as f64 / self.total as f64,
+        }
+    }
+}
+
+/// Outcome of a single generation attempt in the self-repair loop
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RepairAttempt {
+    pub attempt: u32,
+    pub tests_passed: u32,
+    pub tests_total: u32,
+    pub tokens: u64,
+    #[serde(default)]
+    pub ttft_ms: f64,
+    pub generation_time_ms: f64,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl RepairAttempt {
+    pub fn solved(&self) -> bool {
+        all_tests_passed(self.tests_passed, self.tests_total)
+    }
+}
+
+/// Whether a solution counts as solved: it ran at least one test and passed them all
+pub fn all_tests_passed(tests_passed: u32, tests_total: u32) -> bool {
+    tests_total > 0 && tests_passed == tests_total
+}
+
+/// Result of one test case for the final code, kept for post-run diagnosis
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TestCaseResult {
+    pub input: String,
+    pub expected: String,
+    pub actual: String,
+    pub passed: bool,
+    #[serde(default)]
+    pub error: Option<String>,
+    pub execution_time_ms: f64,
+    #[serde(default)]
+    pub cpu_time_ms: f64,
+    #[serde(default)]
+    pub peak_rss_kb: u64,
+    #[serde(default)]
+    pub performance: bool,
+}
+
+/// Static analysis of the final code (only when analysis is enabled)
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CodeQuality {
+    /// Linters that were installed and ran, e.g. ["ruff"]
+    #[serde(default)]
+    pub tools: Vec<String>,
+    pub lint_warnings: u32,
+    /// First few linter messages
+    #[serde(default)]
+    pub lint_messages: Vec<String>,
+    /// Formatter check result; None when no formatter is installed
+    #[serde(default)]
+    pub formatted: Option<bool>,
+    pub lines_of_code: u32,
+    pub cyclomatic_complexity: u32,
+}
+
+/// Files of a small repository that a repo-patch problem is solved against
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RepoFixture {
+    /// Relative path -> file contents
+    pub files: BTreeMap<String, String>,
+}
+
+/// Size of a generated diff and whether it applied to the fixture
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PatchStats {
+    /// None when tests were skipped, so the patch was never applied
+    #[serde(default)]
+    pub applied: Option<bool>,
+    pub files_changed: u32,
+    pub lines_added: u32,
+    pub lines_removed: u32,
+}
+
+impl PatchStats {
+    pub fn diff_lines(&self) -> u32 {
+        self.lines_added + self.lines_removed
+    }
+
+    pub fn status_label(&self) -> &'static str {
+        match self.applied {
+            Some(true) => "applied",
+            Some(false) => "did not apply",
+            None => "not applied",
+        }
+    }
 }
 
 // =============================================================================
@@ -76,16 +393,62 @@ pub struct CodeBenchmarkMetrics {
     pub problem_id: String,
     #[serde(default)]
     pub difficulty: Difficulty,
+    #[serde(default)]
+    pub tags: Vec<String>,
     pub ttft_ms: f64,
     pub tokens_per_sec: f64,
     pub tests_passed: u32,
     pub tests_total: u32,
+    /// LLM generation wall-clock time (test harness time is tracked separately)
     pub execution_time_ms: f64,
     pub generated_code: String,
     #[serde(default)]
     pub compilation_error: Option<String>,
     #[serde(default)]
     pub runtime_error: Option<String>,
+    /// Tokens generated as reported by Ollama (summed across repair attempts)
+    #[serde(default)]
+    pub output_tokens: u64,
+    #[serde(default)]
+    pub prompt_tokens: u64,
+    #[serde(default)]
+    pub load_duration_ms: f64,
+    /// Wall-clock time spent running the test harness for the final code
+    #[serde(default)]
+    pub test_execution_time_ms: f64,
+    /// Per-attempt results when self-repair is enabled (first attempt included)
+    #[serde(default)]
+    pub attempts: Vec<RepairAttempt>,
+    /// Per-test results for the final code (empty when tests were skipped)
+    #[serde(default)]
+    pub test_results: Vec<TestCaseResult>,
+    #[serde(default)]
+    pub quality: Option<CodeQuality>,
+    /// CPU time summed over the final code's test runs
+    #[serde(default)]
+    pub cpu_time_ms: f64,
+    /// Largest peak RSS of any test run
+    #[serde(default)]
+    pub peak_rss_kb: u64,
+    /// CPU time relative to the problem's reference solution (None without one)
+    #[serde(default)]
+    pub slowdown: Option<f64>,
+    /// Diff size and apply result (repo-patch problems only)
+    #[serde(default)]
+    pub patch: Option<PatchStats>,
+}
+
+/// Slowdown vs the reference at which a solution is flagged as inefficient
+pub const INEFFICIENT_SLOWDOWN: f64 = 10.0;
+
+impl CodeBenchmarkMetrics {
+    pub fn solved(&self) -> bool {
+        all_tests_passed(self.tests_passed, self.tests_total)
+    }
+
+    pub fn is_inefficient(&self) -> bool {
+        self.slowdown.is_some_and(|s| s >= INEFFICIENT_SLOWDOWN)
+    }
 }
 
 fn default_time_limit() -> u32 {
@@ -98,10 +461,26 @@ pub struct CodeProblem {
     pub title: String,
     pub description: String,
     pub difficulty: Difficulty,
+    #[serde(default)]
+    pub problem_kind: ProblemKind,
+    /// Free-form categories (e.g. "dp", "graphs", "strings") for per-tag pass rates
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Known-efficient solutions, run through the same harness to compute slowdown
+    #[serde(default)]
+    pub reference_solutions: HashMap<Language, String>,
+    /// Solutions with a planted bug, given to the model in bug-fix tasks
+    #[serde(default)]
+    pub buggy_solutions: HashMap<Language, String>,
+    /// Repository the model patches (repo-patch problems only)
+    #[serde(default)]
+    pub repo: Option<RepoFixture>,
     #[serde(default = "default_time_limit")]
     pub time_limit_ms: u32,
     pub signatures: HashMap<Language, String>,
     pub test_cases: Vec<TestCase>,
+    #[serde(default)]
+    pub comparator: Comparator,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -118,6 +497,24 @@ pub struct BenchmarkCombo {
     pub language: Language,
     pub temperature: f32,
     pub max_tokens: Option<u32>,
+    #[serde(default)]
+    pub generation_mode: GenerationMode,
+    #[serde(default)]
+    pub task: CodeTask,
+}
+
+impl BenchmarkCombo {
+    /// Persisted form for a batch queue
+    pub fn to_batch_combo(&self) -> BatchCombo {
+        BatchCombo::Code(CodeBatchCombo {
+            model: self.model.clone(),
+            language: self.language,
+            temperature: self.temperature,
+            max_tokens: self.max_tokens.unwrap_or(2048),
+            generation_mode: self.generation_mode,
+            task: self.task,
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
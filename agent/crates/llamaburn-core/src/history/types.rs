@@ -1,11 +1,14 @@
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    AudioBenchmarkConfig, AudioBenchmarkMetrics, AudioBenchmarkSummary, AudioMode,
+    all_tests_passed, AudioBenchmarkConfig, AudioBenchmarkMetrics, AudioBenchmarkSummary, AudioMode,
     BenchmarkMetrics, BenchmarkType, CodeBenchmarkConfig, CodeBenchmarkMetrics,
-    CodeBenchmarkSummary, EffectDetectionResult, EffectDetectionTool, Language,
-    TextBenchmarkConfig, TextBenchmarkSummary,
+    CodeBenchmarkSummary, CodeTask, EffectDetectionResult, EffectDetectionTool,
+    EnvironmentFingerprint, GenerationMode, Language, TagStats, TextBenchmarkConfig,
+    TextBenchmarkSummary, WhisperModel,
 };
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 
 // Re-export Preset from code module (already defined there)
 pub use crate::code::Preset;
@@ -37,6 +40,16 @@ impl RunStatus {
         }
     }
 
+    pub fn all() -> &'static [RunStatus] {
+        &[
+            RunStatus::Success,
+            RunStatus::Error,
+            RunStatus::Skipped,
+            RunStatus::Paused,
+            RunStatus::Cancelled,
+        ]
+    }
+
     pub fn from_str(s: &str) -> Self {
         match s {
             "error" => RunStatus::Error,
@@ -89,6 +102,11 @@ pub struct BenchmarkHistoryEntry {
     pub config: TextBenchmarkConfig,
     pub summary: TextBenchmarkSummary,
     pub metrics: Vec<BenchmarkMetrics>,
+    #[serde(default)]
+    pub environment: Option<EnvironmentFingerprint>,
+    /// Set on runs imported from another machine's bundle
+    #[serde(default)]
+    pub origin: Option<RunOrigin>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -101,6 +119,11 @@ pub struct AudioHistoryEntry {
     pub config: AudioBenchmarkConfig,
     pub summary: AudioBenchmarkSummary,
     pub metrics: Vec<AudioBenchmarkMetrics>,
+    #[serde(default)]
+    pub environment: Option<EnvironmentFingerprint>,
+    /// Set on runs imported from another machine's bundle
+    #[serde(default)]
+    pub origin: Option<RunOrigin>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -119,6 +142,85 @@ pub struct CodeHistoryEntry {
     pub status: RunStatus,
     #[serde(default)]
     pub preset_id: Option<String>,
+    #[serde(default)]
+    pub environment: Option<EnvironmentFingerprint>,
+    /// Set on runs imported from another machine's bundle
+    #[serde(default)]
+    pub origin: Option<RunOrigin>,
+}
+
+impl BenchmarkHistoryEntry {
+    /// Settings that must match for two runs to be compared (everything but iteration counts)
+    pub fn config_key(&self) -> String {
+        let c = &self.config;
+        format!(
+            "text|{}|{}|t={}|max={}|top_p={}|top_k={}",
+            self.model_id,
+            c.prompt_set,
+            c.temperature,
+            key_part(c.max_tokens),
+            key_part(c.top_p),
+            key_part(c.top_k)
+        )
+    }
+}
+
+impl AudioHistoryEntry {
+    /// Settings that must match for two runs to be compared (everything but iteration counts)
+    pub fn config_key(&self) -> String {
+        let c = &self.config;
+        format!(
+            "audio|{}|{:?}|{}|{}|lang={}",
+            self.model_id,
+            self.audio_mode,
+            key_part(c.model_size.map(|m| m.label())),
+            c.audio_path.display(),
+            key_part(c.language.as_deref())
+        )
+    }
+}
+
+impl CodeHistoryEntry {
+    /// Settings that must match for two runs to be compared; problem order doesn't matter
+    pub fn config_key(&self) -> String {
+        let c = &self.config;
+        let mut problems = c.problem_ids.clone();
+        problems.sort();
+        format!(
+            "code|{}|{:?}|{:?}|{:?}|t={}|max={}|repair={}|problems={}",
+            self.model_id,
+            self.language,
+            c.task,
+            c.generation_mode,
+            c.temperature,
+            key_part(c.max_tokens),
+            c.max_repair_attempts,
+            problems.join(",")
+        )
+    }
+}
+
+fn key_part<T: std::fmt::Display>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_else(|| "-".to_string())
+}
+
+/// A model's best code run for one language, with its per-tag breakdown
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeLeaderboardEntry {
+    pub model_id: String,
+    pub pass_rate: f64,
+    pub tag_stats: BTreeMap<String, TagStats>,
+    /// Code-quality averages from the same run (0 problems when not analyzed)
+    pub problems_analyzed: u32,
+    pub avg_lint_warnings: f64,
+    pub avg_cyclomatic_complexity: f64,
+}
+
+/// Leaderboard rows plus the best runs left out because their summary didn't parse
+#[derive(Debug, Clone, Default)]
+pub struct CodeLeaderboard {
+    pub entries: Vec<CodeLeaderboardEntry>,
+    pub skipped: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -134,19 +236,51 @@ pub struct EffectDetectionHistoryEntry {
 // Batch Types
 // =============================================================================
 
-/// A single benchmark combination in a matrix run
+/// A single code benchmark combination in a matrix run
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BatchCombo {
+pub struct CodeBatchCombo {
     pub model: String,
     pub language: Language,
     pub temperature: f32,
     pub max_tokens: u32,
+    #[serde(default)]
+    pub generation_mode: GenerationMode,
+    #[serde(default)]
+    pub task: CodeTask,
 }
 
-/// Persisted state for a resumable batch benchmark session
+/// A single text benchmark combination: model × prompt set × sampling params
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextBatchCombo {
+    pub model: String,
+    pub prompt_set: String,
+    pub temperature: f32,
+    pub max_tokens: Option<u32>,
+}
+
+/// A single STT benchmark combination: whisper model × audio file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SttBatchCombo {
+    pub model: WhisperModel,
+    pub audio_path: PathBuf,
+}
+
+/// Combo payload of a batch; the variant follows the batch's benchmark type.
+/// Untagged so code queues saved before text and STT batches still load.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum BatchCombo {
+    Code(CodeBatchCombo),
+    Text(TextBatchCombo),
+    Stt(SttBatchCombo),
+}
+
+/// Persisted state for a resumable batch benchmark session.
+/// Language, problem and test selections are only used by code batches.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BatchState {
     pub session_id: String,
+    pub benchmark_type: BenchmarkType,
     pub created_at: i64,
     pub updated_at: i64,
     pub status: BatchStatus,
@@ -168,9 +302,553 @@ pub struct BatchState {
 // Filter Types
 // =============================================================================
 
+/// History query; every set field narrows the result (tags must all match)
 #[derive(Debug, Clone, Default)]
 pub struct HistoryFilter {
     pub model_id: Option<String>,
     pub benchmark_type: Option<BenchmarkType>,
+    /// Inclusive bounds on the run timestamp (unix seconds)
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    pub language: Option<Language>,
+    pub status: Option<RunStatus>,
+    pub preset_id: Option<String>,
+    pub session_id: Option<String>,
+    pub whisper_model: Option<WhisperModel>,
+    /// Problem tags; a code run matches when its problems cover every tag
+    pub tags: Vec<String>,
+    /// Case-insensitive substring of the model, prompt set, session, problem ids or transcripts
+    pub search: Option<String>,
+    pub sort: HistorySort,
+    /// Continue after this position (from the previous page's `next_cursor`)
+    pub cursor: Option<HistoryCursor>,
+    /// Page size; `None` returns everything
     pub limit: Option<u32>,
 }
+
+/// Ordering of history results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HistorySort {
+    #[default]
+    Newest,
+    Oldest,
+    Model,
+    /// Best first: pass rate for code, tokens/sec for text, real-time speed for audio
+    Score,
+}
+
+impl HistorySort {
+    pub fn label(&self) -> &'static str {
+        match self {
+            HistorySort::Newest => "Newest",
+            HistorySort::Oldest => "Oldest",
+            HistorySort::Model => "Model",
+            HistorySort::Score => "Best Score",
+        }
+    }
+
+    pub fn all() -> &'static [HistorySort] {
+        &[
+            HistorySort::Newest,
+            HistorySort::Oldest,
+            HistorySort::Model,
+            HistorySort::Score,
+        ]
+    }
+}
+
+/// Sort keys of the last row on a page, for keyset pagination
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryCursor {
+    pub id: String,
+    pub timestamp: i64,
+    pub model_id: String,
+    pub score: f64,
+}
+
+/// One page of history results
+#[derive(Debug, Clone)]
+pub struct HistoryPage<T> {
+    pub entries: Vec<T>,
+    /// Set when more rows follow; pass it back as `HistoryFilter::cursor`
+    pub next_cursor: Option<HistoryCursor>,
+    /// Ids of runs on this page whose stored JSON no longer parses; left out of `entries`
+    pub unreadable: Vec<String>,
+}
+
+/// A history entry of any benchmark type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "entry", rename_all = "lowercase")]
+pub enum HistoryRecord {
+    Text(BenchmarkHistoryEntry),
+    Audio(AudioHistoryEntry),
+    Code(CodeHistoryEntry),
+}
+
+impl HistoryRecord {
+    pub fn id(&self) -> &str {
+        match self {
+            HistoryRecord::Text(e) => &e.id,
+            HistoryRecord::Audio(e) => &e.id,
+            HistoryRecord::Code(e) => &e.id,
+        }
+    }
+
+    pub fn benchmark_type(&self) -> BenchmarkType {
+        match self {
+            HistoryRecord::Text(_) => BenchmarkType::Text,
+            HistoryRecord::Audio(_) => BenchmarkType::Audio,
+            HistoryRecord::Code(_) => BenchmarkType::Code,
+        }
+    }
+
+    pub fn config_key(&self) -> String {
+        match self {
+            HistoryRecord::Text(e) => e.config_key(),
+            HistoryRecord::Audio(e) => e.config_key(),
+            HistoryRecord::Code(e) => e.config_key(),
+        }
+    }
+
+    pub fn origin_mut(&mut self) -> &mut Option<RunOrigin> {
+        match self {
+            HistoryRecord::Text(e) => &mut e.origin,
+            HistoryRecord::Audio(e) => &mut e.origin,
+            HistoryRecord::Code(e) => &mut e.origin,
+        }
+    }
+}
+
+// =============================================================================
+// Baseline Types
+// =============================================================================
+
+/// Significance test behind a metric comparison
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignificanceTest {
+    /// Welch's unequal-variance t-test on per-iteration (or per-problem) values
+    WelchT,
+    /// Exact binomial test of the solved count against the baseline pass rate
+    /// (reports saved by older versions)
+    Binomial,
+    /// Fisher's exact test of the two runs' solved and failed counts
+    FisherExact,
+}
+
+impl SignificanceTest {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SignificanceTest::WelchT => "Welch's t",
+            SignificanceTest::Binomial => "Binomial",
+            SignificanceTest::FisherExact => "Fisher's exact",
+        }
+    }
+}
+
+/// When a worse result counts as a regression: both limits must be crossed
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RegressionThresholds {
+    /// Largest one-sided p-value treated as significant
+    pub max_p_value: f64,
+    /// Smallest relative change, in percent, worth flagging
+    pub min_change_pct: f64,
+}
+
+impl Default for RegressionThresholds {
+    fn default() -> Self {
+        Self {
+            max_p_value: 0.05,
+            min_change_pct: 5.0,
+        }
+    }
+}
+
+/// One metric of a run compared with the same metric of its baseline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricComparison {
+    /// e.g. "tokens_per_sec", "pass_rate"
+    pub metric: String,
+    pub test: SignificanceTest,
+    pub higher_is_better: bool,
+    pub baseline_mean: f64,
+    pub candidate_mean: f64,
+    pub baseline_samples: usize,
+    pub candidate_samples: usize,
+    /// Relative change of the mean, in percent (negative = lower)
+    pub change_pct: f64,
+    /// One-sided p-value for "the run is worse"; `None` with too few samples to test
+    pub p_value: Option<f64>,
+    pub regressed: bool,
+}
+
+impl MetricComparison {
+    /// e.g. "tokens_per_sec: 42.10 → 36.80 (-12.6%, p=0.004)"
+    pub fn describe(&self) -> String {
+        let p = match self.p_value {
+            Some(p) => format!("p={:.3}", p),
+            None => "untested".to_string(),
+        };
+        format!(
+            "{}: {:.2} → {:.2} ({:+.1}%, {})",
+            self.metric, self.baseline_mean, self.candidate_mean, self.change_pct, p
+        )
+    }
+}
+
+/// A run compared with the pinned baseline of its config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegressionReport {
+    pub run_id: String,
+    pub baseline_id: String,
+    pub config_key: String,
+    pub compared_at: i64,
+    pub thresholds: RegressionThresholds,
+    pub metrics: Vec<MetricComparison>,
+    /// Set when any metric regressed
+    pub regressed: bool,
+}
+
+impl RegressionReport {
+    pub fn regressions(&self) -> impl Iterator<Item = &MetricComparison> {
+        self.metrics.iter().filter(|m| m.regressed)
+    }
+
+    /// One line per metric, regressions first
+    pub fn describe(&self) -> String {
+        let (mut lines, rest): (Vec<_>, Vec<_>) =
+            self.metrics.iter().partition(|m| m.regressed);
+        lines.extend(rest);
+        lines
+            .iter()
+            .map(|m| match m.regressed {
+                true => format!("⚠ {}", m.describe()),
+                false => m.describe(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+// =============================================================================
+// Bundle Types
+// =============================================================================
+
+/// `HistoryBundle::format` marker
+pub const HISTORY_BUNDLE_FORMAT: &str = "llamaburn-history";
+
+/// Newest bundle version this build reads and the one it writes
+pub const HISTORY_BUNDLE_VERSION: u32 = 1;
+
+/// Machine an imported run was recorded on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunOrigin {
+    pub host: String,
+    pub llamaburn_version: String,
+    /// When the bundle was exported on that machine (unix seconds)
+    pub exported_at: i64,
+    /// When the run was imported here (unix seconds)
+    pub imported_at: i64,
+}
+
+/// Full-fidelity export of history for moving results between machines
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryBundle {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: i64,
+    /// Host name and llamaburn version of the exporting machine
+    pub host: String,
+    pub llamaburn_version: String,
+    pub runs: Vec<HistoryRecord>,
+    #[serde(default)]
+    pub presets: Vec<Preset>,
+    #[serde(default)]
+    pub batches: Vec<BatchState>,
+    #[serde(default)]
+    pub effect_detections: Vec<EffectDetectionHistoryEntry>,
+    /// Ids of stored runs whose payload couldn't be read, left out of `runs`.
+    /// Reported to the exporter only; never written to the file.
+    #[serde(skip)]
+    pub unreadable: Vec<String>,
+}
+
+/// What goes into an exported bundle
+#[derive(Debug, Clone, Default)]
+pub struct BundleContents {
+    /// Runs to export; `None` exports every run
+    pub run_ids: Option<Vec<String>>,
+    pub presets: bool,
+    pub batches: bool,
+    pub effect_detections: bool,
+}
+
+/// What an export wrote; `unreadable` lists stored runs that couldn't be read and were left out
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BundleExportSummary {
+    pub runs_exported: usize,
+    pub unreadable: Vec<String>,
+}
+
+/// Counts from importing a bundle; items already present (same id) are skipped
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BundleImportSummary {
+    pub runs_imported: usize,
+    pub runs_skipped: usize,
+    pub presets_imported: usize,
+    pub presets_skipped: usize,
+    pub batches_imported: usize,
+    pub batches_skipped: usize,
+    /// Running or paused batches, which can only be resumed where they ran
+    #[serde(default)]
+    pub batches_unfinished: usize,
+    pub effect_detections_imported: usize,
+    pub effect_detections_skipped: usize,
+}
+
+impl BundleImportSummary {
+    /// e.g. "12 runs, 2 presets imported (3 already present)"
+    pub fn describe(&self) -> String {
+        let imported: Vec<String> = [
+            (self.runs_imported, "runs"),
+            (self.presets_imported, "presets"),
+            (self.batches_imported, "batches"),
+            (self.effect_detections_imported, "effect detections"),
+        ]
+        .iter()
+        .filter(|(n, _)| *n > 0)
+        .map(|(n, label)| format!("{} {}", n, label))
+        .collect();
+        let skipped = self.runs_skipped
+            + self.presets_skipped
+            + self.batches_skipped
+            + self.effect_detections_skipped;
+
+        let imported = match imported.is_empty() {
+            true => "Nothing new imported".to_string(),
+            false => format!("{} imported", imported.join(", ")),
+        };
+        let imported = match skipped {
+            0 => imported,
+            n => format!("{} ({} already present)", imported, n),
+        };
+        match self.batches_unfinished {
+            0 => imported,
+            n => format!("{}; {} unfinished batches left out", imported, n),
+        }
+    }
+}
+
+// =============================================================================
+// Search Types
+// =============================================================================
+
+/// Marks the start of a matched term in `SearchHit::snippet`
+pub const SEARCH_MATCH_START: char = '\u{2}';
+
+/// Marks the end of a matched term in `SearchHit::snippet`
+pub const SEARCH_MATCH_END: char = '\u{3}';
+
+/// Kind of text a full-text search hit came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchSource {
+    GeneratedCode,
+    Transcription,
+    EffectDescription,
+}
+
+impl SearchSource {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SearchSource::GeneratedCode => "Code",
+            SearchSource::Transcription => "Transcript",
+            SearchSource::EffectDescription => "Effect",
+        }
+    }
+
+    pub fn all() -> &'static [SearchSource] {
+        &[
+            SearchSource::GeneratedCode,
+            SearchSource::Transcription,
+            SearchSource::EffectDescription,
+        ]
+    }
+}
+
+/// One full-text match, best first
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub source: SearchSource,
+    /// History run the text belongs to; `None` for effect descriptions
+    pub run_id: Option<String>,
+    /// `effect_detection_history` row for effect descriptions
+    pub effect_detection_id: Option<i64>,
+    /// Model of the run, or the LLM that wrote the effect description
+    pub model_id: String,
+    pub timestamp: i64,
+    /// Problem id, "Iteration N" or the analyzed audio file
+    pub label: String,
+    /// Excerpt around the match, matched terms wrapped in `SEARCH_MATCH_START`/`SEARCH_MATCH_END`
+    pub snippet: String,
+}
+
+impl SearchHit {
+    /// Snippet split into `(text, is_match)` pieces for highlighting
+    pub fn snippet_parts(&self) -> Vec<(&str, bool)> {
+        let mut parts = Vec::new();
+        let mut rest = self.snippet.as_str();
+        while let Some(start) = rest.find(SEARCH_MATCH_START) {
+            if start > 0 {
+                parts.push((&rest[..start], false));
+            }
+            rest = &rest[start + SEARCH_MATCH_START.len_utf8()..];
+            let end = rest.find(SEARCH_MATCH_END).unwrap_or(rest.len());
+            parts.push((&rest[..end], true));
+            rest = rest.get(end + SEARCH_MATCH_END.len_utf8()..).unwrap_or("");
+        }
+        if !rest.is_empty() {
+            parts.push((rest, false));
+        }
+        parts
+    }
+
+    /// Snippet with matches wrapped in `marker`, e.g. `**` for Markdown
+    pub fn snippet_with(&self, marker: &str) -> String {
+        self.snippet.replace([SEARCH_MATCH_START, SEARCH_MATCH_END], marker)
+    }
+}
+
+// =============================================================================
+// Solution Types
+// =============================================================================
+
+/// How many stored solutions a problem has and how many of them pass
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProblemSolutions {
+    pub problem_id: String,
+    pub solutions: u32,
+    pub passing: u32,
+}
+
+impl ProblemSolutions {
+    /// Some solutions pass and some fail
+    pub fn disputed(&self) -> bool {
+        self.passing > 0 && self.passing < self.solutions
+    }
+}
+
+/// Code one history run generated for one problem
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredSolution {
+    pub run_id: String,
+    pub model_id: String,
+    pub language: Language,
+    pub temperature: f32,
+    pub timestamp: i64,
+    pub problem_id: String,
+    pub code: String,
+    pub tests_passed: u32,
+    pub tests_total: u32,
+    pub compilation_error: Option<String>,
+    pub runtime_error: Option<String>,
+}
+
+impl StoredSolution {
+    pub fn passed(&self) -> bool {
+        all_tests_passed(self.tests_passed, self.tests_total)
+    }
+
+    /// "qwen2.5-coder:7b · Python · T=0.2"
+    pub fn describe(&self) -> String {
+        format!("{} · {} · T={:.1}", self.model_id, self.language.label(), self.temperature)
+    }
+}
+
+// =============================================================================
+// Trend Types
+// =============================================================================
+
+/// Run-level metric plotted over time in the History trends view
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrendMetric {
+    #[default]
+    Tps,
+    Ttft,
+    PassRate,
+    Rtf,
+    Wer,
+}
+
+impl TrendMetric {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TrendMetric::Tps => "Tokens/sec",
+            TrendMetric::Ttft => "TTFT (ms)",
+            TrendMetric::PassRate => "Pass Rate (%)",
+            TrendMetric::Rtf => "RTF",
+            TrendMetric::Wer => "WER (%)",
+        }
+    }
+
+    pub fn all() -> &'static [TrendMetric] {
+        &[
+            TrendMetric::Tps,
+            TrendMetric::Ttft,
+            TrendMetric::PassRate,
+            TrendMetric::Rtf,
+            TrendMetric::Wer,
+        ]
+    }
+
+    /// Benchmark types that record this metric
+    pub fn benchmark_types(&self) -> &'static [BenchmarkType] {
+        match self {
+            TrendMetric::Tps | TrendMetric::Ttft => &[BenchmarkType::Text, BenchmarkType::Code],
+            TrendMetric::PassRate => &[BenchmarkType::Code],
+            TrendMetric::Rtf | TrendMetric::Wer => &[BenchmarkType::Audio],
+        }
+    }
+
+    pub fn higher_is_better(&self) -> bool {
+        matches!(self, TrendMetric::Tps | TrendMetric::PassRate)
+    }
+
+    /// The run's value; `None` for other benchmark types, unsuccessful code runs and
+    /// code runs that didn't measure speed, and audio runs without a reference transcript
+    pub fn value(&self, record: &HistoryRecord) -> Option<f64> {
+        let measured = |v: f64| (v > 0.0).then_some(v);
+        match (self, record) {
+            (TrendMetric::Tps, HistoryRecord::Text(e)) => Some(e.summary.avg_tps),
+            (TrendMetric::Ttft, HistoryRecord::Text(e)) => Some(e.summary.avg_ttft_ms),
+            (_, HistoryRecord::Code(e)) if e.status != RunStatus::Success => None,
+            (TrendMetric::Tps, HistoryRecord::Code(e)) => measured(e.summary.avg_tps),
+            (TrendMetric::Ttft, HistoryRecord::Code(e)) => measured(e.summary.avg_ttft_ms),
+            (TrendMetric::PassRate, HistoryRecord::Code(e)) => Some(e.summary.pass_rate * 100.0),
+            (TrendMetric::Rtf, HistoryRecord::Audio(e)) => Some(e.summary.avg_rtf),
+            (TrendMetric::Wer, HistoryRecord::Audio(e)) => e.summary.accuracy.map(|a| a.wer() * 100.0),
+            _ => None,
+        }
+    }
+}
+
+/// One run on a trend line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrendPoint {
+    pub run_id: String,
+    pub timestamp: i64,
+    pub value: f64,
+    /// Pinned as the baseline of its config
+    pub baseline: bool,
+    /// Environment differences from the previous point, e.g. "Ollama: 0.5.1 vs 0.6.0"
+    pub environment_changes: Vec<String>,
+}
+
+/// One model's runs of one benchmark type, oldest first
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrendSeries {
+    pub model_id: String,
+    pub benchmark_type: BenchmarkType,
+    pub points: Vec<TrendPoint>,
+}
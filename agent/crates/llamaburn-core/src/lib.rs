@@ -12,25 +12,32 @@ pub mod text;
 pub use ai::{Modality, ModelConfig, ModelInfo};
 pub use ai_selector::ModelList;
 pub use audio::{
-    AppliedEffect, AudioBenchmark, AudioBenchmarkConfig, AudioBenchmarkMetrics,
-    AudioBenchmarkResult, AudioBenchmarkSummary, AudioCaptureConfig, AudioDevice, AudioMode,
-    AudioSampleFormat, AudioSource, AudioSourceMode, DetectedEffect, DeviceType,
-    EffectDetectionConfig, EffectDetectionResult, EffectDetectionTool, Segment, SignalAnalysis,
+    normalize_transcript, score_transcript, word_error_rate, AppliedEffect, AudioBenchmark,
+    AudioBenchmarkConfig, AudioBenchmarkMetrics, AudioBenchmarkResult, AudioBenchmarkSummary,
+    AudioCaptureConfig, AudioDevice, AudioMode, AudioSampleFormat, AudioSource, AudioSourceMode,
+    DatasetClip, DetectedEffect, DeviceType, EffectDetectionConfig, EffectDetectionResult,
+    EffectDetectionTool, FailedClip, Segment, SignalAnalysis, TranscriptAccuracy,
     TranscriptionResult, TranscriptionSegment, WhisperEvent, WhisperModel, CHANNEL_OPTIONS,
     SAMPLE_RATES,
 };
 pub use benchmark_type::BenchmarkType;
 pub use code::{
-    BenchmarkCombo, CodeBenchmark, CodeBenchmarkConfig, CodeBenchmarkMetrics, CodeBenchmarkResult,
-    CodeBenchmarkSummary, CodeProblem, Difficulty, ErrorLogEntry, EvaluationMode, Language, Preset,
-    ProblemSet, TestCase,
+    all_tests_passed, BenchmarkCombo, CodeBenchmark, CodeBenchmarkConfig, CodeBenchmarkMetrics,
+    CodeBenchmarkResult, CodeBenchmarkSummary, CodeProblem, CodeQuality, CodeTask, Comparator,
+    Difficulty, ErrorLogEntry, EvaluationMode, GenerationMode, Language, PatchStats, Preset,
+    ProblemKind, ProblemSet, RepairAttempt, RepoFixture, TagStats, TestCase, TestCaseResult,
 };
 pub use error::{LlamaBurnError, Result};
 pub use history::{
-    AudioHistoryEntry, BatchCombo, BatchState, BatchStatus, BenchmarkHistoryEntry,
-    CodeHistoryEntry, EffectDetectionHistoryEntry, HistoryFilter, RunStatus,
+    AudioHistoryEntry, BatchCombo, BatchState, BatchStatus, BenchmarkHistoryEntry, BundleContents,
+    BundleExportSummary, BundleImportSummary, CodeBatchCombo, CodeHistoryEntry, CodeLeaderboard,
+    CodeLeaderboardEntry, EffectDetectionHistoryEntry, HistoryBundle, HistoryCursor, HistoryFilter,
+    HistoryPage, HistoryRecord, HistorySort, MetricComparison, ProblemSolutions, RegressionReport,
+    RegressionThresholds, RunOrigin, RunStatus, SearchHit, SearchSource, SignificanceTest,
+    StoredSolution, SttBatchCombo, TextBatchCombo, TrendMetric, TrendPoint, TrendSeries,
+    HISTORY_BUNDLE_FORMAT, HISTORY_BUNDLE_VERSION, SEARCH_MATCH_END, SEARCH_MATCH_START,
 };
-pub use system::GpuMetrics;
+pub use system::{EnvironmentFingerprint, GpuMetrics};
 pub use text::{
     BenchmarkMetrics, TextBenchmark, TextBenchmarkConfig, TextBenchmarkResult, TextBenchmarkSummary,
 };
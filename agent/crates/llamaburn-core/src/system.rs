@@ -1,8 +1,115 @@
 //! System-level types for hardware monitoring
 
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
 /// GPU metrics collected from monitoring
 #[derive(Debug, Clone, Default)]
 pub struct GpuMetrics {
     pub raw_output: String,
     pub connected: bool,
 }
+
+/// Software and hardware a benchmark ran on, recorded with every history entry.
+/// Fields are `None` when they couldn't be detected.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EnvironmentFingerprint {
+    pub llamaburn_version: String,
+    #[serde(default)]
+    pub ollama_version: Option<String>,
+    /// Model blob digest from Ollama
+    #[serde(default)]
+    pub model_digest: Option<String>,
+    #[serde(default)]
+    pub model_family: Option<String>,
+    #[serde(default)]
+    pub parameter_size: Option<String>,
+    #[serde(default)]
+    pub quantization: Option<String>,
+    #[serde(default)]
+    pub gpu_name: Option<String>,
+    #[serde(default)]
+    pub gpu_driver: Option<String>,
+    #[serde(default)]
+    pub kernel: Option<String>,
+    #[serde(default)]
+    pub cpu_model: Option<String>,
+    #[serde(default)]
+    pub ram_mb: Option<u64>,
+    /// Effective sampling options: model defaults overlaid with what the run sent
+    #[serde(default)]
+    pub sampling: BTreeMap<String, String>,
+}
+
+impl EnvironmentFingerprint {
+    /// Fields that differ, e.g. "Ollama: 0.5.1 vs 0.6.0". Model fields are only
+    /// compared when `same_model` is set, since different models always differ there.
+    pub fn differences(&self, other: &Self, same_model: bool) -> Vec<String> {
+        let mut fields = vec![
+            ("LlamaBurn", Some(self.llamaburn_version.clone()), Some(other.llamaburn_version.clone())),
+            ("Ollama", self.ollama_version.clone(), other.ollama_version.clone()),
+            ("GPU", self.gpu_name.clone(), other.gpu_name.clone()),
+            ("GPU driver", self.gpu_driver.clone(), other.gpu_driver.clone()),
+            ("Kernel", self.kernel.clone(), other.kernel.clone()),
+            ("CPU", self.cpu_model.clone(), other.cpu_model.clone()),
+            ("RAM", self.ram_mb.map(format_ram), other.ram_mb.map(format_ram)),
+        ];
+        if same_model {
+            fields.extend([
+                ("Model digest", short_digest(&self.model_digest), short_digest(&other.model_digest)),
+                ("Quantization", self.quantization.clone(), other.quantization.clone()),
+            ]);
+        }
+
+        let mut diffs: Vec<String> = fields
+            .into_iter()
+            .filter(|(_, a, b)| a != b)
+            .map(|(label, a, b)| format!("{}: {} vs {}", label, or_unknown(a), or_unknown(b)))
+            .collect();
+
+        let keys: BTreeSet<&String> =
+            self.sampling.keys().chain(other.sampling.keys()).collect();
+        for key in keys {
+            let a = self.sampling.get(key);
+            let b = other.sampling.get(key);
+            if a != b {
+                diffs.push(format!(
+                    "{}: {} vs {}",
+                    key,
+                    or_unknown(a.cloned()),
+                    or_unknown(b.cloned())
+                ));
+            }
+        }
+        diffs
+    }
+
+    /// One-line summary, e.g. "Ollama 0.5.1 | Radeon RX 7900 XTX | Q4_K_M"
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(v) = &self.ollama_version {
+            parts.push(format!("Ollama {}", v));
+        }
+        if let Some(gpu) = &self.gpu_name {
+            parts.push(gpu.clone());
+        }
+        if let Some(q) = &self.quantization {
+            parts.push(q.clone());
+        }
+        parts.push(format!("llamaburn {}", self.llamaburn_version));
+        parts.join(" | ")
+    }
+}
+
+fn format_ram(mb: u64) -> String {
+    format!("{:.1} GB", mb as f64 / 1024.0)
+}
+
+fn short_digest(digest: &Option<String>) -> Option<String> {
+    digest.as_ref().map(|d| d.chars().take(12).collect())
+}
+
+fn or_unknown(value: Option<String>) -> String {
+    value.unwrap_or_else(|| "unknown".to_string())
+}
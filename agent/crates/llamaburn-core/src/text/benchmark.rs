@@ -1,11 +1,28 @@
+use std::collections::VecDeque;
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use serde::{Deserialize, Serialize};
 
 use super::{TextBenchmarkConfig, BenchmarkMetrics, TextBenchmarkResult};
+use crate::{BatchCombo, BatchState, BatchStatus, BenchmarkType, TextBatchCombo};
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TextBenchmark {
     pub config: TextBenchmarkConfig,
 
+    pub selected_models: Vec<String>,
+    pub selected_prompt_sets: Vec<String>,
+    pub selected_temperatures: Vec<f32>,
+    /// None runs without a token limit
+    pub selected_max_tokens: Vec<Option<u32>>,
+    pub skip_on_error: bool,
+
+    pub combo_queue: VecDeque<TextBatchCombo>,
+    pub current_combo: Option<TextBatchCombo>,
+    pub queue_total: usize,
+    pub queue_completed: usize,
+    pub batch_session_id: Option<String>,
+
     pub running: bool,
 
     #[serde(skip)]
@@ -23,15 +40,21 @@ pub struct TextBenchmark {
 
 impl TextBenchmark {
     pub fn new() -> Self {
-        Self::default()
+        Self {
+            selected_prompt_sets: vec!["default".to_string()],
+            selected_temperatures: vec![0.7],
+            selected_max_tokens: vec![None],
+            skip_on_error: true,
+            ..Default::default()
+        }
     }
 
-    pub fn start(&mut self, model_id: &str) {
-        self.config.model_id = model_id.to_string();
+    /// Start a run of the current config, keeping earlier combos' output
+    pub fn start(&mut self) {
         self.running = true;
         self.result = None;
         self.collected_metrics.clear();
-        self.clear_output();
+        self.error = None;
     }
 
     pub fn stop(&mut self) {
@@ -64,4 +87,126 @@ impl TextBenchmark {
     pub fn add_metrics(&mut self, metrics: BenchmarkMetrics) {
         self.collected_metrics.push(metrics);
     }
+
+    /// Every selected model × prompt set × temperature × max tokens
+    pub fn generate_combos(&self) -> Vec<TextBatchCombo> {
+        let mut combos = Vec::new();
+
+        for model in &self.selected_models {
+            for prompt_set in &self.selected_prompt_sets {
+                for &temperature in &self.selected_temperatures {
+                    for &max_tokens in &self.selected_max_tokens {
+                        combos.push(TextBatchCombo {
+                            model: model.clone(),
+                            prompt_set: prompt_set.clone(),
+                            temperature,
+                            max_tokens,
+                        });
+                    }
+                }
+            }
+        }
+
+        combos
+    }
+
+    /// Queue every combination under a new batch session
+    pub fn start_matrix(&mut self, session_id: String) {
+        self.combo_queue = self.generate_combos().into();
+        self.queue_total = self.combo_queue.len();
+        self.queue_completed = 0;
+        self.current_combo = None;
+        self.batch_session_id = Some(session_id);
+    }
+
+    /// Pop the next combo and point the run config at it
+    pub fn advance_to_next(&mut self) -> Option<TextBatchCombo> {
+        self.current_combo = self.combo_queue.pop_front();
+        let combo = self.current_combo.clone()?;
+        self.config.model_id = combo.model.clone();
+        self.config.prompt_set = combo.prompt_set.clone();
+        self.config.temperature = combo.temperature;
+        self.config.max_tokens = combo.max_tokens;
+        Some(combo)
+    }
+
+    pub fn complete_current(&mut self) {
+        self.queue_completed += 1;
+    }
+
+    pub fn clear_matrix(&mut self) {
+        self.combo_queue.clear();
+        self.current_combo = None;
+        self.queue_total = 0;
+        self.queue_completed = 0;
+        self.batch_session_id = None;
+    }
+
+    /// Batch state for persistence; the current combo stays at the front of the queue
+    pub fn to_batch_state(&self, status: BatchStatus) -> Option<BatchState> {
+        let session_id = self.batch_session_id.clone()?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64;
+
+        let pending = self
+            .current_combo
+            .iter()
+            .chain(self.combo_queue.iter())
+            .map(|c| BatchCombo::Text(c.clone()))
+            .collect();
+
+        Some(BatchState {
+            session_id,
+            benchmark_type: BenchmarkType::Text,
+            created_at: now,
+            updated_at: now,
+            status,
+            selected_models: self.selected_models.clone(),
+            selected_languages: Vec::new(),
+            selected_temperatures: self.selected_temperatures.clone(),
+            selected_max_tokens: self.selected_max_tokens.iter().flatten().copied().collect(),
+            selected_problem_ids: Vec::new(),
+            auto_run_tests: false,
+            skip_on_error: self.skip_on_error,
+            pending_combos: pending,
+            queue_total: self.queue_total,
+            queue_completed: self.queue_completed,
+            failed_combo: None,
+            error_message: None,
+        })
+    }
+
+    /// Restore selections and the remaining queue from a saved text batch
+    pub fn restore_from_batch(&mut self, batch: &BatchState) {
+        self.combo_queue = batch
+            .pending_combos
+            .iter()
+            .filter_map(|c| match c {
+                BatchCombo::Text(c) => Some(c.clone()),
+                _ => None,
+            })
+            .collect();
+
+        // Prompt sets and token limits aren't persisted as a selection; recover them
+        self.selected_models = batch.selected_models.clone();
+        self.selected_temperatures = batch.selected_temperatures.clone();
+        self.selected_prompt_sets.clear();
+        self.selected_max_tokens.clear();
+        for combo in &self.combo_queue {
+            if !self.selected_prompt_sets.contains(&combo.prompt_set) {
+                self.selected_prompt_sets.push(combo.prompt_set.clone());
+            }
+            if !self.selected_max_tokens.contains(&combo.max_tokens) {
+                self.selected_max_tokens.push(combo.max_tokens);
+            }
+        }
+
+        self.skip_on_error = batch.skip_on_error;
+        self.current_combo = None;
+        self.queue_total = batch.queue_total;
+        self.queue_completed = batch.queue_completed;
+        self.batch_session_id = Some(batch.session_id.clone());
+    }
 }
@@ -133,6 +133,12 @@ impl eframe::App for LlamaBurnApp {
             self.handle_load_request(req);
         }
 
+        // Check for solution browser request from a finished matrix run
+        if let Some(problem_id) = self.benchmark.take_solutions_request() {
+            self.current_tab = Tab::History;
+            self.history.open_solutions(problem_id);
+        }
+
         egui::SidePanel::right("gpu_panel")
             .default_width(420.0)
             .show(ctx, |ui| {
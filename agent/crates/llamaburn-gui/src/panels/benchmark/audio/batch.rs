@@ -0,0 +1,332 @@
+//! STT batch runs - every selected whisper model × audio file, resumable
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use eframe::egui;
+use llamaburn_services::{
+    load_reference_transcript, BatchCombo, BatchState, BatchStatus, BenchmarkType, SttBatchCombo,
+    WhisperModel,
+};
+
+use super::{AudioAction, AudioBenchmarkPanel};
+use crate::panels::benchmark::components::{
+    incomplete_sessions, multi_select_dropdown, SessionChoice,
+};
+
+impl AudioBenchmarkPanel {
+    /// Every selected whisper model × audio file
+    pub fn generate_stt_combos(&self) -> Vec<SttBatchCombo> {
+        let mut combos = Vec::new();
+        for &model in &self.batch_models {
+            for audio_path in &self.batch_audio_files {
+                combos.push(SttBatchCombo {
+                    model,
+                    audio_path: audio_path.clone(),
+                });
+            }
+        }
+        combos
+    }
+
+    /// Queue every combination and start the first
+    pub fn start_stt_batch(&mut self) -> Vec<AudioAction> {
+        self.combo_queue = self.generate_stt_combos().into();
+        self.queue_total = self.combo_queue.len();
+        self.queue_completed = 0;
+        self.current_combo = None;
+        self.batch_session_id = Some(uuid::Uuid::new_v4().to_string());
+
+        let mut actions = vec![AudioAction::SetError(None), AudioAction::ClearOutput];
+
+        // Save initial batch state for resume capability
+        if let Some(batch) = self.to_batch_state(BatchStatus::Running) {
+            actions.push(AudioAction::InsertBatch(Box::new(batch)));
+        }
+
+        actions.push(AudioAction::AppendOutput(format!(
+            "=== STT Batch: {} combinations ===\n",
+            self.queue_total
+        )));
+        actions.extend(self.advance_stt_batch());
+        actions
+    }
+
+    /// Start the next queued combo, or finish the batch when the queue is empty
+    pub fn advance_stt_batch(&mut self) -> Vec<AudioAction> {
+        let Some(combo) = self.combo_queue.pop_front() else {
+            let mut actions = Vec::new();
+            if let Some(sid) = self.batch_session_id.take() {
+                actions.push(AudioAction::DeleteBatch(sid));
+            }
+            actions.push(AudioAction::AppendOutput(
+                "\n=== All Combinations Complete ===\n".into(),
+            ));
+            self.clear_stt_batch();
+            return actions;
+        };
+
+        self.current_combo = Some(combo.clone());
+        self.whisper_model = Some(combo.model);
+        self.audio_file_path = Some(combo.audio_path.clone());
+        self.audio_duration_ms = None;
+        self.reference_text = load_reference_transcript(&combo.audio_path).unwrap_or_default();
+
+        let mut actions = vec![AudioAction::AppendOutput(format!(
+            "\n--- Combo {}/{}: {} | {} ---\n",
+            self.queue_completed + 1,
+            self.queue_total,
+            combo.model.label(),
+            combo.audio_path.display()
+        ))];
+        actions.extend(self.run_stt_file(combo.model, combo.audio_path));
+        actions
+    }
+
+    /// Record the outcome of the current combo and move on, skip or auto-pause
+    pub fn finish_stt_combo(&mut self, error: Option<String>) -> Vec<AudioAction> {
+        let mut actions = Vec::new();
+
+        let Some(message) = error else {
+            self.queue_completed += 1;
+            self.current_combo = None;
+            if let Some(batch) = self.to_batch_state(BatchStatus::Running) {
+                actions.push(AudioAction::UpdateBatch(Box::new(batch)));
+            }
+            actions.extend(self.advance_stt_batch());
+            return actions;
+        };
+
+        if self.skip_on_error {
+            actions.push(AudioAction::AppendOutput("(Skipping to next combo...)\n".into()));
+            self.queue_completed += 1;
+            self.current_combo = None;
+            actions.extend(self.advance_stt_batch());
+            return actions;
+        }
+
+        // Auto-pause so the failed combo can be retried with Resume
+        actions.push(AudioAction::AppendOutput(
+            "(Auto-paused - use Resume to continue)\n".into(),
+        ));
+        if let Some(mut batch) = self.to_batch_state(BatchStatus::Paused) {
+            batch.error_message = Some(message);
+            batch.failed_combo = self.current_combo.clone().map(BatchCombo::Stt);
+            actions.push(AudioAction::UpdateBatch(Box::new(batch.clone())));
+            self.pending_resume_batches.push(batch);
+        }
+        self.clear_stt_batch();
+        actions
+    }
+
+    /// Save the batch as paused; the running transcription finishes in the background
+    pub fn pause_stt_batch(&mut self) -> Vec<AudioAction> {
+        let mut actions = Vec::new();
+        if let Some(batch) = self.to_batch_state(BatchStatus::Paused) {
+            actions.push(AudioAction::UpdateBatch(Box::new(batch.clone())));
+            self.pending_resume_batches.push(batch);
+        }
+        self.stop_stt_batch();
+        actions.push(AudioAction::AppendOutput(
+            "\n=== STT Batch Paused ===\n".into(),
+        ));
+        actions
+    }
+
+    pub fn cancel_stt_batch(&mut self) -> Vec<AudioAction> {
+        let mut actions = Vec::new();
+        if let Some(sid) = &self.batch_session_id {
+            actions.push(AudioAction::DeleteBatch(sid.clone()));
+        }
+        self.stop_stt_batch();
+        actions.push(AudioAction::AppendOutput(
+            "\n=== STT Batch Cancelled ===\n".into(),
+        ));
+        actions
+    }
+
+    /// Detach from the running combo; whisper can't be interrupted mid-file
+    fn stop_stt_batch(&mut self) {
+        self.audio_rx = None;
+        self.running = false;
+        self.clear_stt_batch();
+    }
+
+    fn clear_stt_batch(&mut self) {
+        self.combo_queue.clear();
+        self.current_combo = None;
+        self.queue_total = 0;
+        self.queue_completed = 0;
+        self.batch_session_id = None;
+    }
+
+    /// Batch state for persistence; the current combo stays at the front of the queue
+    fn to_batch_state(&self, status: BatchStatus) -> Option<BatchState> {
+        let session_id = self.batch_session_id.clone()?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64;
+
+        let pending = self
+            .current_combo
+            .iter()
+            .chain(self.combo_queue.iter())
+            .map(|c| BatchCombo::Stt(c.clone()))
+            .collect();
+
+        Some(BatchState {
+            session_id,
+            benchmark_type: BenchmarkType::Audio,
+            created_at: now,
+            updated_at: now,
+            status,
+            selected_models: self.batch_models.iter().map(|m| m.label().to_string()).collect(),
+            selected_languages: Vec::new(),
+            selected_temperatures: Vec::new(),
+            selected_max_tokens: Vec::new(),
+            selected_problem_ids: Vec::new(),
+            auto_run_tests: false,
+            skip_on_error: self.skip_on_error,
+            pending_combos: pending,
+            queue_total: self.queue_total,
+            queue_completed: self.queue_completed,
+            failed_combo: None,
+            error_message: None,
+        })
+    }
+
+    /// Continue a saved batch from its first pending combo
+    fn resume_stt_batch(&mut self, idx: usize) -> Vec<AudioAction> {
+        let batch = self.pending_resume_batches.remove(idx);
+
+        self.combo_queue = batch
+            .pending_combos
+            .iter()
+            .filter_map(|c| match c {
+                BatchCombo::Stt(c) => Some(c.clone()),
+                _ => None,
+            })
+            .collect();
+
+        // Selections aren't persisted in a typed form; recover them from the queue
+        self.batch_models.clear();
+        self.batch_audio_files.clear();
+        for combo in &self.combo_queue {
+            if !self.batch_models.contains(&combo.model) {
+                self.batch_models.push(combo.model);
+            }
+            if !self.batch_audio_files.contains(&combo.audio_path) {
+                self.batch_audio_files.push(combo.audio_path.clone());
+            }
+        }
+
+        self.skip_on_error = batch.skip_on_error;
+        self.current_combo = None;
+        self.queue_total = batch.queue_total;
+        self.queue_completed = batch.queue_completed;
+        self.batch_session_id = Some(batch.session_id.clone());
+
+        let mut actions = vec![AudioAction::SetError(None), AudioAction::ClearOutput];
+        if let Some(updated) = self.to_batch_state(BatchStatus::Running) {
+            actions.push(AudioAction::UpdateBatch(Box::new(updated)));
+        }
+        actions.push(AudioAction::AppendOutput(format!(
+            "=== Resuming Batch {} ===\n{}/{} combinations remaining\n",
+            batch.session_id,
+            batch.queue_total - batch.queue_completed,
+            batch.queue_total
+        )));
+        actions.extend(self.advance_stt_batch());
+        actions
+    }
+
+    /// Batch selection, run/pause controls and saved sessions (File mode)
+    pub fn render_stt_batch(&mut self, ui: &mut egui::Ui) -> Vec<AudioAction> {
+        let mut actions = Vec::new();
+        let batch_running = self.batch_session_id.is_some();
+
+        ui.add_space(10.0);
+        ui.label(egui::RichText::new("Batch").strong());
+
+        let choice = incomplete_sessions(
+            ui,
+            &self.pending_resume_batches,
+            |b| format!("{} whisper models, {} combos", b.selected_models.len(), b.queue_total),
+            !self.running,
+        );
+        match choice {
+            Some(SessionChoice::Resume(idx)) => actions.extend(self.resume_stt_batch(idx)),
+            Some(SessionChoice::Discard(idx)) => {
+                let batch = self.pending_resume_batches.remove(idx);
+                actions.push(AudioAction::DeleteBatch(batch.session_id));
+            }
+            None => {}
+        }
+
+        let interactive = !self.running && !batch_running;
+        multi_select_dropdown(
+            ui,
+            "stt_batch_models_popup",
+            "Models",
+            WhisperModel::all(),
+            &mut self.batch_models,
+            |m| m.label().to_string(),
+            interactive,
+            150.0,
+        );
+
+        ui.horizontal(|ui| {
+            if ui.add_enabled(interactive, egui::Button::new("Add Files...")).clicked() {
+                let files = rfd::FileDialog::new()
+                    .add_filter("Audio", &["wav", "mp3", "flac", "m4a", "ogg"])
+                    .pick_files()
+                    .unwrap_or_default();
+                for file in files {
+                    if !self.batch_audio_files.contains(&file) {
+                        self.batch_audio_files.push(file);
+                    }
+                }
+            }
+            ui.label(format!("{} files", self.batch_audio_files.len()));
+            let can_clear = interactive && !self.batch_audio_files.is_empty();
+            if ui.add_enabled(can_clear, egui::Button::new("Clear")).clicked() {
+                self.batch_audio_files.clear();
+            }
+        });
+
+        if batch_running {
+            ui.horizontal(|ui| {
+                let progress = (self.queue_completed as f32 + 0.5) / self.queue_total as f32;
+                ui.add(egui::ProgressBar::new(progress.min(1.0)).show_percentage());
+                ui.label(format!("{} of {} executed", self.queue_completed, self.queue_total));
+            });
+            ui.horizontal(|ui| {
+                if ui.button("Pause").clicked() {
+                    actions.extend(self.pause_stt_batch());
+                }
+                if ui.button("Cancel").clicked() {
+                    actions.extend(self.cancel_stt_batch());
+                }
+                ui.spinner();
+            });
+        } else {
+            let combo_count = self.batch_models.len() * self.batch_audio_files.len();
+            let label = format!(
+                "Run {} combo{} ({} x {})",
+                combo_count,
+                if combo_count == 1 { "" } else { "s" },
+                self.batch_models.len(),
+                self.batch_audio_files.len()
+            );
+            let can_run = !self.running && combo_count > 0;
+            if ui.add_enabled(can_run, egui::Button::new(label)).clicked() {
+                actions.extend(self.start_stt_batch());
+            }
+        }
+
+        ui.checkbox(&mut self.skip_on_error, "Skip on Error")
+            .on_hover_text("Skip failed combos and continue (for unattended runs)");
+
+        actions
+    }
+}
@@ -0,0 +1,268 @@
+//! STT dataset runs - every clip of a directory or manifest, saved as one history entry
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use eframe::egui;
+use tracing::info;
+
+use llamaburn_services::{
+    capture_host_environment, dataset_stats, load_dataset, AudioBenchmarkConfig,
+    AudioBenchmarkMetrics, AudioMode, AudioSource, FailedClip, OutlierKind, WhisperService,
+};
+
+use super::{AudioAction, AudioBenchmarkEvent, AudioBenchmarkPanel};
+
+/// Outliers and failed clips listed in the live output; the rest are in the History drill-down
+const SUMMARY_OUTLIERS: usize = 10;
+
+impl AudioBenchmarkPanel {
+    /// Dataset rows of the config grid (Dataset mode only)
+    pub(super) fn render_dataset_rows(&mut self, ui: &mut egui::Ui, disabled: bool, error: &mut Option<String>) {
+        ui.label("Dataset:");
+        ui.horizontal(|ui| {
+            let folder = ui
+                .add_enabled(!disabled, egui::Button::new("Select Folder..."))
+                .on_hover_text("LibriSpeech tree, Common Voice release, or audio files with .txt transcripts");
+            if folder.clicked() {
+                if let Some(dir) = rfd::FileDialog::new().pick_folder() {
+                    self.open_dataset(dir, error);
+                }
+            }
+
+            let manifest = ui
+                .add_enabled(!disabled, egui::Button::new("Select Manifest..."))
+                .on_hover_text("JSONL, CSV or Common Voice TSV with audio path and reference text");
+            if manifest.clicked() {
+                let file = rfd::FileDialog::new()
+                    .add_filter("Manifest", &["jsonl", "json", "csv", "tsv"])
+                    .pick_file();
+                if let Some(file) = file {
+                    self.open_dataset(file, error);
+                }
+            }
+        });
+        ui.end_row();
+
+        let Some(dataset) = &self.dataset else {
+            return;
+        };
+        let name = dataset
+            .source
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| dataset.source.display().to_string());
+        ui.label("Clips:");
+        ui.label(format!(
+            "{} ({}) · {} clips, {} with reference",
+            name,
+            dataset.layout.label(),
+            dataset.clips.len(),
+            dataset.referenced()
+        ))
+        .on_hover_text(dataset.source.display().to_string());
+        ui.end_row();
+
+        ui.label("Max Clips:");
+        let clips = dataset.clips.len();
+        ui.add_enabled(
+            !disabled,
+            egui::DragValue::new(&mut self.dataset_max_clips)
+                .range(0..=clips)
+                .custom_formatter(|n, _| match n as usize {
+                    0 => "All".to_string(),
+                    n => n.to_string(),
+                }),
+        )
+        .on_hover_text("Run only the first clips of a large dataset; 0 runs all");
+        ui.end_row();
+    }
+
+    fn open_dataset(&mut self, path: PathBuf, error: &mut Option<String>) {
+        match load_dataset(&path) {
+            Ok(dataset) => {
+                info!("Loaded {} clips from {:?} ({})", dataset.clips.len(), path, dataset.layout.label());
+                self.dataset = Some(dataset);
+                self.dataset_max_clips = 0;
+                *error = None;
+            }
+            Err(e) => {
+                *error = Some(format!("Failed to load dataset: {}", e));
+            }
+        }
+    }
+
+    /// Transcribe every clip once in the background
+    pub(super) fn start_dataset_benchmark(&mut self) -> Vec<AudioAction> {
+        let Some(dataset) = &self.dataset else {
+            return vec![];
+        };
+        let Some(model) = self.whisper_model else {
+            return vec![];
+        };
+        let clips = match self.dataset_max_clips {
+            0 => dataset.clips.clone(),
+            n => dataset.clips.iter().take(n).cloned().collect(),
+        };
+        let total = clips.len();
+        let header = format!(
+            "Dataset Benchmark\n\
+             =================\n\
+             Model: {} (~{}MB)\n\
+             Dataset: {} ({})\n\
+             Clips: {} of {}\n\
+             Warmup: {}\n\n",
+            model.label(),
+            model.size_mb(),
+            dataset.source.display(),
+            dataset.layout.label(),
+            total,
+            dataset.clips.len(),
+            self.warmup,
+        );
+        info!("Starting dataset benchmark: {} clips", total);
+
+        self.running = true;
+        self.audio_result = None;
+        self.run_environment = None;
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.dataset_cancel = Some(cancel.clone());
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.audio_rx = Some(rx);
+        let warmup = self.warmup;
+
+        std::thread::spawn(move || {
+            let _ = tx.send(AudioBenchmarkEvent::Environment(capture_host_environment()));
+            let _ = tx.send(AudioBenchmarkEvent::Progress("Loading model...".to_string()));
+
+            let mut service = WhisperService::default();
+            let result = service.run_dataset(model, &clips, warmup, |index, outcome| {
+                let outcome = outcome.as_ref().cloned().map_err(|e| e.to_string());
+                let _ = tx.send(AudioBenchmarkEvent::ClipComplete { index, total, outcome });
+                !cancel.load(Ordering::Relaxed)
+            });
+
+            let event = match result {
+                Ok((metrics, _)) if metrics.is_empty() => {
+                    AudioBenchmarkEvent::Error("No clip could be transcribed".to_string())
+                }
+                Ok((metrics, failed)) => AudioBenchmarkEvent::Done { metrics, failed },
+                Err(e) => AudioBenchmarkEvent::Error(e.to_string()),
+            };
+            let _ = tx.send(event);
+        });
+
+        vec![
+            AudioAction::SetError(None),
+            AudioAction::ClearOutput,
+            AudioAction::SetProgress("Loading model...".to_string()),
+            AudioAction::AppendOutput(header),
+        ]
+    }
+
+    /// Config saved with a dataset run; the dataset stands in for the audio file
+    pub(super) fn dataset_config(&self, clips: usize) -> AudioBenchmarkConfig {
+        AudioBenchmarkConfig {
+            audio_mode: AudioMode::Stt,
+            audio_source: AudioSource::Dataset,
+            model_size: self.whisper_model,
+            audio_path: self.dataset.as_ref().map(|d| d.source.clone()).unwrap_or_default(),
+            language: None,
+            iterations: clips as u32,
+            warmup_runs: self.warmup,
+            reference_text: None,
+        }
+    }
+
+    /// Live output line for one finished clip
+    pub(super) fn dataset_clip_line(
+        index: usize,
+        total: usize,
+        outcome: &Result<AudioBenchmarkMetrics, String>,
+    ) -> String {
+        match outcome {
+            Ok(m) => {
+                let wer = m
+                    .accuracy
+                    .map_or("—".to_string(), |a| format!("{:.1}%", a.wer() * 100.0));
+                format!("[{}/{}] {}  RTF={:.3}x  WER={}\n", index + 1, total, clip_name(m), m.real_time_factor, wer)
+            }
+            Err(e) => format!("[{}/{}] failed: {}\n", index + 1, total, e),
+        }
+    }
+
+    /// Corpus WER, RTF distribution, failed clips and the worst outliers, for the live output
+    pub(super) fn dataset_summary(metrics: &[AudioBenchmarkMetrics], failed: &[FailedClip]) -> String {
+        let Some(stats) = dataset_stats(metrics, failed.len()) else {
+            return String::new();
+        };
+        let rtf = stats.rtf;
+        let mut out = format!(
+            "\nDataset Summary\n---------------\n\
+             Clips: {} ({} scored, {} failed)\n\
+             Audio: {:.1} min in {:.1} s (RTF {:.3}x)\n\
+             RTF min/p50/p90/p99/max: {:.3}/{:.3}/{:.3}/{:.3}/{:.3}\n",
+            stats.clips + stats.failed,
+            stats.scored,
+            stats.failed,
+            stats.audio_ms / 60_000.0,
+            stats.processing_ms / 1000.0,
+            stats.overall_rtf(),
+            rtf.min,
+            rtf.p50,
+            rtf.p90,
+            rtf.p99,
+            rtf.max,
+        );
+        if let Some(a) = stats.accuracy {
+            let excluded = match stats.failed {
+                0 => String::new(),
+                n => format!(", {} failed clips not counted", n),
+            };
+            out.push_str(&format!(
+                "Corpus WER: {:.2}% (S={} I={} D={} / {} words{})\nCorpus CER: {:.2}%\n",
+                a.wer() * 100.0,
+                a.substitutions,
+                a.insertions,
+                a.deletions,
+                a.reference_words,
+                excluded,
+                a.cer() * 100.0,
+            ));
+        }
+        if !failed.is_empty() {
+            out.push_str(&format!("\nFailed ({}):\n", failed.len()));
+            for f in failed.iter().take(SUMMARY_OUTLIERS) {
+                out.push_str(&format!("  {}  {}\n", f.clip.audio_path.display(), f.error));
+            }
+        }
+        if !stats.outliers.is_empty() {
+            out.push_str(&format!("\nOutliers ({}):\n", stats.outliers.len()));
+            for outlier in stats.outliers.iter().take(SUMMARY_OUTLIERS) {
+                let value = match outlier.kind {
+                    OutlierKind::Wer => format!("{:.1}%", outlier.value * 100.0),
+                    OutlierKind::Rtf => format!("{:.3}x", outlier.value),
+                };
+                out.push_str(&format!(
+                    "  {} {}  {}\n",
+                    outlier.kind.label(),
+                    value,
+                    clip_name(&metrics[outlier.index])
+                ));
+            }
+        }
+        out
+    }
+}
+
+/// File name of the clip a dataset iteration transcribed
+fn clip_name(metrics: &AudioBenchmarkMetrics) -> String {
+    metrics
+        .clip
+        .as_ref()
+        .and_then(|c| c.audio_path.file_name())
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "—".to_string())
+}
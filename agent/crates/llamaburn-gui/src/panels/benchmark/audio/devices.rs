@@ -605,7 +605,7 @@ impl AudioBenchmarkPanel {
     }
 
     pub fn pick_audio_file(&mut self, error: &mut Option<String>) {
-        use llamaburn_services::get_audio_duration_ms;
+        use llamaburn_services::{get_audio_duration_ms, load_reference_transcript};
 
         let file = rfd::FileDialog::new()
             .add_filter("Audio", &["wav", "mp3", "flac", "m4a", "ogg"])
@@ -618,6 +618,7 @@ impl AudioBenchmarkPanel {
         match get_audio_duration_ms(&path) {
             Ok(duration) => {
                 self.audio_duration_ms = Some(duration);
+                self.reference_text = load_reference_transcript(&path).unwrap_or_default();
                 self.audio_file_path = Some(path);
                 *error = None;
             }
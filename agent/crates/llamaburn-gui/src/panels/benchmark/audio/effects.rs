@@ -238,6 +238,7 @@ impl AudioBenchmarkPanel {
         info!("Starting effect detection: {:?}", audio_path);
 
         self.effect_detection_running = true;
+        self.effect_detection_source = audio_path.display().to_string();
         self.effect_detection_result = None;
 
         let tool = self.selected_effect_tool;
@@ -279,6 +280,7 @@ impl AudioBenchmarkPanel {
         );
 
         self.effect_detection_running = true;
+        self.effect_detection_source = format!("capture:{}", device_id);
         self.effect_detection_result = None;
         self.live_recording = true;
         self.waveform_peaks.clear();
@@ -416,6 +418,7 @@ impl AudioBenchmarkPanel {
         );
 
         self.effect_detection_running = true;
+        self.effect_detection_source = format!("capture:{}", device_id);
         self.effect_detection_result = None;
         self.live_recording = true;
         self.waveform_peaks.clear();
@@ -504,6 +507,10 @@ impl AudioBenchmarkPanel {
                         // Format results for Live Output
                         let output = self.format_detection_results(&detection_result);
                         actions.push(AudioAction::AppendOutput(output));
+                        actions.push(AudioAction::SaveEffectDetection(
+                            self.effect_detection_source.clone(),
+                            Box::new(detection_result.clone()),
+                        ));
                         self.effect_detection_result = Some(detection_result);
                     }
                     Err(e) => {
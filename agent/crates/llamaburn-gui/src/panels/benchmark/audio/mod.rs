@@ -1,10 +1,13 @@
+mod batch;
+mod dataset;
 mod devices;
 mod effects;
 mod stt;
 mod ui;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::Receiver;
 use std::sync::Arc;
 use std::time::Instant;
@@ -12,8 +15,9 @@ use std::time::Instant;
 use eframe::egui;
 
 use llamaburn_services::{
-    AudioBenchmarkResult, AudioSourceMode, BenchmarkType, EffectDetectionResult,
-    EffectDetectionTool, WhisperModel,
+    AudioBenchmarkResult, AudioSource, AudioSourceMode, BatchState, BenchmarkType,
+    EffectDetectionResult, EffectDetectionTool, EnvironmentFingerprint, SttBatchCombo, SttDataset,
+    WhisperModel,
 };
 use llamaburn_services::{
     AudioHistoryEntry, EffectDetectionService, HistoryService,
@@ -121,13 +125,23 @@ pub enum AudioTestEvent {
 
 /// Events from async audio benchmark
 pub enum AudioBenchmarkEvent {
+    /// Sent first, from the benchmark thread
+    Environment(EnvironmentFingerprint),
     Progress(String),
     IterationComplete {
         iteration: u32,
         metrics: llamaburn_services::AudioBenchmarkMetrics,
     },
+    /// One dataset clip finished (index from 0)
+    ClipComplete {
+        index: usize,
+        total: usize,
+        outcome: Result<llamaburn_services::AudioBenchmarkMetrics, String>,
+    },
     Done {
         metrics: Vec<llamaburn_services::AudioBenchmarkMetrics>,
+        /// Dataset clips that couldn't be transcribed; empty for other runs
+        failed: Vec<llamaburn_services::FailedClip>,
     },
     Error(String),
 }
@@ -162,7 +176,12 @@ pub enum AudioAction {
     SetError(Option<String>),
 
     // History operations
-    SaveHistory(AudioHistoryEntry),
+    SaveHistory(Box<AudioHistoryEntry>),
+    /// Effect detection result and the audio it analyzed
+    SaveEffectDetection(String, Box<EffectDetectionResult>),
+    InsertBatch(Box<BatchState>),
+    UpdateBatch(Box<BatchState>),
+    DeleteBatch(String),
 
     // Model management
     RefreshModels,
@@ -192,10 +211,14 @@ pub struct AudioBenchmarkPanel {
     pub running: bool,
     pub audio_file_path: Option<PathBuf>,
     pub audio_duration_ms: Option<f64>,
+    /// Expected transcript of the audio file; empty skips WER scoring
+    pub reference_text: String,
     pub whisper_model: Option<WhisperModel>,
     pub whisper_service: WhisperService,
     pub audio_result: Option<AudioBenchmarkResult>,
     pub audio_rx: Option<Receiver<AudioBenchmarkEvent>>,
+    /// Fingerprint reported at the start of the running benchmark
+    pub run_environment: Option<EnvironmentFingerprint>,
 
     // Audio recording state
     pub audio_source_mode: AudioSourceMode,
@@ -242,8 +265,28 @@ pub struct AudioBenchmarkPanel {
     pub effect_detection_result: Option<EffectDetectionResult>,
     pub effect_detection_running: bool,
     pub effect_detection_rx: Option<Receiver<Result<EffectDetectionResult, String>>>,
+    /// Audio file or capture device being analyzed, saved with the result
+    pub effect_detection_source: String,
     pub effect_tool_availability: HashMap<EffectDetectionTool, bool>,
     pub effect_tool_check_rx: Option<Receiver<(EffectDetectionTool, bool)>>,
+
+    // STT batch (whisper models × audio files)
+    pub batch_models: Vec<WhisperModel>,
+    pub batch_audio_files: Vec<PathBuf>,
+    pub skip_on_error: bool,
+    pub combo_queue: VecDeque<SttBatchCombo>,
+    pub current_combo: Option<SttBatchCombo>,
+    pub queue_total: usize,
+    pub queue_completed: usize,
+    pub batch_session_id: Option<String>,
+    pub pending_resume_batches: Vec<BatchState>,
+
+    // STT dataset (Dataset mode)
+    pub dataset: Option<SttDataset>,
+    /// Run only the first N clips; 0 runs all
+    pub dataset_max_clips: usize,
+    /// Set to stop a running dataset after the current clip
+    pub dataset_cancel: Option<Arc<AtomicBool>>,
 }
 
 impl Default for AudioBenchmarkPanel {
@@ -261,10 +304,12 @@ impl AudioBenchmarkPanel {
             running: false,
             audio_file_path: None,
             audio_duration_ms: None,
+            reference_text: String::new(),
             whisper_model: None,
             whisper_service: WhisperService::default(),
             audio_result: None,
             audio_rx: None,
+            run_environment: None,
 
             audio_source_mode: AudioSourceMode::default(),
             audio_devices: Vec::new(),
@@ -306,8 +351,23 @@ impl AudioBenchmarkPanel {
             effect_detection_result: None,
             effect_detection_running: false,
             effect_detection_rx: None,
+            effect_detection_source: String::new(),
             effect_tool_availability: HashMap::new(),
             effect_tool_check_rx: None,
+
+            batch_models: Vec::new(),
+            batch_audio_files: Vec::new(),
+            skip_on_error: true,
+            combo_queue: VecDeque::new(),
+            current_combo: None,
+            queue_total: 0,
+            queue_completed: 0,
+            batch_session_id: None,
+            pending_resume_batches: Vec::new(),
+
+            dataset: None,
+            dataset_max_clips: 0,
+            dataset_cancel: None,
         };
 
         // Start async tool availability check on startup
@@ -418,7 +478,10 @@ impl AudioBenchmarkPanel {
 
             // Only show VU meter when device is selected AND in Capture/Live mode
             let has_device = self.selected_device_id.is_some();
-            let needs_capture = self.audio_source_mode != AudioSourceMode::File;
+            let needs_capture = matches!(
+                self.audio_source_mode,
+                AudioSourceMode::Capture | AudioSourceMode::LiveStream
+            );
             let needs_monitor = has_device && needs_capture && self.level_monitor_handle.is_none();
             if needs_monitor {
                 self.start_level_monitor();
@@ -576,10 +639,12 @@ impl AudioBenchmarkPanel {
                         ui.selectable_value(&mut self.audio_source_mode, AudioSourceMode::File, "File");
                         ui.selectable_value(&mut self.audio_source_mode, AudioSourceMode::Capture, "Capture");
                         ui.selectable_value(&mut self.audio_source_mode, AudioSourceMode::LiveStream, "Live");
+                        ui.selectable_value(&mut self.audio_source_mode, AudioSourceMode::Dataset, "Dataset")
+                            .on_hover_text("Every clip of a LibriSpeech, Common Voice or manifest dataset");
                     });
                 });
                 if self.audio_source_mode != prev_mode
-                    && self.audio_source_mode != AudioSourceMode::File
+                    && matches!(self.audio_source_mode, AudioSourceMode::Capture | AudioSourceMode::LiveStream)
                     && self.audio_devices.is_empty()
                 {
                     self.refresh_audio_devices(&mut shared.audio.error);
@@ -606,6 +671,21 @@ impl AudioBenchmarkPanel {
                         ui.label(format!("{:.1}s", duration_ms / 1000.0));
                         ui.end_row();
                     }
+
+                    ui.label("Reference:");
+                    ui.add_enabled(
+                        !disabled,
+                        egui::TextEdit::multiline(&mut self.reference_text)
+                            .hint_text("Expected transcript for WER/CER (optional)")
+                            .desired_rows(2)
+                            .desired_width(300.0),
+                    )
+                    .on_hover_text("Loaded from a .txt file next to the audio when present");
+                    ui.end_row();
+                }
+
+                if self.audio_source_mode == AudioSourceMode::Dataset {
+                    self.render_dataset_rows(ui, disabled, &mut shared.audio.error);
                 }
 
                 // Duration slider (Capture mode only)
@@ -627,9 +707,12 @@ impl AudioBenchmarkPanel {
                     ui.end_row();
                 }
 
-                ui.label("Iterations:");
-                ui.add_enabled(!disabled, egui::DragValue::new(&mut self.iterations).range(1..=20));
-                ui.end_row();
+                // Dataset clips run once each
+                if self.audio_source_mode != AudioSourceMode::Dataset {
+                    ui.label("Iterations:");
+                    ui.add_enabled(!disabled, egui::DragValue::new(&mut self.iterations).range(1..=20));
+                    ui.end_row();
+                }
 
                 ui.label("Warmup:");
                 ui.add_enabled(!disabled, egui::DragValue::new(&mut self.warmup).range(0..=5));
@@ -639,6 +722,10 @@ impl AudioBenchmarkPanel {
         ui.add_space(10.0);
         actions.extend(self.render_transport_controls(ui, &shared.model_list.selected));
 
+        if self.audio_source_mode == AudioSourceMode::File {
+            actions.extend(self.render_stt_batch(ui));
+        }
+
         // Effect detection results
         if let Some(result) = &self.effect_detection_result {
             ui.add_space(10.0);
@@ -694,7 +781,22 @@ impl AudioBenchmarkPanel {
             ui.label(format!("Avg Time: {:.0} ms", result.summary.avg_processing_ms));
             ui.label(format!("Min/Max RTF: {:.3}/{:.3}", result.summary.min_rtf, result.summary.max_rtf));
             ui.label(format!("WPS: {:.1} words/sec", wps));
-            ui.label(format!("Iterations: {}", result.summary.iterations));
+            let runs_label = match result.config.audio_source {
+                AudioSource::Dataset => "Clips",
+                _ => "Iterations",
+            };
+            ui.label(format!("{}: {}", runs_label, result.summary.iterations));
+            if let Some(accuracy) = result.summary.accuracy {
+                ui.label(format!(
+                    "WER: {:.1}% | CER: {:.1}% (S{} I{} D{} / {} words)",
+                    accuracy.wer() * 100.0,
+                    accuracy.cer() * 100.0,
+                    accuracy.substitutions,
+                    accuracy.insertions,
+                    accuracy.deletions,
+                    accuracy.reference_words,
+                ));
+            }
 
             if let Some(first) = result.metrics.first() {
                 ui.label(format!("Audio: {:.1}s | Words: {}", first.audio_duration_ms / 1000.0, first.word_count));
@@ -719,13 +821,18 @@ impl AudioBenchmarkPanel {
         let source_ready = match self.audio_source_mode {
             AudioSourceMode::File => self.audio_file_path.is_some(),
             AudioSourceMode::Capture | AudioSourceMode::LiveStream => self.selected_device_id.is_some(),
+            AudioSourceMode::Dataset => self.dataset.is_some(),
         };
 
         let whisper_ready = self.whisper_model
             .map(|m| self.whisper_service.is_model_downloaded(m))
             .unwrap_or(false);
         let fx_ready = self.is_effect_tool_available(self.selected_effect_tool);
-        let any_model_ready = whisper_ready || fx_ready;
+        // Effect detection doesn't run on datasets
+        let any_model_ready = match self.audio_source_mode {
+            AudioSourceMode::Dataset => whisper_ready,
+            _ => whisper_ready || fx_ready,
+        };
 
         ui.horizontal(|ui| {
             ui.add_space(10.0);
@@ -803,6 +910,11 @@ impl AudioBenchmarkPanel {
                     actions.extend(self.start_effect_detection_live());
                 }
             }
+            AudioSourceMode::Dataset => {
+                if whisper_ready {
+                    actions.extend(self.start_dataset_benchmark());
+                }
+            }
         }
 
         actions
@@ -813,6 +925,10 @@ impl AudioBenchmarkPanel {
         self.effect_detection_running = false;
         self.live_recording = false;
         self.recording_start = None;
+        // A dataset run finishes its current clip and saves what it has
+        if let Some(cancel) = self.dataset_cancel.take() {
+            cancel.store(true, Ordering::Relaxed);
+        }
         if let Some(handle) = self.live_stream_handle.take() {
             handle.stop();
         }
@@ -1,7 +1,9 @@
+use std::path::PathBuf;
+
 use tracing::info;
 
 use llamaburn_services::{AudioBenchmarkConfig, AudioBenchmarkResult, AudioMode, AudioSource, WhisperModel};
-use llamaburn_services::{EffectDetectionService, WhisperService};
+use llamaburn_services::{capture_host_environment, EffectDetectionService, WhisperService};
 
 use super::{
     AudioAction, AudioBenchmarkEvent, AudioBenchmarkPanel, AudioTestState,
@@ -16,10 +18,14 @@ impl AudioBenchmarkPanel {
 
         let mut actions = Vec::new();
         let mut should_clear = false;
+        let mut batch_outcome: Option<Option<String>> = None;
         let mut result_for_history: Option<AudioBenchmarkResult> = None;
 
         while let Ok(event) = rx.try_recv() {
             match event {
+                AudioBenchmarkEvent::Environment(fingerprint) => {
+                    self.run_environment = Some(fingerprint);
+                }
                 AudioBenchmarkEvent::Progress(msg) => {
                     actions.push(AudioAction::AppendOutput(format!("{}\n", msg)));
                 }
@@ -30,23 +36,43 @@ impl AudioBenchmarkPanel {
                         iteration, metrics.real_time_factor, metrics.processing_time_ms, metrics.word_count
                     )));
                 }
-                AudioBenchmarkEvent::Done { metrics } => {
-                    let summary = AudioBenchmarkResult::calculate_summary(&metrics);
+                AudioBenchmarkEvent::ClipComplete { index, total, outcome } => {
+                    actions.push(AudioAction::SetProgress(format!("Clip {}/{}", index + 1, total)));
+                    actions.push(AudioAction::AppendOutput(Self::dataset_clip_line(index, total, &outcome)));
+                }
+                AudioBenchmarkEvent::Done { metrics, failed } => {
+                    let mut summary = AudioBenchmarkResult::calculate_summary(&metrics);
+                    let dataset_run = metrics.iter().any(|m| m.clip.is_some());
 
                     actions.push(AudioAction::AppendOutput(format!(
                         "\nSummary\n-------\nAvg RTF: {:.3}x ({:.0}x real-time)\nAvg Time: {:.0}ms\nMin/Max RTF: {:.3}/{:.3}\n",
                         summary.avg_rtf, 1.0 / summary.avg_rtf, summary.avg_processing_ms, summary.min_rtf, summary.max_rtf,
                     )));
 
-                    if let Some(first) = metrics.first() {
+                    if dataset_run {
+                        actions.push(AudioAction::AppendOutput(Self::dataset_summary(&metrics, &failed)));
+                    } else if let Some(accuracy) = summary.accuracy {
+                        actions.push(AudioAction::AppendOutput(format!(
+                            "WER: {:.1}% (S={} I={} D={} / {} words)\nCER: {:.1}%\n",
+                            accuracy.wer() * 100.0,
+                            accuracy.substitutions,
+                            accuracy.insertions,
+                            accuracy.deletions,
+                            accuracy.reference_words,
+                            accuracy.cer() * 100.0,
+                        )));
+                    }
+
+                    if let Some(first) = metrics.first().filter(|_| !dataset_run) {
                         actions.push(AudioAction::AppendOutput(format!(
                             "\nTranscription ({} words):\n{}\n",
                             first.word_count, first.transcription
                         )));
                     }
 
-                    let result = AudioBenchmarkResult {
-                        config: AudioBenchmarkConfig {
+                    let config = match dataset_run {
+                        true => self.dataset_config(metrics.len() + failed.len()),
+                        false => AudioBenchmarkConfig {
                             audio_mode: AudioMode::Stt,
                             audio_source: AudioSource::File,
                             model_size: self.whisper_model,
@@ -54,23 +80,28 @@ impl AudioBenchmarkPanel {
                             language: None,
                             iterations: self.iterations,
                             warmup_runs: self.warmup,
+                            reference_text: self.reference(),
                         },
-                        metrics,
-                        summary,
                     };
+                    summary.failed_clips = failed;
+                    let result = AudioBenchmarkResult { config, metrics, summary };
 
                     self.audio_result = Some(result.clone());
                     result_for_history = Some(result);
                     actions.push(AudioAction::SetProgress("Complete".to_string()));
                     self.running = false;
+                    self.dataset_cancel = None;
                     should_clear = true;
+                    batch_outcome = Some(None);
                 }
                 AudioBenchmarkEvent::Error(msg) => {
                     actions.push(AudioAction::AppendOutput(format!("\nError: {}\n", msg)));
-                    actions.push(AudioAction::SetError(Some(msg)));
+                    actions.push(AudioAction::SetError(Some(msg.clone())));
                     actions.push(AudioAction::SetProgress("Error".to_string()));
                     self.running = false;
+                    self.dataset_cancel = None;
                     should_clear = true;
+                    batch_outcome = Some(Some(msg));
                 }
             }
         }
@@ -82,10 +113,16 @@ impl AudioBenchmarkPanel {
         // Build history entry after the loop to avoid borrow issues
         if let Some(result) = result_for_history {
             if let Some(entry) = self.build_audio_history_entry(&result) {
-                actions.push(AudioAction::SaveHistory(entry));
+                actions.push(AudioAction::SaveHistory(Box::new(entry)));
             }
         }
 
+        // Batch runs move on to the next combo once this one finishes
+        let batch_active = self.batch_session_id.is_some();
+        if let Some(error) = batch_outcome.filter(|_| batch_active) {
+            actions.extend(self.finish_stt_combo(error));
+        }
+
         actions
     }
 
@@ -97,16 +134,24 @@ impl AudioBenchmarkPanel {
             return vec![];
         };
 
+        let mut actions = vec![AudioAction::SetError(None), AudioAction::ClearOutput];
+        actions.extend(self.run_stt_file(model, audio_path));
+        actions
+    }
+
+    /// Reference transcript to score against, if one was entered
+    fn reference(&self) -> Option<String> {
+        Some(self.reference_text.trim().to_string()).filter(|r| !r.is_empty())
+    }
+
+    /// Transcribe one file in the background; output is appended, not cleared
+    pub(super) fn run_stt_file(&mut self, model: WhisperModel, audio_path: PathBuf) -> Vec<AudioAction> {
         info!("Starting audio benchmark: {:?}", audio_path);
 
         self.running = true;
         self.audio_result = None;
 
-        let mut actions = vec![
-            AudioAction::SetError(None),
-            AudioAction::ClearOutput,
-            AudioAction::SetProgress("Loading model...".to_string()),
-        ];
+        let mut actions = vec![AudioAction::SetProgress("Loading model...".to_string())];
 
         // Show config in live output
         let model_path = self.whisper_service.model_path(model);
@@ -117,13 +162,18 @@ impl AudioBenchmarkPanel {
              Path: {}\n\
              Audio: {}\n\
              Iterations: {}\n\
-             Warmup: {}\n\n",
+             Warmup: {}\n\
+             Reference: {}\n\n",
             model.label(),
             model.size_mb(),
             model_path.display(),
             audio_path.display(),
             self.iterations,
             self.warmup,
+            match self.reference_text.trim().is_empty() {
+                true => "none (WER not scored)".to_string(),
+                false => format!("{} words", self.reference_text.split_whitespace().count()),
+            },
         )));
 
         // Create channel for async communication
@@ -131,11 +181,15 @@ impl AudioBenchmarkPanel {
         self.audio_rx = Some(rx);
         let iterations = self.iterations;
         let warmup = self.warmup;
+        let reference = self.reference();
 
         // Spawn background thread with stderr capture
+        self.run_environment = None;
         std::thread::spawn(move || {
             use std::io::{BufRead, BufReader};
 
+            let _ = tx.send(AudioBenchmarkEvent::Environment(capture_host_environment()));
+
             // Create pipe to capture stderr
             let (stderr_read, stderr_write) = match os_pipe::pipe() {
                 Ok(p) => p,
@@ -189,7 +243,7 @@ impl AudioBenchmarkPanel {
             ));
 
             let mut service = WhisperService::default();
-            let result = service.run_benchmark(model, &audio_path, iterations, warmup, None);
+            let result = service.run_benchmark(model, &audio_path, iterations, warmup, reference.as_deref(), None);
 
             // Restore stderr
             unsafe {
@@ -209,7 +263,7 @@ impl AudioBenchmarkPanel {
                             metrics: m.clone(),
                         });
                     }
-                    let _ = tx.send(AudioBenchmarkEvent::Done { metrics });
+                    let _ = tx.send(AudioBenchmarkEvent::Done { metrics, failed: Vec::new() });
                 }
                 Err(e) => {
                     let _ = tx.send(AudioBenchmarkEvent::Error(e.to_string()));
@@ -268,7 +322,10 @@ impl AudioBenchmarkPanel {
         self.audio_rx = Some(rx);
         let iterations = self.iterations;
 
+        self.run_environment = None;
         std::thread::spawn(move || {
+            let _ = tx.send(AudioBenchmarkEvent::Environment(capture_host_environment()));
+
             // Step 1: Capture audio
             let _ = tx.send(AudioBenchmarkEvent::Progress(
                 "Recording audio...".to_string(),
@@ -316,6 +373,8 @@ impl AudioBenchmarkPanel {
                             audio_duration_ms,
                             transcription: result.text.clone(),
                             word_count,
+                            accuracy: None,
+                            clip: None,
                         };
 
                         let _ = tx.send(AudioBenchmarkEvent::IterationComplete {
@@ -337,6 +396,7 @@ impl AudioBenchmarkPanel {
 
             let _ = tx.send(AudioBenchmarkEvent::Done {
                 metrics: metrics_vec,
+                failed: Vec::new(),
             });
         });
 
@@ -686,6 +746,8 @@ impl AudioBenchmarkPanel {
             config: result.config.clone(),
             summary: result.summary.clone(),
             metrics: result.metrics.clone(),
+            environment: self.run_environment.clone(),
+            origin: None,
         })
     }
 
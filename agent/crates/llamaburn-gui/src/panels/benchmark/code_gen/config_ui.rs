@@ -3,7 +3,7 @@
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use eframe::egui;
-use llamaburn_services::{Difficulty, Language};
+use llamaburn_services::{CodeTask, Difficulty, GenerationMode, Language, ProblemKind};
 use llamaburn_services::{BatchStatus, Preset};
 
 use super::util::{format_temp_label, format_tokens_label, MAX_TOKENS_BUCKETS, TEMPERATURE_BUCKETS};
@@ -69,6 +69,36 @@ impl CodeGenBenchmarkPanel {
         // Max tokens dropdown
         self.render_max_tokens_dropdown(ui, interactive);
 
+        ui.add_space(3.0);
+
+        // Generation mode dropdown (structured JSON vs free-form chat)
+        let all_modes = GenerationMode::all().to_vec();
+        multi_select_dropdown(
+            ui,
+            "modes_popup",
+            "Modes",
+            &all_modes,
+            &mut self.selected_generation_modes,
+            |m| m.label().to_string(),
+            interactive,
+            200.0,
+        );
+
+        ui.add_space(3.0);
+
+        // Task dropdown (write from scratch, fix a buggy solution, translate one)
+        let all_tasks = CodeTask::all();
+        multi_select_dropdown(
+            ui,
+            "tasks_popup",
+            "Tasks",
+            &all_tasks,
+            &mut self.selected_tasks,
+            |t| t.label(),
+            interactive,
+            200.0,
+        );
+
         ui.add_space(8.0);
         ui.separator();
         ui.add_space(5.0);
@@ -100,6 +130,10 @@ impl CodeGenBenchmarkPanel {
         // Run button and options
         actions.extend(self.render_run_button(ui));
 
+        if let Some(action) = self.render_compare_solutions(ui) {
+            actions.push(action);
+        }
+
         actions
     }
 
@@ -214,7 +248,7 @@ impl CodeGenBenchmarkPanel {
             .unwrap_or(0);
         updated_batch.error_message = None;
         updated_batch.failed_combo = None;
-        actions.push(CodeGenAction::UpdateBatch(updated_batch));
+        actions.push(CodeGenAction::UpdateBatch(Box::new(updated_batch)));
 
         // Remove from pending list
         self.pending_resume_batches.remove(idx);
@@ -525,11 +559,37 @@ impl CodeGenBenchmarkPanel {
                             }
                         }
                     });
+
+                let tags = self.current_tags();
+                if tags.is_empty() {
+                    return;
+                }
+                // Drop a filter the newly chosen set doesn't use
+                if self.tag_filter.as_ref().is_some_and(|t| !tags.contains(t)) {
+                    self.tag_filter = None;
+                }
+                let current_tag = self.tag_filter.as_deref().unwrap_or("All tags");
+                egui::ComboBox::from_id_salt("problem_tag_filter")
+                    .selected_text(current_tag)
+                    .show_ui(ui, |ui| {
+                        if ui
+                            .selectable_label(self.tag_filter.is_none(), "All tags")
+                            .clicked()
+                        {
+                            self.tag_filter = None;
+                        }
+                        for tag in &tags {
+                            let is_current = self.tag_filter.as_ref() == Some(tag);
+                            if ui.selectable_label(is_current, tag).clicked() {
+                                self.tag_filter = Some(tag.clone());
+                            }
+                        }
+                    });
             });
         });
 
         let current_set_ids: Vec<String> = self
-            .current_problems()
+            .visible_problems()
             .iter()
             .map(|p| p.id.clone())
             .collect();
@@ -564,7 +624,7 @@ impl CodeGenBenchmarkPanel {
         });
         ui.add_space(5.0);
 
-        let problems = self.current_problems().to_vec();
+        let problems: Vec<_> = self.visible_problems().into_iter().cloned().collect();
         let problems_height = (ui.available_height() - 50.0).max(80.0);
         egui::ScrollArea::vertical()
             .max_height(problems_height)
@@ -595,6 +655,12 @@ impl CodeGenBenchmarkPanel {
                             format!("[{}]", problem.difficulty.label()),
                         );
                         ui.label(&problem.title);
+                        if problem.problem_kind != ProblemKind::Function {
+                            ui.weak(format!("({})", problem.problem_kind.label()));
+                        }
+                        if !problem.tags.is_empty() {
+                            ui.weak(problem.tags.join(", "));
+                        }
                     });
                 }
             });
@@ -690,16 +756,21 @@ impl CodeGenBenchmarkPanel {
                 && !self.selected_languages.is_empty()
                 && !self.selected_temperatures.is_empty()
                 && !self.selected_max_tokens.is_empty()
-                && !self.selected_problem_ids.is_empty();
+                && !self.selected_generation_modes.is_empty()
+                && !self.selected_tasks.is_empty()
+                && !self.selected_problem_ids.is_empty()
+                && combo_count > 0;
 
             let button_label = format!(
-                "Run {} combo{} ({} x {} x {} x {})",
+                "Run {} combo{} ({} x {} x {} x {} x {} x {})",
                 combo_count,
                 if combo_count == 1 { "" } else { "s" },
                 self.selected_models.len(),
                 self.selected_languages.len(),
                 self.selected_temperatures.len(),
-                self.selected_max_tokens.len()
+                self.selected_max_tokens.len(),
+                self.selected_generation_modes.len(),
+                self.selected_tasks.len()
             );
 
             let green = egui::Color32::from_rgb(34, 139, 34);
@@ -717,11 +788,48 @@ impl CodeGenBenchmarkPanel {
             ui.checkbox(&mut self.auto_run_tests, "Run Tests");
             ui.checkbox(&mut self.skip_on_error, "Skip on Error")
                 .on_hover_text("Skip failed combos and continue (for unattended runs)");
+            ui.add_enabled_ui(self.auto_run_tests, |ui| {
+                ui.label("Repair:");
+                ui.add(egui::DragValue::new(&mut self.repair_attempts).range(0..=5))
+                    .on_hover_text("Follow-up turns feeding compiler/test errors back to the model");
+            });
+            ui.checkbox(&mut self.analyze_code, "Analyze")
+                .on_hover_text("Run installed linters/formatters and complexity metrics on the final code");
         });
 
         actions
     }
 
+    /// Render the solution comparison picker for the last completed matrix
+    fn render_compare_solutions(&mut self, ui: &mut egui::Ui) -> Option<CodeGenAction> {
+        if self.finished_problem_ids.is_empty() {
+            return None;
+        }
+
+        let mut action = None;
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            ui.label("Compare Solutions:");
+            let idx = self.compare_problem_idx.min(self.finished_problem_ids.len() - 1);
+            egui::ComboBox::from_id_salt("compare_solutions_problem")
+                .selected_text(&self.finished_problem_ids[idx])
+                .show_ui(ui, |ui| {
+                    for (i, id) in self.finished_problem_ids.iter().enumerate() {
+                        ui.selectable_value(&mut self.compare_problem_idx, i, id);
+                    }
+                });
+            if ui
+                .button("Open")
+                .on_hover_text("Every stored solution to this problem, across models, in History")
+                .clicked()
+            {
+                action = Some(CodeGenAction::OpenSolutions(self.finished_problem_ids[idx].clone()));
+            }
+        });
+
+        action
+    }
+
     /// Set presets (called after loading from history service)
     pub fn set_presets(&mut self, presets: Vec<Preset>) {
         self.presets = presets;
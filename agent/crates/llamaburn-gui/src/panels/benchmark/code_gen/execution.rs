@@ -27,6 +27,10 @@ impl CodeGenBenchmarkPanel {
             max_tokens: combo.max_tokens,
             warmup_runs: 0,
             run_tests: self.auto_run_tests,
+            max_repair_attempts: self.repair_attempts,
+            generation_mode: combo.generation_mode,
+            analyze_code: self.analyze_code,
+            task: combo.task,
         };
 
         let (tx, rx) = std::sync::mpsc::channel();
@@ -34,6 +38,7 @@ impl CodeGenBenchmarkPanel {
         self.running = true;
         self.code_output.clear();
         self.code_metrics.clear();
+        self.run_environment = None;
         self.code_summary = None;
         self.generated_code.clear();
 
@@ -85,10 +90,11 @@ impl CodeGenBenchmarkPanel {
         self.queue_completed = 0;
         self.batch_session_id = Some(uuid::Uuid::new_v4().to_string());
         self.combo_durations_ms.clear();
+        self.finished_problem_ids.clear();
 
         // Save initial batch state for resume capability
         if let Some(batch) = self.to_batch_state() {
-            actions.push(CodeGenAction::InsertBatch(batch));
+            actions.push(CodeGenAction::InsertBatch(Box::new(batch)));
         }
 
         actions.push(CodeGenAction::AppendOutput(format!(
@@ -119,6 +125,8 @@ impl CodeGenBenchmarkPanel {
             self.queue_total = 0;
             self.queue_completed = 0;
             self.batch_session_id = None;
+            self.finished_problem_ids = self.selected_problem_ids.clone();
+            self.compare_problem_idx = 0;
             return actions;
         };
 
@@ -133,13 +141,15 @@ impl CodeGenBenchmarkPanel {
         actions.push(CodeGenAction::SetSelectedModel(combo.model.clone()));
 
         actions.push(CodeGenAction::AppendOutput(format!(
-            "\n--- Combo {}/{}: {} | {} | T={:.1} | {}tok ---\n",
+            "\n--- Combo {}/{}: {} | {} | T={:.1} | {}tok | {} | {} ---\n",
             self.queue_completed + 1,
             self.queue_total,
             combo.model,
             combo.language.label(),
             combo.temperature,
-            combo.max_tokens.unwrap_or(2048)
+            combo.max_tokens.unwrap_or(2048),
+            combo.generation_mode.label(),
+            combo.task.label()
         )));
 
         if model_changed {
@@ -188,7 +198,7 @@ impl CodeGenBenchmarkPanel {
         // Save state with Paused status
         if let Some(mut batch) = self.to_batch_state() {
             batch.status = BatchStatus::Paused;
-            actions.push(CodeGenAction::UpdateBatch(batch.clone()));
+            actions.push(CodeGenAction::UpdateBatch(Box::new(batch.clone())));
             // Add to pending resume list
             self.pending_resume_batches.push(batch);
         }
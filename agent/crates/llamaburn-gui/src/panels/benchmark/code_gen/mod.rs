@@ -5,7 +5,7 @@ mod error_log;
 mod execution;
 mod polling;
 mod state;
-mod util;
+pub(super) mod util;
 
 use std::collections::VecDeque;
 use std::sync::mpsc::Receiver;
@@ -13,7 +13,8 @@ use std::sync::Arc;
 
 use llamaburn_services::CodeBenchmarkEvent;
 use llamaburn_services::{
-    BenchmarkCombo, CodeBenchmarkMetrics, CodeBenchmarkSummary, CodeProblem, Language, ProblemSet,
+    BenchmarkCombo, CodeBenchmarkMetrics, CodeBenchmarkSummary, CodeProblem, CodeTask,
+    EnvironmentFingerprint, GenerationMode, Language, ProblemSet,
 };
 use llamaburn_services::{BatchState, CodeHistoryEntry, Preset, RunStatus};
 use tokio_util::sync::CancellationToken;
@@ -34,13 +35,13 @@ pub enum CodeGenAction {
     SetError(Option<String>),
 
     // History operations
-    SaveCodeHistory(CodeHistoryEntry),
+    SaveCodeHistory(Box<CodeHistoryEntry>),
     SaveFailedHistory {
         error_message: String,
         status: RunStatus,
     },
-    InsertBatch(BatchState),
-    UpdateBatch(BatchState),
+    InsertBatch(Box<BatchState>),
+    UpdateBatch(Box<BatchState>),
     DeleteBatch(String),
     InsertPreset(Preset),
     DeletePreset(String),
@@ -60,6 +61,10 @@ pub enum CodeGenAction {
     // Cancellation token
     SetCancelToken(Arc<CancellationToken>),
     ClearCancelToken,
+
+    // Navigation
+    /// Open the cross-model solution browser on a problem
+    OpenSolutions(String),
 }
 
 /// Read-only context for rendering config UI
@@ -79,13 +84,19 @@ pub struct CodeGenBenchmarkPanel {
     pub selected_temperatures: Vec<f32>,
     pub custom_temperature: f32,
     pub selected_max_tokens: Vec<u32>,
+    pub selected_generation_modes: Vec<GenerationMode>,
+    pub selected_tasks: Vec<CodeTask>,
 
     // Problem selection
     pub problem_sets: Vec<ProblemSet>,
     pub selected_problem_set_idx: usize,
     pub selected_problem_ids: Vec<String>,
+    /// Only list problems carrying this tag (None = all)
+    pub tag_filter: Option<String>,
     pub auto_run_tests: bool,
     pub skip_on_error: bool,
+    pub repair_attempts: u32,
+    pub analyze_code: bool,
 
     // Resume state
     pub pending_resume_batches: Vec<BatchState>,
@@ -97,6 +108,8 @@ pub struct CodeGenBenchmarkPanel {
     pub current_problem_id: Option<String>,
     pub generated_code: String,
     pub code_metrics: Vec<CodeBenchmarkMetrics>,
+    /// Fingerprint reported at the start of the current run
+    pub run_environment: Option<EnvironmentFingerprint>,
     pub code_summary: Option<CodeBenchmarkSummary>,
     pub code_output: String,
 
@@ -113,6 +126,9 @@ pub struct CodeGenBenchmarkPanel {
     pub queue_total: usize,
     pub queue_completed: usize,
     pub batch_session_id: Option<String>,
+    /// Problems of the last completed matrix, offered for solution comparison
+    pub finished_problem_ids: Vec<String>,
+    pub compare_problem_idx: usize,
 
     // Timing for ETA calculation
     pub combo_start_time: Option<std::time::Instant>,
@@ -139,12 +155,17 @@ impl CodeGenBenchmarkPanel {
             selected_temperatures: vec![0.0],
             custom_temperature: 0.0,
             selected_max_tokens: vec![2048],
+            selected_generation_modes: vec![GenerationMode::Structured],
+            selected_tasks: vec![CodeTask::Generate],
 
             problem_sets: util::load_problem_sets_from_disk(),
             selected_problem_set_idx: 0,
             selected_problem_ids: Vec::new(),
+            tag_filter: None,
             auto_run_tests: true,
             skip_on_error: false,
+            repair_attempts: 0,
+            analyze_code: false,
 
             pending_resume_batches: Vec::new(),
 
@@ -154,6 +175,7 @@ impl CodeGenBenchmarkPanel {
             current_problem_id: None,
             generated_code: String::new(),
             code_metrics: Vec::new(),
+            run_environment: None,
             code_summary: None,
             code_output: String::new(),
 
@@ -167,6 +189,8 @@ impl CodeGenBenchmarkPanel {
             queue_total: 0,
             queue_completed: 0,
             batch_session_id: None,
+            finished_problem_ids: Vec::new(),
+            compare_problem_idx: 0,
 
             combo_start_time: None,
             combo_durations_ms: Vec::new(),
@@ -185,6 +209,29 @@ impl CodeGenBenchmarkPanel {
             .unwrap_or(&[])
     }
 
+    /// Problems in the current set that pass the tag filter
+    pub fn visible_problems(&self) -> Vec<&CodeProblem> {
+        self.current_problems()
+            .iter()
+            .filter(|p| match &self.tag_filter {
+                Some(tag) => p.tags.contains(tag),
+                None => true,
+            })
+            .collect()
+    }
+
+    /// Sorted, de-duplicated tags used by the current set
+    pub fn current_tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = self
+            .current_problems()
+            .iter()
+            .flat_map(|p| p.tags.iter().cloned())
+            .collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+
     pub fn selected_problems(&self) -> Vec<&CodeProblem> {
         self.problem_sets
             .iter()
@@ -4,7 +4,7 @@ use std::time::{SystemTime, UNIX_EPOCH};
 
 use llamaburn_services::CodeBenchmarkEvent;
 use llamaburn_services::{CodeBenchmark, Language};
-use llamaburn_services::{BatchCombo, BatchStatus, RunStatus};
+use llamaburn_services::{BatchStatus, RunStatus};
 
 use super::error_log::ErrorLogEntry;
 use super::util::is_harness_error;
@@ -22,6 +22,9 @@ impl CodeGenBenchmarkPanel {
 
         while let Ok(event) = rx.try_recv() {
             match event {
+                CodeBenchmarkEvent::Environment { fingerprint } => {
+                    self.run_environment = Some(fingerprint);
+                }
                 CodeBenchmarkEvent::Warmup { current, total } => {
                     actions.push(CodeGenAction::SetProgress(format!(
                         "Warmup {}/{}",
@@ -42,6 +45,12 @@ impl CodeGenBenchmarkPanel {
                     model.set_current_problem(Some(title), problem_id);
                     model.clear_generated_code();
                 }
+                CodeBenchmarkEvent::ProblemSkipped { problem_id, reason } => {
+                    actions.push(CodeGenAction::AppendOutput(format!(
+                        "Skipped {}: {}\n",
+                        problem_id, reason
+                    )));
+                }
                 CodeBenchmarkEvent::GeneratingCode => {
                     actions.push(CodeGenAction::AppendOutput("Generating code...\n".into()));
                 }
@@ -49,6 +58,23 @@ impl CodeGenBenchmarkPanel {
                     model.append_generated_code(&content);
                     actions.push(CodeGenAction::AppendOutput(content));
                 }
+                CodeBenchmarkEvent::RepairAttempt { attempt, total } => {
+                    actions.push(CodeGenAction::SetProgress(format!(
+                        "Repair attempt {}/{}",
+                        attempt, total
+                    )));
+                    actions.push(CodeGenAction::AppendOutput(format!(
+                        "\n\n--- Repair attempt {}/{} ---\n",
+                        attempt, total
+                    )));
+                    model.clear_generated_code();
+                }
+                CodeBenchmarkEvent::AnalyzingCode => {
+                    actions.push(CodeGenAction::AppendOutput("\nAnalyzing code quality...".into()));
+                }
+                CodeBenchmarkEvent::TimingReference => {
+                    actions.push(CodeGenAction::AppendOutput("\nTiming reference solution...".into()));
+                }
                 CodeBenchmarkEvent::ExecutingTests { total } => {
                     actions.push(CodeGenAction::AppendOutput(format!(
                         "\nRunning {} tests...",
@@ -87,10 +113,52 @@ impl CodeGenBenchmarkPanel {
                     }
                 }
                 CodeBenchmarkEvent::ProblemComplete { metrics } => {
+                    let attempts = match metrics.attempts.len() > 1 {
+                        true => format!(" after {} attempts", metrics.attempts.len()),
+                        false => String::new(),
+                    };
                     actions.push(CodeGenAction::AppendOutput(format!(
-                        "\n\n--- {} complete: {}/{} tests passed ---\n",
-                        metrics.problem_id, metrics.tests_passed, metrics.tests_total
+                        "\n\n--- {} complete: {}/{} tests passed{} ---\n",
+                        metrics.problem_id, metrics.tests_passed, metrics.tests_total, attempts
                     )));
+                    actions.push(CodeGenAction::AppendOutput(format!(
+                        "TTFT: {:.0}ms | {:.1} tok/s | {} tokens | Gen: {:.0}ms | Tests: {:.0}ms\n",
+                        metrics.ttft_ms,
+                        metrics.tokens_per_sec,
+                        metrics.output_tokens,
+                        metrics.execution_time_ms,
+                        metrics.test_execution_time_ms
+                    )));
+                    if metrics.cpu_time_ms > 0.0 {
+                        let slowdown = match metrics.slowdown {
+                            Some(s) if metrics.is_inefficient() => format!(" | {:.1}x reference (inefficient)", s),
+                            Some(s) => format!(" | {:.1}x reference", s),
+                            None => String::new(),
+                        };
+                        actions.push(CodeGenAction::AppendOutput(format!(
+                            "CPU: {:.0}ms | Peak RSS: {:.1} MB{}\n",
+                            metrics.cpu_time_ms,
+                            metrics.peak_rss_kb as f64 / 1024.0,
+                            slowdown
+                        )));
+                    }
+                    if let Some(ref patch) = metrics.patch {
+                        actions.push(CodeGenAction::AppendOutput(format!(
+                            "Patch: {} | {} files | +{}/-{}\n",
+                            patch.status_label(),
+                            patch.files_changed,
+                            patch.lines_added,
+                            patch.lines_removed
+                        )));
+                    }
+                    if let Some(ref quality) = metrics.quality {
+                        actions.push(CodeGenAction::AppendOutput(format!(
+                            "Lint: {} warnings | LOC: {} | Complexity: {}\n",
+                            quality.lint_warnings,
+                            quality.lines_of_code,
+                            quality.cyclomatic_complexity
+                        )));
+                    }
                     // Write to model
                     model.add_metrics(metrics.clone());
                     // Keep in panel for history building
@@ -104,14 +172,23 @@ impl CodeGenBenchmarkPanel {
 
                     // Build history entry for parent to save
                     if let Some(entry) = self.build_history_entry(&summary) {
-                        actions.push(CodeGenAction::SaveCodeHistory(entry));
+                        actions.push(CodeGenAction::SaveCodeHistory(Box::new(entry)));
                     }
                     actions.push(CodeGenAction::AppendOutput(format!(
-                        "\n=== Benchmark Complete ===\nPass Rate: {:.1}%\nSolved: {}/{}\n",
+                        "\n=== Benchmark Complete ===\nPass Rate: {:.1}%\nSolved: {}\n",
                         summary.pass_rate * 100.0,
-                        summary.problems_solved,
-                        summary.problems_total
+                        summary.solved_label()
                     )));
+                    if summary.pass_at_attempt.len() > 1 {
+                        let curve = summary
+                            .pass_at_attempt
+                            .iter()
+                            .enumerate()
+                            .map(|(k, rate)| format!("pass@{}={:.1}%", k + 1, rate * 100.0))
+                            .collect::<Vec<_>>()
+                            .join("  ");
+                        actions.push(CodeGenAction::AppendOutput(format!("{}\n", curve)));
+                    }
                     should_clear = true;
 
                     // Record combo duration for ETA calculation
@@ -132,7 +209,7 @@ impl CodeGenBenchmarkPanel {
                     } else {
                         // Update batch state for resume capability
                         if let Some(batch) = self.to_batch_state() {
-                            actions.push(CodeGenAction::UpdateBatch(batch));
+                            actions.push(CodeGenAction::UpdateBatch(Box::new(batch)));
                         }
                         actions.push(CodeGenAction::AdvanceToNextCombo);
                     }
@@ -191,13 +268,9 @@ impl CodeGenBenchmarkPanel {
                     if let Some(mut batch) = self.to_batch_state() {
                         batch.status = BatchStatus::Paused;
                         batch.error_message = Some(message);
-                        batch.failed_combo = self.current_combo.as_ref().map(|c| BatchCombo {
-                            model: c.model.clone(),
-                            language: c.language,
-                            temperature: c.temperature,
-                            max_tokens: c.max_tokens.unwrap_or(2048),
-                        });
-                        actions.push(CodeGenAction::UpdateBatch(batch));
+                        batch.failed_combo =
+                            self.current_combo.as_ref().map(|c| c.to_batch_combo());
+                        actions.push(CodeGenAction::UpdateBatch(Box::new(batch)));
                     }
 
                     // Clear queue to stop processing
@@ -272,6 +345,10 @@ impl CodeGenBenchmarkPanel {
             max_tokens: combo.max_tokens,
             warmup_runs: 0,
             run_tests: self.auto_run_tests,
+            max_repair_attempts: self.repair_attempts,
+            generation_mode: combo.generation_mode,
+            analyze_code: self.analyze_code,
+            task: combo.task,
         };
 
         Some(llamaburn_services::CodeHistoryEntry {
@@ -286,6 +363,8 @@ impl CodeGenBenchmarkPanel {
             session_id: self.batch_session_id.clone(),
             status: RunStatus::Success,
             preset_id: self.active_preset_id.clone(),
+            environment: self.run_environment.clone(),
+            origin: None,
         })
     }
 
@@ -310,6 +389,10 @@ impl CodeGenBenchmarkPanel {
             max_tokens: combo.max_tokens,
             warmup_runs: 0,
             run_tests: self.auto_run_tests,
+            max_repair_attempts: self.repair_attempts,
+            generation_mode: combo.generation_mode,
+            analyze_code: self.analyze_code,
+            task: combo.task,
         };
 
         let summary = llamaburn_services::CodeBenchmarkSummary {
@@ -318,12 +401,28 @@ impl CodeGenBenchmarkPanel {
             problems_total: 0,
             avg_tps: 0.0,
             avg_execution_time_ms: 0.0,
+            avg_ttft_ms: 0.0,
+            avg_test_execution_time_ms: 0.0,
+            total_output_tokens: 0,
             easy_solved: 0,
             easy_total: 0,
             medium_solved: 0,
             medium_total: 0,
             hard_solved: 0,
             hard_total: 0,
+            pass_at_attempt: Vec::new(),
+            tag_stats: Default::default(),
+            problems_analyzed: 0,
+            avg_lint_warnings: 0.0,
+            avg_lines_of_code: 0.0,
+            avg_cyclomatic_complexity: 0.0,
+            avg_slowdown: None,
+            problems_inefficient: 0,
+            max_peak_rss_kb: 0,
+            patch_problems: 0,
+            patches_applied: 0,
+            avg_diff_lines: 0.0,
+            skipped_problems: Vec::new(),
         };
 
         Some(llamaburn_services::CodeHistoryEntry {
@@ -338,6 +437,8 @@ impl CodeGenBenchmarkPanel {
             session_id: self.batch_session_id.clone(),
             status,
             preset_id: self.active_preset_id.clone(),
+            environment: self.run_environment.clone(),
+            origin: None,
         })
     }
 }
@@ -2,7 +2,7 @@
 
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use llamaburn_services::Language;
+use llamaburn_services::{BenchmarkType, CodeTask, GenerationMode, Language};
 use llamaburn_services::{BatchCombo, BatchState, BatchStatus, Preset};
 
 use super::{BenchmarkCombo, CodeGenBenchmarkPanel};
@@ -33,12 +33,21 @@ impl CodeGenBenchmarkPanel {
             for lang in &self.selected_languages {
                 for temp in &self.selected_temperatures {
                     for tokens in &self.selected_max_tokens {
-                        combos.push(BenchmarkCombo {
-                            model: model.clone(),
-                            language: *lang,
-                            temperature: *temp,
-                            max_tokens: Some(*tokens),
-                        });
+                        for mode in &self.selected_generation_modes {
+                            for task in &self.selected_tasks {
+                                if *task == (CodeTask::Translate { from: *lang }) {
+                                    continue;
+                                }
+                                combos.push(BenchmarkCombo {
+                                    model: model.clone(),
+                                    language: *lang,
+                                    temperature: *temp,
+                                    max_tokens: Some(*tokens),
+                                    generation_mode: *mode,
+                                    task: *task,
+                                });
+                            }
+                        }
                     }
                 }
             }
@@ -52,7 +61,15 @@ impl CodeGenBenchmarkPanel {
         let langs = self.selected_languages.len().max(1);
         let temps = self.selected_temperatures.len().max(1);
         let tokens = self.selected_max_tokens.len().max(1);
-        models * langs * temps * tokens
+        let modes = self.selected_generation_modes.len().max(1);
+        let tasks = self.selected_tasks.len().max(1);
+        // Translating a language into itself is skipped
+        let self_translations = self
+            .selected_languages
+            .iter()
+            .filter(|&&from| self.selected_tasks.contains(&CodeTask::Translate { from }))
+            .count();
+        models * temps * tokens * modes * (langs * tasks - self_translations)
     }
 
     /// Load params from a history entry
@@ -83,22 +100,13 @@ impl CodeGenBenchmarkPanel {
         let mut pending: Vec<BatchCombo> = self
             .current_combo
             .iter()
-            .map(|c| BatchCombo {
-                model: c.model.clone(),
-                language: c.language,
-                temperature: c.temperature,
-                max_tokens: c.max_tokens.unwrap_or(2048),
-            })
+            .map(|c| c.to_batch_combo())
             .collect();
-        pending.extend(self.combo_queue.iter().map(|c| BatchCombo {
-            model: c.model.clone(),
-            language: c.language,
-            temperature: c.temperature,
-            max_tokens: c.max_tokens.unwrap_or(2048),
-        }));
+        pending.extend(self.combo_queue.iter().map(|c| c.to_batch_combo()));
 
         Some(BatchState {
             session_id,
+            benchmark_type: BenchmarkType::Code,
             created_at: now,
             updated_at: now,
             status: BatchStatus::Running,
@@ -129,13 +137,38 @@ impl CodeGenBenchmarkPanel {
         self.combo_queue = batch
             .pending_combos
             .iter()
-            .map(|c| BenchmarkCombo {
-                model: c.model.clone(),
-                language: c.language,
-                temperature: c.temperature,
-                max_tokens: Some(c.max_tokens),
+            .filter_map(|c| match c {
+                BatchCombo::Code(c) => Some(BenchmarkCombo {
+                    model: c.model.clone(),
+                    language: c.language,
+                    temperature: c.temperature,
+                    max_tokens: Some(c.max_tokens),
+                    generation_mode: c.generation_mode,
+                    task: c.task,
+                }),
+                _ => None,
             })
             .collect();
+
+        // Modes and tasks aren't persisted as a selection; recover them from the queue
+        let mut modes: Vec<GenerationMode> = Vec::new();
+        for combo in &self.combo_queue {
+            if !modes.contains(&combo.generation_mode) {
+                modes.push(combo.generation_mode);
+            }
+        }
+        if !modes.is_empty() {
+            self.selected_generation_modes = modes;
+        }
+        let mut tasks: Vec<CodeTask> = Vec::new();
+        for combo in &self.combo_queue {
+            if !tasks.contains(&combo.task) {
+                tasks.push(combo.task);
+            }
+        }
+        if !tasks.is_empty() {
+            self.selected_tasks = tasks;
+        }
         self.queue_total = batch.queue_total;
         self.queue_completed = batch.queue_completed;
         self.batch_session_id = Some(batch.session_id.clone());
@@ -0,0 +1,73 @@
+//! Incomplete batch sessions list - Resume/Discard for paused or interrupted matrix runs
+
+use eframe::egui;
+use llamaburn_services::{BatchState, BatchStatus};
+
+/// Choice made in the incomplete sessions list
+pub enum SessionChoice {
+    Resume(usize),
+    Discard(usize),
+}
+
+/// Render saved sessions with Resume/Discard buttons.
+/// `describe` summarises a batch's selections in one line.
+pub fn incomplete_sessions<D>(
+    ui: &mut egui::Ui,
+    batches: &[BatchState],
+    describe: D,
+    interactive: bool,
+) -> Option<SessionChoice>
+where
+    D: Fn(&BatchState) -> String,
+{
+    if batches.is_empty() {
+        return None;
+    }
+
+    let mut choice = None;
+
+    egui::Frame::group(ui.style())
+        .inner_margin(egui::vec2(8.0, 6.0))
+        .show(ui, |ui| {
+            ui.label(
+                egui::RichText::new(format!("Incomplete Sessions ({})", batches.len())).strong(),
+            );
+            ui.add_space(4.0);
+
+            for (idx, batch) in batches.iter().enumerate() {
+                ui.separator();
+                ui.add_space(2.0);
+
+                let status_text = match batch.status {
+                    BatchStatus::Paused => "Paused",
+                    BatchStatus::Running => "Interrupted",
+                    BatchStatus::Completed => "Completed",
+                };
+                ui.label(format!(
+                    "{} - {}/{} complete",
+                    status_text, batch.queue_completed, batch.queue_total
+                ));
+                ui.label(egui::RichText::new(describe(batch)).small().weak());
+
+                if let Some(ref error) = batch.error_message {
+                    ui.label(
+                        egui::RichText::new(format!("Error: {}", error))
+                            .small()
+                            .color(egui::Color32::RED),
+                    );
+                }
+
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(interactive, egui::Button::new("Resume")).clicked() {
+                        choice = Some(SessionChoice::Resume(idx));
+                    }
+                    if ui.add_enabled(interactive, egui::Button::new("Discard")).clicked() {
+                        choice = Some(SessionChoice::Discard(idx));
+                    }
+                });
+            }
+        });
+
+    ui.add_space(8.0);
+    choice
+}
@@ -1,8 +1,10 @@
+mod batch_sessions;
 mod model_selector;
 mod multi_select;
 mod transport;
 
 // Widget-based API (preferred)
+pub use batch_sessions::{incomplete_sessions, SessionChoice};
 pub use model_selector::{ModelSelector, ModelSelectorResponse};
 pub use transport::{TransportControls, TransportResponse};
 
@@ -11,11 +11,12 @@ use llamaburn_services::AppModels;
 use tokio_util::sync::CancellationToken;
 use tracing::{info, warn};
 
-use llamaburn_services::{BenchmarkEvent, BenchmarkType, IoServices, OllamaError};
+use llamaburn_services::{BenchmarkType, IoServices, OllamaError};
 
 // Re-export panel types
 pub use audio::AudioBenchmarkPanel;
 pub use code_gen::{CodeGenAction, CodeGenBenchmarkPanel, CodeGenRenderContext};
+pub(crate) use code_gen::util::load_problem_sets_from_disk;
 
 pub struct BenchmarkPanel {
     // =========================================
@@ -23,7 +24,7 @@ pub struct BenchmarkPanel {
     // =========================================
     model_rx: Option<Receiver<Result<Vec<String>, OllamaError>>>,
     model_preload_rx: Option<Receiver<Result<(), OllamaError>>>,
-    text_rx: Option<Receiver<BenchmarkEvent>>,
+    text_run: text::TextRunState,
 
     // Legacy field (async cancellation)
     cancel_token: Option<Arc<CancellationToken>>,
@@ -40,6 +41,8 @@ pub struct BenchmarkPanel {
     config_panel_height: f32,
     live_output_expanded: bool,
     live_output_height: f32,
+    /// Problem to open in the History solution browser (taken by the app)
+    solutions_request: Option<String>,
 }
 
 impl BenchmarkPanel {
@@ -64,21 +67,34 @@ impl BenchmarkPanel {
             vec![]
         });
 
+        let (code_batches, other_batches): (Vec<_>, Vec<_>) = pending_resume_batches
+            .into_iter()
+            .partition(|b| b.benchmark_type == BenchmarkType::Code);
+        let (text_batches, audio_batches): (Vec<_>, Vec<_>) = other_batches
+            .into_iter()
+            .partition(|b| b.benchmark_type == BenchmarkType::Text);
+
         let mut code_panel = CodeGenBenchmarkPanel::new();
-        code_panel.pending_resume_batches = pending_resume_batches;
+        code_panel.pending_resume_batches = code_batches;
         code_panel.set_presets(presets);
 
+        let mut audio_panel = AudioBenchmarkPanel::new();
+        audio_panel.pending_resume_batches = audio_batches;
+
         Self {
             // Async receivers
             model_rx,
             model_preload_rx: None,
-            text_rx: None,
+            text_run: text::TextRunState {
+                pending_resume_batches: text_batches,
+                ..Default::default()
+            },
 
             // Legacy field (async cancellation)
             cancel_token: None,
 
             // Sub-panels
-            audio_panel: AudioBenchmarkPanel::new(),
+            audio_panel,
             code_panel,
 
             // UI state
@@ -87,6 +103,7 @@ impl BenchmarkPanel {
             config_panel_height: 280.0,
             live_output_expanded: true,
             live_output_height: 2000.0,
+            solutions_request: None,
         }
     }
 
@@ -95,6 +112,11 @@ impl BenchmarkPanel {
         self.benchmark_type = bt;
     }
 
+    /// Take the pending solution browser request (clears it)
+    pub fn take_solutions_request(&mut self) -> Option<String> {
+        self.solutions_request.take()
+    }
+
     /// Load code benchmark params from history
     pub fn load_code_from_history(
         &mut self,
@@ -380,6 +402,9 @@ impl BenchmarkPanel {
                     self.code_panel.batch_session_id = None;
                     app_models.code.append_output("\n=== Matrix Benchmark Cancelled ===\n");
                 }
+                CodeGenAction::OpenSolutions(problem_id) => {
+                    self.solutions_request = Some(problem_id);
+                }
             }
         }
     }
@@ -407,6 +432,26 @@ impl BenchmarkPanel {
                         info!("Saved audio benchmark result to history: {}", entry.id);
                     }
                 }
+                audio::AudioAction::SaveEffectDetection(audio_path, result) => {
+                    if let Err(e) = io.history.save_effect_detection(result.tool, &audio_path, &result) {
+                        warn!("Failed to save effect detection history: {}", e);
+                    }
+                }
+                audio::AudioAction::InsertBatch(batch) => {
+                    if let Err(e) = io.history.insert_batch(&batch) {
+                        warn!("Failed to insert batch: {}", e);
+                    }
+                }
+                audio::AudioAction::UpdateBatch(batch) => {
+                    if let Err(e) = io.history.update_batch(&batch) {
+                        warn!("Failed to update batch: {}", e);
+                    }
+                }
+                audio::AudioAction::DeleteBatch(session_id) => {
+                    if let Err(e) = io.history.delete_batch(&session_id) {
+                        warn!("Failed to delete batch: {}", e);
+                    }
+                }
                 audio::AudioAction::RefreshModels => {
                     self.refresh_models(app_models, io);
                 }
@@ -624,7 +669,8 @@ impl BenchmarkPanel {
         ui.horizontal(|ui| {
             for bt in BenchmarkType::all() {
                 let selected = self.benchmark_type == *bt;
-                let running = self.audio_panel.running || self.code_panel.running;
+                let running =
+                    self.audio_panel.running || self.code_panel.running || app_models.text.running;
                 let enabled = bt.is_implemented() && !running;
 
                 let response =
@@ -668,7 +714,7 @@ impl BenchmarkPanel {
                     text,
                     &io.benchmark,
                     models,
-                    &mut self.text_rx,
+                    &mut self.text_run,
                     &mut self.model_preload_rx,
                     &io.ollama,
                     &io.history,
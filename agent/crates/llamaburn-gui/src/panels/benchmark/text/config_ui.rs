@@ -1,25 +1,31 @@
 //! Text benchmark config view - composes shared widgets
 
-use std::sync::mpsc::{Receiver, TryRecvError};
+use std::sync::mpsc::TryRecvError;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use eframe::egui::{self, Widget};
 use tracing::{info, warn};
 
 use llamaburn_services::{
-    BenchmarkEvent, BenchmarkHistoryEntry, BenchmarkService, BenchmarkType,
-    HistoryService, ModelList, OllamaClient, OllamaError, TextBenchmark, TextBenchmarkResult,
+    BatchState, BatchStatus, BenchmarkEvent, BenchmarkHistoryEntry, BenchmarkService,
+    BenchmarkType, HistoryService, ModelList, OllamaClient, OllamaError, TextBenchmark,
+    TextBenchmarkResult,
 };
 
-use crate::panels::benchmark::components::{ModelSelector, TransportControls};
+use super::TextRunState;
+use crate::panels::benchmark::code_gen::util::{MAX_TOKENS_BUCKETS, TEMPERATURE_BUCKETS};
+use crate::panels::benchmark::components::{
+    incomplete_sessions, multi_select_dropdown, toggle_selection, ModelSelector, SessionChoice,
+    TransportControls,
+};
 
 /// Text benchmark configuration view
 pub struct ConfigView<'a> {
     text: &'a mut TextBenchmark,
     service: &'a BenchmarkService,
     model_list: &'a mut ModelList,
-    benchmark_rx: &'a mut Option<Receiver<BenchmarkEvent>>,
-    preload_rx: &'a mut Option<Receiver<Result<(), OllamaError>>>,
+    run: &'a mut TextRunState,
+    preload_rx: &'a mut Option<std::sync::mpsc::Receiver<Result<(), OllamaError>>>,
     ollama: &'a OllamaClient,
     history: &'a HistoryService,
 }
@@ -29,8 +35,8 @@ impl<'a> ConfigView<'a> {
         text: &'a mut TextBenchmark,
         service: &'a BenchmarkService,
         model_list: &'a mut ModelList,
-        benchmark_rx: &'a mut Option<Receiver<BenchmarkEvent>>,
-        preload_rx: &'a mut Option<Receiver<Result<(), OllamaError>>>,
+        run: &'a mut TextRunState,
+        preload_rx: &'a mut Option<std::sync::mpsc::Receiver<Result<(), OllamaError>>>,
         ollama: &'a OllamaClient,
         history: &'a HistoryService,
     ) -> Self {
@@ -38,40 +44,144 @@ impl<'a> ConfigView<'a> {
             text,
             service,
             model_list,
-            benchmark_rx,
+            run,
             preload_rx,
             ollama,
             history,
         }
     }
 
-    /// Start a text benchmark
-    fn start_benchmark(&mut self) {
-        self.text.start(&self.model_list.selected);
+    /// Queue every combination and start the first
+    fn start_matrix(&mut self) {
+        self.text.clear_output();
+        self.text.start_matrix(uuid::Uuid::new_v4().to_string());
+
+        // Save initial batch state for resume capability
+        if let Some(batch) = self.text.to_batch_state(BatchStatus::Running) {
+            if let Err(e) = self.history.insert_batch(&batch) {
+                warn!("Failed to insert batch: {}", e);
+            }
+        }
+
         self.text.append_output(&format!(
-            "Starting text benchmark: {} iterations, {} warmup, temp={:.1}\n",
-            self.text.config.iterations, self.text.config.warmup_runs, self.text.config.temperature
+            "=== Text Matrix: {} combinations, {} iterations, {} warmup ===\n",
+            self.text.queue_total, self.text.config.iterations, self.text.config.warmup_runs
         ));
+        self.advance();
+    }
 
-        let (rx, _cancel_token) = self.service.run_streaming(self.text.config.clone());
-        *self.benchmark_rx = Some(rx);
+    /// Start the next queued combo, or finish the batch when the queue is empty
+    fn advance(&mut self) {
+        let Some(combo) = self.text.advance_to_next() else {
+            if let Some(sid) = &self.text.batch_session_id {
+                if let Err(e) = self.history.delete_batch(sid) {
+                    warn!("Failed to delete batch: {}", e);
+                }
+            }
+            self.text.append_output("\n=== All Combinations Complete ===\n");
+            self.text.clear_matrix();
+            self.text.stop();
+            return;
+        };
+
+        self.text.append_output(&format!(
+            "\n--- Combo {}/{}: {} | {} | T={:.1} | {} ---\n",
+            self.text.queue_completed + 1,
+            self.text.queue_total,
+            combo.model,
+            combo.prompt_set,
+            combo.temperature,
+            format_max_tokens(&combo.max_tokens)
+        ));
+
+        self.text.start();
+        self.run.environment = None;
+        let (rx, cancel_token) = self.service.run_streaming(self.text.config.clone());
+        self.run.rx = Some(rx);
+        self.run.cancel_token = Some(cancel_token);
+    }
+
+    /// Save the batch as paused and stop the running combo
+    fn pause_matrix(&mut self) {
+        if let Some(batch) = self.text.to_batch_state(BatchStatus::Paused) {
+            if let Err(e) = self.history.update_batch(&batch) {
+                warn!("Failed to update batch: {}", e);
+            }
+            self.run.pending_resume_batches.push(batch);
+        }
+        self.stop_run();
+        self.text.append_output("\n=== Matrix Benchmark Paused ===\n");
+    }
+
+    /// Drop the batch and stop the running combo
+    fn cancel_matrix(&mut self) {
+        if let Some(sid) = &self.text.batch_session_id {
+            if let Err(e) = self.history.delete_batch(sid) {
+                warn!("Failed to delete batch: {}", e);
+            }
+        }
+        self.stop_run();
+        self.text.append_output("\n=== Matrix Benchmark Cancelled ===\n");
     }
 
-    /// Cancel text benchmark
-    fn cancel_benchmark(&mut self) {
+    fn stop_run(&mut self) {
+        if let Some(token) = self.run.cancel_token.take() {
+            BenchmarkService::cancel(&token);
+        }
+        self.run.rx = None;
         self.text.stop();
-        *self.benchmark_rx = None;
+        self.text.clear_matrix();
+        self.text.set_progress(String::new());
+    }
+
+    /// Continue a saved batch from its first pending combo
+    fn resume_batch(&mut self, idx: usize) {
+        let batch = self.run.pending_resume_batches.remove(idx);
+        self.text.clear_output();
+        self.text.restore_from_batch(&batch);
+
+        if let Some(updated) = self.text.to_batch_state(BatchStatus::Running) {
+            if let Err(e) = self.history.update_batch(&updated) {
+                warn!("Failed to update batch: {}", e);
+            }
+        }
+
+        self.text.append_output(&format!(
+            "=== Resuming Batch {} ===\n{}/{} combinations remaining\n",
+            batch.session_id,
+            batch.queue_total - batch.queue_completed,
+            batch.queue_total
+        ));
+        self.advance();
+    }
+
+    fn discard_batch(&mut self, idx: usize) {
+        let batch = self.run.pending_resume_batches.remove(idx);
+        if let Err(e) = self.history.delete_batch(&batch.session_id) {
+            warn!("Failed to delete batch: {}", e);
+        }
     }
 
     /// Poll and handle benchmark events
     fn poll_events(&mut self) {
-        let Some(receiver) = self.benchmark_rx.take() else { return };
+        let Some(receiver) = self.run.rx.take() else { return };
 
         loop {
             match receiver.try_recv() {
-                Ok(event) => self.handle_event(event),
+                Ok(event) => {
+                    let finished = matches!(
+                        event,
+                        BenchmarkEvent::Done { .. }
+                            | BenchmarkEvent::Cancelled
+                            | BenchmarkEvent::Error { .. }
+                    );
+                    self.handle_event(event);
+                    if finished {
+                        break;
+                    }
+                }
                 Err(TryRecvError::Empty) => {
-                    *self.benchmark_rx = Some(receiver);
+                    self.run.rx.get_or_insert(receiver);
                     break;
                 }
                 Err(TryRecvError::Disconnected) => {
@@ -85,6 +195,9 @@ impl<'a> ConfigView<'a> {
     /// Handle a single benchmark event
     fn handle_event(&mut self, event: BenchmarkEvent) {
         match event {
+            BenchmarkEvent::Environment { fingerprint } => {
+                self.run.environment = Some(fingerprint);
+            }
             BenchmarkEvent::Warmup { current, total } => {
                 self.text.set_progress(format!("Warmup {}/{}", current, total));
             }
@@ -133,6 +246,8 @@ impl<'a> ConfigView<'a> {
                     config: self.text.config.clone(),
                     summary: summary.clone(),
                     metrics: self.text.collected_metrics.clone(),
+                    environment: self.run.environment.take(),
+                    origin: None,
                 };
 
                 if let Err(e) = self.history.insert(&entry) {
@@ -142,24 +257,156 @@ impl<'a> ConfigView<'a> {
                 }
 
                 self.text.set_result(result);
-                *self.benchmark_rx = None;
+                self.run.cancel_token = None;
                 self.text.set_progress(String::new());
+
+                self.text.complete_current();
+                self.save_progress();
+                self.advance();
             }
             BenchmarkEvent::Cancelled => {
                 self.text.append_output("\n⚠️ Benchmark cancelled\n");
                 self.text.stop();
-                *self.benchmark_rx = None;
+                self.run.cancel_token = None;
                 self.text.set_progress(String::new());
             }
             BenchmarkEvent::Error { message } => {
                 self.text.append_output(&format!("\n❌ Error: {}\n", message));
-                self.text.set_error(Some(message));
                 self.text.stop();
-                *self.benchmark_rx = None;
+                self.run.cancel_token = None;
                 self.text.set_progress(String::new());
+
+                if self.text.skip_on_error {
+                    self.text.append_output("(Skipping to next combo...)\n");
+                    self.text.complete_current();
+                    self.save_progress();
+                    self.advance();
+                    return;
+                }
+
+                // Auto-pause so the failed combo can be retried with Resume
+                self.text.append_output("(Auto-paused - use Resume to continue)\n");
+                if let Some(mut batch) = self.text.to_batch_state(BatchStatus::Paused) {
+                    batch.error_message = Some(message.clone());
+                    batch.failed_combo = batch.pending_combos.first().cloned();
+                    if let Err(e) = self.history.update_batch(&batch) {
+                        warn!("Failed to update batch: {}", e);
+                    }
+                    self.run.pending_resume_batches.push(batch);
+                }
+                self.text.clear_matrix();
+                self.text.set_error(Some(message));
             }
         }
     }
+
+    /// Persist the remaining queue after a combo finishes
+    fn save_progress(&mut self) {
+        if self.text.combo_queue.is_empty() {
+            return;
+        }
+        // The finished combo is no longer pending
+        self.text.current_combo = None;
+        if let Some(batch) = self.text.to_batch_state(BatchStatus::Running) {
+            if let Err(e) = self.history.update_batch(&batch) {
+                warn!("Failed to update batch: {}", e);
+            }
+        }
+    }
+
+    fn render_matrix_selection(&mut self, ui: &mut egui::Ui, disabled: bool) {
+        let models = self.model_list.models.clone();
+        multi_select_dropdown(
+            ui,
+            "text_models_popup",
+            "Models",
+            &models,
+            &mut self.text.selected_models,
+            |m| m.clone(),
+            !disabled,
+            200.0,
+        );
+
+        let prompt_sets: Vec<String> = BenchmarkService::prompt_sets()
+            .into_iter()
+            .map(String::from)
+            .collect();
+        multi_select_dropdown(
+            ui,
+            "text_prompt_sets_popup",
+            "Prompt Sets",
+            &prompt_sets,
+            &mut self.text.selected_prompt_sets,
+            |s| s.clone(),
+            !disabled,
+            150.0,
+        );
+
+        multi_select_dropdown(
+            ui,
+            "text_temps_popup",
+            "Temp",
+            TEMPERATURE_BUCKETS,
+            &mut self.text.selected_temperatures,
+            |t| format!("{:.1}", t),
+            !disabled,
+            100.0,
+        );
+
+        let token_limits: Vec<Option<u32>> = std::iter::once(None)
+            .chain(MAX_TOKENS_BUCKETS.iter().map(|&t| Some(t)))
+            .collect();
+        multi_select_dropdown(
+            ui,
+            "text_tokens_popup",
+            "Tokens",
+            &token_limits,
+            &mut self.text.selected_max_tokens,
+            format_max_tokens,
+            !disabled,
+            100.0,
+        );
+    }
+
+    fn render_running_controls(&mut self, ui: &mut egui::Ui) {
+        let completed = self.text.queue_completed;
+        let total = self.text.queue_total;
+        if total > 0 {
+            let progress = (completed as f32 + 0.5) / total as f32;
+            ui.horizontal(|ui| {
+                ui.add(egui::ProgressBar::new(progress.min(1.0)).show_percentage());
+                ui.label(format!("{} of {} executed", completed, total));
+            });
+        }
+
+        ui.horizontal(|ui| {
+            if ui.button("Pause").clicked() {
+                self.pause_matrix();
+            }
+            if ui.button("Cancel").clicked() {
+                self.cancel_matrix();
+            }
+            ui.spinner();
+        });
+    }
+}
+
+/// Token limit label, None meaning no limit
+fn format_max_tokens(max_tokens: &Option<u32>) -> String {
+    match max_tokens {
+        Some(t) => format!("{}tok", t),
+        None => "No limit".to_string(),
+    }
+}
+
+/// One-line summary of a saved text batch
+fn describe_batch(batch: &BatchState) -> String {
+    format!(
+        "{} models x {} temps, {} combos",
+        batch.selected_models.len(),
+        batch.selected_temperatures.len(),
+        batch.queue_total
+    )
 }
 
 impl Widget for ConfigView<'_> {
@@ -167,11 +414,23 @@ impl Widget for ConfigView<'_> {
         let response = ui.vertical(|ui| {
             let disabled = self.text.running || self.model_list.loading;
 
+            let choice = incomplete_sessions(
+                ui,
+                &self.run.pending_resume_batches,
+                describe_batch,
+                !self.text.running,
+            );
+            match choice {
+                Some(SessionChoice::Resume(idx)) => self.resume_batch(idx),
+                Some(SessionChoice::Discard(idx)) => self.discard_batch(idx),
+                None => {}
+            }
+
             egui::Grid::new("text_config_grid")
                 .num_columns(2)
                 .spacing([10.0, 8.0])
                 .show(ui, |ui| {
-                    // Model selector
+                    // Model selector - preloads the model and adds it to the matrix
                     ui.label("Model:");
                     let selector_resp = ModelSelector::new(self.model_list, "text_model_select")
                         .disabled(disabled)
@@ -179,6 +438,7 @@ impl Widget for ConfigView<'_> {
 
                     if let Some(model_name) = selector_resp.selected {
                         self.model_list.select(model_name.clone());
+                        toggle_selection(&mut self.text.selected_models, model_name.clone(), true);
                         *self.preload_rx = Some(self.ollama.preload_model_async(&model_name));
                         self.model_list.start_preload(&model_name);
                         self.text
@@ -208,33 +468,31 @@ impl Widget for ConfigView<'_> {
                         egui::DragValue::new(&mut self.text.config.warmup_runs).range(0..=10),
                     );
                     ui.end_row();
-
-                    // Temperature
-                    ui.label("Temperature:");
-                    ui.add_enabled(
-                        !disabled,
-                        egui::DragValue::new(&mut self.text.config.temperature)
-                            .range(0.0..=2.0)
-                            .speed(0.1),
-                    );
-                    ui.end_row();
                 });
 
+            ui.add_space(8.0);
+            self.render_matrix_selection(ui, disabled);
             ui.add_space(10.0);
 
             // Transport controls
-            let can_run = !self.text.running
-                && !self.model_list.loading
-                && !self.model_list.selected.is_empty();
-
-            let transport_resp = TransportControls::new(self.text.running, can_run).show(ui);
+            if self.text.batch_session_id.is_some() {
+                self.render_running_controls(ui);
+            } else {
+                let combo_count = self.text.generate_combos().len();
+                let can_run = !self.text.running && !self.model_list.loading && combo_count > 0;
 
-            if transport_resp.run_clicked {
-                self.start_benchmark();
-            }
-            if transport_resp.cancel_clicked {
-                self.cancel_benchmark();
+                let transport_resp = TransportControls::new(self.text.running, can_run).show(ui);
+                if transport_resp.run_clicked {
+                    self.start_matrix();
+                }
+                ui.label(
+                    egui::RichText::new(format!("{} combinations", combo_count))
+                        .small()
+                        .weak(),
+                );
             }
+            ui.checkbox(&mut self.text.skip_on_error, "Skip on Error")
+                .on_hover_text("Skip failed combos and continue (for unattended runs)");
 
             // Poll for benchmark events
             self.poll_events();
@@ -4,4 +4,21 @@
 
 mod config_ui;
 
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+
+use llamaburn_services::{BatchState, BenchmarkEvent, EnvironmentFingerprint};
+use tokio_util::sync::CancellationToken;
+
 pub use config_ui::ConfigView;
+
+/// Run handles and saved sessions of the text matrix, owned by the parent panel
+/// so they outlive the per-frame view
+#[derive(Default)]
+pub struct TextRunState {
+    pub rx: Option<Receiver<BenchmarkEvent>>,
+    pub cancel_token: Option<Arc<CancellationToken>>,
+    pub pending_resume_batches: Vec<BatchState>,
+    /// Fingerprint reported at the start of the running combo
+    pub environment: Option<EnvironmentFingerprint>,
+}
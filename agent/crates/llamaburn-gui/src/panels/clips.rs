@@ -0,0 +1,296 @@
+//! Per-clip drill-down of an STT run: corpus statistics, outliers, and each clip's
+//! transcript diffed word by word against its reference
+
+use eframe::egui;
+use llamaburn_services::{dataset_stats, line_diff, normalize_transcript, DiffLine, OutlierKind};
+use llamaburn_services::{AudioBenchmarkMetrics, AudioHistoryEntry};
+use std::collections::HashSet;
+
+/// Order of the clip table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum ClipOrder {
+    #[default]
+    Manifest,
+    Wer,
+    Rtf,
+}
+
+impl ClipOrder {
+    fn label(&self) -> &'static str {
+        match self {
+            ClipOrder::Manifest => "Manifest order",
+            ClipOrder::Wer => "WER (worst first)",
+            ClipOrder::Rtf => "RTF (slowest first)",
+        }
+    }
+
+    fn all() -> &'static [ClipOrder] {
+        &[ClipOrder::Manifest, ClipOrder::Wer, ClipOrder::Rtf]
+    }
+}
+
+#[derive(Default)]
+pub struct ClipsView {
+    /// Run the view state belongs to; reset when another run is opened
+    run_id: String,
+    order: ClipOrder,
+    outliers_only: bool,
+    /// Clip whose transcript is shown, by index into the run's metrics
+    selected: Option<usize>,
+}
+
+impl ClipsView {
+    pub fn ui(&mut self, ui: &mut egui::Ui, entry: &AudioHistoryEntry) {
+        if self.run_id != entry.id {
+            *self = Self {
+                run_id: entry.id.clone(),
+                ..Default::default()
+            };
+        }
+
+        let dataset_run = entry.metrics.iter().any(|m| m.clip.is_some());
+        let source = entry
+            .config
+            .audio_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        ui.heading(format!("{} · {}", entry.model_id, source));
+        ui.weak(format!(
+            "{} {}{}",
+            entry.metrics.len(),
+            match dataset_run {
+                true => "clips",
+                false => "iterations",
+            },
+            match entry.summary.failed_clips.len() {
+                0 => String::new(),
+                n => format!(", {} failed", n),
+            }
+        ));
+        ui.add_space(8.0);
+
+        let failed = &entry.summary.failed_clips;
+        let Some(stats) = dataset_stats(&entry.metrics, failed.len()) else {
+            ui.weak("No iterations stored for this run");
+            return;
+        };
+
+        egui::Grid::new("clip_stats").num_columns(2).striped(true).show(ui, |ui| {
+            ui.label("Audio:");
+            ui.label(format!(
+                "{:.1} min in {:.1} s (RTF {:.3}x)",
+                stats.audio_ms / 60_000.0,
+                stats.processing_ms / 1000.0,
+                stats.overall_rtf()
+            ));
+            ui.end_row();
+
+            let rtf = stats.rtf;
+            ui.label("RTF min/p50/p90/p99/max:");
+            ui.label(format!(
+                "{:.3} / {:.3} / {:.3} / {:.3} / {:.3}",
+                rtf.min, rtf.p50, rtf.p90, rtf.p99, rtf.max
+            ));
+            ui.end_row();
+
+            let Some(a) = stats.accuracy else {
+                return;
+            };
+            ui.label("Corpus WER:");
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "{:.2}% (S={} I={} D={} / {} words, {} of {} scored)",
+                    a.wer() * 100.0,
+                    a.substitutions,
+                    a.insertions,
+                    a.deletions,
+                    a.reference_words,
+                    stats.scored,
+                    stats.clips + stats.failed
+                ));
+                if stats.failed > 0 {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(255, 180, 100),
+                        format!("⚠ {} failed", stats.failed),
+                    )
+                    .on_hover_text("Clips that couldn't be transcribed aren't counted in the WER");
+                }
+            });
+            ui.end_row();
+
+            ui.label("Corpus CER:");
+            ui.label(format!("{:.2}%", a.cer() * 100.0));
+            ui.end_row();
+        });
+
+        if !failed.is_empty() {
+            ui.add_space(8.0);
+            ui.collapsing(format!("Failed clips ({})", failed.len()), |ui| {
+                for f in failed {
+                    let name = f
+                        .clip
+                        .audio_path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_else(|| f.clip.audio_path.display().to_string());
+                    ui.horizontal(|ui| {
+                        ui.label(name).on_hover_text(f.clip.audio_path.display().to_string());
+                        ui.weak(&f.error);
+                    });
+                }
+            });
+        }
+
+        let outliers: HashSet<usize> = stats.outliers.iter().map(|o| o.index).collect();
+        if !stats.outliers.is_empty() {
+            ui.add_space(8.0);
+            ui.collapsing(format!("Outliers ({})", stats.outliers.len()), |ui| {
+                for outlier in &stats.outliers {
+                    let value = match outlier.kind {
+                        OutlierKind::Wer => format!("{:.1}%", outlier.value * 100.0),
+                        OutlierKind::Rtf => format!("{:.3}x", outlier.value),
+                    };
+                    let text = format!(
+                        "{} {}  {}",
+                        outlier.kind.label(),
+                        value,
+                        clip_label(&entry.metrics[outlier.index], outlier.index)
+                    );
+                    if ui.selectable_label(self.selected == Some(outlier.index), text).clicked() {
+                        self.selected = Some(outlier.index);
+                    }
+                }
+            });
+        }
+
+        ui.add_space(8.0);
+        ui.horizontal(|ui| {
+            ui.label("Sort:");
+            egui::ComboBox::from_id_salt("clip_order")
+                .selected_text(self.order.label())
+                .show_ui(ui, |ui| {
+                    for order in ClipOrder::all() {
+                        ui.selectable_value(&mut self.order, *order, order.label());
+                    }
+                });
+            ui.add_enabled(
+                !outliers.is_empty(),
+                egui::Checkbox::new(&mut self.outliers_only, "Outliers only"),
+            );
+        });
+
+        let mut rows: Vec<usize> = (0..entry.metrics.len())
+            .filter(|i| !self.outliers_only || outliers.contains(i))
+            .collect();
+        let wer = |i: &usize| entry.metrics[*i].accuracy.map_or(-1.0, |a| a.wer());
+        let rtf = |i: &usize| entry.metrics[*i].real_time_factor;
+        match self.order {
+            ClipOrder::Manifest => {}
+            ClipOrder::Wer => rows.sort_by(|a, b| wer(b).total_cmp(&wer(a))),
+            ClipOrder::Rtf => rows.sort_by(|a, b| rtf(b).total_cmp(&rtf(a))),
+        }
+
+        egui::ScrollArea::vertical()
+            .id_salt("clip_table")
+            .max_height(320.0)
+            .show(ui, |ui| {
+                egui::Grid::new("clip_table_grid").num_columns(7).striped(true).show(ui, |ui| {
+                    for header in ["#", "Clip", "Speaker", "Duration", "RTF", "WER", "S/I/D"] {
+                        ui.strong(header);
+                    }
+                    ui.end_row();
+
+                    for &i in &rows {
+                        let m = &entry.metrics[i];
+                        ui.label(format!("{}", i + 1));
+                        let name = clip_label(m, i);
+                        let name = match outliers.contains(&i) {
+                            true => egui::RichText::new(name).color(egui::Color32::from_rgb(255, 165, 0)),
+                            false => egui::RichText::new(name),
+                        };
+                        if ui.selectable_label(self.selected == Some(i), name).clicked() {
+                            self.selected = Some(i);
+                        }
+                        ui.label(m.clip.as_ref().and_then(|c| c.speaker.clone()).unwrap_or_else(|| "—".to_string()));
+                        ui.label(format!("{:.1}s", m.audio_duration_ms / 1000.0));
+                        ui.label(format!("{:.3}x", m.real_time_factor));
+                        match m.accuracy {
+                            Some(a) => {
+                                ui.label(format!("{:.1}%", a.wer() * 100.0));
+                                ui.label(format!("{}/{}/{}", a.substitutions, a.insertions, a.deletions));
+                            }
+                            None => {
+                                ui.weak("—");
+                                ui.weak("—");
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+
+        let Some(index) = self.selected else {
+            return;
+        };
+        let Some(m) = entry.metrics.get(index) else {
+            return;
+        };
+        ui.separator();
+        ui.strong(clip_label(m, index));
+        if let Some(clip) = &m.clip {
+            ui.weak(clip.audio_path.display().to_string());
+        }
+        let reference = match &m.clip {
+            Some(clip) => Some(clip.reference.as_str()),
+            None => entry.config.reference_text.as_deref(),
+        }
+        .filter(|r| !r.trim().is_empty());
+        let Some(reference) = reference else {
+            ui.label(&m.transcription);
+            ui.weak("No reference transcript");
+            return;
+        };
+        render_word_diff(ui, reference, &m.transcription);
+    }
+}
+
+/// Clip file name, or the iteration number for single-file runs
+fn clip_label(metrics: &AudioBenchmarkMetrics, index: usize) -> String {
+    metrics
+        .clip
+        .as_ref()
+        .and_then(|c| c.audio_path.file_name())
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| format!("Iteration {}", index + 1))
+}
+
+/// Reference and transcription, then the normalized words with deletions struck through
+/// and insertions in green
+fn render_word_diff(ui: &mut egui::Ui, reference: &str, transcription: &str) {
+    egui::Grid::new("clip_transcripts").num_columns(2).show(ui, |ui| {
+        ui.label("Reference:");
+        ui.label(reference);
+        ui.end_row();
+        ui.label("Transcription:");
+        ui.label(transcription);
+        ui.end_row();
+    });
+
+    let words = |text: &str| normalize_transcript(text).split(' ').collect::<Vec<_>>().join("\n");
+    let diff = line_diff(&words(reference), &words(transcription));
+    ui.add_space(4.0);
+    ui.horizontal_wrapped(|ui| {
+        ui.spacing_mut().item_spacing.x = 4.0;
+        for word in &diff {
+            let text = match word {
+                DiffLine::Same(w) => egui::RichText::new(w),
+                DiffLine::Removed(w) => egui::RichText::new(w)
+                    .strikethrough()
+                    .color(egui::Color32::from_rgb(255, 100, 100)),
+                DiffLine::Added(w) => egui::RichText::new(w).color(egui::Color32::GREEN),
+            };
+            ui.label(text.monospace());
+        }
+    });
+}
@@ -1,9 +1,34 @@
 use eframe::egui;
-use llamaburn_services::{BenchmarkType, Language};
+use llamaburn_services::{BenchmarkType, CodeTask, GenerationMode, Language, RunStatus, WhisperModel};
 use llamaburn_services::{AudioHistoryEntry, BenchmarkHistoryEntry, CodeHistoryEntry, HistoryFilter, HistoryService};
+use llamaburn_services::{EnvironmentFingerprint, HistoryCursor, HistoryRecord, HistorySort, RegressionReport};
+use llamaburn_services::{render_report, render_session_report, BundleContents, ReportFormat, RunOrigin};
+use llamaburn_services::{AudioMode, AudioSource, SearchHit, SearchSource};
+use llamaburn_services::CodeLeaderboard;
 use sha2::{Sha256, Digest};
 use std::collections::{HashMap, HashSet};
+use std::sync::mpsc::{self, Receiver};
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::clips::ClipsView;
+use super::solutions::SolutionsView;
+use super::trends::TrendsView;
+
+/// Rows fetched per page; "Load More" fetches the next page
+const PAGE_SIZE: u32 = 100;
+
+/// Most full-text matches shown
+const SEARCH_LIMIT: u32 = 200;
+
+/// Date range choices: label and window in seconds before now
+const DATE_RANGES: &[(&str, Option<i64>)] = &[
+    ("Any time", None),
+    ("Last 24 hours", Some(86_400)),
+    ("Last 7 days", Some(7 * 86_400)),
+    ("Last 30 days", Some(30 * 86_400)),
+    ("Last 90 days", Some(90 * 86_400)),
+];
 
 /// Request to load benchmark params from history
 #[derive(Clone)]
@@ -23,6 +48,26 @@ pub enum HistoryEntry {
     Code(CodeHistoryEntry),
 }
 
+impl From<HistoryRecord> for HistoryEntry {
+    fn from(record: HistoryRecord) -> Self {
+        match record {
+            HistoryRecord::Text(e) => HistoryEntry::Text(e),
+            HistoryRecord::Audio(e) => HistoryEntry::Audio(e),
+            HistoryRecord::Code(e) => HistoryEntry::Code(e),
+        }
+    }
+}
+
+impl From<HistoryEntry> for HistoryRecord {
+    fn from(entry: HistoryEntry) -> Self {
+        match entry {
+            HistoryEntry::Text(e) => HistoryRecord::Text(e),
+            HistoryEntry::Audio(e) => HistoryRecord::Audio(e),
+            HistoryEntry::Code(e) => HistoryRecord::Code(e),
+        }
+    }
+}
+
 impl HistoryEntry {
     pub fn id(&self) -> &str {
         match self {
@@ -56,6 +101,23 @@ impl HistoryEntry {
         }
     }
 
+    /// Environment captured with the run (None for runs recorded before capture existed)
+    pub fn environment(&self) -> Option<&EnvironmentFingerprint> {
+        match self {
+            HistoryEntry::Text(e) => e.environment.as_ref(),
+            HistoryEntry::Audio(e) => e.environment.as_ref(),
+            HistoryEntry::Code(e) => e.environment.as_ref(),
+        }
+    }
+
+    pub fn origin(&self) -> Option<&RunOrigin> {
+        match self {
+            HistoryEntry::Text(e) => e.origin.as_ref(),
+            HistoryEntry::Audio(e) => e.origin.as_ref(),
+            HistoryEntry::Code(e) => e.origin.as_ref(),
+        }
+    }
+
     pub fn metric_1(&self) -> String {
         match self {
             HistoryEntry::Text(e) => format!("{:.1}", e.summary.avg_tps),
@@ -141,7 +203,108 @@ impl HistoryEntry {
             return "—".to_string();
         };
         let tokens = e.config.max_tokens.map(|t| t.to_string()).unwrap_or("—".to_string());
-        format!("{} T={:.1} {}tok", e.language.label(), e.config.temperature, tokens)
+        let repair = match e.config.max_repair_attempts {
+            0 => String::new(),
+            n => format!(" R{}", n),
+        };
+        let mode = match e.config.generation_mode {
+            GenerationMode::Structured => "",
+            GenerationMode::FreeForm => " FF",
+        };
+        let task = match e.config.task {
+            CodeTask::Generate => String::new(),
+            CodeTask::BugFix => " Fix".to_string(),
+            CodeTask::Translate { from } => format!(" from {}", from.label()),
+        };
+        format!(
+            "{} T={:.1} {}tok{}{}{}",
+            e.language.label(),
+            e.config.temperature,
+            tokens,
+            repair,
+            mode,
+            task
+        )
+    }
+
+    /// Pass@attempt curve for self-repair runs, e.g. "pass@1=40.0% pass@2=55.0%"
+    pub fn pass_at_attempt_display(&self) -> Option<String> {
+        let HistoryEntry::Code(e) = self else {
+            return None;
+        };
+        if e.summary.pass_at_attempt.len() < 2 {
+            return None;
+        }
+        let curve = e
+            .summary
+            .pass_at_attempt
+            .iter()
+            .enumerate()
+            .map(|(k, rate)| format!("pass@{}={:.1}%", k + 1, rate * 100.0))
+            .collect::<Vec<_>>()
+            .join(" ");
+        Some(curve)
+    }
+
+    /// Per-tag solved/total, e.g. "dp 1/2, strings 3/3"
+    pub fn tag_breakdown_display(&self) -> Option<String> {
+        let HistoryEntry::Code(e) = self else {
+            return None;
+        };
+        if e.summary.tag_stats.is_empty() {
+            return None;
+        }
+        let breakdown = e
+            .summary
+            .tag_stats
+            .iter()
+            .map(|(tag, stats)| format!("{} {}/{}", tag, stats.solved, stats.total))
+            .collect::<Vec<_>>()
+            .join(", ");
+        Some(breakdown)
+    }
+
+    /// Code-quality averages, e.g. "lint 1.5 | LOC 12.0 | CC 3.2"
+    pub fn quality_display(&self) -> Option<String> {
+        let HistoryEntry::Code(e) = self else {
+            return None;
+        };
+        if e.summary.problems_analyzed == 0 {
+            return None;
+        }
+        Some(format!(
+            "lint {:.1} | LOC {:.1} | CC {:.1}",
+            e.summary.avg_lint_warnings,
+            e.summary.avg_lines_of_code,
+            e.summary.avg_cyclomatic_complexity
+        ))
+    }
+
+    /// Slowdown vs reference solutions, e.g. "2.3x ref (1 inefficient)"
+    pub fn slowdown_display(&self) -> Option<String> {
+        let HistoryEntry::Code(e) = self else {
+            return None;
+        };
+        let slowdown = e.summary.avg_slowdown?;
+        let flagged = match e.summary.problems_inefficient {
+            0 => String::new(),
+            n => format!(" ({} inefficient)", n),
+        };
+        Some(format!("{:.1}x ref{}", slowdown, flagged))
+    }
+
+    /// Repo-patch results, e.g. "patches 2/3 applied | diff 14.5 lines"
+    pub fn patch_display(&self) -> Option<String> {
+        let HistoryEntry::Code(e) = self else {
+            return None;
+        };
+        if e.summary.patch_problems == 0 {
+            return None;
+        }
+        Some(format!(
+            "patches {}/{} applied | diff {:.1} lines",
+            e.summary.patches_applied, e.summary.patch_problems, e.summary.avg_diff_lines
+        ))
     }
 
     /// Run status (Success, Error, Paused, Cancelled)
@@ -152,14 +315,14 @@ impl HistoryEntry {
         e.status.as_str()
     }
 
-    /// Get list of failed problem IDs (problems where tests_passed < tests_total)
+    /// Get list of failed problem IDs (problems that didn't pass every test)
     pub fn failed_problems(&self) -> Vec<String> {
         let HistoryEntry::Code(e) = self else {
             return Vec::new();
         };
         e.metrics
             .iter()
-            .filter(|m| m.tests_passed < m.tests_total)
+            .filter(|m| !m.solved())
             .map(|m| m.problem_id.clone())
             .collect()
     }
@@ -201,13 +364,46 @@ impl HistoryEntry {
 pub struct HistoryPanel {
     history_service: Arc<HistoryService>,
     entries: Vec<HistoryEntry>,
-    filter_type: Option<BenchmarkType>,
+    filter: HistoryFilter,
+    /// Window for `filter.since`, resolved against the clock on refresh
+    date_range: Option<i64>,
+    /// Search box text, applied to `filter.search` on Enter
+    search_text: String,
+    /// Full-text query over generated code, transcripts and effect descriptions
+    content_query: String,
+    /// Matches for `content_query`; `Some` while the results view is open
+    search_hits: Option<Vec<SearchHit>>,
+    /// Cursor for "Load More"; None once the last page is loaded
+    next_cursor: Option<HistoryCursor>,
+    /// Loaded runs whose stored JSON doesn't parse, by id
+    unreadable_ids: Vec<String>,
+    models_cache: Vec<String>,
+    tags_cache: Vec<String>,
     needs_refresh: bool,
     delete_confirm: Option<String>,
     selected_ids: HashSet<String>,
     show_comparison: bool,
+    show_leaderboard: bool,
+    leaderboard_language: Language,
+    leaderboard: CodeLeaderboard,
+    /// Entry being drilled into: per-test results of a code run, per-clip results of an
+    /// audio run
+    detail_id: Option<String>,
+    /// Sort and selection of the audio drill-down
+    clips: ClipsView,
+    /// Cross-model solution browser; `Some` while open
+    solutions: Option<SolutionsView>,
+    /// Metric-over-time charts; `Some` while open
+    trends: Option<TrendsView>,
     pub load_request: Option<LoadCodeBenchmarkRequest>,
     presets_cache: HashMap<String, String>, // preset_id -> preset_name
+    /// Runs pinned as the baseline of their config
+    baseline_ids: HashSet<String>,
+    /// Baseline comparisons of the loaded runs, by run id
+    regression_reports: HashMap<String, RegressionReport>,
+    /// Outcome of a running bundle export/import or report export
+    file_rx: Option<Receiver<Result<String, String>>>,
+    file_status: Option<Result<String, String>>,
 }
 
 impl HistoryPanel {
@@ -215,13 +411,32 @@ impl HistoryPanel {
         Self {
             history_service,
             entries: Vec::new(),
-            filter_type: None,
+            filter: HistoryFilter::default(),
+            date_range: None,
+            search_text: String::new(),
+            content_query: String::new(),
+            search_hits: None,
+            next_cursor: None,
+            unreadable_ids: Vec::new(),
+            models_cache: Vec::new(),
+            tags_cache: Vec::new(),
             needs_refresh: true,
             delete_confirm: None,
             selected_ids: HashSet::new(),
             show_comparison: false,
+            show_leaderboard: false,
+            leaderboard_language: Language::Python,
+            leaderboard: CodeLeaderboard::default(),
+            detail_id: None,
+            clips: ClipsView::default(),
+            solutions: None,
+            trends: None,
             load_request: None,
             presets_cache: HashMap::new(),
+            baseline_ids: HashSet::new(),
+            regression_reports: HashMap::new(),
+            file_rx: None,
+            file_status: None,
         }
     }
 
@@ -230,43 +445,29 @@ impl HistoryPanel {
         self.load_request.take()
     }
 
-    fn refresh(&mut self) {
-        let limit = Some(100);
-        let mut entries: Vec<HistoryEntry> = Vec::new();
-
-        // Load based on filter
-        let load_text = self.filter_type.is_none() || self.filter_type == Some(BenchmarkType::Text);
-        let load_audio = self.filter_type.is_none() || self.filter_type == Some(BenchmarkType::Audio);
-        let load_code = self.filter_type.is_none() || self.filter_type == Some(BenchmarkType::Code);
-
-        if load_text {
-            let filter = HistoryFilter {
-                benchmark_type: Some(BenchmarkType::Text),
-                limit,
-                ..Default::default()
-            };
-            if let Ok(text_entries) = self.history_service.list(filter) {
-                entries.extend(text_entries.into_iter().map(HistoryEntry::Text));
-            }
-        }
-
-        if load_audio {
-            if let Ok(audio_entries) = self.history_service.list_audio(limit) {
-                entries.extend(audio_entries.into_iter().map(HistoryEntry::Audio));
-            }
-        }
-
-        if load_code {
-            if let Ok(code_entries) = self.history_service.list_code(limit) {
-                entries.extend(code_entries.into_iter().map(HistoryEntry::Code));
-            }
-        }
+    /// Open the solution browser on a problem
+    pub fn open_solutions(&mut self, problem_id: String) {
+        self.solutions = Some(SolutionsView::new(self.history_service.clone(), Some(problem_id)));
+    }
 
-        // Sort by timestamp descending
-        entries.sort_by(|a, b| b.timestamp().cmp(&a.timestamp()));
+    fn refresh(&mut self) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64;
+        self.filter.since = self.date_range.map(|window| now - window);
+        self.filter.search = match self.search_text.trim() {
+            "" => None,
+            text => Some(text.to_string()),
+        };
+        self.filter.limit = Some(PAGE_SIZE);
 
-        // Limit total entries
-        entries.truncate(100);
+        self.entries.clear();
+        self.regression_reports.clear();
+        self.next_cursor = None;
+        self.unreadable_ids.clear();
+        self.load_page();
+        self.baseline_ids = self.history_service.baseline_ids().unwrap_or_default();
 
         // Refresh presets cache
         self.presets_cache.clear();
@@ -276,11 +477,99 @@ impl HistoryPanel {
             }
         }
 
-        self.entries = entries;
+        // Filter choices
+        self.models_cache = self.history_service.history_models().unwrap_or_default();
+        self.tags_cache = self.history_service.history_tags().unwrap_or_default();
+
+        if self.show_leaderboard {
+            self.refresh_leaderboard();
+        }
         self.needs_refresh = false;
     }
 
+    /// Append the page after `next_cursor` (the first page when it's None)
+    fn load_page(&mut self) {
+        let filter = HistoryFilter {
+            cursor: self.next_cursor.take(),
+            ..self.filter.clone()
+        };
+        let page = match self.history_service.list_page(&filter) {
+            Ok(page) => page,
+            Err(e) => {
+                tracing::warn!("Failed to load history: {}", e);
+                return;
+            }
+        };
+
+        let ids: Vec<String> = page.entries.iter().map(|e| e.id().to_string()).collect();
+        match self.history_service.regression_reports(&ids) {
+            Ok(reports) => self.regression_reports.extend(reports),
+            Err(e) => tracing::warn!("Failed to load baseline comparisons: {}", e),
+        }
+        self.entries.extend(page.entries.into_iter().map(HistoryEntry::from));
+        self.unreadable_ids.extend(page.unreadable);
+        self.next_cursor = page.next_cursor;
+    }
+
+    /// "baseline" for pinned runs, else the outcome of the run's baseline comparison
+    fn baseline_status(&self, id: &str) -> &'static str {
+        if self.baseline_ids.contains(id) {
+            return "baseline";
+        }
+        match self.regression_reports.get(id).map(|r| r.regressed) {
+            Some(true) => "regressed",
+            Some(false) => "ok",
+            None => "",
+        }
+    }
+
+    fn toggle_baseline(&mut self, id: &str) {
+        let result = match self.baseline_ids.contains(id) {
+            true => self.history_service.unpin_baseline(id),
+            false => self.history_service.pin_baseline(id),
+        };
+        if let Err(e) = result {
+            tracing::warn!("Failed to update baseline: {}", e);
+        }
+        self.baseline_ids = self.history_service.baseline_ids().unwrap_or_default();
+    }
+
+    fn filters_active(&self) -> bool {
+        let f = &self.filter;
+        f.model_id.is_some()
+            || f.benchmark_type.is_some()
+            || f.language.is_some()
+            || f.status.is_some()
+            || f.preset_id.is_some()
+            || f.session_id.is_some()
+            || f.whisper_model.is_some()
+            || !f.tags.is_empty()
+            || f.search.is_some()
+            || self.date_range.is_some()
+    }
+
+    fn clear_filters(&mut self) {
+        self.filter = HistoryFilter {
+            sort: self.filter.sort,
+            ..Default::default()
+        };
+        self.date_range = None;
+        self.search_text.clear();
+        self.needs_refresh = true;
+    }
+
+    fn refresh_leaderboard(&mut self) {
+        self.leaderboard = self
+            .history_service
+            .get_code_tag_leaderboard(self.leaderboard_language, 50)
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to load code leaderboard: {}", e);
+                CodeLeaderboard::default()
+            });
+    }
+
     pub fn ui(&mut self, ui: &mut egui::Ui) {
+        self.poll_file_task();
         if self.needs_refresh {
             self.refresh();
         }
@@ -293,12 +582,41 @@ impl HistoryPanel {
         ui.add_space(10.0);
 
         self.render_filters(ui);
+        match &self.file_status {
+            Some(Ok(msg)) => {
+                ui.label(egui::RichText::new(msg).color(egui::Color32::GREEN));
+            }
+            Some(Err(e)) => {
+                ui.label(egui::RichText::new(e).color(egui::Color32::from_rgb(255, 100, 100)));
+            }
+            None => {}
+        }
         ui.add_space(10.0);
 
+        if let Some(view) = &mut self.solutions {
+            view.ui(ui);
+            return;
+        }
+        if let Some(view) = &mut self.trends {
+            view.ui(ui);
+            return;
+        }
+        if self.search_hits.is_some() {
+            self.render_search_results(ui);
+            return;
+        }
         if self.show_comparison {
             self.render_comparison(ui);
             return;
         }
+        if self.show_leaderboard {
+            self.render_leaderboard(ui);
+            return;
+        }
+        if self.detail_id.is_some() {
+            self.render_detail(ui);
+            return;
+        }
         self.render_table(ui);
     }
 
@@ -306,29 +624,16 @@ impl HistoryPanel {
         ui.horizontal(|ui| {
             ui.label("Filter by type:");
 
-            let selected_label = self
-                .filter_type
-                .map(|t| t.label())
-                .unwrap_or("All");
-
-            egui::ComboBox::from_id_salt("history_type_filter")
-                .selected_text(selected_label)
-                .show_ui(ui, |ui| {
-                    if ui
-                        .selectable_value(&mut self.filter_type, None, "All")
-                        .changed()
-                    {
-                        self.needs_refresh = true;
-                    }
-                    for bt in BenchmarkType::all() {
-                        if ui
-                            .selectable_value(&mut self.filter_type, Some(*bt), bt.label())
-                            .changed()
-                        {
-                            self.needs_refresh = true;
-                        }
-                    }
-                });
+            if option_combo(
+                ui,
+                "history_type_filter",
+                "All",
+                &mut self.filter.benchmark_type,
+                BenchmarkType::all().iter().copied(),
+                |t| t.label().to_string(),
+            ) {
+                self.needs_refresh = true;
+            }
 
             ui.add_space(20.0);
 
@@ -345,10 +650,21 @@ impl HistoryPanel {
             let selected_count = self.selected_ids.len();
             let can_compare = selected_count >= 2;
 
-            match self.show_comparison {
+            let sub_view = self.show_comparison
+                || self.show_leaderboard
+                || self.detail_id.is_some()
+                || self.search_hits.is_some()
+                || self.solutions.is_some()
+                || self.trends.is_some();
+            match sub_view {
                 true => {
                     if ui.button("← Back to List").clicked() {
                         self.show_comparison = false;
+                        self.show_leaderboard = false;
+                        self.detail_id = None;
+                        self.search_hits = None;
+                        self.solutions = None;
+                        self.trends = None;
                     }
                 }
                 false => {
@@ -359,6 +675,27 @@ impl HistoryPanel {
                         self.show_comparison = true;
                     }
 
+                    if ui.button("Leaderboard").clicked() {
+                        self.show_leaderboard = true;
+                        self.refresh_leaderboard();
+                    }
+
+                    if ui
+                        .button("Solutions")
+                        .on_hover_text("Compare generated code for a problem across models")
+                        .clicked()
+                    {
+                        self.solutions = Some(SolutionsView::new(self.history_service.clone(), None));
+                    }
+
+                    if ui
+                        .button("Trends")
+                        .on_hover_text("TPS, TTFT, pass rate and RTF per model over time")
+                        .clicked()
+                    {
+                        self.trends = Some(TrendsView::new(self.history_service.clone()));
+                    }
+
                     if selected_count > 0 && ui.button(format!("Delete Selected ({})", selected_count)).clicked() {
                         self.delete_confirm = Some("__selected__".to_string());
                     }
@@ -366,10 +703,51 @@ impl HistoryPanel {
                     if !self.entries.is_empty() && ui.button("Export CSV").clicked() {
                         self.export_csv();
                     }
+
+                    let busy = self.file_rx.is_some();
+                    let export_label = match selected_count {
+                        0 => "Export Bundle".to_string(),
+                        n => format!("Export Bundle ({})", n),
+                    };
+                    if ui
+                        .add_enabled(!busy, egui::Button::new(export_label))
+                        .on_hover_text("Runs (selected, or all), presets, batches and effect detections as JSON")
+                        .clicked()
+                    {
+                        self.export_bundle();
+                    }
+                    if ui
+                        .add_enabled(!busy, egui::Button::new("Import Bundle"))
+                        .on_hover_text("Merge a bundle from another machine; runs already here are skipped")
+                        .clicked()
+                    {
+                        self.import_bundle();
+                    }
+
+                    let report_label = match (selected_count, &self.filter.session_id) {
+                        (0, Some(_)) => "Report (session)".to_string(),
+                        (0, None) => "Report".to_string(),
+                        (n, _) => format!("Report ({})", n),
+                    };
+                    ui.add_enabled_ui(!busy && !self.entries.is_empty(), |ui| {
+                        ui.menu_button(report_label, |ui| {
+                            for format in ReportFormat::all() {
+                                if ui.button(format!("{}…", format.label())).clicked() {
+                                    self.export_report(*format);
+                                    ui.close_menu();
+                                }
+                            }
+                        })
+                        .response
+                        .on_hover_text("Summary tables, charts, breakdowns, failures, environments and configs of the selected runs, the filtered session, or all loaded runs");
+                    });
                 }
             }
         });
 
+        self.render_query_filters(ui);
+        self.render_content_search(ui);
+
         // Delete confirmation dialog
         if let Some(ref id) = self.delete_confirm.clone() {
             egui::Window::new("Confirm Delete")
@@ -419,24 +797,274 @@ impl HistoryPanel {
         }
     }
 
+    /// Date, model, language, status, whisper model, preset, tag, search and sort controls
+    fn render_query_filters(&mut self, ui: &mut egui::Ui) {
+        let mut changed = false;
+
+        ui.horizontal_wrapped(|ui| {
+            let range_label = DATE_RANGES
+                .iter()
+                .find(|(_, window)| *window == self.date_range)
+                .map(|(label, _)| *label)
+                .unwrap_or("Any time");
+            egui::ComboBox::from_id_salt("history_date_filter")
+                .selected_text(range_label)
+                .show_ui(ui, |ui| {
+                    for (label, window) in DATE_RANGES {
+                        changed |= ui.selectable_value(&mut self.date_range, *window, *label).changed();
+                    }
+                });
+
+            changed |= option_combo(
+                ui,
+                "history_model_filter",
+                "All models",
+                &mut self.filter.model_id,
+                self.models_cache.iter().cloned(),
+                |m| m.clone(),
+            );
+            changed |= option_combo(
+                ui,
+                "history_language_filter",
+                "All languages",
+                &mut self.filter.language,
+                Language::all().iter().copied(),
+                |l| l.label().to_string(),
+            );
+            changed |= option_combo(
+                ui,
+                "history_status_filter",
+                "Any status",
+                &mut self.filter.status,
+                RunStatus::all().iter().copied(),
+                |s| s.as_str().to_string(),
+            );
+            changed |= option_combo(
+                ui,
+                "history_whisper_filter",
+                "All whisper models",
+                &mut self.filter.whisper_model,
+                WhisperModel::all().iter().copied(),
+                |m| m.label().to_string(),
+            );
+
+            let mut presets: Vec<(&String, &String)> = self.presets_cache.iter().collect();
+            presets.sort_by(|a, b| a.1.cmp(b.1));
+            let preset_label = self
+                .filter
+                .preset_id
+                .as_ref()
+                .and_then(|id| self.presets_cache.get(id))
+                .map(|name| name.as_str())
+                .unwrap_or("All presets");
+            egui::ComboBox::from_id_salt("history_preset_filter")
+                .selected_text(preset_label)
+                .show_ui(ui, |ui| {
+                    changed |= ui.selectable_value(&mut self.filter.preset_id, None, "All presets").changed();
+                    for (id, name) in presets {
+                        changed |= ui
+                            .selectable_value(&mut self.filter.preset_id, Some(id.clone()), name.as_str())
+                            .changed();
+                    }
+                });
+
+            let tags_label = match self.filter.tags.len() {
+                0 => "Tags".to_string(),
+                n => format!("Tags ({})", n),
+            };
+            ui.menu_button(tags_label, |ui| {
+                if self.tags_cache.is_empty() {
+                    ui.weak("No tagged code runs");
+                }
+                for tag in &self.tags_cache {
+                    let mut checked = self.filter.tags.contains(tag);
+                    if ui.checkbox(&mut checked, tag.as_str()).changed() {
+                        match checked {
+                            true => self.filter.tags.push(tag.clone()),
+                            false => self.filter.tags.retain(|t| t != tag),
+                        }
+                        changed = true;
+                    }
+                }
+            })
+            .response
+            .on_hover_text("Code runs covering every checked tag");
+
+            let search = ui.add(
+                egui::TextEdit::singleline(&mut self.search_text)
+                    .hint_text("Search models, problems, transcripts...")
+                    .desired_width(220.0),
+            );
+            let search_text = self.search_text.trim();
+            if search.lost_focus() && self.filter.search.as_deref().unwrap_or("") != search_text {
+                changed = true;
+            }
+
+            ui.label("Sort:");
+            egui::ComboBox::from_id_salt("history_sort")
+                .selected_text(self.filter.sort.label())
+                .show_ui(ui, |ui| {
+                    for sort in HistorySort::all() {
+                        changed |= ui.selectable_value(&mut self.filter.sort, *sort, sort.label()).changed();
+                    }
+                });
+
+            if let Some(session_id) = self.filter.session_id.clone() {
+                let short: String = session_id.chars().take(6).collect();
+                if ui
+                    .button(format!("Session {} ✕", short))
+                    .on_hover_text("Show all sessions")
+                    .clicked()
+                {
+                    self.filter.session_id = None;
+                    changed = true;
+                }
+            }
+
+            if self.filters_active() && ui.button("Clear Filters").clicked() {
+                self.clear_filters();
+            }
+        });
+
+        if changed {
+            self.needs_refresh = true;
+        }
+    }
+
+    /// Full-text search box; Enter or "Search" opens the results view
+    fn render_content_search(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Content:");
+            let edit = ui.add(
+                egui::TextEdit::singleline(&mut self.content_query)
+                    .hint_text("Search generated code, transcripts, effect descriptions...")
+                    .desired_width(360.0),
+            );
+            let submitted = edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            let has_query = !self.content_query.trim().is_empty();
+            let clicked = ui.add_enabled(has_query, egui::Button::new("Search")).clicked();
+            if (submitted || clicked) && has_query {
+                self.run_content_search();
+            }
+        });
+    }
+
+    fn run_content_search(&mut self) {
+        match self.history_service.search_text(&self.content_query, SEARCH_LIMIT) {
+            Ok(hits) => {
+                self.search_hits = Some(hits);
+                self.show_comparison = false;
+                self.show_leaderboard = false;
+                self.detail_id = None;
+                self.solutions = None;
+                self.trends = None;
+            }
+            Err(e) => tracing::warn!("Full-text search failed: {}", e),
+        }
+    }
+
+    fn render_search_results(&mut self, ui: &mut egui::Ui) {
+        let Some(hits) = &self.search_hits else {
+            return;
+        };
+        ui.label(
+            egui::RichText::new(match hits.len() {
+                n if n as u32 >= SEARCH_LIMIT => format!("First {} matches for \"{}\"", n, self.content_query.trim()),
+                n => format!("{} matches for \"{}\"", n, self.content_query.trim()),
+            })
+            .heading()
+            .color(egui::Color32::GRAY),
+        );
+        ui.add_space(10.0);
+
+        let mut open_detail = None;
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                if hits.is_empty() {
+                    ui.weak("No matches. Every word must appear; punctuation inside a word (collections.Counter) is matched literally.");
+                }
+                for hit in hits {
+                    ui.horizontal(|ui| {
+                        ui.label(egui::RichText::new(hit.source.label()).strong());
+                        ui.label(hit.model_id.as_str());
+                        ui.label(hit.label.as_str());
+                        ui.weak(format_timestamp(hit.timestamp));
+                        let run_id = hit
+                            .run_id
+                            .as_deref()
+                            .filter(|_| matches!(hit.source, SearchSource::GeneratedCode | SearchSource::Transcription));
+                        if let Some(run_id) = run_id {
+                            if ui.small_button("Details").clicked() {
+                                open_detail = Some(run_id.to_string());
+                            }
+                        }
+                    });
+                    ui.label(snippet_layout(ui, hit));
+                    ui.separator();
+                }
+            });
+
+        // The detail view reads the loaded page, so fetch the run when it isn't on it
+        if let Some(run_id) = open_detail {
+            if !self.entries.iter().any(|e| e.id() == run_id) {
+                match self.history_service.get_record(&run_id) {
+                    Ok(Some(record)) => self.entries.push(record.into()),
+                    Ok(None) => {}
+                    Err(e) => tracing::warn!("Failed to load {}: {}", run_id, e),
+                }
+            }
+            self.search_hits = None;
+            self.detail_id = Some(run_id);
+        }
+    }
+
     fn render_table(&mut self, ui: &mut egui::Ui) {
+        if !self.unreadable_ids.is_empty() {
+            ui.colored_label(
+                egui::Color32::from_rgb(255, 180, 100),
+                format!("⚠ {} run(s) hidden: stored data unreadable", self.unreadable_ids.len()),
+            )
+            .on_hover_text(self.unreadable_ids.join("\n"));
+        }
+
         if self.entries.is_empty() {
-            ui.label("No benchmark history yet. Run some benchmarks!");
+            match self.filters_active() {
+                true => ui.label("No runs match the current filters"),
+                false => ui.label("No benchmark history yet. Run some benchmarks!"),
+            };
             return;
         }
 
-        ui.label(format!("{} entries", self.entries.len()));
+        let more = match self.next_cursor.is_some() {
+            true => "+",
+            false => "",
+        };
+        ui.horizontal(|ui| {
+            ui.label(format!("{}{} entries", self.entries.len(), more));
+            let regressed = self.regression_reports.values().filter(|r| r.regressed).count();
+            if regressed > 0 {
+                ui.label(
+                    egui::RichText::new(format!("⚠ {} regressed against their baseline", regressed))
+                        .color(egui::Color32::from_rgb(255, 100, 100)),
+                );
+            }
+        });
         ui.add_space(5.0);
 
         let mut toggle_id: Option<String> = None;
+        let mut session_filter: Option<String> = None;
+        let mut load_more = false;
         let mut delete_id: Option<String> = None;
         let mut load_entry: Option<LoadCodeBenchmarkRequest> = None;
+        let mut detail_id: Option<String> = None;
+        let mut pin_id: Option<String> = None;
 
         egui::ScrollArea::vertical()
             .auto_shrink([false, false])
             .show(ui, |ui| {
                 egui::Grid::new("history_table")
-                    .num_columns(20)
+                    .num_columns(23)
                     .spacing([10.0, 6.0])
                     .striped(true)
                     .show(ui, |ui| {
@@ -455,10 +1083,13 @@ impl HistoryPanel {
                         ui.label(egui::RichText::new("Failed").strong());
                         ui.label(egui::RichText::new("Preset").strong());
                         ui.label(egui::RichText::new("Sig").strong());
+                        ui.label(egui::RichText::new("Baseline").strong());
                         ui.label(egui::RichText::new("Session").strong());
                         ui.label(egui::RichText::new("Status").strong());
                         ui.label(egui::RichText::new("Date").strong());
+                        ui.label(egui::RichText::new("").strong()); // Details
                         ui.label(egui::RichText::new("").strong()); // Load
+                        ui.label(egui::RichText::new("").strong()); // Pin
                         ui.label(egui::RichText::new("").strong()); // Delete
                         ui.label(egui::RichText::new("").strong()); // padding
                         ui.end_row();
@@ -471,7 +1102,19 @@ impl HistoryPanel {
                                 toggle_id = Some(entry_id.clone());
                             }
 
-                            ui.label(entry.model_id());
+                            let model_response = ui.label(entry.model_id());
+                            let model_hover: Vec<String> = [
+                                entry.environment().map(|env| env.summary()),
+                                entry.origin().map(|o| {
+                                    format!("Imported from {} ({})", o.host, format_timestamp(o.imported_at))
+                                }),
+                            ]
+                            .into_iter()
+                            .flatten()
+                            .collect();
+                            if !model_hover.is_empty() {
+                                model_response.on_hover_text(model_hover.join("\n"));
+                            }
                             ui.label(entry.benchmark_type().label());
                             ui.label(entry.code_params());
 
@@ -493,12 +1136,19 @@ impl HistoryPanel {
                                     format!("{:.3}x", e.summary.avg_rtf),
                                     e.summary.iterations.to_string(),
                                     String::new(),
-                                    format!("{:.3}/{:.3}", e.summary.min_rtf, e.summary.max_rtf),
+                                    match e.summary.accuracy {
+                                        Some(a) => format!("{:.3}/{:.3} WER {:.1}%",
+                                            e.summary.min_rtf, e.summary.max_rtf, a.wer() * 100.0),
+                                        None => format!("{:.3}/{:.3}", e.summary.min_rtf, e.summary.max_rtf),
+                                    },
                                 ),
                                 HistoryEntry::Code(e) => (
                                     format!("{:.1}", e.summary.avg_tps),
                                     format!("{:.1}%", e.summary.pass_rate * 100.0),
-                                    String::new(),
+                                    match e.summary.avg_ttft_ms > 0.0 {
+                                        true => format!("{:.0}ms", e.summary.avg_ttft_ms),
+                                        false => String::new(),
+                                    },
                                     String::new(),
                                     String::new(),
                                     format!("{:.0}ms", e.summary.avg_execution_time_ms),
@@ -509,12 +1159,44 @@ impl HistoryPanel {
                                 ),
                             };
                             ui.label(tps);
-                            ui.label(pass);
+                            let pass_response = ui.label(pass);
+                            if let Some(curve) = entry.pass_at_attempt_display() {
+                                pass_response.on_hover_text(curve);
+                            }
                             ui.label(ttft);
                             ui.label(rtf);
                             ui.label(runs);
-                            ui.label(exec);
-                            ui.label(detail);
+                            let exec_response = ui.label(exec);
+                            if let HistoryEntry::Code(e) = entry {
+                                if e.summary.avg_test_execution_time_ms > 0.0 {
+                                    let resources = match e.summary.max_peak_rss_kb > 0 {
+                                        true => format!(
+                                            "\nPeak RSS: {:.1} MB",
+                                            e.summary.max_peak_rss_kb as f64 / 1024.0
+                                        ),
+                                        false => String::new(),
+                                    };
+                                    exec_response.on_hover_text(format!(
+                                        "Generation: {:.0}ms | Tests: {:.0}ms{}",
+                                        e.summary.avg_execution_time_ms,
+                                        e.summary.avg_test_execution_time_ms,
+                                        resources
+                                    ));
+                                }
+                            }
+                            let detail_response = ui.label(detail);
+                            let detail_hover: Vec<String> = [
+                                entry.tag_breakdown_display(),
+                                entry.quality_display(),
+                                entry.slowdown_display(),
+                                entry.patch_display(),
+                            ]
+                            .into_iter()
+                            .flatten()
+                            .collect();
+                            if !detail_hover.is_empty() {
+                                detail_response.on_hover_text(detail_hover.join("\n"));
+                            }
 
                             // Failed problems column (red text)
                             let failed = entry.failed_problems();
@@ -539,7 +1221,41 @@ impl HistoryPanel {
                             let sig = entry.result_signature().unwrap_or_else(|| "—".to_string());
                             ui.label(egui::RichText::new(&sig).monospace());
 
-                            ui.label(entry.session_display());
+                            // Baseline column: pinned marker or the comparison with the pinned run
+                            let report = self.regression_reports.get(&entry_id);
+                            let (baseline_text, baseline_color) = match self.baseline_status(&entry_id) {
+                                "baseline" => ("📌 baseline".to_string(), egui::Color32::LIGHT_BLUE),
+                                "regressed" => (
+                                    format!("⚠ {} regressed", report.map_or(0, |r| r.regressions().count())),
+                                    egui::Color32::from_rgb(255, 100, 100),
+                                ),
+                                "ok" => ("✓ ok".to_string(), egui::Color32::GREEN),
+                                _ => ("—".to_string(), ui.visuals().text_color()),
+                            };
+                            let baseline_response =
+                                ui.label(egui::RichText::new(baseline_text).color(baseline_color));
+                            if let Some(report) = report {
+                                baseline_response.on_hover_text(format!(
+                                    "vs baseline {}\n{}",
+                                    &report.baseline_id[..8.min(report.baseline_id.len())],
+                                    report.describe()
+                                ));
+                            }
+
+                            match entry {
+                                HistoryEntry::Code(CodeHistoryEntry { session_id: Some(sid), .. }) => {
+                                    if ui
+                                        .link(entry.session_display())
+                                        .on_hover_text("Show only this session")
+                                        .clicked()
+                                    {
+                                        session_filter = Some(sid.clone());
+                                    }
+                                }
+                                _ => {
+                                    ui.label(entry.session_display());
+                                }
+                            }
                             let status = entry.status();
                             let status_color = match status {
                                 "error" => egui::Color32::from_rgb(255, 100, 100),
@@ -551,19 +1267,44 @@ impl HistoryPanel {
                             ui.label(egui::RichText::new(status).color(status_color));
                             ui.label(format_timestamp(entry.timestamp()));
 
-                            // Load button (only for Code entries)
-                            if let HistoryEntry::Code(code_entry) = entry {
-                                if ui.small_button("📋").on_hover_text("Load params").clicked() {
-                                    load_entry = Some(LoadCodeBenchmarkRequest {
-                                        model_id: code_entry.model_id.clone(),
-                                        language: code_entry.language,
-                                        temperature: code_entry.config.temperature,
-                                        max_tokens: code_entry.config.max_tokens,
-                                        problem_ids: code_entry.config.problem_ids.clone(),
-                                    });
+                            // Details for Code and STT runs, Load only for Code runs
+                            match entry {
+                                HistoryEntry::Code(code_entry) => {
+                                    if ui.small_button("🔍").on_hover_text("Test details").clicked() {
+                                        detail_id = Some(entry_id.clone());
+                                    }
+                                    if ui.small_button("📋").on_hover_text("Load params").clicked() {
+                                        load_entry = Some(LoadCodeBenchmarkRequest {
+                                            model_id: code_entry.model_id.clone(),
+                                            language: code_entry.language,
+                                            temperature: code_entry.config.temperature,
+                                            max_tokens: code_entry.config.max_tokens,
+                                            problem_ids: code_entry.config.problem_ids.clone(),
+                                        });
+                                    }
+                                }
+                                HistoryEntry::Audio(audio_entry) if audio_entry.audio_mode == AudioMode::Stt => {
+                                    let hover = match audio_entry.config.audio_source {
+                                        AudioSource::Dataset => "Clip details",
+                                        _ => "Iteration details",
+                                    };
+                                    if ui.small_button("🔍").on_hover_text(hover).clicked() {
+                                        detail_id = Some(entry_id.clone());
+                                    }
+                                    ui.label("");
+                                }
+                                _ => {
+                                    ui.label("");
+                                    ui.label("");
                                 }
-                            } else {
-                                ui.label("");
+                            }
+
+                            let pin_hover = match self.baseline_ids.contains(&entry_id) {
+                                true => "Unpin baseline",
+                                false => "Pin as baseline: later runs of this config are compared with it",
+                            };
+                            if ui.small_button("📌").on_hover_text(pin_hover).clicked() {
+                                pin_id = Some(entry_id.clone());
                             }
 
                             if ui.small_button("🗑").clicked() {
@@ -573,8 +1314,28 @@ impl HistoryPanel {
                             ui.end_row();
                         }
                     });
+
+                if self.next_cursor.is_some() {
+                    ui.add_space(5.0);
+                    if ui.button("Load More").clicked() {
+                        load_more = true;
+                    }
+                }
             });
 
+        if load_more {
+            self.load_page();
+        }
+
+        if let Some(id) = pin_id {
+            self.toggle_baseline(&id);
+        }
+
+        if let Some(sid) = session_filter {
+            self.filter.session_id = Some(sid);
+            self.needs_refresh = true;
+        }
+
         // Handle toggle outside the borrow
         if let Some(id) = toggle_id {
             let was_present = self.selected_ids.remove(&id);
@@ -590,75 +1351,463 @@ impl HistoryPanel {
         if load_entry.is_some() {
             self.load_request = load_entry;
         }
+
+        if detail_id.is_some() {
+            self.detail_id = detail_id;
+        }
+    }
+
+    fn render_detail(&mut self, ui: &mut egui::Ui) {
+        let found = self
+            .entries
+            .iter()
+            .find(|e| Some(e.id()) == self.detail_id.as_deref());
+        let Some(found) = found else {
+            ui.label("Entry no longer exists");
+            return;
+        };
+        let entry = match found {
+            HistoryEntry::Code(entry) => entry,
+            HistoryEntry::Audio(entry) => {
+                self.clips.ui(ui, entry);
+                return;
+            }
+            _ => return,
+        };
+
+        ui.label(
+            egui::RichText::new(format!(
+                "{} — {} ({:.1}% pass)",
+                entry.model_id,
+                found.code_params(),
+                entry.summary.pass_rate * 100.0
+            ))
+            .heading()
+            .color(egui::Color32::GRAY),
+        );
+        ui.label(format_timestamp(entry.timestamp));
+        ui.add_space(10.0);
+
+        let fail_color = egui::Color32::from_rgb(255, 100, 100);
+        let mut compare_problem: Option<String> = None;
+
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                for metric in &entry.metrics {
+                    let solved = metric.solved();
+                    let color = match solved {
+                        true => egui::Color32::GREEN,
+                        false => fail_color,
+                    };
+                    let slowdown = match metric.slowdown {
+                        Some(s) if metric.is_inefficient() => format!("  {:.1}x reference ⚠", s),
+                        Some(s) => format!("  {:.1}x reference", s),
+                        None => String::new(),
+                    };
+                    let header = egui::RichText::new(format!(
+                        "{}  {}/{}{}",
+                        metric.problem_id, metric.tests_passed, metric.tests_total, slowdown
+                    ))
+                    .color(color);
+
+                    egui::CollapsingHeader::new(header)
+                        .id_salt(("history_detail", &metric.problem_id))
+                        .default_open(!solved)
+                        .show(ui, |ui| {
+                            if ui
+                                .link("Compare solutions")
+                                .on_hover_text("Every stored solution to this problem, across models")
+                                .clicked()
+                            {
+                                compare_problem = Some(metric.problem_id.clone());
+                            }
+
+                            if let Some(ref err) = metric.compilation_error {
+                                ui.label(egui::RichText::new(err).monospace().color(fail_color));
+                            }
+
+                            if let Some(ref p) = metric.patch {
+                                ui.label(format!(
+                                    "Patch: {} | {} files | +{}/-{}",
+                                    p.status_label(), p.files_changed, p.lines_added, p.lines_removed
+                                ));
+                            }
+
+                            if let Some(ref q) = metric.quality {
+                                let formatted = match q.formatted {
+                                    Some(true) => " | formatted",
+                                    Some(false) => " | not formatted",
+                                    None => "",
+                                };
+                                let quality = ui.label(format!(
+                                    "Lint: {} warnings ({}) | LOC: {} | Complexity: {}{}",
+                                    q.lint_warnings,
+                                    match q.tools.is_empty() {
+                                        true => "no linters installed".to_string(),
+                                        false => q.tools.join(", "),
+                                    },
+                                    q.lines_of_code,
+                                    q.cyclomatic_complexity,
+                                    formatted
+                                ));
+                                if !q.lint_messages.is_empty() {
+                                    quality.on_hover_text(q.lint_messages.join("\n"));
+                                }
+                            }
+
+                            if metric.test_results.is_empty() {
+                                ui.weak("Per-test results were not recorded for this run");
+                                return;
+                            }
+
+                            egui::Grid::new(("history_detail_tests", &metric.problem_id))
+                                .num_columns(9)
+                                .spacing([12.0, 4.0])
+                                .striped(true)
+                                .show(ui, |ui| {
+                                    ui.label(egui::RichText::new("#").strong());
+                                    ui.label(egui::RichText::new("Result").strong());
+                                    ui.label(egui::RichText::new("Input").strong());
+                                    ui.label(egui::RichText::new("Expected").strong());
+                                    ui.label(egui::RichText::new("Actual").strong());
+                                    ui.label(egui::RichText::new("Error").strong());
+                                    ui.label(egui::RichText::new("Time").strong());
+                                    ui.label(egui::RichText::new("CPU").strong());
+                                    ui.label(egui::RichText::new("Peak RSS").strong());
+                                    ui.end_row();
+
+                                    for (idx, case) in metric.test_results.iter().enumerate() {
+                                        let number = match case.performance {
+                                            true => format!("{} (perf)", idx + 1),
+                                            false => format!("{}", idx + 1),
+                                        };
+                                        ui.label(number);
+                                        match case.passed {
+                                            true => ui.colored_label(egui::Color32::GREEN, "PASS"),
+                                            false => ui.colored_label(fail_color, "FAIL"),
+                                        };
+                                        ui.label(egui::RichText::new(&case.input).monospace());
+                                        ui.label(egui::RichText::new(&case.expected).monospace());
+                                        ui.label(egui::RichText::new(&case.actual).monospace());
+                                        ui.label(
+                                            egui::RichText::new(case.error.as_deref().unwrap_or(""))
+                                                .monospace()
+                                                .color(fail_color),
+                                        );
+                                        ui.label(format!("{:.0}ms", case.execution_time_ms));
+                                        ui.label(format!("{:.0}ms", case.cpu_time_ms));
+                                        ui.label(format!("{:.1} MB", case.peak_rss_kb as f64 / 1024.0));
+                                        ui.end_row();
+                                    }
+                                });
+                        });
+                }
+            });
+
+        if let Some(problem_id) = compare_problem {
+            self.open_solutions(problem_id);
+        }
     }
 
     fn render_comparison(&self, ui: &mut egui::Ui) {
-        // Only compare Text entries for now
-        let selected_entries: Vec<&BenchmarkHistoryEntry> = self
+        let selected: Vec<&HistoryEntry> = self
             .entries
             .iter()
             .filter(|e| self.selected_ids.contains(e.id()))
-            .filter_map(|e| match e {
-                HistoryEntry::Text(entry) => Some(entry),
-                _ => None,
-            })
             .collect();
+        let same_type = selected
+            .first()
+            .is_some_and(|first| selected.iter().all(|e| e.benchmark_type() == first.benchmark_type()));
 
-        if selected_entries.len() < 2 {
-            ui.label("Select at least 2 Text benchmark entries to compare");
-            ui.label("(Audio and Code comparison not yet supported)");
+        if selected.len() < 2 || !same_type {
+            ui.label("Select at least 2 entries of the same benchmark type to compare");
             return;
         }
 
         // Header with model names
-        let model_names: Vec<&str> = selected_entries.iter().map(|e| e.model_id.as_str()).collect();
+        let model_names: Vec<&str> = selected.iter().map(|e| e.model_id()).collect();
         ui.label(
             egui::RichText::new(format!("Comparison: {}", model_names.join(" vs ")))
                 .heading()
                 .color(egui::Color32::GRAY),
         );
+
+        let warnings = environment_warnings(&selected);
+        if !warnings.is_empty() {
+            ui.add_space(5.0);
+            ui.colored_label(
+                egui::Color32::from_rgb(255, 180, 100),
+                "⚠ These runs were recorded in different environments:",
+            );
+            for warning in &warnings {
+                ui.label(format!("  • {}", warning));
+            }
+        }
         ui.add_space(10.0);
 
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show(ui, |ui| match selected[0] {
+                HistoryEntry::Text(_) => {
+                    let entries: Vec<&BenchmarkHistoryEntry> = selected
+                        .iter()
+                        .filter_map(|e| match e {
+                            HistoryEntry::Text(entry) => Some(entry),
+                            _ => None,
+                        })
+                        .collect();
+                    self.render_text_comparison(ui, &entries);
+                }
+                HistoryEntry::Code(_) => {
+                    let entries: Vec<&CodeHistoryEntry> = selected
+                        .iter()
+                        .filter_map(|e| match e {
+                            HistoryEntry::Code(entry) => Some(entry),
+                            _ => None,
+                        })
+                        .collect();
+                    self.render_code_comparison(ui, &entries);
+                }
+                HistoryEntry::Audio(_) => {
+                    let entries: Vec<&AudioHistoryEntry> = selected
+                        .iter()
+                        .filter_map(|e| match e {
+                            HistoryEntry::Audio(entry) => Some(entry),
+                            _ => None,
+                        })
+                        .collect();
+                    self.render_audio_comparison(ui, &entries);
+                }
+            });
+    }
+
+    fn render_text_comparison(&self, ui: &mut egui::Ui, entries: &[&BenchmarkHistoryEntry]) {
         egui::Grid::new("comparison_table")
-            .num_columns(selected_entries.len() + 2)
+            .num_columns(entries.len() + 2)
             .spacing([20.0, 8.0])
             .striped(true)
             .show(ui, |ui| {
-                // Header row
-                ui.label(egui::RichText::new("Metric").strong());
-                for entry in &selected_entries {
-                    ui.label(egui::RichText::new(&entry.model_id).strong());
-                }
-                ui.label(egui::RichText::new("Best").strong());
-                ui.end_row();
+                comparison_header(ui, entries.iter().map(|e| e.model_id.as_str()));
 
                 // Avg TPS (higher is better)
                 self.render_metric_row(
-                    ui, &selected_entries, "Avg TPS",
-                    |e| e.summary.avg_tps, |v| format!("{:.1}", v), true,
+                    ui, entries, "Avg TPS",
+                    |e| Some(e.summary.avg_tps), |v| format!("{:.1}", v), true,
                 );
 
                 // Avg TTFT (lower is better)
                 self.render_metric_row(
-                    ui, &selected_entries, "Avg TTFT",
-                    |e| e.summary.avg_ttft_ms, |v| format!("{:.0}ms", v), false,
+                    ui, entries, "Avg TTFT",
+                    |e| Some(e.summary.avg_ttft_ms), |v| format!("{:.0}ms", v), false,
                 );
 
                 // Min TPS (higher is better)
                 self.render_metric_row(
-                    ui, &selected_entries, "Min TPS",
-                    |e| e.summary.min_tps, |v| format!("{:.1}", v), true,
+                    ui, entries, "Min TPS",
+                    |e| Some(e.summary.min_tps), |v| format!("{:.1}", v), true,
                 );
 
                 // Max TPS (higher is better)
                 self.render_metric_row(
-                    ui, &selected_entries, "Max TPS",
-                    |e| e.summary.max_tps, |v| format!("{:.1}", v), true,
+                    ui, entries, "Max TPS",
+                    |e| Some(e.summary.max_tps), |v| format!("{:.1}", v), true,
                 );
 
                 // Iterations row
                 ui.label("Iterations");
-                for entry in &selected_entries {
+                for entry in entries {
+                    ui.label(format!("{}", entry.summary.iterations));
+                }
+                ui.label("");
+                ui.end_row();
+            });
+    }
+
+    fn render_code_comparison(&self, ui: &mut egui::Ui, entries: &[&CodeHistoryEntry]) {
+        let pass_rate = |solved: u32, total: u32| match total {
+            0 => None,
+            _ => Some(solved as f64 / total as f64 * 100.0),
+        };
+
+        egui::Grid::new("comparison_table")
+            .num_columns(entries.len() + 2)
+            .spacing([20.0, 8.0])
+            .striped(true)
+            .show(ui, |ui| {
+                comparison_header(ui, entries.iter().map(|e| e.model_id.as_str()));
+
+                ui.label("Config");
+                for entry in entries {
+                    ui.label(format!(
+                        "{} · {} · T={:.1}",
+                        entry.language.label(),
+                        entry.config.task.label(),
+                        entry.config.temperature
+                    ));
+                }
+                ui.label("");
+                ui.end_row();
+
+                self.render_metric_row(
+                    ui, entries, "Pass Rate",
+                    |e| Some(e.summary.pass_rate * 100.0), |v| format!("{:.1}%", v), true,
+                );
+
+                ui.label("Solved");
+                for entry in entries {
+                    let label = ui.label(entry.summary.solved_label());
+                    if !entry.summary.skipped_problems.is_empty() {
+                        label.on_hover_text(format!("Skipped: {}", entry.summary.skipped_problems.join(", ")));
+                    }
+                }
+                ui.label("");
+                ui.end_row();
+
+                self.render_metric_row(
+                    ui, entries, "Easy",
+                    |e| pass_rate(e.summary.easy_solved, e.summary.easy_total), |v| format!("{:.0}%", v), true,
+                );
+                self.render_metric_row(
+                    ui, entries, "Medium",
+                    |e| pass_rate(e.summary.medium_solved, e.summary.medium_total), |v| format!("{:.0}%", v), true,
+                );
+                self.render_metric_row(
+                    ui, entries, "Hard",
+                    |e| pass_rate(e.summary.hard_solved, e.summary.hard_total), |v| format!("{:.0}%", v), true,
+                );
+
+                self.render_metric_row(
+                    ui, entries, "Avg TPS",
+                    |e| Some(e.summary.avg_tps), |v| format!("{:.1}", v), true,
+                );
+                self.render_metric_row(
+                    ui, entries, "Avg TTFT",
+                    |e| (e.summary.avg_ttft_ms > 0.0).then_some(e.summary.avg_ttft_ms), |v| format!("{:.0}ms", v), false,
+                );
+                self.render_metric_row(
+                    ui, entries, "Avg Generation",
+                    |e| Some(e.summary.avg_execution_time_ms), |v| format!("{:.0}ms", v), false,
+                );
+            });
+
+        // Per-problem results, in the order problems first appear
+        let mut problem_ids: Vec<&str> = Vec::new();
+        for entry in entries {
+            for metric in &entry.metrics {
+                if !problem_ids.contains(&metric.problem_id.as_str()) {
+                    problem_ids.push(&metric.problem_id);
+                }
+            }
+        }
+
+        ui.add_space(15.0);
+        ui.label(egui::RichText::new("Per Problem").strong());
+        ui.add_space(5.0);
+
+        let fail_color = egui::Color32::from_rgb(255, 100, 100);
+        egui::Grid::new("comparison_problems")
+            .num_columns(entries.len() + 2)
+            .spacing([20.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label(egui::RichText::new("Problem").strong());
+                for entry in entries {
+                    ui.label(egui::RichText::new(&entry.model_id).strong());
+                }
+                ui.label(egui::RichText::new("Solved By").strong());
+                ui.end_row();
+
+                for problem_id in &problem_ids {
+                    let difficulty = entries
+                        .iter()
+                        .flat_map(|e| &e.metrics)
+                        .find(|m| m.problem_id == *problem_id)
+                        .map(|m| m.difficulty.label())
+                        .unwrap_or("");
+                    ui.label(format!("{} ({})", problem_id, difficulty));
+
+                    let mut solved_by = 0;
+                    for entry in entries {
+                        let Some(metric) = entry.metrics.iter().find(|m| m.problem_id == *problem_id) else {
+                            ui.weak("—");
+                            continue;
+                        };
+                        let solved = metric.solved();
+                        solved_by += usize::from(solved);
+                        let (mark, color) = match solved {
+                            true => ("✔", egui::Color32::GREEN),
+                            false => ("✘", fail_color),
+                        };
+                        let cell = ui.colored_label(
+                            color,
+                            format!("{} {}/{}", mark, metric.tests_passed, metric.tests_total),
+                        );
+                        let error = metric.compilation_error.as_deref().or(metric.runtime_error.as_deref());
+                        if let Some(error) = error {
+                            cell.on_hover_text(error);
+                        }
+                    }
+                    ui.label(format!("{}/{}", solved_by, entries.len()));
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn render_audio_comparison(&self, ui: &mut egui::Ui, entries: &[&AudioHistoryEntry]) {
+        egui::Grid::new("comparison_table")
+            .num_columns(entries.len() + 2)
+            .spacing([20.0, 8.0])
+            .striped(true)
+            .show(ui, |ui| {
+                comparison_header(ui, entries.iter().map(|e| e.model_id.as_str()));
+
+                ui.label("Audio");
+                for entry in entries {
+                    let file = entry
+                        .config
+                        .audio_path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_else(|| "—".to_string());
+                    ui.label(format!("{} · {}", entry.audio_mode.label(), file));
+                }
+                ui.label("");
+                ui.end_row();
+
+                self.render_metric_row(
+                    ui, entries, "Avg RTF",
+                    |e| Some(e.summary.avg_rtf), |v| format!("{:.3}x", v), false,
+                );
+                self.render_metric_row(
+                    ui, entries, "Min RTF",
+                    |e| Some(e.summary.min_rtf), |v| format!("{:.3}x", v), false,
+                );
+                self.render_metric_row(
+                    ui, entries, "Max RTF",
+                    |e| Some(e.summary.max_rtf), |v| format!("{:.3}x", v), false,
+                );
+                self.render_metric_row(
+                    ui, entries, "RTF Std Dev",
+                    |e| std_dev(e.metrics.iter().map(|m| m.real_time_factor)), |v| format!("{:.3}", v), false,
+                );
+                self.render_metric_row(
+                    ui, entries, "Avg Processing",
+                    |e| Some(e.summary.avg_processing_ms), |v| format!("{:.0}ms", v), false,
+                );
+                if entries.iter().any(|e| e.summary.accuracy.is_some()) {
+                    self.render_metric_row(
+                        ui, entries, "WER",
+                        |e| e.summary.accuracy.map(|a| a.wer() * 100.0), |v| format!("{:.1}%", v), false,
+                    );
+                    self.render_metric_row(
+                        ui, entries, "CER",
+                        |e| e.summary.accuracy.map(|a| a.cer() * 100.0), |v| format!("{:.1}%", v), false,
+                    );
+                }
+
+                ui.label("Iterations");
+                for entry in entries {
                     ui.label(format!("{}", entry.summary.iterations));
                 }
                 ui.label("");
@@ -666,30 +1815,121 @@ impl HistoryPanel {
             });
     }
 
-    fn render_metric_row<F, G>(
+    fn render_leaderboard(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Language:");
+            let mut language = self.leaderboard_language;
+            egui::ComboBox::from_id_salt("leaderboard_language")
+                .selected_text(language.label())
+                .show_ui(ui, |ui| {
+                    for lang in Language::all() {
+                        ui.selectable_value(&mut language, *lang, lang.label());
+                    }
+                });
+            if language != self.leaderboard_language {
+                self.leaderboard_language = language;
+                self.refresh_leaderboard();
+            }
+        });
+        ui.add_space(10.0);
+
+        if self.leaderboard.skipped > 0 {
+            ui.colored_label(
+                egui::Color32::from_rgb(255, 180, 100),
+                format!("⚠ {} run(s) left out: summary unreadable", self.leaderboard.skipped),
+            );
+        }
+
+        if self.leaderboard.entries.is_empty() {
+            ui.label(format!("No {} code benchmarks yet", self.leaderboard_language.label()));
+            return;
+        }
+
+        // One column per tag seen in any model's best run
+        let mut tags: Vec<&String> = self
+            .leaderboard
+            .entries
+            .iter()
+            .flat_map(|e| e.tag_stats.keys())
+            .collect();
+        tags.sort();
+        tags.dedup();
+
+        egui::ScrollArea::both()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                egui::Grid::new("leaderboard_table")
+                    .num_columns(tags.len() + 5)
+                    .spacing([16.0, 6.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label(egui::RichText::new("#").strong());
+                        ui.label(egui::RichText::new("Model").strong());
+                        ui.label(egui::RichText::new("Pass").strong());
+                        ui.label(egui::RichText::new("Lint").strong());
+                        ui.label(egui::RichText::new("CC").strong());
+                        for tag in &tags {
+                            ui.label(egui::RichText::new(tag.as_str()).strong());
+                        }
+                        ui.end_row();
+
+                        for (rank, entry) in self.leaderboard.entries.iter().enumerate() {
+                            ui.label(format!("{}", rank + 1));
+                            ui.label(&entry.model_id);
+                            ui.label(format!("{:.1}%", entry.pass_rate * 100.0));
+                            match entry.problems_analyzed > 0 {
+                                true => {
+                                    ui.label(format!("{:.1}", entry.avg_lint_warnings));
+                                    ui.label(format!("{:.1}", entry.avg_cyclomatic_complexity));
+                                }
+                                false => {
+                                    ui.label("—");
+                                    ui.label("—");
+                                }
+                            }
+                            for tag in &tags {
+                                let Some(stats) = entry.tag_stats.get(tag.as_str()) else {
+                                    ui.label("—");
+                                    continue;
+                                };
+                                ui.label(format!("{:.0}%", stats.pass_rate() * 100.0))
+                                    .on_hover_text(format!("{}/{}", stats.solved, stats.total));
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+
+    /// One metric across the compared runs; the best value is highlighted and repeated in
+    /// the last column. Runs without a value show "—".
+    fn render_metric_row<T, F, G>(
         &self,
         ui: &mut egui::Ui,
-        entries: &[&BenchmarkHistoryEntry],
+        entries: &[&T],
         label: &str,
         get_value: F,
         format_value: G,
         higher_is_better: bool,
     ) where
-        F: Fn(&BenchmarkHistoryEntry) -> f64,
+        F: Fn(&T) -> Option<f64>,
         G: Fn(f64) -> String,
     {
         ui.label(label);
 
-        let values: Vec<f64> = entries.iter().map(|e| get_value(e)).collect();
-        let (init, fold_fn): (f64, fn(f64, f64) -> f64) = match higher_is_better {
-            true => (f64::MIN, f64::max),
-            false => (f64::MAX, f64::min),
+        let values: Vec<Option<f64>> = entries.iter().map(|e| get_value(e)).collect();
+        let fold_fn: fn(f64, f64) -> f64 = match higher_is_better {
+            true => f64::max,
+            false => f64::min,
         };
-        let best = values.iter().cloned().fold(init, fold_fn);
+        let best = values.iter().flatten().cloned().reduce(fold_fn);
 
-        for entry in entries {
-            let value = get_value(entry);
-            let is_best = (value - best).abs() < 0.01;
+        for value in &values {
+            let Some(value) = *value else {
+                ui.weak("—");
+                continue;
+            };
+            let is_best = best.is_some_and(|best| (value - best).abs() < 0.01);
             let text = format_value(value);
             let label_text = match is_best {
                 true => egui::RichText::new(text).strong().color(egui::Color32::GREEN),
@@ -698,13 +1938,142 @@ impl HistoryPanel {
             ui.label(label_text);
         }
 
-        ui.label(format_value(best));
+        ui.label(best.map(&format_value).unwrap_or_default());
         ui.end_row();
     }
 
+    /// Export the selected runs (or every run) with presets, batches and effect detections
+    fn export_bundle(&mut self) {
+        let contents = BundleContents {
+            run_ids: match self.selected_ids.is_empty() {
+                true => None,
+                false => Some(self.selected_ids.iter().cloned().collect()),
+            },
+            presets: true,
+            batches: true,
+            effect_detections: true,
+        };
+        let service = self.history_service.clone();
+        let (tx, rx) = mpsc::channel();
+        self.file_rx = Some(rx);
+        std::thread::spawn(move || {
+            let path = rfd::FileDialog::new()
+                .set_title("Export History Bundle")
+                .add_filter("LlamaBurn Bundle", &["json"])
+                .set_file_name("llamaburn_bundle.json")
+                .save_file();
+            let result = match path {
+                Some(path) => service
+                    .export_bundle_to(&path, &contents)
+                    .map(|summary| match summary.unreadable.is_empty() {
+                        true => format!("Exported {} runs to {}", summary.runs_exported, path.display()),
+                        false => format!(
+                            "Exported {} runs to {}; left out {} unreadable: {}",
+                            summary.runs_exported,
+                            path.display(),
+                            summary.unreadable.len(),
+                            summary.unreadable.join(", ")
+                        ),
+                    })
+                    .map_err(|e| format!("Export failed: {}", e)),
+                None => Ok(String::new()),
+            };
+            let _ = tx.send(result);
+        });
+    }
+
+    fn import_bundle(&mut self) {
+        let service = self.history_service.clone();
+        let (tx, rx) = mpsc::channel();
+        self.file_rx = Some(rx);
+        std::thread::spawn(move || {
+            let path = rfd::FileDialog::new()
+                .set_title("Import History Bundle")
+                .add_filter("LlamaBurn Bundle", &["json"])
+                .pick_file();
+            let result = match path {
+                Some(path) => service
+                    .import_bundle_from(&path)
+                    .map(|summary| summary.describe())
+                    .map_err(|e| format!("Import failed: {}", e)),
+                None => Ok(String::new()),
+            };
+            let _ = tx.send(result);
+        });
+    }
+
+    /// Selected runs first, then the filtered batch session, then every loaded run
+    fn export_report(&mut self, format: ReportFormat) {
+        let runs: Vec<HistoryRecord> = match self.selected_ids.is_empty() {
+            true => self.entries.iter().cloned().map(HistoryRecord::from).collect(),
+            false => self
+                .entries
+                .iter()
+                .filter(|e| self.selected_ids.contains(e.id()))
+                .cloned()
+                .map(HistoryRecord::from)
+                .collect(),
+        };
+        let session_id = match self.selected_ids.is_empty() {
+            true => self.filter.session_id.clone(),
+            false => None,
+        };
+        let service = self.history_service.clone();
+        let (tx, rx) = mpsc::channel();
+        self.file_rx = Some(rx);
+        std::thread::spawn(move || {
+            let path = rfd::FileDialog::new()
+                .set_title("Export Report")
+                .add_filter(format.label(), &[format.extension()])
+                .set_file_name(format!("llamaburn_report.{}", format.extension()))
+                .save_file();
+            let Some(path) = path else {
+                let _ = tx.send(Ok(String::new()));
+                return;
+            };
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs() as i64;
+            let report = match session_id {
+                Some(sid) => render_session_report(&service, &sid, format, now)
+                    .map_err(|e| format!("Report failed: {}", e)),
+                None => Ok(render_report("LlamaBurn Benchmark Report", &runs, format, now)),
+            };
+            let result = report.and_then(|report| {
+                std::fs::write(&path, report)
+                    .map(|_| format!("Saved {} report to {}", format.label(), path.display()))
+                    .map_err(|e| format!("Report failed: {}", e))
+            });
+            let _ = tx.send(result);
+        });
+    }
+
+    fn poll_file_task(&mut self) {
+        let Some(rx) = &self.file_rx else {
+            return;
+        };
+        let Ok(result) = rx.try_recv() else {
+            return;
+        };
+        self.file_rx = None;
+        // An empty message means the file dialog was cancelled
+        if result.as_ref().is_ok_and(|msg| msg.is_empty()) {
+            return;
+        }
+        if result.is_ok() {
+            self.needs_refresh = true;
+        }
+        self.file_status = Some(result);
+    }
+
     fn export_csv(&self) {
         let entries = self.entries.clone();
         let presets_cache = self.presets_cache.clone();
+        let baseline_statuses: HashMap<String, &'static str> = entries
+            .iter()
+            .map(|e| (e.id().to_string(), self.baseline_status(e.id())))
+            .collect();
         std::thread::spawn(move || {
             let path = rfd::FileDialog::new()
                 .set_title("Export History")
@@ -713,7 +2082,7 @@ impl HistoryPanel {
                 .save_file();
             let Some(path) = path else { return };
 
-            let mut csv = String::from("Timestamp,Model,Type,Params,TPS,Test Pass,TTFT,RTF,Runs,ExecTime,Detail,Failed,Preset,Signature,Session,Status\n");
+            let mut csv = String::from("Timestamp,Model,Type,Params,TPS,Test Pass,TTFT,RTF,Runs,ExecTime,Detail,Tags,Quality,Slowdown,Failed,Preset,Signature,Session,Status,Baseline,Environment,Origin\n");
             for entry in &entries {
                 let (tps, pass, ttft, rtf, runs, exec, detail) = match &entry {
                     HistoryEntry::Text(e) => (
@@ -737,7 +2106,10 @@ impl HistoryPanel {
                     HistoryEntry::Code(e) => (
                         format!("{:.1}", e.summary.avg_tps),
                         format!("{:.1}", e.summary.pass_rate * 100.0),
-                        String::new(),
+                        match e.summary.avg_ttft_ms > 0.0 {
+                            true => format!("{:.0}", e.summary.avg_ttft_ms),
+                            false => String::new(),
+                        },
                         String::new(),
                         String::new(),
                         format!("{:.0}", e.summary.avg_execution_time_ms),
@@ -757,18 +2129,34 @@ impl HistoryPanel {
                     .map(|s| s.as_str())
                     .unwrap_or("");
                 let sig = entry.result_signature().unwrap_or_default();
+                let tags = entry
+                    .tag_breakdown_display()
+                    .unwrap_or_default()
+                    .replace(',', ";");
+                let quality = entry.quality_display().unwrap_or_default();
+                let slowdown = entry.slowdown_display().unwrap_or_default();
+                let environment = entry
+                    .environment()
+                    .map(|env| env.summary().replace(',', ";"))
+                    .unwrap_or_default();
                 let row = format!(
-                    "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}\n",
+                    "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}\n",
                     entry.timestamp(),
                     entry.model_id(),
                     format!("{:?}", entry.benchmark_type()),
                     entry.code_params().replace(',', ";"),
                     tps, pass, ttft, rtf, runs, exec, detail,
+                    tags,
+                    quality,
+                    slowdown,
                     failed_str,
                     preset_name,
                     sig,
                     entry.session_display(),
                     entry.status(),
+                    baseline_statuses.get(entry.id()).copied().unwrap_or_default(),
+                    environment,
+                    entry.origin().map(|o| o.host.as_str()).unwrap_or_default(),
                 );
                 csv.push_str(&row);
             }
@@ -780,7 +2168,100 @@ impl HistoryPanel {
     }
 }
 
-fn format_timestamp(ts: i64) -> String {
+/// Fingerprint differences between compared runs, relative to the first run that has one
+fn environment_warnings(entries: &[&HistoryEntry]) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    let missing = entries.iter().filter(|e| e.environment().is_none()).count();
+    if missing > 0 {
+        warnings.push(format!("{} of {} runs have no environment recorded", missing, entries.len()));
+    }
+
+    let Some((base, base_env)) = entries.iter().find_map(|e| Some((e, e.environment()?))) else {
+        return warnings;
+    };
+    for entry in entries {
+        let Some(env) = entry.environment() else { continue };
+        let same_model = entry.model_id() == base.model_id();
+        for diff in base_env.differences(env, same_model) {
+            let warning = format!("{} vs {}: {}", base.model_id(), entry.model_id(), diff);
+            if !warnings.contains(&warning) {
+                warnings.push(warning);
+            }
+        }
+    }
+    warnings
+}
+
+/// Monospace snippet with the matched terms highlighted
+fn snippet_layout(ui: &egui::Ui, hit: &SearchHit) -> egui::text::LayoutJob {
+    let mut job = egui::text::LayoutJob::default();
+    let font = egui::FontId::monospace(12.0);
+    let normal_color = ui.visuals().text_color();
+    for (text, is_match) in hit.snippet_parts() {
+        let format = match is_match {
+            true => egui::text::TextFormat {
+                font_id: font.clone(),
+                color: egui::Color32::BLACK,
+                background: egui::Color32::from_rgb(255, 210, 80),
+                ..Default::default()
+            },
+            false => egui::text::TextFormat {
+                font_id: font.clone(),
+                color: normal_color,
+                ..Default::default()
+            },
+        };
+        job.append(text, 0.0, format);
+    }
+    job
+}
+
+/// "Metric | run… | Best" header row of a comparison grid
+fn comparison_header<'a>(ui: &mut egui::Ui, models: impl Iterator<Item = &'a str>) {
+    ui.label(egui::RichText::new("Metric").strong());
+    for model in models {
+        ui.label(egui::RichText::new(model).strong());
+    }
+    ui.label(egui::RichText::new("Best").strong());
+    ui.end_row();
+}
+
+/// Sample standard deviation; `None` with fewer than two values
+fn std_dev(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let values: Vec<f64> = values.collect();
+    if values.len() < 2 {
+        return None;
+    }
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64;
+    Some(variance.sqrt())
+}
+
+/// ComboBox over an optional value; `None` is shown as `none_label`. Returns true on change.
+fn option_combo<T: Clone + PartialEq>(
+    ui: &mut egui::Ui,
+    id: &str,
+    none_label: &str,
+    value: &mut Option<T>,
+    options: impl Iterator<Item = T>,
+    label: impl Fn(&T) -> String,
+) -> bool {
+    let mut changed = false;
+    let selected = value.as_ref().map(&label).unwrap_or_else(|| none_label.to_string());
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(selected)
+        .show_ui(ui, |ui| {
+            changed |= ui.selectable_value(value, None, none_label).changed();
+            for option in options {
+                let text = label(&option);
+                changed |= ui.selectable_value(value, Some(option), text).changed();
+            }
+        });
+    changed
+}
+
+pub(crate) fn format_timestamp(ts: i64) -> String {
     use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
     let entry_time = UNIX_EPOCH + Duration::from_secs(ts as u64);
@@ -1,4 +1,7 @@
 pub mod benchmark;
 pub mod gpu_monitor;
+pub mod clips;
 pub mod history;
 pub mod setup;
+pub mod solutions;
+pub mod trends;
@@ -0,0 +1,300 @@
+//! Cross-model solution browser: every stored solution for a problem, line diffs between
+//! any two of them, and test re-runs
+
+use eframe::egui;
+use llamaburn_services::{all_tests_passed, line_diff, run_tests_only, DiffLine, HistoryService};
+use llamaburn_services::{CodeProblem, ProblemSet, ProblemSolutions, StoredSolution};
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use tracing::warn;
+
+use super::benchmark::load_problem_sets_from_disk;
+use super::history::format_timestamp;
+
+/// Outcome of re-running a stored solution: (tests_passed, tests_total, execution_time_ms)
+type RerunResult = Result<(u32, u32, f64), String>;
+
+pub struct SolutionsView {
+    history_service: Arc<HistoryService>,
+    problems: Vec<ProblemSolutions>,
+    /// Only list problems where some solutions pass and some fail
+    disputed_only: bool,
+    problem_id: Option<String>,
+    solutions: Vec<StoredSolution>,
+    /// Solutions picked for the diff, by index into `solutions`
+    diff_old: Option<usize>,
+    diff_new: Option<usize>,
+    /// Problem definitions for re-runs, loaded on first use
+    problem_sets: Option<Vec<ProblemSet>>,
+    rerun_rx: Option<Receiver<(String, RerunResult)>>,
+    /// Re-run outcomes by run id; `None` while running
+    reruns: HashMap<String, Option<RerunResult>>,
+}
+
+impl SolutionsView {
+    pub fn new(history_service: Arc<HistoryService>, problem_id: Option<String>) -> Self {
+        let mut view = Self {
+            history_service,
+            problems: Vec::new(),
+            disputed_only: false,
+            problem_id: None,
+            solutions: Vec::new(),
+            diff_old: None,
+            diff_new: None,
+            problem_sets: None,
+            rerun_rx: None,
+            reruns: HashMap::new(),
+        };
+        view.problems = view.history_service.problem_solutions().unwrap_or_else(|e| {
+            warn!("Failed to load problem solutions: {}", e);
+            Vec::new()
+        });
+        let first = view.problems.iter().find(|p| p.disputed()).or_else(|| view.problems.first());
+        let problem_id = problem_id.or_else(|| first.map(|p| p.problem_id.clone()));
+        if let Some(id) = problem_id {
+            view.select_problem(id);
+        }
+        view
+    }
+
+    fn select_problem(&mut self, problem_id: String) {
+        self.solutions = self
+            .history_service
+            .solutions_for_problem(&problem_id)
+            .unwrap_or_else(|e| {
+                warn!("Failed to load solutions for {}: {}", problem_id, e);
+                Vec::new()
+            });
+        // Default to diffing a failing solution against a passing one
+        self.diff_old = self.solutions.iter().position(|s| !s.passed());
+        self.diff_new = self.solutions.iter().position(|s| s.passed());
+        self.problem_id = Some(problem_id);
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        self.poll_reruns();
+
+        if self.problems.is_empty() {
+            ui.label("No code benchmark solutions stored yet");
+            return;
+        }
+
+        self.render_problem_picker(ui);
+        ui.add_space(10.0);
+
+        if self.solutions.is_empty() {
+            ui.label("No solutions stored for this problem");
+            return;
+        }
+
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                self.render_solution_table(ui);
+                ui.add_space(10.0);
+                ui.separator();
+                self.render_diff(ui);
+            });
+    }
+
+    fn render_problem_picker(&mut self, ui: &mut egui::Ui) {
+        let problem_label = |p: &ProblemSolutions| {
+            let marker = match p.disputed() {
+                true => " ⚠",
+                false => "",
+            };
+            format!("{}  ({}/{} passing){}", p.problem_id, p.passing, p.solutions, marker)
+        };
+        let selected_text = self
+            .problems
+            .iter()
+            .find(|p| Some(&p.problem_id) == self.problem_id.as_ref())
+            .map(problem_label)
+            .unwrap_or_else(|| "Select problem".to_string());
+
+        let mut picked: Option<String> = None;
+        ui.horizontal(|ui| {
+            ui.label("Problem:");
+            egui::ComboBox::from_id_salt("solutions_problem")
+                .selected_text(selected_text)
+                .width(320.0)
+                .show_ui(ui, |ui| {
+                    for p in self.problems.iter().filter(|p| !self.disputed_only || p.disputed()) {
+                        let selected = Some(&p.problem_id) == self.problem_id.as_ref();
+                        if ui.selectable_label(selected, problem_label(p)).clicked() {
+                            picked = Some(p.problem_id.clone());
+                        }
+                    }
+                });
+            ui.checkbox(&mut self.disputed_only, "Disputed only")
+                .on_hover_text("Problems where some solutions pass and others fail");
+        });
+
+        if let Some(id) = picked {
+            self.select_problem(id);
+        }
+    }
+
+    fn render_solution_table(&mut self, ui: &mut egui::Ui) {
+        let fail_color = egui::Color32::from_rgb(255, 100, 100);
+        let mut rerun: Option<usize> = None;
+
+        egui::Grid::new("solutions_table")
+            .num_columns(9)
+            .spacing([12.0, 6.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label(egui::RichText::new("Old").strong());
+                ui.label(egui::RichText::new("New").strong());
+                ui.label(egui::RichText::new("Model").strong());
+                ui.label(egui::RichText::new("Language").strong());
+                ui.label(egui::RichText::new("Temp").strong());
+                ui.label(egui::RichText::new("Date").strong());
+                ui.label(egui::RichText::new("Tests").strong());
+                ui.label(egui::RichText::new("Re-run").strong());
+                ui.label("");
+                ui.end_row();
+
+                for (idx, solution) in self.solutions.iter().enumerate() {
+                    ui.radio_value(&mut self.diff_old, Some(idx), "");
+                    ui.radio_value(&mut self.diff_new, Some(idx), "");
+                    ui.label(&solution.model_id);
+                    ui.label(solution.language.label());
+                    ui.label(format!("{:.1}", solution.temperature));
+                    ui.label(format_timestamp(solution.timestamp));
+
+                    let tests = format!("{}/{}", solution.tests_passed, solution.tests_total);
+                    let tests = match solution.passed() {
+                        true => ui.colored_label(egui::Color32::GREEN, tests),
+                        false => ui.colored_label(fail_color, tests),
+                    };
+                    let error = solution.compilation_error.as_ref().or(solution.runtime_error.as_ref());
+                    if let Some(error) = error {
+                        tests.on_hover_text(error);
+                    }
+
+                    match self.reruns.get(&solution.run_id) {
+                        Some(None) => {
+                            ui.spinner();
+                        }
+                        Some(Some(Ok((passed, total, time_ms)))) => {
+                            let color = match all_tests_passed(*passed, *total) {
+                                true => egui::Color32::GREEN,
+                                false => fail_color,
+                            };
+                            ui.colored_label(color, format!("{}/{} ({:.0}ms)", passed, total, time_ms));
+                        }
+                        Some(Some(Err(e))) => {
+                            ui.colored_label(fail_color, "Error").on_hover_text(e);
+                        }
+                        None => {
+                            ui.weak("—");
+                        }
+                    }
+
+                    let running = matches!(self.reruns.get(&solution.run_id), Some(None));
+                    if ui
+                        .add_enabled(!running && self.rerun_rx.is_none(), egui::Button::new("Run Tests"))
+                        .on_hover_text("Re-run this code against the problem's current test cases")
+                        .clicked()
+                    {
+                        rerun = Some(idx);
+                    }
+                    ui.end_row();
+                }
+            });
+
+        if let Some(idx) = rerun {
+            self.start_rerun(idx);
+        }
+    }
+
+    fn render_diff(&self, ui: &mut egui::Ui) {
+        let old = self.diff_old.and_then(|i| self.solutions.get(i));
+        let new = self.diff_new.and_then(|i| self.solutions.get(i));
+
+        let (old, new) = match (old, new) {
+            (Some(old), Some(new)) => (old, new),
+            (Some(only), None) | (None, Some(only)) => {
+                ui.label(egui::RichText::new(only.describe()).strong());
+                ui.add_space(5.0);
+                ui.label(egui::RichText::new(&only.code).monospace());
+                return;
+            }
+            (None, None) => {
+                ui.label("Pick an old and a new solution to diff them");
+                return;
+            }
+        };
+
+        ui.horizontal(|ui| {
+            ui.colored_label(egui::Color32::from_rgb(255, 100, 100), format!("− {}", old.describe()));
+            ui.colored_label(egui::Color32::GREEN, format!("+ {}", new.describe()));
+        });
+        ui.add_space(5.0);
+
+        let diff = line_diff(&old.code, &new.code);
+        if diff.iter().all(|line| matches!(line, DiffLine::Same(_))) {
+            ui.weak("Identical code");
+        }
+        for line in &diff {
+            let (text, color) = match line {
+                DiffLine::Same(l) => (format!("  {}", l), ui.visuals().text_color()),
+                DiffLine::Removed(l) => (format!("- {}", l), egui::Color32::from_rgb(255, 100, 100)),
+                DiffLine::Added(l) => (format!("+ {}", l), egui::Color32::GREEN),
+            };
+            ui.label(egui::RichText::new(text).monospace().color(color));
+        }
+    }
+
+    /// Re-run a stored solution's tests in the background
+    fn start_rerun(&mut self, idx: usize) {
+        let Some(solution) = self.solutions.get(idx) else {
+            return;
+        };
+        let run_id = solution.run_id.clone();
+
+        let problem_sets = self.problem_sets.get_or_insert_with(load_problem_sets_from_disk);
+        let problem: Option<CodeProblem> = problem_sets
+            .iter()
+            .flat_map(|ps| ps.problems.iter())
+            .find(|p| p.id == solution.problem_id)
+            .cloned();
+        let Some(problem) = problem else {
+            let msg = format!("Problem '{}' not found in the problem sets", solution.problem_id);
+            self.reruns.insert(run_id, Some(Err(msg)));
+            return;
+        };
+
+        let code = solution.code.clone();
+        let language = solution.language;
+        let (tx, rx) = mpsc::channel();
+        self.rerun_rx = Some(rx);
+        self.reruns.insert(run_id.clone(), None);
+
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().expect("failed to create tokio runtime");
+            let result = rt.block_on(async {
+                let (event_tx, mut event_rx) = tokio::sync::mpsc::channel(100);
+                // Per-test events aren't shown here; drain them so the runner never blocks
+                let drain = tokio::spawn(async move { while event_rx.recv().await.is_some() {} });
+                let result = run_tests_only(&code, language, &problem, event_tx).await;
+                let _ = drain.await;
+                result
+            });
+            let _ = tx.send((run_id, result));
+        });
+    }
+
+    fn poll_reruns(&mut self) {
+        let Some(rx) = &self.rerun_rx else {
+            return;
+        };
+        let Ok((run_id, result)) = rx.try_recv() else {
+            return;
+        };
+        self.rerun_rx = None;
+        self.reruns.insert(run_id, Some(result));
+    }
+}
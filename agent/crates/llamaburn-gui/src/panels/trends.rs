@@ -0,0 +1,354 @@
+//! Trend charts: a run metric per model over time, with baseline and environment markers
+
+use eframe::egui;
+use llamaburn_services::{format_utc, trend_series, HistoryFilter, HistoryRecord, HistoryService, HistorySort};
+use llamaburn_services::{Preset, TrendMetric, TrendPoint, TrendSeries};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+use tracing::warn;
+
+use super::history::HistoryEntry;
+
+/// Line colors, cycled per series
+const SERIES_COLORS: &[egui::Color32] = &[
+    egui::Color32::from_rgb(255, 140, 0),
+    egui::Color32::from_rgb(80, 160, 255),
+    egui::Color32::from_rgb(120, 200, 80),
+    egui::Color32::from_rgb(220, 90, 200),
+    egui::Color32::from_rgb(255, 210, 60),
+    egui::Color32::from_rgb(60, 200, 190),
+    egui::Color32::from_rgb(255, 100, 100),
+    egui::Color32::from_rgb(170, 140, 255),
+];
+
+const CHART_HEIGHT: f32 = 320.0;
+
+pub struct TrendsView {
+    history_service: Arc<HistoryService>,
+    metric: TrendMetric,
+    presets: Vec<Preset>,
+    preset_id: Option<String>,
+    /// Result signature of preset-linked code runs (see `HistoryEntry::result_signature`)
+    signature: Option<String>,
+    /// Runs of the metric's benchmark types matching the preset
+    runs: Vec<HistoryEntry>,
+    baseline_ids: HashSet<String>,
+    series: Vec<TrendSeries>,
+    /// Series switched off in the legend, by label
+    hidden: HashSet<String>,
+}
+
+impl TrendsView {
+    pub fn new(history_service: Arc<HistoryService>) -> Self {
+        let presets = history_service.list_presets().unwrap_or_else(|e| {
+            warn!("Failed to load presets: {}", e);
+            Vec::new()
+        });
+        let mut view = Self {
+            history_service,
+            metric: TrendMetric::default(),
+            presets,
+            preset_id: None,
+            signature: None,
+            runs: Vec::new(),
+            baseline_ids: HashSet::new(),
+            series: Vec::new(),
+            hidden: HashSet::new(),
+        };
+        view.refresh();
+        view
+    }
+
+    /// Reload runs for the metric and preset
+    fn refresh(&mut self) {
+        self.runs.clear();
+        for benchmark_type in self.metric.benchmark_types() {
+            let filter = HistoryFilter {
+                benchmark_type: Some(*benchmark_type),
+                preset_id: self.preset_id.clone(),
+                sort: HistorySort::Oldest,
+                ..Default::default()
+            };
+            match self.history_service.list_page(&filter) {
+                Ok(page) => {
+                    if !page.unreadable.is_empty() {
+                        warn!("{} unreadable runs left out of trends", page.unreadable.len());
+                    }
+                    self.runs.extend(page.entries.into_iter().map(HistoryEntry::from));
+                }
+                Err(e) => warn!("Failed to load {} runs for trends: {}", benchmark_type.label(), e),
+            }
+        }
+        self.baseline_ids = self.history_service.baseline_ids().unwrap_or_else(|e| {
+            warn!("Failed to load baselines: {}", e);
+            HashSet::new()
+        });
+        // Drop a signature the new runs don't have
+        let stale = self.signature.as_ref().is_some_and(|sig| !self.signatures().contains_key(sig));
+        if stale {
+            self.signature = None;
+        }
+        self.rebuild_series();
+    }
+
+    fn rebuild_series(&mut self) {
+        let records: Vec<HistoryRecord> = self
+            .runs
+            .iter()
+            .filter(|run| match &self.signature {
+                Some(sig) => run.result_signature().as_ref() == Some(sig),
+                None => true,
+            })
+            .cloned()
+            .map(HistoryRecord::from)
+            .collect();
+        self.series = trend_series(&records, self.metric, &self.baseline_ids);
+    }
+
+    /// Result signatures among the loaded runs, with how many runs share each
+    fn signatures(&self) -> BTreeMap<String, usize> {
+        let mut signatures = BTreeMap::new();
+        for sig in self.runs.iter().filter_map(|run| run.result_signature()) {
+            *signatures.entry(sig).or_insert(0) += 1;
+        }
+        signatures
+    }
+
+    fn series_label(&self, series: &TrendSeries) -> String {
+        match self.metric.benchmark_types().len() {
+            1 => series.model_id.clone(),
+            _ => format!("{} ({})", series.model_id, series.benchmark_type.label()),
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        self.render_controls(ui);
+        ui.add_space(10.0);
+
+        if self.series.is_empty() {
+            ui.label(format!("No runs with {} for this filter", self.metric.label()));
+            return;
+        }
+
+        self.render_chart(ui);
+        ui.add_space(6.0);
+        self.render_legend(ui);
+    }
+
+    fn render_controls(&mut self, ui: &mut egui::Ui) {
+        let mut refresh = false;
+        let mut rebuild = false;
+        let signatures = self.signatures();
+
+        ui.horizontal(|ui| {
+            ui.label("Metric:");
+            egui::ComboBox::from_id_salt("trends_metric")
+                .selected_text(self.metric.label())
+                .show_ui(ui, |ui| {
+                    for metric in TrendMetric::all() {
+                        refresh |= ui.selectable_value(&mut self.metric, *metric, metric.label()).changed();
+                    }
+                });
+
+            ui.add_space(10.0);
+            ui.label("Preset:");
+            let preset_name = self
+                .preset_id
+                .as_ref()
+                .and_then(|id| self.presets.iter().find(|p| &p.id == id))
+                .map(|p| p.name.clone())
+                .unwrap_or_else(|| "Any".to_string());
+            egui::ComboBox::from_id_salt("trends_preset")
+                .selected_text(preset_name)
+                .show_ui(ui, |ui| {
+                    refresh |= ui.selectable_value(&mut self.preset_id, None, "Any").changed();
+                    for preset in &self.presets {
+                        refresh |= ui
+                            .selectable_value(&mut self.preset_id, Some(preset.id.clone()), &preset.name)
+                            .changed();
+                    }
+                });
+
+            if signatures.is_empty() {
+                return;
+            }
+            ui.add_space(10.0);
+            ui.label("Signature:").on_hover_text(
+                "Result signature of preset runs: same problems, test results and generated code",
+            );
+            egui::ComboBox::from_id_salt("trends_signature")
+                .selected_text(self.signature.clone().unwrap_or_else(|| "Any".to_string()))
+                .show_ui(ui, |ui| {
+                    rebuild |= ui.selectable_value(&mut self.signature, None, "Any").changed();
+                    for (sig, count) in &signatures {
+                        rebuild |= ui
+                            .selectable_value(&mut self.signature, Some(sig.clone()), format!("{} ({} runs)", sig, count))
+                            .changed();
+                    }
+                });
+        });
+
+        match (refresh, rebuild) {
+            (true, _) => self.refresh(),
+            (false, true) => self.rebuild_series(),
+            (false, false) => {}
+        }
+    }
+
+    fn render_chart(&self, ui: &mut egui::Ui) {
+        let visible: Vec<(usize, &TrendSeries)> = self
+            .series
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| !self.hidden.contains(&self.series_label(s)))
+            .collect();
+
+        let (response, painter) =
+            ui.allocate_painter(egui::vec2(ui.available_width(), CHART_HEIGHT), egui::Sense::hover());
+        let plot = egui::Rect::from_min_max(
+            response.rect.min + egui::vec2(60.0, 10.0),
+            response.rect.max - egui::vec2(16.0, 28.0),
+        );
+        let visuals = ui.visuals();
+        let axis_color = visuals.weak_text_color();
+        let font = egui::FontId::proportional(11.0);
+        painter.rect_stroke(plot, 0.0, egui::Stroke::new(1.0, visuals.widgets.noninteractive.bg_stroke.color));
+
+        let points = || visible.iter().flat_map(|(_, s)| s.points.iter());
+        let (Some(t_min), Some(t_max)) = (points().map(|p| p.timestamp).min(), points().map(|p| p.timestamp).max()) else {
+            return;
+        };
+        // A single day of padding keeps lone runs off the edges
+        let (t_min, t_max) = match t_min == t_max {
+            true => (t_min - 86_400, t_max + 86_400),
+            false => (t_min, t_max),
+        };
+        let v_max = match self.metric {
+            TrendMetric::PassRate => 100.0,
+            _ => points().map(|p| p.value).fold(0.0, f64::max) * 1.1,
+        };
+        let v_max = match v_max > 0.0 {
+            true => v_max,
+            false => 1.0,
+        };
+
+        let to_screen = |timestamp: i64, value: f64| {
+            let x = (timestamp - t_min) as f64 / (t_max - t_min) as f64;
+            egui::pos2(
+                plot.left() + x as f32 * plot.width(),
+                plot.bottom() - (value / v_max) as f32 * plot.height(),
+            )
+        };
+
+        // Value grid
+        for step in 0..=4 {
+            let value = v_max * step as f64 / 4.0;
+            let y = to_screen(t_min, value).y;
+            painter.hline(plot.x_range(), y, egui::Stroke::new(0.5, axis_color.gamma_multiply(0.4)));
+            painter.text(
+                egui::pos2(plot.left() - 6.0, y),
+                egui::Align2::RIGHT_CENTER,
+                self.format_value(value),
+                font.clone(),
+                axis_color,
+            );
+        }
+
+        // Date axis
+        for step in 0..=3 {
+            let timestamp = t_min + (t_max - t_min) * step / 3;
+            let x = to_screen(timestamp, 0.0).x;
+            let align = match step {
+                0 => egui::Align2::LEFT_TOP,
+                3 => egui::Align2::RIGHT_TOP,
+                _ => egui::Align2::CENTER_TOP,
+            };
+            let date: String = format_utc(timestamp).chars().take(10).collect();
+            painter.text(egui::pos2(x, plot.bottom() + 6.0), align, date, font.clone(), axis_color);
+        }
+
+        let mut hovered: Option<(f32, &TrendSeries, &TrendPoint)> = None;
+        let pointer = response.hover_pos();
+
+        for (idx, series) in &visible {
+            let color = SERIES_COLORS[idx % SERIES_COLORS.len()];
+            let line: Vec<egui::Pos2> = series.points.iter().map(|p| to_screen(p.timestamp, p.value)).collect();
+            painter.add(egui::Shape::line(line.clone(), egui::Stroke::new(1.5, color)));
+
+            for (point, pos) in series.points.iter().zip(&line) {
+                // Environment changed since the model's previous run (Ollama upgrade, new driver...)
+                if !point.environment_changes.is_empty() {
+                    painter.add(egui::Shape::dashed_line(
+                        &[egui::pos2(pos.x, plot.top()), egui::pos2(pos.x, plot.bottom())],
+                        egui::Stroke::new(1.0, color.gamma_multiply(0.5)),
+                        4.0,
+                        4.0,
+                    ));
+                }
+                if point.baseline {
+                    painter.add(egui::Shape::dashed_line(
+                        &[egui::pos2(plot.left(), pos.y), egui::pos2(plot.right(), pos.y)],
+                        egui::Stroke::new(1.0, color.gamma_multiply(0.6)),
+                        6.0,
+                        4.0,
+                    ));
+                    painter.circle_stroke(*pos, 6.0, egui::Stroke::new(2.0, color));
+                }
+                painter.circle_filled(*pos, 3.0, color);
+
+                let Some(pointer) = pointer else {
+                    continue;
+                };
+                let distance = pointer.distance(*pos);
+                if distance < 8.0 && hovered.is_none_or(|(best, _, _)| distance < best) {
+                    hovered = Some((distance, series, point));
+                }
+            }
+        }
+
+        let Some((_, series, point)) = hovered else {
+            return;
+        };
+        let label = self.series_label(series);
+        let value = self.format_value(point.value);
+        response.on_hover_ui_at_pointer(|ui| {
+            ui.label(egui::RichText::new(label).strong());
+            ui.label(format!("{}: {}", self.metric.label(), value));
+            ui.label(format_utc(point.timestamp));
+            ui.weak(format!("Run {}", point.run_id.chars().take(8).collect::<String>()));
+            if point.baseline {
+                ui.label("📌 Baseline");
+            }
+            for change in &point.environment_changes {
+                ui.colored_label(egui::Color32::from_rgb(255, 200, 100), change);
+            }
+        });
+    }
+
+    fn render_legend(&mut self, ui: &mut egui::Ui) {
+        let labels: Vec<String> = self.series.iter().map(|s| self.series_label(s)).collect();
+        ui.horizontal_wrapped(|ui| {
+            for (idx, label) in labels.into_iter().enumerate() {
+                let color = SERIES_COLORS[idx % SERIES_COLORS.len()];
+                let mut shown = !self.hidden.contains(&label);
+                if ui.checkbox(&mut shown, egui::RichText::new(&label).color(color)).changed() {
+                    match shown {
+                        true => self.hidden.remove(&label),
+                        false => self.hidden.insert(label),
+                    };
+                }
+            }
+        });
+        ui.weak("Ringed points are pinned baselines (dashed line at their value); dashed verticals mark environment changes");
+    }
+
+    fn format_value(&self, value: f64) -> String {
+        match self.metric {
+            TrendMetric::Tps => format!("{:.1}", value),
+            TrendMetric::Ttft => format!("{:.0}ms", value),
+            TrendMetric::PassRate => format!("{:.0}%", value),
+            TrendMetric::Rtf => format!("{:.3}x", value),
+            TrendMetric::Wer => format!("{:.1}%", value),
+        }
+    }
+}
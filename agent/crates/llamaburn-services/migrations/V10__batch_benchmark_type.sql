@@ -0,0 +1,3 @@
+-- Resumable batches for text and STT benchmarks, not just code.
+-- pending_combos/failed_combo hold the combo payload for the batch's benchmark type.
+ALTER TABLE batch_state ADD COLUMN benchmark_type TEXT NOT NULL DEFAULT '"Code"';
@@ -0,0 +1,194 @@
+-- Normalized, typed copies of benchmark_history so filtering and aggregation run in SQL.
+-- benchmark_history keeps the full JSON (generated code, test output); these tables hold the
+-- queryable numbers. Enum columns use the same JSON-encoded strings as benchmark_history.
+
+-- One row per benchmark_history entry
+CREATE TABLE runs (
+    id TEXT PRIMARY KEY,
+    timestamp INTEGER NOT NULL,
+    benchmark_type TEXT NOT NULL,
+    model_id TEXT NOT NULL,
+    session_id TEXT,
+    status TEXT NOT NULL DEFAULT 'success',
+    preset_id TEXT,
+    -- Config
+    language TEXT,
+    audio_mode TEXT,
+    whisper_model TEXT,
+    prompt_set TEXT,
+    temperature REAL,
+    max_tokens INTEGER,
+    iterations INTEGER,
+    -- Text summary (avg_tps/avg_ttft_ms are also set for code runs)
+    avg_tps REAL,
+    min_tps REAL,
+    max_tps REAL,
+    avg_ttft_ms REAL,
+    avg_total_ms REAL,
+    -- Code summary
+    pass_rate REAL,
+    problems_solved INTEGER,
+    problems_total INTEGER,
+    avg_execution_time_ms REAL,
+    total_output_tokens INTEGER,
+    -- Audio summary
+    avg_rtf REAL,
+    min_rtf REAL,
+    max_rtf REAL,
+    avg_processing_ms REAL
+);
+
+CREATE INDEX idx_runs_timestamp ON runs(timestamp DESC);
+CREATE INDEX idx_runs_type_model ON runs(benchmark_type, model_id);
+CREATE INDEX idx_runs_language ON runs(language);
+CREATE INDEX idx_runs_session ON runs(session_id);
+
+-- Text benchmark iterations
+CREATE TABLE iterations (
+    run_id TEXT NOT NULL,
+    iteration INTEGER NOT NULL,
+    ttft_ms REAL,
+    inter_token_latency_ms REAL,
+    tokens_per_sec REAL,
+    total_generation_ms REAL,
+    prompt_eval_ms REAL,
+    load_duration_ms REAL,
+    input_tokens INTEGER,
+    output_tokens INTEGER,
+    power_draw_watts REAL,
+    energy_wh REAL,
+    PRIMARY KEY (run_id, iteration)
+);
+
+-- Code benchmark results, one row per problem in the run
+CREATE TABLE code_problem_results (
+    run_id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    problem_id TEXT NOT NULL,
+    difficulty TEXT,
+    passed INTEGER,
+    tests_passed INTEGER,
+    tests_total INTEGER,
+    ttft_ms REAL,
+    tokens_per_sec REAL,
+    execution_time_ms REAL,
+    test_execution_time_ms REAL,
+    output_tokens INTEGER,
+    prompt_tokens INTEGER,
+    compilation_error TEXT,
+    runtime_error TEXT,
+    cpu_time_ms REAL,
+    peak_rss_kb INTEGER,
+    slowdown REAL,
+    PRIMARY KEY (run_id, position)
+);
+
+CREATE INDEX idx_code_results_problem ON code_problem_results(problem_id);
+
+-- Audio benchmark iterations
+CREATE TABLE audio_iterations (
+    run_id TEXT NOT NULL,
+    iteration INTEGER NOT NULL,
+    real_time_factor REAL,
+    processing_time_ms REAL,
+    audio_duration_ms REAL,
+    word_count INTEGER,
+    transcription TEXT,
+    PRIMARY KEY (run_id, iteration)
+);
+
+-- Backfill. Corrupt config/summary JSON reads as '{}' so every history row still gets a run.
+INSERT INTO runs (
+    id, timestamp, benchmark_type, model_id, session_id, status, preset_id,
+    language, audio_mode, whisper_model, prompt_set, temperature, max_tokens, iterations,
+    avg_tps, min_tps, max_tps, avg_ttft_ms, avg_total_ms,
+    pass_rate, problems_solved, problems_total, avg_execution_time_ms, total_output_tokens,
+    avg_rtf, min_rtf, max_rtf, avg_processing_ms
+)
+SELECT
+    h.id, h.timestamp, h.benchmark_type, h.model_id, h.session_id, h.status, h.preset_id,
+    h.language, h.audio_mode,
+    NULLIF(h.config -> '$.model_size', 'null'),
+    json_extract(h.config, '$.prompt_set'),
+    json_extract(h.config, '$.temperature'),
+    json_extract(h.config, '$.max_tokens'),
+    COALESCE(json_extract(h.summary, '$.iterations'), json_extract(h.config, '$.iterations')),
+    json_extract(h.summary, '$.avg_tps'),
+    json_extract(h.summary, '$.min_tps'),
+    json_extract(h.summary, '$.max_tps'),
+    json_extract(h.summary, '$.avg_ttft_ms'),
+    json_extract(h.summary, '$.avg_total_ms'),
+    json_extract(h.summary, '$.pass_rate'),
+    json_extract(h.summary, '$.problems_solved'),
+    json_extract(h.summary, '$.problems_total'),
+    json_extract(h.summary, '$.avg_execution_time_ms'),
+    json_extract(h.summary, '$.total_output_tokens'),
+    json_extract(h.summary, '$.avg_rtf'),
+    json_extract(h.summary, '$.min_rtf'),
+    json_extract(h.summary, '$.max_rtf'),
+    json_extract(h.summary, '$.avg_processing_ms')
+FROM (
+    SELECT *,
+        CASE WHEN json_valid(config_json) THEN config_json ELSE '{}' END AS config,
+        CASE WHEN json_valid(summary_json) THEN summary_json ELSE '{}' END AS summary
+    FROM benchmark_history
+) AS h;
+
+INSERT INTO iterations (
+    run_id, iteration, ttft_ms, inter_token_latency_ms, tokens_per_sec, total_generation_ms,
+    prompt_eval_ms, load_duration_ms, input_tokens, output_tokens, power_draw_watts, energy_wh
+)
+SELECT
+    h.id, m.key + 1,
+    json_extract(m.value, '$.time_to_first_token_ms'),
+    json_extract(m.value, '$.inter_token_latency_ms'),
+    json_extract(m.value, '$.tokens_per_sec'),
+    json_extract(m.value, '$.total_generation_ms'),
+    json_extract(m.value, '$.prompt_eval_ms'),
+    json_extract(m.value, '$.load_duration_ms'),
+    json_extract(m.value, '$.input_sequence_length'),
+    json_extract(m.value, '$.output_sequence_length'),
+    json_extract(m.value, '$.power_draw_watts'),
+    json_extract(m.value, '$.energy_wh')
+FROM benchmark_history AS h, json_each(h.metrics_json) AS m
+WHERE h.benchmark_type = '"Text"' AND json_valid(h.metrics_json);
+
+INSERT INTO code_problem_results (
+    run_id, position, problem_id, difficulty, passed, tests_passed, tests_total,
+    ttft_ms, tokens_per_sec, execution_time_ms, test_execution_time_ms, output_tokens,
+    prompt_tokens, compilation_error, runtime_error, cpu_time_ms, peak_rss_kb, slowdown
+)
+SELECT
+    h.id, m.key,
+    json_extract(m.value, '$.problem_id'),
+    NULLIF(m.value -> '$.difficulty', 'null'),
+    json_extract(m.value, '$.tests_passed') = json_extract(m.value, '$.tests_total'),
+    json_extract(m.value, '$.tests_passed'),
+    json_extract(m.value, '$.tests_total'),
+    json_extract(m.value, '$.ttft_ms'),
+    json_extract(m.value, '$.tokens_per_sec'),
+    json_extract(m.value, '$.execution_time_ms'),
+    json_extract(m.value, '$.test_execution_time_ms'),
+    json_extract(m.value, '$.output_tokens'),
+    json_extract(m.value, '$.prompt_tokens'),
+    json_extract(m.value, '$.compilation_error'),
+    json_extract(m.value, '$.runtime_error'),
+    json_extract(m.value, '$.cpu_time_ms'),
+    json_extract(m.value, '$.peak_rss_kb'),
+    json_extract(m.value, '$.slowdown')
+FROM benchmark_history AS h, json_each(h.metrics_json) AS m
+WHERE h.benchmark_type = '"Code"' AND json_valid(h.metrics_json);
+
+INSERT INTO audio_iterations (
+    run_id, iteration, real_time_factor, processing_time_ms, audio_duration_ms,
+    word_count, transcription
+)
+SELECT
+    h.id, m.key + 1,
+    json_extract(m.value, '$.real_time_factor'),
+    json_extract(m.value, '$.processing_time_ms'),
+    json_extract(m.value, '$.audio_duration_ms'),
+    json_extract(m.value, '$.word_count'),
+    json_extract(m.value, '$.transcription')
+FROM benchmark_history AS h, json_each(h.metrics_json) AS m
+WHERE h.benchmark_type = '"Audio"' AND json_valid(h.metrics_json);
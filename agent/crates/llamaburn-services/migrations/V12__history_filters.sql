@@ -0,0 +1,18 @@
+-- Problem tags per code run, for filtering history by tag
+CREATE TABLE run_tags (
+    run_id TEXT NOT NULL,
+    tag TEXT NOT NULL,
+    PRIMARY KEY (run_id, tag)
+);
+
+CREATE INDEX idx_run_tags_tag ON run_tags(tag);
+
+INSERT OR IGNORE INTO run_tags (run_id, tag)
+SELECT h.id, t.value
+FROM benchmark_history AS h, json_each(h.metrics_json) AS m, json_each(m.value, '$.tags') AS t
+WHERE h.benchmark_type = '"Code"' AND json_valid(h.metrics_json);
+
+-- Indexes for the remaining history filters
+CREATE INDEX idx_runs_status ON runs(status);
+CREATE INDEX idx_runs_preset ON runs(preset_id);
+CREATE INDEX idx_runs_whisper_model ON runs(whisper_model);
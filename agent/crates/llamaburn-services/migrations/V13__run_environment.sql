@@ -0,0 +1,2 @@
+-- Environment fingerprint (Ollama version, model digest, GPU, host, sampling options) per run
+ALTER TABLE benchmark_history ADD COLUMN environment_json TEXT;
@@ -0,0 +1,20 @@
+-- Pinned baseline runs, one per benchmark config. config_key is computed from the run's
+-- settings (model, prompt set / problems, sampling) so re-runs of the same config find it.
+CREATE TABLE baselines (
+    config_key TEXT PRIMARY KEY,
+    run_id TEXT NOT NULL,
+    benchmark_type TEXT NOT NULL,
+    pinned_at INTEGER NOT NULL
+);
+
+CREATE INDEX idx_baselines_run ON baselines(run_id);
+
+-- Each new run compared with the baseline pinned for its config when it was saved
+CREATE TABLE baseline_comparisons (
+    run_id TEXT PRIMARY KEY,
+    baseline_id TEXT NOT NULL,
+    regressed INTEGER NOT NULL,
+    report_json TEXT NOT NULL
+);
+
+CREATE INDEX idx_baseline_comparisons_regressed ON baseline_comparisons(regressed);
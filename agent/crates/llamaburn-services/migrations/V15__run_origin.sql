@@ -0,0 +1,2 @@
+-- Provenance of runs imported from another machine's bundle (NULL = recorded here)
+ALTER TABLE benchmark_history ADD COLUMN origin_json TEXT;
@@ -0,0 +1,33 @@
+-- Full-text index over generated code, STT transcriptions and LLM effect descriptions.
+-- source is the JSON-encoded SearchSource; run_id points at the run (label names the problem
+-- or iteration), effect_id at effect_detection_history.
+
+-- LLM blind-analysis output wasn't stored before
+ALTER TABLE effect_detection_history ADD COLUMN llm_description TEXT;
+ALTER TABLE effect_detection_history ADD COLUMN llm_model_used TEXT;
+
+CREATE VIRTUAL TABLE history_fts USING fts5(
+    content,
+    source UNINDEXED,
+    run_id UNINDEXED,
+    label UNINDEXED,
+    effect_id UNINDEXED,
+    tokenize = 'unicode61'
+);
+
+-- Backfill generated code
+INSERT INTO history_fts (content, source, run_id, label)
+SELECT
+    json_extract(m.value, '$.generated_code'),
+    '"generated_code"',
+    h.id,
+    json_extract(m.value, '$.problem_id')
+FROM benchmark_history AS h, json_each(h.metrics_json) AS m
+WHERE h.benchmark_type = '"Code"' AND json_valid(h.metrics_json)
+    AND COALESCE(json_extract(m.value, '$.generated_code'), '') <> '';
+
+-- Backfill transcriptions from the normalized iterations
+INSERT INTO history_fts (content, source, run_id, label)
+SELECT transcription, '"transcription"', run_id, 'Iteration ' || iteration
+FROM audio_iterations
+WHERE COALESCE(transcription, '') <> '';
@@ -0,0 +1,12 @@
+-- STT accuracy against a reference transcript. runs holds the pooled WER/CER of an audio run;
+-- audio_iterations the per-iteration rates and word-level edit counts. Earlier runs had no
+-- reference, so they stay NULL.
+
+ALTER TABLE runs ADD COLUMN wer REAL;
+ALTER TABLE runs ADD COLUMN cer REAL;
+
+ALTER TABLE audio_iterations ADD COLUMN wer REAL;
+ALTER TABLE audio_iterations ADD COLUMN cer REAL;
+ALTER TABLE audio_iterations ADD COLUMN substitutions INTEGER;
+ALTER TABLE audio_iterations ADD COLUMN insertions INTEGER;
+ALTER TABLE audio_iterations ADD COLUMN deletions INTEGER;
@@ -0,0 +1,7 @@
+-- STT dataset runs store one audio_iterations row per clip, with the clip's audio file,
+-- reference transcript, speaker and language. NULL for single-file runs.
+
+ALTER TABLE audio_iterations ADD COLUMN clip_path TEXT;
+ALTER TABLE audio_iterations ADD COLUMN reference TEXT;
+ALTER TABLE audio_iterations ADD COLUMN speaker TEXT;
+ALTER TABLE audio_iterations ADD COLUMN language TEXT;
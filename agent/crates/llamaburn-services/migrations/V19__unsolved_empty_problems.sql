@@ -0,0 +1,3 @@
+-- A problem that ran no tests isn't solved. Earlier rows marked 0/0 results as passed.
+
+UPDATE code_problem_results SET passed = 0 WHERE tests_total = 0 OR tests_total IS NULL;
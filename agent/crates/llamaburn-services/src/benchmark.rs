@@ -10,15 +10,50 @@ use tracing::{debug, error, info, instrument};
 use crate::runners::{BenchmarkEvent, BenchmarkRunner};
 use llamaburn_core::TextBenchmarkConfig;
 
-/// Default prompts for text benchmarks
-const DEFAULT_PROMPTS: &[&str] = &[
-    "Explain the concept of recursion in programming.",
-    "What are the benefits of functional programming?",
-    "Describe how a hash table works.",
-    "What is the difference between a stack and a queue?",
-    "Explain the CAP theorem in distributed systems.",
+/// Named prompt sets for text benchmarks; the first is the default
+const PROMPT_SETS: &[(&str, &[&str])] = &[
+    (
+        "default",
+        &[
+            "Explain the concept of recursion in programming.",
+            "What are the benefits of functional programming?",
+            "Describe how a hash table works.",
+            "What is the difference between a stack and a queue?",
+            "Explain the CAP theorem in distributed systems.",
+        ],
+    ),
+    (
+        "short",
+        &[
+            "Name three primary colors.",
+            "What is the capital of Japan?",
+            "Give a synonym for 'quick'.",
+            "How many days are in a leap year?",
+            "What does CPU stand for?",
+        ],
+    ),
+    (
+        "long-form",
+        &[
+            "Write a detailed tutorial on building a REST API with validation and tests.",
+            "Write an 800-word story about a lighthouse keeper who finds a message.",
+            "Compare three sorting algorithms in depth, with example code for each.",
+        ],
+    ),
 ];
 
+/// Prompts of the named set, falling back to the default set
+fn prompts_for(prompt_set: &str) -> Vec<String> {
+    PROMPT_SETS
+        .iter()
+        .find(|(name, _)| *name == prompt_set)
+        .unwrap_or(&PROMPT_SETS[0])
+        .1
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
 /// Stateless benchmark service - operates on models via &mut references
 pub struct BenchmarkService {
     ollama_host: String,
@@ -35,6 +70,11 @@ impl BenchmarkService {
         Self::new("http://localhost:11434")
     }
 
+    /// Names of the built-in prompt sets
+    pub fn prompt_sets() -> Vec<&'static str> {
+        PROMPT_SETS.iter().map(|(name, _)| *name).collect()
+    }
+
     /// Start a streaming benchmark run
     #[instrument(skip(self, config), fields(model = %config.model_id, iterations = config.iterations))]
     pub fn run_streaming(
@@ -64,7 +104,7 @@ impl BenchmarkService {
                 let runner = BenchmarkRunner::new(&host);
                 let (tokio_tx, mut tokio_rx) = tokio_mpsc::channel(100);
 
-                let prompts: Vec<String> = DEFAULT_PROMPTS.iter().map(|s| s.to_string()).collect();
+                let prompts = prompts_for(&config.prompt_set);
 
                 let runner_cancel = (*cancel_clone).clone();
                 tokio::spawn(async move {
@@ -0,0 +1,556 @@
+//! STT datasets: clips and reference transcripts from a directory or manifest, and the
+//! corpus-level statistics of a dataset run
+
+use std::path::{Path, PathBuf};
+
+use llamaburn_core::{AudioBenchmarkMetrics, DatasetClip, TranscriptAccuracy};
+use serde_json::Value;
+
+use crate::whisper::load_reference_transcript;
+
+const AUDIO_EXTENSIONS: &[&str] = &["wav", "mp3", "flac", "m4a", "ogg"];
+
+/// Common Voice splits, in the order one is picked from a release directory
+const COMMON_VOICE_SPLITS: &[&str] = &["test.tsv", "dev.tsv", "validated.tsv", "train.tsv"];
+
+// Manifest field names, first match wins
+const PATH_KEYS: &[&str] = &["audio_filepath", "audio_path", "audio", "path", "file", "filename"];
+const TEXT_KEYS: &[&str] = &["text", "reference", "transcript", "transcription", "sentence"];
+const SPEAKER_KEYS: &[&str] = &["speaker", "speaker_id", "client_id"];
+const LANGUAGE_KEYS: &[&str] = &["language", "lang", "locale"];
+
+#[derive(Debug, thiserror::Error)]
+pub enum DatasetError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("{path}, line {line}: {message}")]
+    Parse {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+    #[error("No audio clips found in {0}")]
+    Empty(PathBuf),
+}
+
+/// Line number and message of a manifest parse error
+type LineError = (usize, String);
+
+/// How a dataset lays out its clips and transcripts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatasetLayout {
+    /// `speaker/chapter/*.flac` with a `speaker-chapter.trans.txt` per chapter
+    LibriSpeech,
+    /// A split TSV (`test.tsv`, `validated.tsv`, ...) with `path` and `sentence` columns;
+    /// audio in `clips/` next to it
+    CommonVoice,
+    /// One JSON object per line
+    Jsonl,
+    /// CSV with a header row, one clip per line
+    Csv,
+    /// Audio files with `.txt` transcripts next to them
+    Directory,
+}
+
+impl DatasetLayout {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DatasetLayout::LibriSpeech => "LibriSpeech",
+            DatasetLayout::CommonVoice => "Common Voice",
+            DatasetLayout::Jsonl => "JSONL manifest",
+            DatasetLayout::Csv => "CSV manifest",
+            DatasetLayout::Directory => "Audio directory",
+        }
+    }
+}
+
+/// A loaded dataset
+#[derive(Debug, Clone)]
+pub struct SttDataset {
+    /// The directory or manifest file it was loaded from
+    pub source: PathBuf,
+    pub layout: DatasetLayout,
+    pub clips: Vec<DatasetClip>,
+}
+
+impl SttDataset {
+    /// Clips that have a reference transcript to score against
+    pub fn referenced(&self) -> usize {
+        self.clips.iter().filter(|c| !c.reference.trim().is_empty()).count()
+    }
+}
+
+/// Load a dataset from a manifest (`.jsonl`, `.csv`, Common Voice `.tsv`) or a directory
+/// (Common Voice release, LibriSpeech tree, or audio files with `.txt` sidecars).
+/// Relative audio paths are resolved against the manifest's directory.
+pub fn load_dataset(path: &Path) -> Result<SttDataset, DatasetError> {
+    let (layout, clips) = match path.is_dir() {
+        true => load_directory(path)?,
+        false => load_manifest(path)?,
+    };
+    if clips.is_empty() {
+        return Err(DatasetError::Empty(path.to_path_buf()));
+    }
+    Ok(SttDataset {
+        source: path.to_path_buf(),
+        layout,
+        clips,
+    })
+}
+
+fn load_manifest(path: &Path) -> Result<(DatasetLayout, Vec<DatasetClip>), DatasetError> {
+    let content = std::fs::read_to_string(path)?;
+    let base = path.parent().unwrap_or(Path::new(""));
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    let (layout, parsed) = match extension.as_str() {
+        "jsonl" | "json" => (DatasetLayout::Jsonl, parse_jsonl(&content, base)),
+        "tsv" => {
+            let clips_dir = base.join("clips");
+            let audio_dir = match clips_dir.is_dir() {
+                true => clips_dir,
+                false => base.to_path_buf(),
+            };
+            (DatasetLayout::CommonVoice, parse_delimited(&content, '\t', &audio_dir))
+        }
+        _ => (DatasetLayout::Csv, parse_delimited(&content, ',', base)),
+    };
+    let clips = parsed.map_err(|(line, message)| DatasetError::Parse {
+        path: path.to_path_buf(),
+        line,
+        message,
+    })?;
+    Ok((layout, clips))
+}
+
+fn load_directory(dir: &Path) -> Result<(DatasetLayout, Vec<DatasetClip>), DatasetError> {
+    if let Some(split) = COMMON_VOICE_SPLITS.iter().map(|s| dir.join(s)).find(|p| p.is_file()) {
+        return load_manifest(&split);
+    }
+
+    let files = files_under(dir)?;
+    let transcripts: Vec<&PathBuf> = files
+        .iter()
+        .filter(|p| p.to_string_lossy().ends_with(".trans.txt"))
+        .collect();
+    if !transcripts.is_empty() {
+        let mut clips = Vec::new();
+        for transcript in transcripts {
+            let content = std::fs::read_to_string(transcript)?;
+            clips.extend(parse_librispeech(&content, transcript.parent().unwrap_or(dir)));
+        }
+        return Ok((DatasetLayout::LibriSpeech, clips));
+    }
+
+    let clips = files
+        .into_iter()
+        .filter(|p| is_audio(p))
+        .map(|audio_path| DatasetClip {
+            reference: load_reference_transcript(&audio_path).unwrap_or_default(),
+            audio_path,
+            speaker: None,
+            language: None,
+        })
+        .collect();
+    Ok((DatasetLayout::Directory, clips))
+}
+
+/// Every file below `dir`, sorted
+fn files_under(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in std::fs::read_dir(&dir)?.flatten() {
+            let path = entry.path();
+            match path.is_dir() {
+                true => pending.push(path),
+                false => files.push(path),
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn is_audio(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| AUDIO_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+}
+
+/// LibriSpeech `*.trans.txt`: `<speaker>-<chapter>-<utterance> TEXT` per line, audio as
+/// `<id>.flac` (or a converted `<id>.wav`) in the same directory
+fn parse_librispeech(content: &str, chapter_dir: &Path) -> Vec<DatasetClip> {
+    content
+        .lines()
+        .filter_map(|line| line.trim().split_once(' '))
+        .map(|(id, text)| {
+            let flac = chapter_dir.join(format!("{}.flac", id));
+            let wav = chapter_dir.join(format!("{}.wav", id));
+            DatasetClip {
+                audio_path: match !flac.exists() && wav.exists() {
+                    true => wav,
+                    false => flac,
+                },
+                reference: text.trim().to_string(),
+                speaker: id.split('-').next().map(str::to_string),
+                language: Some("en".to_string()),
+            }
+        })
+        .collect()
+}
+
+/// One JSON object per line, e.g. NeMo's `{"audio_filepath": ..., "text": ...}`
+fn parse_jsonl(content: &str, base: &Path) -> Result<Vec<DatasetClip>, LineError> {
+    let mut clips = Vec::new();
+    for (idx, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let value: Value = serde_json::from_str(line).map_err(|e| (idx + 1, e.to_string()))?;
+        let field = |keys: &[&str]| {
+            let value = keys.iter().find_map(|k| value.get(*k))?;
+            let text = match value {
+                Value::String(s) => s.trim().to_string(),
+                Value::Number(n) => n.to_string(),
+                _ => return None,
+            };
+            Some(text).filter(|t| !t.is_empty())
+        };
+        let Some(audio) = field(PATH_KEYS) else {
+            return Err((idx + 1, "no audio path (audio_filepath, audio or path)".to_string()));
+        };
+        clips.push(DatasetClip {
+            audio_path: base.join(audio),
+            reference: field(TEXT_KEYS).unwrap_or_default(),
+            speaker: field(SPEAKER_KEYS),
+            language: field(LANGUAGE_KEYS),
+        });
+    }
+    Ok(clips)
+}
+
+/// CSV or TSV with a header row naming the columns
+fn parse_delimited(
+    content: &str,
+    delimiter: char,
+    audio_dir: &Path,
+) -> Result<Vec<DatasetClip>, LineError> {
+    let mut lines = content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    let Some((header_idx, header)) = lines.next() else {
+        return Ok(Vec::new());
+    };
+    let header: Vec<String> = split_row(header, delimiter)
+        .iter()
+        .map(|h| h.trim().trim_start_matches('\u{feff}').to_lowercase())
+        .collect();
+    let column = |keys: &[&str]| keys.iter().find_map(|k| header.iter().position(|h| h == k));
+    let Some(path_column) = column(PATH_KEYS) else {
+        let message = format!("no audio path column in header ({})", header.join(", "));
+        return Err((header_idx + 1, message));
+    };
+    let (text_column, speaker_column, language_column) =
+        (column(TEXT_KEYS), column(SPEAKER_KEYS), column(LANGUAGE_KEYS));
+
+    let mut clips = Vec::new();
+    for (idx, line) in lines {
+        let row = split_row(line, delimiter);
+        let cell = |column: Option<usize>| {
+            let text = row.get(column?)?.trim();
+            Some(text.to_string()).filter(|t| !t.is_empty())
+        };
+        let Some(audio) = cell(Some(path_column)) else {
+            return Err((idx + 1, "empty audio path".to_string()));
+        };
+        clips.push(DatasetClip {
+            audio_path: audio_dir.join(audio),
+            reference: cell(text_column).unwrap_or_default(),
+            speaker: cell(speaker_column),
+            language: cell(language_column),
+        });
+    }
+    Ok(clips)
+}
+
+/// Split one row. CSV fields may be double-quoted with `""` escapes; TSV fields are taken
+/// as-is, since Common Voice sentences contain bare quotes.
+fn split_row(line: &str, delimiter: char) -> Vec<String> {
+    if delimiter == '\t' {
+        return line.split('\t').map(str::to_string).collect();
+    }
+
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', true) => quoted = false,
+            ('"', false) if field.trim().is_empty() => {
+                field.clear();
+                quoted = true;
+            }
+            (c, false) if c == delimiter => fields.push(std::mem::take(&mut field)),
+            (c, _) => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+// =============================================================================
+// Dataset run statistics
+// =============================================================================
+
+/// Percentiles of per-clip real-time factor
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RtfDistribution {
+    pub min: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+/// Why a clip stands out from the rest of the dataset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutlierKind {
+    Wer,
+    Rtf,
+}
+
+impl OutlierKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            OutlierKind::Wer => "WER",
+            OutlierKind::Rtf => "RTF",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClipOutlier {
+    /// Index into the run's metrics
+    pub index: usize,
+    pub kind: OutlierKind,
+    pub value: f64,
+}
+
+/// Corpus-level results of a dataset run
+#[derive(Debug, Clone, PartialEq)]
+pub struct DatasetStats {
+    /// Clips transcribed
+    pub clips: usize,
+    /// Clips that failed to transcribe, left out of every other figure
+    pub failed: usize,
+    /// Clips with a reference transcript
+    pub scored: usize,
+    /// Edit counts pooled over the scored clips, so WER is weighted by clip length
+    pub accuracy: Option<TranscriptAccuracy>,
+    pub audio_ms: f64,
+    pub processing_ms: f64,
+    pub rtf: RtfDistribution,
+    /// Clips beyond the upper Tukey fence (Q3 + 1.5 × IQR), worst first. WER outliers
+    /// must also be at least double the corpus WER.
+    pub outliers: Vec<ClipOutlier>,
+}
+
+impl DatasetStats {
+    /// Total processing time over total audio time
+    pub fn overall_rtf(&self) -> f64 {
+        match self.audio_ms > 0.0 {
+            true => self.processing_ms / self.audio_ms,
+            false => 0.0,
+        }
+    }
+}
+
+/// Statistics of a dataset run's per-clip metrics, plus how many clips failed;
+/// `None` without metrics
+pub fn dataset_stats(metrics: &[AudioBenchmarkMetrics], failed: usize) -> Option<DatasetStats> {
+    if metrics.is_empty() {
+        return None;
+    }
+    let accuracy = TranscriptAccuracy::combine(metrics.iter().filter_map(|m| m.accuracy.as_ref()));
+
+    let mut rtfs: Vec<f64> = metrics.iter().map(|m| m.real_time_factor).collect();
+    rtfs.sort_by(f64::total_cmp);
+    let rtf = RtfDistribution {
+        min: rtfs[0],
+        p50: quantile(&rtfs, 0.5),
+        p90: quantile(&rtfs, 0.9),
+        p99: quantile(&rtfs, 0.99),
+        max: rtfs[rtfs.len() - 1],
+    };
+
+    let wers: Vec<(usize, f64)> = metrics
+        .iter()
+        .enumerate()
+        .filter_map(|(i, m)| Some((i, m.accuracy?.wer())))
+        .collect();
+    let corpus_wer = accuracy.map_or(0.0, |a| a.wer());
+    let mut outliers: Vec<ClipOutlier> = upper_outliers(&wers)
+        .filter(|(_, wer)| *wer >= corpus_wer * 2.0)
+        .map(|(index, value)| ClipOutlier { index, kind: OutlierKind::Wer, value })
+        .collect();
+    let rtf_by_clip: Vec<(usize, f64)> =
+        metrics.iter().map(|m| m.real_time_factor).enumerate().collect();
+    outliers.extend(
+        upper_outliers(&rtf_by_clip)
+            .map(|(index, value)| ClipOutlier { index, kind: OutlierKind::Rtf, value }),
+    );
+    // WER outliers first, each kind worst first
+    outliers.sort_by(|a, b| {
+        (a.kind == OutlierKind::Rtf)
+            .cmp(&(b.kind == OutlierKind::Rtf))
+            .then(b.value.total_cmp(&a.value))
+    });
+
+    Some(DatasetStats {
+        clips: metrics.len(),
+        failed,
+        scored: wers.len(),
+        accuracy,
+        audio_ms: metrics.iter().map(|m| m.audio_duration_ms).sum(),
+        processing_ms: metrics.iter().map(|m| m.processing_time_ms).sum(),
+        rtf,
+        outliers,
+    })
+}
+
+/// Values above Q3 + 1.5 × IQR; needs at least four values to say anything
+fn upper_outliers(values: &[(usize, f64)]) -> impl Iterator<Item = (usize, f64)> + '_ {
+    let mut sorted: Vec<f64> = values.iter().map(|(_, v)| *v).collect();
+    sorted.sort_by(f64::total_cmp);
+    let fence = match sorted.len() >= 4 {
+        true => {
+            let (q1, q3) = (quantile(&sorted, 0.25), quantile(&sorted, 0.75));
+            q3 + 1.5 * (q3 - q1)
+        }
+        false => f64::INFINITY,
+    };
+    values.iter().copied().filter(move |(_, v)| *v > fence)
+}
+
+/// Linear-interpolated quantile of sorted values
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let pos = q * (sorted.len() - 1) as f64;
+    let (lo, hi) = (pos.floor() as usize, pos.ceil() as usize);
+    sorted[lo] + (sorted[hi] - sorted[lo]) * (pos - lo as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_manifest() {
+        let base = Path::new("/data/ds");
+        let jsonl = concat!(
+            r#"{"audio_filepath": "a.wav", "text": "hello world", "speaker": 12}"#,
+            "\n\n",
+            r#"{"audio": "/abs/b.flac", "sentence": "hi", "locale": "de"}"#,
+        );
+        let clips = parse_jsonl(jsonl, base).unwrap();
+        assert_eq!(clips.len(), 2);
+        assert_eq!(clips[0].audio_path, PathBuf::from("/data/ds/a.wav"));
+        assert_eq!(clips[0].reference, "hello world");
+        assert_eq!(clips[0].speaker.as_deref(), Some("12"));
+        assert_eq!(clips[1].audio_path, PathBuf::from("/abs/b.flac"));
+        assert_eq!(clips[1].language.as_deref(), Some("de"));
+        assert_eq!(parse_jsonl(r#"{"text": "x"}"#, base).unwrap_err().0, 1);
+
+        let csv =
+            "Path,Transcript,Speaker\n\"c,1.wav\",\"She said \"\"hi\"\", then left\",s1\nd.wav,,\n";
+        let clips = parse_delimited(csv, ',', base).unwrap();
+        assert_eq!(clips[0].audio_path, PathBuf::from("/data/ds/c,1.wav"));
+        assert_eq!(clips[0].reference, "She said \"hi\", then left");
+        assert_eq!(clips[1].reference, "");
+        assert_eq!(clips[1].speaker, None);
+        assert_eq!(parse_delimited("name,text\nx,y", ',', base).unwrap_err().0, 1);
+
+        let tsv =
+            "client_id\tpath\tsentence\tup_votes\tlocale\nab12\tcv_1.mp3\tHe said \"no\".\t2\tfr\n";
+        let clips = parse_delimited(tsv, '\t', Path::new("/cv/clips")).unwrap();
+        assert_eq!(clips[0].audio_path, PathBuf::from("/cv/clips/cv_1.mp3"));
+        assert_eq!(clips[0].reference, "He said \"no\".");
+        assert_eq!(clips[0].speaker.as_deref(), Some("ab12"));
+        assert_eq!(clips[0].language.as_deref(), Some("fr"));
+    }
+
+    #[test]
+    fn test_librispeech_tree() {
+        let dir = tempfile::tempdir().unwrap();
+        let chapter = dir.path().join("84/121123");
+        std::fs::create_dir_all(&chapter).unwrap();
+        std::fs::write(
+            chapter.join("84-121123.trans.txt"),
+            "84-121123-0000 GO DO YOU HEAR\n84-121123-0001 BUT IN LESS THAN FIVE MINUTES\n",
+        )
+        .unwrap();
+        std::fs::write(chapter.join("84-121123-0000.flac"), b"").unwrap();
+
+        let dataset = load_dataset(dir.path()).unwrap();
+        assert_eq!(dataset.layout, DatasetLayout::LibriSpeech);
+        assert_eq!(dataset.clips.len(), 2);
+        assert_eq!(dataset.clips[0].audio_path, chapter.join("84-121123-0000.flac"));
+        assert_eq!(dataset.clips[1].reference, "BUT IN LESS THAN FIVE MINUTES");
+        assert_eq!(dataset.clips[1].speaker.as_deref(), Some("84"));
+        assert_eq!(dataset.referenced(), 2);
+
+        let empty = tempfile::tempdir().unwrap();
+        assert!(matches!(load_dataset(empty.path()), Err(DatasetError::Empty(_))));
+    }
+
+    fn clip(rtf: f64, reference_words: u32, errors: u32) -> AudioBenchmarkMetrics {
+        AudioBenchmarkMetrics {
+            real_time_factor: rtf,
+            processing_time_ms: rtf * 1000.0,
+            audio_duration_ms: 1000.0,
+            transcription: String::new(),
+            word_count: reference_words,
+            accuracy: Some(TranscriptAccuracy {
+                reference_words,
+                substitutions: errors,
+                reference_chars: reference_words * 5,
+                char_errors: errors * 2,
+                ..Default::default()
+            }),
+            clip: None,
+        }
+    }
+
+    #[test]
+    fn test_dataset_stats() {
+        let mut metrics: Vec<AudioBenchmarkMetrics> =
+            (0..8).map(|i| clip(0.10 + i as f64 * 0.01, 10, 1)).collect();
+        metrics.push(clip(0.12, 10, 9));
+        metrics.push(clip(0.90, 30, 0));
+
+        let stats = dataset_stats(&metrics, 2).unwrap();
+        assert_eq!((stats.clips, stats.scored, stats.failed), (10, 10, 2));
+        let accuracy = stats.accuracy.unwrap();
+        assert_eq!((accuracy.reference_words, accuracy.errors()), (120, 17));
+        assert!((stats.rtf.p50 - 0.135).abs() < 1e-9);
+        assert_eq!(stats.rtf.max, 0.90);
+        assert!((stats.overall_rtf() - stats.processing_ms / 10_000.0).abs() < 1e-9);
+        assert_eq!(
+            stats.outliers.iter().map(|o| (o.index, o.kind)).collect::<Vec<_>>(),
+            [(8, OutlierKind::Wer), (9, OutlierKind::Rtf)]
+        );
+
+        assert!(dataset_stats(&[], 0).is_none());
+        assert!(dataset_stats(&metrics[..3], 0).unwrap().outliers.is_empty());
+    }
+}
@@ -0,0 +1,85 @@
+//! Line diff for comparing generated solutions
+
+/// One line of a diff from `old` to `new`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    Same(String),
+    Removed(String),
+    Added(String),
+}
+
+/// Line diff from `old` to `new` via longest common subsequence; changed blocks list
+/// removals before additions
+pub fn line_diff(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // lcs[i][j]: length of the LCS of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = match old[i] == new[j] {
+                true => lcs[i + 1][j + 1] + 1,
+                false => lcs[i + 1][j].max(lcs[i][j + 1]),
+            };
+        }
+    }
+
+    let mut diff = Vec::with_capacity(old.len().max(new.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            diff.push(DiffLine::Same(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            diff.push(DiffLine::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    diff.extend(old[i..].iter().map(|l| DiffLine::Removed(l.to_string())));
+    diff.extend(new[j..].iter().map(|l| DiffLine::Added(l.to_string())));
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_diff() {
+        let old = "def f(xs):\n    total = 0\n    for x in xs:\n        total += x\n    return total";
+        let new = "def f(xs):\n    return sum(xs)";
+        assert_eq!(
+            line_diff(old, new),
+            vec![
+                DiffLine::Same("def f(xs):".into()),
+                DiffLine::Removed("    total = 0".into()),
+                DiffLine::Removed("    for x in xs:".into()),
+                DiffLine::Removed("        total += x".into()),
+                DiffLine::Removed("    return total".into()),
+                DiffLine::Added("    return sum(xs)".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_insertions_keep_context() {
+        let diff = line_diff("a\nb\nc", "a\nx\nb\nc\ny");
+        assert_eq!(
+            diff,
+            vec![
+                DiffLine::Same("a".into()),
+                DiffLine::Added("x".into()),
+                DiffLine::Same("b".into()),
+                DiffLine::Same("c".into()),
+                DiffLine::Added("y".into()),
+            ]
+        );
+        assert!(line_diff("same\ncode", "same\ncode").iter().all(|l| matches!(l, DiffLine::Same(_))));
+        assert_eq!(line_diff("", "new"), vec![DiffLine::Added("new".into())]);
+    }
+}
@@ -1,11 +1,22 @@
 use llamaburn_core::{
     AudioHistoryEntry, AudioMode, BatchState, BatchStatus, BenchmarkHistoryEntry, BenchmarkType,
-    CodeHistoryEntry, EffectDetectionHistoryEntry, EffectDetectionResult, EffectDetectionTool,
-    HistoryFilter, Language, Preset, RunStatus,
+    CodeBenchmarkConfig, CodeBenchmarkSummary, CodeHistoryEntry, CodeLeaderboard, CodeLeaderboardEntry,
+    EffectDetectionHistoryEntry, EffectDetectionResult, EffectDetectionTool, HistoryBundle,
+    HistoryCursor, HistoryFilter, HistoryPage, HistoryRecord, HistorySort, Language, Preset,
+    RegressionReport, RegressionThresholds, RunOrigin, RunStatus, BundleContents,
+    BundleExportSummary, BundleImportSummary, ProblemSolutions, SearchHit, SearchSource, StoredSolution,
+    HISTORY_BUNDLE_FORMAT, HISTORY_BUNDLE_VERSION,
 };
-use rusqlite::{params, Connection};
-use std::path::PathBuf;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::regression::compare_runs;
+use crate::runners::host_name;
 use thiserror::Error;
 
 mod embedded {
@@ -22,6 +33,12 @@ pub enum HistoryError {
     Io(#[from] std::io::Error),
     #[error("Migration error: {0}")]
     Migration(#[from] refinery::Error),
+    #[error("History entry not found: {0}")]
+    NotFound(String),
+    #[error("Not a llamaburn history bundle")]
+    NotABundle,
+    #[error("Bundle version {0} is newer than this build supports ({HISTORY_BUNDLE_VERSION})")]
+    UnsupportedBundleVersion(u32),
     #[error("Lock poisoned")]
     LockPoisoned,
 }
@@ -63,6 +80,10 @@ impl HistoryService {
 
         // Drop all tables
         conn.execute("DROP TABLE IF EXISTS benchmark_history", [])?;
+        for table in RUN_TABLES {
+            conn.execute(&format!("DROP TABLE IF EXISTS {}", table), [])?;
+        }
+        conn.execute("DROP TABLE IF EXISTS history_fts", [])?;
         conn.execute("DROP TABLE IF EXISTS settings", [])?;
         conn.execute("DROP TABLE IF EXISTS refinery_schema_history", [])?;
 
@@ -74,107 +95,224 @@ impl HistoryService {
     }
 
     pub fn insert(&self, entry: &BenchmarkHistoryEntry) -> Result<()> {
+        let mut conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
+        let tx = conn.transaction()?;
+        insert_text_entry(&tx, entry)?;
+        record_baseline_comparison(&tx, &HistoryRecord::Text(entry.clone()))?;
+        tx.commit()?;
+
+        tracing::debug!("Saved benchmark history entry: {}", entry.id);
+        Ok(())
+    }
+
+    /// One page of text benchmark entries
+    pub fn list(&self, filter: &HistoryFilter) -> Result<HistoryPage<BenchmarkHistoryEntry>> {
+        let page = self.query_runs(filter, Some(BenchmarkType::Text))?;
+        Ok(map_page(page, text_entry))
+    }
+
+    /// One page of entries of every benchmark type (or `filter.benchmark_type` when set)
+    pub fn list_page(&self, filter: &HistoryFilter) -> Result<HistoryPage<HistoryRecord>> {
+        let page = self.query_runs(filter, filter.benchmark_type)?;
+        Ok(map_page(page, history_record))
+    }
+
+    /// Distinct models with recorded runs
+    pub fn history_models(&self) -> Result<Vec<String>> {
         let conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
+        let mut stmt = conn.prepare("SELECT DISTINCT model_id FROM runs ORDER BY model_id")?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
 
-        let benchmark_type = serde_json::to_string(&entry.benchmark_type)?;
-        let config_json = serde_json::to_string(&entry.config)?;
-        let summary_json = serde_json::to_string(&entry.summary)?;
-        let metrics_json = serde_json::to_string(&entry.metrics)?;
+    /// Distinct problem tags across code runs
+    pub fn history_tags(&self) -> Result<Vec<String>> {
+        let conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
+        let mut stmt = conn.prepare("SELECT DISTINCT tag FROM run_tags ORDER BY tag")?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
 
-        conn.execute(
-            "INSERT INTO benchmark_history (id, timestamp, benchmark_type, model_id, config_json, summary_json, metrics_json)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            params![
-                entry.id,
-                entry.timestamp,
-                benchmark_type,
-                entry.model_id,
-                config_json,
-                summary_json,
-                metrics_json,
-            ],
+    /// Full-text search over generated code, transcriptions and LLM effect descriptions, best
+    /// match first. Every term must match; each is taken literally (`collections.Counter`).
+    pub fn search_text(&self, query: &str, limit: u32) -> Result<Vec<SearchHit>> {
+        let Some(fts) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+        let conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
+        let mut stmt = conn.prepare(
+            "SELECT history_fts.source, history_fts.run_id, history_fts.effect_id, history_fts.label,
+                    snippet(history_fts, 0, char(2), char(3), '…', 16),
+                    COALESCE(r.model_id, e.llm_model_used, ''),
+                    COALESCE(r.timestamp, e.created_at, 0)
+             FROM history_fts
+             LEFT JOIN runs r ON r.id = history_fts.run_id
+             LEFT JOIN effect_detection_history e ON e.id = history_fts.effect_id
+             WHERE history_fts MATCH ?1
+             ORDER BY rank
+             LIMIT ?2",
         )?;
+        let rows = stmt.query_map(params![fts, limit], |row| {
+            let source: String = row.get(0)?;
+            let run_id: Option<String> = row.get(1)?;
+            let effect_detection_id: Option<i64> = row.get(2)?;
+            let label: Option<String> = row.get(3)?;
+            let snippet: String = row.get(4)?;
+            let model_id: String = row.get(5)?;
+            let timestamp: i64 = row.get(6)?;
+
+            Ok((source, run_id, effect_detection_id, label, snippet, model_id, timestamp))
+        })?;
 
-        tracing::debug!("Saved benchmark history entry: {}", entry.id);
-        Ok(())
+        let mut hits = Vec::new();
+        for row in rows {
+            let (source, run_id, effect_detection_id, label, snippet, model_id, timestamp) = row?;
+            hits.push(SearchHit {
+                source: serde_json::from_str(&source)?,
+                run_id,
+                effect_detection_id,
+                model_id,
+                timestamp,
+                label: label.unwrap_or_default(),
+                snippet,
+            });
+        }
+        Ok(hits)
     }
 
-    pub fn list(&self, filter: HistoryFilter) -> Result<Vec<BenchmarkHistoryEntry>> {
+    /// Filter, sort and page the `runs` table; `benchmark_type` overrides the filter's
+    fn query_runs(
+        &self,
+        filter: &HistoryFilter,
+        benchmark_type: Option<BenchmarkType>,
+    ) -> Result<HistoryPage<RunRow>> {
         let conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
 
-        let mut sql = String::from(
-            "SELECT id, timestamp, benchmark_type, model_id, config_json, summary_json, metrics_json
-             FROM benchmark_history WHERE 1=1",
-        );
+        let mut sql = format!("{} WHERE 1=1", run_select());
 
         let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
 
+        if let Some(benchmark_type) = benchmark_type {
+            sql.push_str(" AND r.benchmark_type = ?");
+            params_vec.push(Box::new(serde_json::to_string(&benchmark_type)?));
+        }
+
         if let Some(ref model_id) = filter.model_id {
-            sql.push_str(" AND model_id = ?");
+            sql.push_str(" AND r.model_id = ?");
             params_vec.push(Box::new(model_id.clone()));
         }
 
-        if let Some(ref benchmark_type) = filter.benchmark_type {
-            let type_str = serde_json::to_string(benchmark_type).unwrap_or_default();
-            sql.push_str(" AND benchmark_type = ?");
-            params_vec.push(Box::new(type_str));
+        if let Some(since) = filter.since {
+            sql.push_str(" AND r.timestamp >= ?");
+            params_vec.push(Box::new(since));
+        }
+
+        if let Some(until) = filter.until {
+            sql.push_str(" AND r.timestamp <= ?");
+            params_vec.push(Box::new(until));
         }
 
-        sql.push_str(" ORDER BY timestamp DESC");
+        if let Some(language) = filter.language {
+            sql.push_str(" AND r.language = ?");
+            params_vec.push(Box::new(serde_json::to_string(&language)?));
+        }
 
+        if let Some(status) = filter.status {
+            sql.push_str(" AND r.status = ?");
+            params_vec.push(Box::new(status.as_str()));
+        }
+
+        if let Some(ref preset_id) = filter.preset_id {
+            sql.push_str(" AND r.preset_id = ?");
+            params_vec.push(Box::new(preset_id.clone()));
+        }
+
+        if let Some(ref session_id) = filter.session_id {
+            sql.push_str(" AND r.session_id = ?");
+            params_vec.push(Box::new(session_id.clone()));
+        }
+
+        if let Some(whisper_model) = filter.whisper_model {
+            sql.push_str(" AND r.whisper_model = ?");
+            params_vec.push(Box::new(serde_json::to_string(&whisper_model)?));
+        }
+
+        for tag in &filter.tags {
+            sql.push_str(" AND EXISTS (SELECT 1 FROM run_tags t WHERE t.run_id = r.id AND t.tag = ?)");
+            params_vec.push(Box::new(tag.clone()));
+        }
+
+        if let Some(search) = filter.search.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            sql.push_str(
+                " AND (r.model_id LIKE ? ESCAPE '\\'
+                       OR r.prompt_set LIKE ? ESCAPE '\\'
+                       OR r.session_id LIKE ? ESCAPE '\\'
+                       OR EXISTS (SELECT 1 FROM code_problem_results c
+                                  WHERE c.run_id = r.id AND c.problem_id LIKE ? ESCAPE '\\')
+                       OR EXISTS (SELECT 1 FROM audio_iterations a
+                                  WHERE a.run_id = r.id AND a.transcription LIKE ? ESCAPE '\\'))",
+            );
+            let pattern = like_pattern(search);
+            for _ in 0..5 {
+                params_vec.push(Box::new(pattern.clone()));
+            }
+        }
+
+        // Keyset pagination: continue strictly after the cursor row in sort order
+        let (cursor_sql, order_sql) = match filter.sort {
+            HistorySort::Newest => ("(r.timestamp, r.id) < (?, ?)", "r.timestamp DESC, r.id DESC"),
+            HistorySort::Oldest => ("(r.timestamp, r.id) > (?, ?)", "r.timestamp ASC, r.id ASC"),
+            HistorySort::Model => ("(r.model_id, r.id) > (?, ?)", "r.model_id ASC, r.id ASC"),
+            HistorySort::Score => ("(score, r.id) < (?, ?)", "score DESC, r.id DESC"),
+        };
+
+        if let Some(ref cursor) = filter.cursor {
+            sql.push_str(" AND ");
+            sql.push_str(cursor_sql);
+            match filter.sort {
+                HistorySort::Newest | HistorySort::Oldest => params_vec.push(Box::new(cursor.timestamp)),
+                HistorySort::Model => params_vec.push(Box::new(cursor.model_id.clone())),
+                HistorySort::Score => params_vec.push(Box::new(cursor.score)),
+            }
+            params_vec.push(Box::new(cursor.id.clone()));
+        }
+
+        sql.push_str(" ORDER BY ");
+        sql.push_str(order_sql);
+
+        // Fetch one extra row to know whether another page follows
         if let Some(limit) = filter.limit {
-            sql.push_str(&format!(" LIMIT {}", limit));
+            sql.push_str(" LIMIT ?");
+            params_vec.push(Box::new(limit as i64 + 1));
         }
 
         let mut stmt = conn.prepare(&sql)?;
 
         let params_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
 
-        let rows = stmt.query_map(params_refs.as_slice(), |row| {
-            Ok(RowData {
-                id: row.get(0)?,
-                timestamp: row.get(1)?,
-                benchmark_type: row.get(2)?,
-                model_id: row.get(3)?,
-                config_json: row.get(4)?,
-                summary_json: row.get(5)?,
-                metrics_json: row.get(6)?,
-            })
-        })?;
+        let rows = stmt.query_map(params_refs.as_slice(), run_row)?;
 
-        let mut entries = Vec::new();
-        for row in rows {
-            let row = row?;
-            // Skip rows that fail deserialization (e.g., Audio/Code entries with different schemas)
-            let Ok(config) = serde_json::from_str(&row.config_json) else {
-                continue;
-            };
-            let Ok(summary) = serde_json::from_str(&row.summary_json) else {
-                continue;
-            };
-            let Ok(metrics) = serde_json::from_str(&row.metrics_json) else {
-                continue;
-            };
-            let entry = BenchmarkHistoryEntry {
-                id: row.id,
-                timestamp: row.timestamp,
-                benchmark_type: serde_json::from_str(&row.benchmark_type).unwrap_or_default(),
-                model_id: row.model_id,
-                config,
-                summary,
-                metrics,
-            };
-            entries.push(entry);
-        }
+        let mut entries = rows.collect::<rusqlite::Result<Vec<_>>>()?;
 
-        Ok(entries)
+        let has_more = filter.limit.is_some_and(|limit| entries.len() > limit as usize);
+        if has_more {
+            entries.pop();
+        }
+        let next_cursor = entries.last().filter(|_| has_more).map(|last| HistoryCursor {
+            id: last.id.clone(),
+            timestamp: last.timestamp,
+            model_id: last.model_id.clone(),
+            score: last.score,
+        });
+
+        Ok(HistoryPage { entries, next_cursor, unreadable: Vec::new() })
     }
 
     pub fn get(&self, id: &str) -> Result<Option<BenchmarkHistoryEntry>> {
         let conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
 
         let mut stmt = conn.prepare(
-            "SELECT id, timestamp, benchmark_type, model_id, config_json, summary_json, metrics_json
+            "SELECT id, timestamp, benchmark_type, model_id, config_json, summary_json, metrics_json, environment_json, origin_json
              FROM benchmark_history WHERE id = ?1",
         )?;
 
@@ -184,29 +322,48 @@ impl HistoryService {
             return Ok(None);
         };
 
+        let id: String = row.get(0)?;
+        let environment = parse_json_column(&id, "environment", row.get(7)?);
+        let origin = parse_json_column(&id, "origin", row.get(8)?);
         let entry = BenchmarkHistoryEntry {
-            id: row.get(0)?,
+            id,
             timestamp: row.get(1)?,
             benchmark_type: serde_json::from_str(&row.get::<_, String>(2)?)?,
             model_id: row.get(3)?,
             config: serde_json::from_str(&row.get::<_, String>(4)?)?,
             summary: serde_json::from_str(&row.get::<_, String>(5)?)?,
             metrics: serde_json::from_str(&row.get::<_, String>(6)?)?,
+            environment,
+            origin,
         };
 
         Ok(Some(entry))
     }
 
     pub fn delete(&self, id: &str) -> Result<()> {
-        let conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
-        conn.execute("DELETE FROM benchmark_history WHERE id = ?1", params![id])?;
+        let mut conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM runs WHERE id = ?1", params![id])?;
+        for table in &RUN_TABLES[1..] {
+            tx.execute(&format!("DELETE FROM {} WHERE run_id = ?1", table), params![id])?;
+        }
+        tx.execute("DELETE FROM history_fts WHERE run_id = ?1", params![id])?;
+        tx.execute("DELETE FROM benchmark_history WHERE id = ?1", params![id])?;
+        tx.commit()?;
         tracing::debug!("Deleted benchmark history entry: {}", id);
         Ok(())
     }
 
     pub fn clear_all(&self) -> Result<()> {
-        let conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
-        conn.execute("DELETE FROM benchmark_history", [])?;
+        let mut conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
+        let tx = conn.transaction()?;
+        for table in RUN_TABLES {
+            tx.execute(&format!("DELETE FROM {}", table), [])?;
+        }
+        // Effect descriptions stay indexed: effect detections aren't cleared
+        tx.execute("DELETE FROM history_fts WHERE run_id IS NOT NULL", [])?;
+        tx.execute("DELETE FROM benchmark_history", [])?;
+        tx.commit()?;
         tracing::info!("Cleared all benchmark history");
         Ok(())
     }
@@ -215,28 +372,11 @@ impl HistoryService {
 
     /// Insert an audio benchmark result
     pub fn insert_audio(&self, entry: &AudioHistoryEntry) -> Result<()> {
-        let conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
-
-        let benchmark_type = serde_json::to_string(&entry.benchmark_type)?;
-        let audio_mode = serde_json::to_string(&entry.audio_mode)?;
-        let config_json = serde_json::to_string(&entry.config)?;
-        let summary_json = serde_json::to_string(&entry.summary)?;
-        let metrics_json = serde_json::to_string(&entry.metrics)?;
-
-        conn.execute(
-            "INSERT INTO benchmark_history (id, timestamp, benchmark_type, audio_mode, model_id, config_json, summary_json, metrics_json)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-            params![
-                entry.id,
-                entry.timestamp,
-                benchmark_type,
-                audio_mode,
-                entry.model_id,
-                config_json,
-                summary_json,
-                metrics_json,
-            ],
-        )?;
+        let mut conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
+        let tx = conn.transaction()?;
+        insert_audio_entry(&tx, entry)?;
+        record_baseline_comparison(&tx, &HistoryRecord::Audio(entry.clone()))?;
+        tx.commit()?;
 
         tracing::debug!("Saved audio benchmark history entry: {}", entry.id);
         Ok(())
@@ -246,31 +386,11 @@ impl HistoryService {
 
     /// Insert a code benchmark result
     pub fn insert_code(&self, entry: &CodeHistoryEntry) -> Result<()> {
-        let conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
-
-        let benchmark_type = serde_json::to_string(&entry.benchmark_type)?;
-        let language = serde_json::to_string(&entry.language)?;
-        let config_json = serde_json::to_string(&entry.config)?;
-        let summary_json = serde_json::to_string(&entry.summary)?;
-        let metrics_json = serde_json::to_string(&entry.metrics)?;
-
-        conn.execute(
-            "INSERT INTO benchmark_history (id, timestamp, benchmark_type, language, model_id, config_json, summary_json, metrics_json, session_id, status, preset_id)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
-            params![
-                entry.id,
-                entry.timestamp,
-                benchmark_type,
-                language,
-                entry.model_id,
-                config_json,
-                summary_json,
-                metrics_json,
-                entry.session_id,
-                entry.status.as_str(),
-                entry.preset_id,
-            ],
-        )?;
+        let mut conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
+        let tx = conn.transaction()?;
+        insert_code_entry(&tx, entry)?;
+        record_baseline_comparison(&tx, &HistoryRecord::Code(entry.clone()))?;
+        tx.commit()?;
 
         tracing::debug!("Saved code benchmark history entry: {}", entry.id);
         Ok(())
@@ -286,19 +406,87 @@ impl HistoryService {
         let type_str = serde_json::to_string(&BenchmarkType::Code)?;
         let lang_str = serde_json::to_string(&language)?;
 
-        let result: std::result::Result<f64, _> = conn.query_row(
-            "SELECT MAX(json_extract(summary_json, '$.pass_rate'))
-             FROM benchmark_history
+        // MAX over no rows is NULL, so a model without runs maps to None
+        let pass_rate = conn.query_row(
+            "SELECT MAX(pass_rate)
+             FROM runs
              WHERE model_id = ?1 AND benchmark_type = ?2 AND language = ?3",
             params![model_id, type_str, lang_str],
-            |row| row.get(0),
-        );
+            |row| row.get::<_, Option<f64>>(0),
+        )?;
+
+        Ok(pass_rate)
+    }
+
+    /// Problems with stored generated code, with how many solutions pass
+    pub fn problem_solutions(&self) -> Result<Vec<ProblemSolutions>> {
+        let conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
+        let mut stmt = conn.prepare(
+            "SELECT problem_id, COUNT(*), COALESCE(SUM(passed), 0)
+             FROM code_problem_results
+             GROUP BY problem_id
+             ORDER BY problem_id",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(ProblemSolutions {
+                problem_id: row.get(0)?,
+                solutions: row.get(1)?,
+                passing: row.get(2)?,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Every stored solution to `problem_id` across models, languages and temperatures,
+    /// grouped by model, newest first
+    pub fn solutions_for_problem(&self, problem_id: &str) -> Result<Vec<StoredSolution>> {
+        let conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
+        // The code itself only lives in the history row's metrics JSON
+        let mut stmt = conn.prepare(
+            "SELECT r.id, r.model_id, r.language, r.temperature, r.timestamp,
+                    json_extract(h.metrics_json, printf('$[%d].generated_code', c.position)),
+                    c.tests_passed, c.tests_total, c.compilation_error, c.runtime_error
+             FROM code_problem_results c
+             JOIN runs r ON r.id = c.run_id
+             JOIN benchmark_history h ON h.id = c.run_id
+             WHERE c.problem_id = ?1
+             ORDER BY r.model_id, r.timestamp DESC",
+        )?;
+        let rows = stmt.query_map(params![problem_id], |row| {
+            let run_id: String = row.get(0)?;
+            let model_id: String = row.get(1)?;
+            let language: Option<String> = row.get(2)?;
+            let temperature: Option<f64> = row.get(3)?;
+            let timestamp: i64 = row.get(4)?;
+            let code: Option<String> = row.get(5)?;
+            let tests_passed: Option<u32> = row.get(6)?;
+            let tests_total: Option<u32> = row.get(7)?;
+            let compilation_error: Option<String> = row.get(8)?;
+            let runtime_error: Option<String> = row.get(9)?;
+
+            Ok((run_id, model_id, language, temperature, timestamp, code, tests_passed, tests_total, compilation_error, runtime_error))
+        })?;
 
-        match result {
-            Ok(pass_rate) => Ok(Some(pass_rate)),
-            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(e.into()),
+        let mut solutions = Vec::new();
+        for row in rows {
+            let (run_id, model_id, language, temperature, timestamp, code, tests_passed, tests_total, compilation_error, runtime_error) = row?;
+            solutions.push(StoredSolution {
+                run_id,
+                model_id,
+                language: language
+                    .and_then(|l| serde_json::from_str(&l).ok())
+                    .unwrap_or_default(),
+                temperature: temperature.unwrap_or_default() as f32,
+                timestamp,
+                problem_id: problem_id.to_string(),
+                code: code.unwrap_or_default(),
+                tests_passed: tests_passed.unwrap_or_default(),
+                tests_total: tests_total.unwrap_or_default(),
+                compilation_error,
+                runtime_error,
+            });
         }
+        Ok(solutions)
     }
 
     /// Get code leaderboard sorted by pass_rate descending (higher is better)
@@ -312,9 +500,9 @@ impl HistoryService {
         let lang_str = serde_json::to_string(&language)?;
 
         let mut stmt = conn.prepare(
-            "SELECT model_id, MAX(json_extract(summary_json, '$.pass_rate')) as best_pass_rate
-             FROM benchmark_history
-             WHERE benchmark_type = ?1 AND language = ?2
+            "SELECT model_id, MAX(pass_rate) as best_pass_rate
+             FROM runs
+             WHERE benchmark_type = ?1 AND language = ?2 AND pass_rate IS NOT NULL
              GROUP BY model_id
              ORDER BY best_pass_rate DESC
              LIMIT ?3",
@@ -331,135 +519,74 @@ impl HistoryService {
         Ok(results)
     }
 
-    /// Get the database path
-    pub fn db_path(&self) -> &PathBuf {
-        &self.db_path
-    }
-
-    /// List audio benchmark history entries
-    pub fn list_audio(&self, limit: Option<u32>) -> Result<Vec<AudioHistoryEntry>> {
+    /// Code leaderboard with the per-tag breakdown of each model's best run
+    pub fn get_code_tag_leaderboard(
+        &self,
+        language: Language,
+        limit: u32,
+    ) -> Result<CodeLeaderboard> {
         let conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
-        let type_str = serde_json::to_string(&BenchmarkType::Audio)?;
-
-        let mut sql = String::from(
-            "SELECT id, timestamp, benchmark_type, audio_mode, model_id, config_json, summary_json, metrics_json
-             FROM benchmark_history WHERE benchmark_type = ?",
-        );
-
-        sql.push_str(" ORDER BY timestamp DESC");
+        let type_str = serde_json::to_string(&BenchmarkType::Code)?;
+        let lang_str = serde_json::to_string(&language)?;
 
-        if let Some(limit) = limit {
-            sql.push_str(&format!(" LIMIT {}", limit));
-        }
+        // Each model's best run (by pass rate); the tag breakdown lives in the summary JSON
+        let mut stmt = conn.prepare(
+            "SELECT b.id, b.model_id, h.summary_json
+             FROM (
+                 SELECT id, model_id, pass_rate,
+                        ROW_NUMBER() OVER (PARTITION BY model_id ORDER BY pass_rate DESC, timestamp DESC) AS rank
+                 FROM runs
+                 WHERE benchmark_type = ?1 AND language = ?2 AND pass_rate IS NOT NULL
+             ) b
+             JOIN benchmark_history h ON h.id = b.id
+             WHERE b.rank = 1
+             ORDER BY b.pass_rate DESC
+             LIMIT ?3",
+        )?;
 
-        let mut stmt = conn.prepare(&sql)?;
-        let rows = stmt.query_map(params![type_str], |row| {
-            Ok((
-                row.get::<_, String>(0)?,
-                row.get::<_, i64>(1)?,
-                row.get::<_, String>(2)?,
-                row.get::<_, Option<String>>(3)?,
-                row.get::<_, String>(4)?,
-                row.get::<_, String>(5)?,
-                row.get::<_, String>(6)?,
-                row.get::<_, String>(7)?,
-            ))
+        let rows = stmt.query_map(params![type_str, lang_str, limit], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
         })?;
 
-        let mut entries = Vec::new();
+        // One malformed summary shouldn't hide every other model's run
+        let mut leaderboard = CodeLeaderboard::default();
         for row in rows {
-            let (id, timestamp, benchmark_type, audio_mode, model_id, config_json, summary_json, metrics_json) = row?;
-            let Ok(audio_mode) = audio_mode.map(|s| serde_json::from_str(&s)).transpose() else {
-                continue;
+            let (id, model_id, summary_json) = row?;
+            let summary: CodeBenchmarkSummary = match serde_json::from_str(&summary_json) {
+                Ok(summary) => summary,
+                Err(e) => {
+                    tracing::warn!("Skipping leaderboard run {}: unreadable summary: {}", id, e);
+                    leaderboard.skipped += 1;
+                    continue;
+                }
             };
-            let Ok(config) = serde_json::from_str(&config_json) else {
-                continue;
-            };
-            let Ok(summary) = serde_json::from_str(&summary_json) else {
-                continue;
-            };
-            let Ok(metrics) = serde_json::from_str(&metrics_json) else {
-                continue;
-            };
-            entries.push(AudioHistoryEntry {
-                id,
-                timestamp,
-                benchmark_type: serde_json::from_str(&benchmark_type).unwrap_or_default(),
-                audio_mode: audio_mode.unwrap_or(AudioMode::Stt),
+            leaderboard.entries.push(CodeLeaderboardEntry {
                 model_id,
-                config,
-                summary,
-                metrics,
+                pass_rate: summary.pass_rate,
+                tag_stats: summary.tag_stats,
+                problems_analyzed: summary.problems_analyzed,
+                avg_lint_warnings: summary.avg_lint_warnings,
+                avg_cyclomatic_complexity: summary.avg_cyclomatic_complexity,
             });
         }
-
-        Ok(entries)
+        Ok(leaderboard)
     }
 
-    /// List code benchmark history entries
-    pub fn list_code(&self, limit: Option<u32>) -> Result<Vec<CodeHistoryEntry>> {
-        let conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
-        let type_str = serde_json::to_string(&BenchmarkType::Code)?;
-
-        let mut sql = String::from(
-            "SELECT id, timestamp, benchmark_type, language, model_id, config_json, summary_json, metrics_json, session_id, status, preset_id
-             FROM benchmark_history WHERE benchmark_type = ?",
-        );
-
-        sql.push_str(" ORDER BY timestamp DESC");
-
-        if let Some(limit) = limit {
-            sql.push_str(&format!(" LIMIT {}", limit));
-        }
-
-        let mut stmt = conn.prepare(&sql)?;
-        let rows = stmt.query_map(params![type_str], |row| {
-            Ok(CodeHistoryRow {
-                id: row.get(0)?,
-                timestamp: row.get(1)?,
-                benchmark_type: row.get(2)?,
-                language: row.get(3)?,
-                model_id: row.get(4)?,
-                config_json: row.get(5)?,
-                summary_json: row.get(6)?,
-                metrics_json: row.get(7)?,
-                session_id: row.get(8)?,
-                status: row.get(9)?,
-                preset_id: row.get(10)?,
-            })
-        })?;
+    /// Get the database path
+    pub fn db_path(&self) -> &PathBuf {
+        &self.db_path
+    }
 
-        let mut entries = Vec::new();
-        for row in rows {
-            let row = row?;
-            let Ok(language) = row.language.map(|s| serde_json::from_str(&s)).transpose() else {
-                continue;
-            };
-            let Ok(config) = serde_json::from_str(&row.config_json) else {
-                continue;
-            };
-            let Ok(summary) = serde_json::from_str(&row.summary_json) else {
-                continue;
-            };
-            let Ok(metrics) = serde_json::from_str(&row.metrics_json) else {
-                continue;
-            };
-            entries.push(CodeHistoryEntry {
-                id: row.id,
-                timestamp: row.timestamp,
-                benchmark_type: serde_json::from_str(&row.benchmark_type).unwrap_or_default(),
-                model_id: row.model_id,
-                language: language.unwrap_or(Language::Python),
-                config,
-                summary,
-                metrics,
-                session_id: row.session_id,
-                status: row.status.map(|s| RunStatus::from_str(&s)).unwrap_or_default(),
-                preset_id: row.preset_id,
-            });
-        }
+    /// One page of audio benchmark entries
+    pub fn list_audio(&self, filter: &HistoryFilter) -> Result<HistoryPage<AudioHistoryEntry>> {
+        let page = self.query_runs(filter, Some(BenchmarkType::Audio))?;
+        Ok(map_page(page, audio_entry))
+    }
 
-        Ok(entries)
+    /// One page of code benchmark entries
+    pub fn list_code(&self, filter: &HistoryFilter) -> Result<HistoryPage<CodeHistoryEntry>> {
+        let page = self.query_runs(filter, Some(BenchmarkType::Code))?;
+        Ok(map_page(page, code_entry))
     }
 
     // --- Effect Detection History Methods ---
@@ -472,23 +599,7 @@ impl HistoryService {
         result: &EffectDetectionResult,
     ) -> Result<i64> {
         let conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
-
-        let tool_str = serde_json::to_string(&tool)?;
-        let effects_json = serde_json::to_string(&result.effects)?;
-
-        conn.execute(
-            "INSERT INTO effect_detection_history (tool, audio_path, effects_json, processing_time_ms, audio_duration_ms)
-             VALUES (?1, ?2, ?3, ?4, ?5)",
-            params![
-                tool_str,
-                audio_path,
-                effects_json,
-                result.processing_time_ms,
-                result.audio_duration_ms,
-            ],
-        )?;
-
-        Ok(conn.last_insert_rowid())
+        insert_effect_detection_row(&conn, tool, audio_path, result, None)
     }
 
     /// Get recent effect detection history
@@ -496,7 +607,8 @@ impl HistoryService {
         let conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
 
         let mut stmt = conn.prepare(
-            "SELECT id, tool, audio_path, effects_json, processing_time_ms, audio_duration_ms, created_at
+            "SELECT id, tool, audio_path, effects_json, processing_time_ms, audio_duration_ms, created_at,
+                    llm_description, llm_model_used
              FROM effect_detection_history
              ORDER BY created_at DESC
              LIMIT ?1",
@@ -510,13 +622,15 @@ impl HistoryService {
             let processing_time_ms: f64 = row.get(4)?;
             let audio_duration_ms: f64 = row.get(5)?;
             let created_at: i64 = row.get(6)?;
+            let llm_description: Option<String> = row.get(7)?;
+            let llm_model_used: Option<String> = row.get(8)?;
 
-            Ok((id, tool_str, audio_path, effects_json, processing_time_ms, audio_duration_ms, created_at))
+            Ok((id, tool_str, audio_path, effects_json, processing_time_ms, audio_duration_ms, created_at, llm_description, llm_model_used))
         })?;
 
         let mut results = Vec::new();
         for row in rows {
-            let (id, tool_str, audio_path, effects_json, processing_time_ms, audio_duration_ms, created_at) = row?;
+            let (id, tool_str, audio_path, effects_json, processing_time_ms, audio_duration_ms, created_at, llm_description, llm_model_used) = row?;
             let tool: EffectDetectionTool = serde_json::from_str(&tool_str).unwrap_or_default();
             let effects = serde_json::from_str(&effects_json).unwrap_or_default();
 
@@ -532,8 +646,8 @@ impl HistoryService {
                     embeddings: None,
                     applied_effects: None,
                     signal_analysis: None,
-                    llm_description: None,
-                    llm_model_used: None,
+                    llm_description,
+                    llm_model_used,
                     embedding_distance: None,
                     cosine_similarity: None,
                 },
@@ -554,7 +668,8 @@ impl HistoryService {
         let tool_str = serde_json::to_string(&tool)?;
 
         let mut stmt = conn.prepare(
-            "SELECT id, tool, audio_path, effects_json, processing_time_ms, audio_duration_ms, created_at
+            "SELECT id, tool, audio_path, effects_json, processing_time_ms, audio_duration_ms, created_at,
+                    llm_description, llm_model_used
              FROM effect_detection_history
              WHERE tool = ?1
              ORDER BY created_at DESC
@@ -569,13 +684,15 @@ impl HistoryService {
             let processing_time_ms: f64 = row.get(4)?;
             let audio_duration_ms: f64 = row.get(5)?;
             let created_at: i64 = row.get(6)?;
+            let llm_description: Option<String> = row.get(7)?;
+            let llm_model_used: Option<String> = row.get(8)?;
 
-            Ok((id, tool_str, audio_path, effects_json, processing_time_ms, audio_duration_ms, created_at))
+            Ok((id, tool_str, audio_path, effects_json, processing_time_ms, audio_duration_ms, created_at, llm_description, llm_model_used))
         })?;
 
         let mut results = Vec::new();
         for row in rows {
-            let (id, tool_str, audio_path, effects_json, processing_time_ms, audio_duration_ms, created_at) = row?;
+            let (id, tool_str, audio_path, effects_json, processing_time_ms, audio_duration_ms, created_at, llm_description, llm_model_used) = row?;
             let tool: EffectDetectionTool = serde_json::from_str(&tool_str).unwrap_or_default();
             let effects = serde_json::from_str(&effects_json).unwrap_or_default();
 
@@ -591,8 +708,8 @@ impl HistoryService {
                     embeddings: None,
                     applied_effects: None,
                     signal_analysis: None,
-                    llm_description: None,
-                    llm_model_used: None,
+                    llm_description,
+                    llm_model_used,
                     embedding_distance: None,
                     cosine_similarity: None,
                 },
@@ -608,35 +725,7 @@ impl HistoryService {
     /// Insert a new batch state
     pub fn insert_batch(&self, batch: &BatchState) -> Result<()> {
         let conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
-        conn.execute(
-            "INSERT INTO batch_state (
-                session_id, created_at, updated_at, status,
-                selected_models, selected_languages, selected_temperatures,
-                selected_max_tokens, selected_problem_ids,
-                auto_run_tests, skip_on_error,
-                pending_combos, queue_total, queue_completed,
-                failed_combo, error_message
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
-            params![
-                batch.session_id,
-                batch.created_at,
-                batch.updated_at,
-                batch.status.as_str(),
-                serde_json::to_string(&batch.selected_models)?,
-                serde_json::to_string(&batch.selected_languages)?,
-                serde_json::to_string(&batch.selected_temperatures)?,
-                serde_json::to_string(&batch.selected_max_tokens)?,
-                serde_json::to_string(&batch.selected_problem_ids)?,
-                batch.auto_run_tests as i32,
-                batch.skip_on_error as i32,
-                serde_json::to_string(&batch.pending_combos)?,
-                batch.queue_total as i64,
-                batch.queue_completed as i64,
-                batch.failed_combo.as_ref().map(|c| serde_json::to_string(c)).transpose()?,
-                batch.error_message.as_ref(),
-            ],
-        )?;
-        Ok(())
+        insert_batch_row(&conn, batch)
     }
 
     /// Update an existing batch state
@@ -661,65 +750,16 @@ impl HistoryService {
         Ok(())
     }
 
-    /// Get all incomplete batches (running or paused)
+    /// Get all incomplete batches (running or paused) of every benchmark type
     pub fn get_incomplete_batches(&self) -> Result<Vec<BatchState>> {
         let conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
-        let mut stmt = conn.prepare(
-            "SELECT session_id, created_at, updated_at, status,
-                    selected_models, selected_languages, selected_temperatures,
-                    selected_max_tokens, selected_problem_ids,
-                    auto_run_tests, skip_on_error,
-                    pending_combos, queue_total, queue_completed,
-                    failed_combo, error_message
-             FROM batch_state
-             WHERE status IN ('running', 'paused')
-             ORDER BY updated_at DESC"
-        )?;
-
-        let rows = stmt.query_map([], |row| {
-            Ok(BatchStateRow {
-                session_id: row.get(0)?,
-                created_at: row.get(1)?,
-                updated_at: row.get(2)?,
-                status: row.get(3)?,
-                selected_models: row.get(4)?,
-                selected_languages: row.get(5)?,
-                selected_temperatures: row.get(6)?,
-                selected_max_tokens: row.get(7)?,
-                selected_problem_ids: row.get(8)?,
-                auto_run_tests: row.get(9)?,
-                skip_on_error: row.get(10)?,
-                pending_combos: row.get(11)?,
-                queue_total: row.get(12)?,
-                queue_completed: row.get(13)?,
-                failed_combo: row.get(14)?,
-                error_message: row.get(15)?,
-            })
-        })?;
+        read_batches(&conn, "WHERE status IN ('running', 'paused')")
+    }
 
-        let mut batches = Vec::new();
-        for row in rows {
-            let row = row?;
-            batches.push(BatchState {
-                session_id: row.session_id,
-                created_at: row.created_at,
-                updated_at: row.updated_at,
-                status: BatchStatus::from_str(&row.status),
-                selected_models: serde_json::from_str(&row.selected_models)?,
-                selected_languages: serde_json::from_str(&row.selected_languages)?,
-                selected_temperatures: serde_json::from_str(&row.selected_temperatures)?,
-                selected_max_tokens: serde_json::from_str(&row.selected_max_tokens)?,
-                selected_problem_ids: serde_json::from_str(&row.selected_problem_ids)?,
-                auto_run_tests: row.auto_run_tests != 0,
-                skip_on_error: row.skip_on_error != 0,
-                pending_combos: serde_json::from_str(&row.pending_combos)?,
-                queue_total: row.queue_total as usize,
-                queue_completed: row.queue_completed as usize,
-                failed_combo: row.failed_combo.map(|s| serde_json::from_str(&s)).transpose()?,
-                error_message: row.error_message,
-            });
-        }
-        Ok(batches)
+    /// Every batch, completed ones included
+    pub fn list_batches(&self) -> Result<Vec<BatchState>> {
+        let conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
+        read_batches(&conn, "")
     }
 
     /// Delete a batch state
@@ -734,25 +774,7 @@ impl HistoryService {
     /// Insert a new preset
     pub fn insert_preset(&self, preset: &Preset) -> Result<()> {
         let conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
-        let language = serde_json::to_string(&preset.language)?;
-        let problem_ids = serde_json::to_string(&preset.problem_ids)?;
-
-        conn.execute(
-            "INSERT INTO benchmark_presets (id, name, created_at, model_id, language, temperature, max_tokens, problem_ids)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-            params![
-                preset.id,
-                preset.name,
-                preset.created_at,
-                preset.model_id,
-                language,
-                preset.temperature,
-                preset.max_tokens,
-                problem_ids,
-            ],
-        )?;
-        tracing::debug!("Saved preset: {}", preset.name);
-        Ok(())
+        insert_preset_row(&conn, preset)
     }
 
     /// List all presets
@@ -830,6 +852,738 @@ impl HistoryService {
         tracing::debug!("Deleted preset: {}", id);
         Ok(())
     }
+
+    // --- Baselines ---
+
+    /// A history entry of any benchmark type
+    pub fn get_record(&self, id: &str) -> Result<Option<HistoryRecord>> {
+        let conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
+        load_record(&conn, id)
+    }
+
+    /// Pin a run as the baseline of its config, replacing the previous baseline.
+    /// Runs saved afterwards with the same config are compared against it.
+    pub fn pin_baseline(&self, run_id: &str) -> Result<()> {
+        let conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
+        let Some(record) = load_record(&conn, run_id)? else {
+            return Err(HistoryError::NotFound(run_id.to_string()));
+        };
+        conn.execute(
+            "INSERT OR REPLACE INTO baselines (config_key, run_id, benchmark_type, pinned_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                record.config_key(),
+                run_id,
+                serde_json::to_string(&record.benchmark_type())?,
+                unix_now(),
+            ],
+        )?;
+        tracing::info!("Pinned baseline {} for {}", run_id, record.config_key());
+        Ok(())
+    }
+
+    pub fn unpin_baseline(&self, run_id: &str) -> Result<()> {
+        let conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
+        conn.execute("DELETE FROM baselines WHERE run_id = ?1", params![run_id])?;
+        tracing::debug!("Unpinned baseline {}", run_id);
+        Ok(())
+    }
+
+    /// Ids of the runs pinned as baselines
+    pub fn baseline_ids(&self) -> Result<HashSet<String>> {
+        let conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
+        let mut stmt = conn.prepare("SELECT run_id FROM baselines")?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Compare a run with the baseline currently pinned for its config. `None` when the
+    /// config has no baseline or the run is the baseline itself.
+    pub fn compare_with_baseline(
+        &self,
+        run_id: &str,
+        thresholds: RegressionThresholds,
+    ) -> Result<Option<RegressionReport>> {
+        let conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
+        let Some(record) = load_record(&conn, run_id)? else {
+            return Err(HistoryError::NotFound(run_id.to_string()));
+        };
+        let Some(baseline) = pinned_baseline(&conn, &record)? else {
+            return Ok(None);
+        };
+        Ok(Some(compare_runs(&baseline, &record, thresholds, unix_now())))
+    }
+
+    /// Comparisons recorded when these runs were saved, keyed by run id
+    pub fn regression_reports(&self, run_ids: &[String]) -> Result<HashMap<String, RegressionReport>> {
+        let conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
+        let mut stmt = conn.prepare("SELECT report_json FROM baseline_comparisons WHERE run_id = ?1")?;
+
+        let mut reports = HashMap::new();
+        for id in run_ids {
+            let Some(json) = stmt.query_row(params![id], |row| row.get::<_, String>(0)).optional()? else {
+                continue;
+            };
+            match serde_json::from_str::<RegressionReport>(&json) {
+                Ok(report) => {
+                    reports.insert(id.clone(), report);
+                }
+                Err(e) => tracing::warn!("Ignoring unreadable baseline comparison of {}: {}", id, e),
+            }
+        }
+        Ok(reports)
+    }
+
+    // --- Bundles ---
+
+    /// Full-fidelity copy of the chosen runs, plus presets, batches and effect detections.
+    /// Runs that can't be read are left out and listed in the bundle's `unreadable`.
+    pub fn export_bundle(&self, contents: &BundleContents) -> Result<HistoryBundle> {
+        let page = match &contents.run_ids {
+            Some(ids) => {
+                let conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
+                let sql = format!("{} WHERE r.id = ?1", run_select());
+                let mut rows = Vec::new();
+                for id in ids {
+                    match conn.query_row(&sql, params![id], run_row).optional()? {
+                        Some(row) => rows.push(row),
+                        None => tracing::warn!("Skipping missing history entry {} in export", id),
+                    }
+                }
+                let page = HistoryPage { entries: rows, next_cursor: None, unreadable: Vec::new() };
+                map_page(page, history_record)
+            }
+            None => {
+                let filter = HistoryFilter { sort: HistorySort::Oldest, ..Default::default() };
+                self.list_page(&filter)?
+            }
+        };
+        if !page.unreadable.is_empty() {
+            tracing::warn!("Leaving {} unreadable runs out of the export", page.unreadable.len());
+        }
+        let presets = match contents.presets {
+            true => self.list_presets()?,
+            false => Vec::new(),
+        };
+        let batches = match contents.batches {
+            true => self.list_batches()?,
+            false => Vec::new(),
+        };
+        let effect_detections = match contents.effect_detections {
+            true => self.get_effect_detection_history(u32::MAX)?,
+            false => Vec::new(),
+        };
+
+        Ok(HistoryBundle {
+            format: HISTORY_BUNDLE_FORMAT.to_string(),
+            version: HISTORY_BUNDLE_VERSION,
+            exported_at: unix_now(),
+            host: host_name(),
+            llamaburn_version: env!("CARGO_PKG_VERSION").to_string(),
+            runs: page.entries,
+            presets,
+            batches,
+            effect_detections,
+            unreadable: page.unreadable,
+        })
+    }
+
+    /// Write a bundle to a JSON file
+    pub fn export_bundle_to(&self, path: &Path, contents: &BundleContents) -> Result<BundleExportSummary> {
+        let bundle = self.export_bundle(contents)?;
+        std::fs::write(path, serde_json::to_string_pretty(&bundle)?)?;
+        tracing::info!("Exported {} runs to {:?}", bundle.runs.len(), path);
+        Ok(BundleExportSummary { runs_exported: bundle.runs.len(), unreadable: bundle.unreadable })
+    }
+
+    /// Merge a bundle into this history in one transaction. Items already present (same
+    /// run, preset or session id) are skipped. Imported runs keep the origin they already
+    /// had, so runs passed along several machines still point at the one that ran them.
+    pub fn import_bundle(&self, bundle: &HistoryBundle) -> Result<BundleImportSummary> {
+        check_bundle_header(&bundle.format, bundle.version)?;
+
+        let mut conn = self.conn.lock().map_err(|_| HistoryError::LockPoisoned)?;
+        let tx = conn.transaction()?;
+        let imported_at = unix_now();
+        let mut summary = BundleImportSummary::default();
+
+        for record in &bundle.runs {
+            if row_exists(&tx, "SELECT 1 FROM benchmark_history WHERE id = ?1", params![record.id()])? {
+                summary.runs_skipped += 1;
+                continue;
+            }
+            let mut record = record.clone();
+            record.origin_mut().get_or_insert_with(|| RunOrigin {
+                host: bundle.host.clone(),
+                llamaburn_version: bundle.llamaburn_version.clone(),
+                exported_at: bundle.exported_at,
+                imported_at,
+            });
+            match &record {
+                HistoryRecord::Text(e) => insert_text_entry(&tx, e)?,
+                HistoryRecord::Audio(e) => insert_audio_entry(&tx, e)?,
+                HistoryRecord::Code(e) => insert_code_entry(&tx, e)?,
+            }
+            summary.runs_imported += 1;
+        }
+
+        for preset in &bundle.presets {
+            if row_exists(&tx, "SELECT 1 FROM benchmark_presets WHERE id = ?1", params![preset.id])? {
+                summary.presets_skipped += 1;
+                continue;
+            }
+            insert_preset_row(&tx, preset)?;
+            summary.presets_imported += 1;
+        }
+
+        for batch in &bundle.batches {
+            // Resuming needs the other machine's models and in-flight state
+            if batch.status != BatchStatus::Completed {
+                summary.batches_unfinished += 1;
+                continue;
+            }
+            if row_exists(&tx, "SELECT 1 FROM batch_state WHERE session_id = ?1", params![batch.session_id])? {
+                summary.batches_skipped += 1;
+                continue;
+            }
+            insert_batch_row(&tx, batch)?;
+            summary.batches_imported += 1;
+        }
+
+        // Detection ids are per-database row ids, so match on content instead
+        for detection in &bundle.effect_detections {
+            let tool = serde_json::to_string(&detection.tool)?;
+            let exists = row_exists(
+                &tx,
+                "SELECT 1 FROM effect_detection_history WHERE tool = ?1 AND audio_path = ?2 AND created_at = ?3",
+                params![tool, detection.audio_path, detection.created_at],
+            )?;
+            if exists {
+                summary.effect_detections_skipped += 1;
+                continue;
+            }
+            insert_effect_detection_row(
+                &tx,
+                detection.tool,
+                &detection.audio_path,
+                &detection.result,
+                Some(detection.created_at),
+            )?;
+            summary.effect_detections_imported += 1;
+        }
+
+        tx.commit()?;
+        tracing::info!("Imported bundle from {}: {}", bundle.host, summary.describe());
+        Ok(summary)
+    }
+
+    /// Read and merge a bundle file written by `export_bundle_to`
+    pub fn import_bundle_from(&self, path: &Path) -> Result<BundleImportSummary> {
+        let json = std::fs::read_to_string(path)?;
+        // Check the version before parsing the rest, whose shape may have changed
+        let header: BundleHeader = serde_json::from_str(&json).map_err(|_| HistoryError::NotABundle)?;
+        check_bundle_header(&header.format, header.version)?;
+        let bundle: HistoryBundle = serde_json::from_str(&json)?;
+        self.import_bundle(&bundle)
+    }
+}
+
+/// Leading fields of a bundle file
+#[derive(serde::Deserialize)]
+struct BundleHeader {
+    format: String,
+    version: u32,
+}
+
+fn check_bundle_header(format: &str, version: u32) -> Result<()> {
+    if format != HISTORY_BUNDLE_FORMAT {
+        return Err(HistoryError::NotABundle);
+    }
+    if version > HISTORY_BUNDLE_VERSION {
+        return Err(HistoryError::UnsupportedBundleVersion(version));
+    }
+    Ok(())
+}
+
+fn row_exists(conn: &Connection, sql: &str, params: impl rusqlite::Params) -> Result<bool> {
+    Ok(conn.query_row(sql, params, |_| Ok(())).optional()?.is_some())
+}
+
+fn insert_batch_row(conn: &Connection, batch: &BatchState) -> Result<()> {
+    conn.execute(
+        "INSERT INTO batch_state (
+            session_id, created_at, updated_at, status,
+            selected_models, selected_languages, selected_temperatures,
+            selected_max_tokens, selected_problem_ids,
+            auto_run_tests, skip_on_error,
+            pending_combos, queue_total, queue_completed,
+            failed_combo, error_message, benchmark_type
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
+        params![
+            batch.session_id,
+            batch.created_at,
+            batch.updated_at,
+            batch.status.as_str(),
+            serde_json::to_string(&batch.selected_models)?,
+            serde_json::to_string(&batch.selected_languages)?,
+            serde_json::to_string(&batch.selected_temperatures)?,
+            serde_json::to_string(&batch.selected_max_tokens)?,
+            serde_json::to_string(&batch.selected_problem_ids)?,
+            batch.auto_run_tests as i32,
+            batch.skip_on_error as i32,
+            serde_json::to_string(&batch.pending_combos)?,
+            batch.queue_total as i64,
+            batch.queue_completed as i64,
+            batch.failed_combo.as_ref().map(|c| serde_json::to_string(c)).transpose()?,
+            batch.error_message.as_ref(),
+            serde_json::to_string(&batch.benchmark_type)?,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Batch states matching `condition` (a WHERE clause, or empty for all), newest first
+fn read_batches(conn: &Connection, condition: &str) -> Result<Vec<BatchState>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT session_id, created_at, updated_at, status,
+                selected_models, selected_languages, selected_temperatures,
+                selected_max_tokens, selected_problem_ids,
+                auto_run_tests, skip_on_error,
+                pending_combos, queue_total, queue_completed,
+                failed_combo, error_message, benchmark_type
+         FROM batch_state {}
+         ORDER BY updated_at DESC",
+        condition
+    ))?;
+
+    let rows = stmt.query_map([], |row| {
+        Ok(BatchStateRow {
+            session_id: row.get(0)?,
+            created_at: row.get(1)?,
+            updated_at: row.get(2)?,
+            status: row.get(3)?,
+            selected_models: row.get(4)?,
+            selected_languages: row.get(5)?,
+            selected_temperatures: row.get(6)?,
+            selected_max_tokens: row.get(7)?,
+            selected_problem_ids: row.get(8)?,
+            auto_run_tests: row.get(9)?,
+            skip_on_error: row.get(10)?,
+            pending_combos: row.get(11)?,
+            queue_total: row.get(12)?,
+            queue_completed: row.get(13)?,
+            failed_combo: row.get(14)?,
+            error_message: row.get(15)?,
+            benchmark_type: row.get(16)?,
+        })
+    })?;
+
+    let mut batches = Vec::new();
+    for row in rows {
+        let row = row?;
+        batches.push(BatchState {
+            session_id: row.session_id,
+            benchmark_type: serde_json::from_str(&row.benchmark_type)?,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+            status: BatchStatus::from_str(&row.status),
+            selected_models: serde_json::from_str(&row.selected_models)?,
+            selected_languages: serde_json::from_str(&row.selected_languages)?,
+            selected_temperatures: serde_json::from_str(&row.selected_temperatures)?,
+            selected_max_tokens: serde_json::from_str(&row.selected_max_tokens)?,
+            selected_problem_ids: serde_json::from_str(&row.selected_problem_ids)?,
+            auto_run_tests: row.auto_run_tests != 0,
+            skip_on_error: row.skip_on_error != 0,
+            pending_combos: serde_json::from_str(&row.pending_combos)?,
+            queue_total: row.queue_total as usize,
+            queue_completed: row.queue_completed as usize,
+            failed_combo: row.failed_combo.map(|s| serde_json::from_str(&s)).transpose()?,
+            error_message: row.error_message,
+        });
+    }
+    Ok(batches)
+}
+
+fn insert_preset_row(conn: &Connection, preset: &Preset) -> Result<()> {
+    let language = serde_json::to_string(&preset.language)?;
+    let problem_ids = serde_json::to_string(&preset.problem_ids)?;
+
+    conn.execute(
+        "INSERT INTO benchmark_presets (id, name, created_at, model_id, language, temperature, max_tokens, problem_ids)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            preset.id,
+            preset.name,
+            preset.created_at,
+            preset.model_id,
+            language,
+            preset.temperature,
+            preset.max_tokens,
+            problem_ids,
+        ],
+    )?;
+    tracing::debug!("Saved preset: {}", preset.name);
+    Ok(())
+}
+
+/// Per-run tables; `runs` first, the rest are keyed by `run_id` (so deleting a run unpins it)
+const RUN_TABLES: [&str; 7] = [
+    "runs",
+    "iterations",
+    "code_problem_results",
+    "audio_iterations",
+    "run_tags",
+    "baselines",
+    "baseline_comparisons",
+];
+
+/// Write a text entry's history row and run tables
+fn insert_text_entry(conn: &Connection, entry: &BenchmarkHistoryEntry) -> Result<()> {
+    let benchmark_type = serde_json::to_string(&entry.benchmark_type)?;
+    let config_json = serde_json::to_string(&entry.config)?;
+    let summary_json = serde_json::to_string(&entry.summary)?;
+    let metrics_json = serde_json::to_string(&entry.metrics)?;
+    let environment_json = json_column(&entry.environment)?;
+    let origin_json = json_column(&entry.origin)?;
+
+    conn.execute(
+        "INSERT INTO benchmark_history (id, timestamp, benchmark_type, model_id, config_json, summary_json, metrics_json, environment_json, origin_json)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            entry.id,
+            entry.timestamp,
+            benchmark_type,
+            entry.model_id,
+            config_json,
+            summary_json,
+            metrics_json,
+            environment_json,
+            origin_json,
+        ],
+    )?;
+    insert_text_run(conn, entry)
+}
+
+/// Write an audio entry's history row and run tables
+fn insert_audio_entry(conn: &Connection, entry: &AudioHistoryEntry) -> Result<()> {
+    let benchmark_type = serde_json::to_string(&entry.benchmark_type)?;
+    let audio_mode = serde_json::to_string(&entry.audio_mode)?;
+    let config_json = serde_json::to_string(&entry.config)?;
+    let summary_json = serde_json::to_string(&entry.summary)?;
+    let metrics_json = serde_json::to_string(&entry.metrics)?;
+    let environment_json = json_column(&entry.environment)?;
+    let origin_json = json_column(&entry.origin)?;
+
+    conn.execute(
+        "INSERT INTO benchmark_history (id, timestamp, benchmark_type, audio_mode, model_id, config_json, summary_json, metrics_json, environment_json, origin_json)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            entry.id,
+            entry.timestamp,
+            benchmark_type,
+            audio_mode,
+            entry.model_id,
+            config_json,
+            summary_json,
+            metrics_json,
+            environment_json,
+            origin_json,
+        ],
+    )?;
+    insert_audio_run(conn, entry)
+}
+
+/// Write a code entry's history row and run tables
+fn insert_code_entry(conn: &Connection, entry: &CodeHistoryEntry) -> Result<()> {
+    let benchmark_type = serde_json::to_string(&entry.benchmark_type)?;
+    let language = serde_json::to_string(&entry.language)?;
+    let config_json = serde_json::to_string(&entry.config)?;
+    let summary_json = serde_json::to_string(&entry.summary)?;
+    let metrics_json = serde_json::to_string(&entry.metrics)?;
+    let environment_json = json_column(&entry.environment)?;
+    let origin_json = json_column(&entry.origin)?;
+
+    conn.execute(
+        "INSERT INTO benchmark_history (id, timestamp, benchmark_type, language, model_id, config_json, summary_json, metrics_json, session_id, status, preset_id, environment_json, origin_json)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+        params![
+            entry.id,
+            entry.timestamp,
+            benchmark_type,
+            language,
+            entry.model_id,
+            config_json,
+            summary_json,
+            metrics_json,
+            entry.session_id,
+            entry.status.as_str(),
+            entry.preset_id,
+            environment_json,
+            origin_json,
+        ],
+    )?;
+    insert_code_run(conn, entry)
+}
+
+/// Write a text entry's typed run row and per-iteration metrics
+fn insert_text_run(conn: &Connection, entry: &BenchmarkHistoryEntry) -> Result<()> {
+    conn.execute(
+        "INSERT INTO runs (id, timestamp, benchmark_type, model_id, prompt_set, temperature, max_tokens,
+                           iterations, avg_tps, min_tps, max_tps, avg_ttft_ms, avg_total_ms)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+        params![
+            entry.id,
+            entry.timestamp,
+            serde_json::to_string(&entry.benchmark_type)?,
+            entry.model_id,
+            entry.config.prompt_set,
+            entry.config.temperature,
+            entry.config.max_tokens,
+            entry.summary.iterations,
+            entry.summary.avg_tps,
+            entry.summary.min_tps,
+            entry.summary.max_tps,
+            entry.summary.avg_ttft_ms,
+            entry.summary.avg_total_ms,
+        ],
+    )?;
+
+    for (i, m) in entry.metrics.iter().enumerate() {
+        conn.execute(
+            "INSERT INTO iterations (run_id, iteration, ttft_ms, inter_token_latency_ms, tokens_per_sec,
+                                     total_generation_ms, prompt_eval_ms, load_duration_ms,
+                                     input_tokens, output_tokens, power_draw_watts, energy_wh)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            params![
+                entry.id,
+                i as i64 + 1,
+                m.time_to_first_token_ms,
+                m.inter_token_latency_ms,
+                m.tokens_per_sec,
+                m.total_generation_ms,
+                m.prompt_eval_ms,
+                m.load_duration_ms,
+                m.input_sequence_length,
+                m.output_sequence_length,
+                m.power_draw_watts,
+                m.energy_wh,
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+/// Write an audio entry's typed run row and per-iteration metrics
+fn insert_audio_run(conn: &Connection, entry: &AudioHistoryEntry) -> Result<()> {
+    conn.execute(
+        "INSERT INTO runs (id, timestamp, benchmark_type, model_id, audio_mode, whisper_model,
+                           iterations, avg_rtf, min_rtf, max_rtf, avg_processing_ms, wer, cer)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+        params![
+            entry.id,
+            entry.timestamp,
+            serde_json::to_string(&entry.benchmark_type)?,
+            entry.model_id,
+            serde_json::to_string(&entry.audio_mode)?,
+            entry.config.model_size.map(|m| serde_json::to_string(&m)).transpose()?,
+            entry.summary.iterations,
+            entry.summary.avg_rtf,
+            entry.summary.min_rtf,
+            entry.summary.max_rtf,
+            entry.summary.avg_processing_ms,
+            entry.summary.accuracy.map(|a| a.wer()),
+            entry.summary.accuracy.map(|a| a.cer()),
+        ],
+    )?;
+
+    for (i, m) in entry.metrics.iter().enumerate() {
+        conn.execute(
+            "INSERT INTO audio_iterations (run_id, iteration, real_time_factor, processing_time_ms,
+                                           audio_duration_ms, word_count, transcription, wer, cer,
+                                           substitutions, insertions, deletions, clip_path,
+                                           reference, speaker, language)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
+            params![
+                entry.id,
+                i as i64 + 1,
+                m.real_time_factor,
+                m.processing_time_ms,
+                m.audio_duration_ms,
+                m.word_count,
+                m.transcription,
+                m.accuracy.map(|a| a.wer()),
+                m.accuracy.map(|a| a.cer()),
+                m.accuracy.map(|a| a.substitutions),
+                m.accuracy.map(|a| a.insertions),
+                m.accuracy.map(|a| a.deletions),
+                m.clip.as_ref().map(|c| c.audio_path.to_string_lossy().to_string()),
+                m.clip.as_ref().map(|c| c.reference.as_str()),
+                m.clip.as_ref().and_then(|c| c.speaker.as_deref()),
+                m.clip.as_ref().and_then(|c| c.language.as_deref()),
+            ],
+        )?;
+        // Dataset clips are found by file name
+        let clip_name = m.clip.as_ref().and_then(|c| c.audio_path.file_name());
+        let label = match clip_name {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => format!("Iteration {}", i + 1),
+        };
+        index_text(conn, SearchSource::Transcription, &entry.id, &label, &m.transcription)?;
+    }
+    Ok(())
+}
+
+/// Write a code entry's typed run row, one result row per problem and its problem tags
+fn insert_code_run(conn: &Connection, entry: &CodeHistoryEntry) -> Result<()> {
+    conn.execute(
+        "INSERT INTO runs (id, timestamp, benchmark_type, model_id, session_id, status, preset_id,
+                           language, temperature, max_tokens, avg_tps, avg_ttft_ms, pass_rate,
+                           problems_solved, problems_total, avg_execution_time_ms, total_output_tokens)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
+        params![
+            entry.id,
+            entry.timestamp,
+            serde_json::to_string(&entry.benchmark_type)?,
+            entry.model_id,
+            entry.session_id,
+            entry.status.as_str(),
+            entry.preset_id,
+            serde_json::to_string(&entry.language)?,
+            entry.config.temperature,
+            entry.config.max_tokens,
+            entry.summary.avg_tps,
+            entry.summary.avg_ttft_ms,
+            entry.summary.pass_rate,
+            entry.summary.problems_solved,
+            entry.summary.problems_total,
+            entry.summary.avg_execution_time_ms,
+            entry.summary.total_output_tokens as i64,
+        ],
+    )?;
+
+    for (i, m) in entry.metrics.iter().enumerate() {
+        conn.execute(
+            "INSERT INTO code_problem_results (run_id, position, problem_id, difficulty, passed,
+                                               tests_passed, tests_total, ttft_ms, tokens_per_sec,
+                                               execution_time_ms, test_execution_time_ms,
+                                               output_tokens, prompt_tokens, compilation_error,
+                                               runtime_error, cpu_time_ms, peak_rss_kb, slowdown)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)",
+            params![
+                entry.id,
+                i as i64,
+                m.problem_id,
+                serde_json::to_string(&m.difficulty)?,
+                m.solved(),
+                m.tests_passed,
+                m.tests_total,
+                m.ttft_ms,
+                m.tokens_per_sec,
+                m.execution_time_ms,
+                m.test_execution_time_ms,
+                m.output_tokens as i64,
+                m.prompt_tokens as i64,
+                m.compilation_error,
+                m.runtime_error,
+                m.cpu_time_ms,
+                m.peak_rss_kb as i64,
+                m.slowdown,
+            ],
+        )?;
+
+        for tag in &m.tags {
+            conn.execute(
+                "INSERT OR IGNORE INTO run_tags (run_id, tag) VALUES (?1, ?2)",
+                params![entry.id, tag],
+            )?;
+        }
+        index_text(conn, SearchSource::GeneratedCode, &entry.id, &m.problem_id, &m.generated_code)?;
+    }
+    Ok(())
+}
+
+/// Add a run's generated code or transcription to the full-text index (empty text is skipped)
+fn index_text(
+    conn: &Connection,
+    source: SearchSource,
+    run_id: &str,
+    label: &str,
+    content: &str,
+) -> Result<()> {
+    if content.trim().is_empty() {
+        return Ok(());
+    }
+    conn.execute(
+        "INSERT INTO history_fts (content, source, run_id, label) VALUES (?1, ?2, ?3, ?4)",
+        params![content, serde_json::to_string(&source)?, run_id, label],
+    )?;
+    Ok(())
+}
+
+/// Insert an effect detection row and index its LLM description
+fn insert_effect_detection_row(
+    conn: &Connection,
+    tool: EffectDetectionTool,
+    audio_path: &str,
+    result: &EffectDetectionResult,
+    created_at: Option<i64>,
+) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO effect_detection_history (tool, audio_path, effects_json, processing_time_ms, audio_duration_ms,
+                                               llm_description, llm_model_used, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, COALESCE(?8, strftime('%s', 'now')))",
+        params![
+            serde_json::to_string(&tool)?,
+            audio_path,
+            serde_json::to_string(&result.effects)?,
+            result.processing_time_ms,
+            result.audio_duration_ms,
+            result.llm_description,
+            result.llm_model_used,
+            created_at,
+        ],
+    )?;
+    let id = conn.last_insert_rowid();
+
+    if let Some(description) = result.llm_description.as_deref().filter(|d| !d.trim().is_empty()) {
+        conn.execute(
+            "INSERT INTO history_fts (content, source, label, effect_id) VALUES (?1, ?2, ?3, ?4)",
+            params![description, serde_json::to_string(&SearchSource::EffectDescription)?, audio_path, id],
+        )?;
+    }
+    Ok(id)
+}
+
+/// FTS5 query matching every whitespace-separated term of `query` as a literal phrase,
+/// so `collections.Counter` or `fn main()` need no FTS syntax
+fn fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
+    match terms.is_empty() {
+        true => None,
+        false => Some(terms.join(" ")),
+    }
+}
+
+/// Deserialize an entry's JSON payload, logging (not hiding) rows that no longer parse
+fn parse_payload<C, S, M>(id: &str, config: &str, summary: &str, metrics: &str) -> Option<(C, S, M)>
+where
+    C: DeserializeOwned,
+    S: DeserializeOwned,
+    M: DeserializeOwned,
+{
+    let parsed = serde_json::from_str(config).and_then(|config| {
+        Ok((config, serde_json::from_str(summary)?, serde_json::from_str(metrics)?))
+    });
+    match parsed {
+        Ok(parsed) => Some(parsed),
+        Err(e) => {
+            tracing::warn!("Skipping unreadable history entry {}: {}", id, e);
+            None
+        }
+    }
 }
 
 struct BatchStateRow {
@@ -849,30 +1603,220 @@ struct BatchStateRow {
     queue_completed: i64,
     failed_combo: Option<String>,
     error_message: Option<String>,
+    benchmark_type: String,
 }
 
-struct RowData {
+/// A `runs` row joined with its JSON payload
+struct RunRow {
     id: String,
     timestamp: i64,
     benchmark_type: String,
     model_id: String,
+    score: f64,
+    language: Option<String>,
+    audio_mode: Option<String>,
+    session_id: Option<String>,
+    status: String,
+    preset_id: Option<String>,
     config_json: String,
     summary_json: String,
     metrics_json: String,
+    environment_json: Option<String>,
+    origin_json: Option<String>,
 }
 
-struct CodeHistoryRow {
-    id: String,
-    timestamp: i64,
-    benchmark_type: String,
-    language: Option<String>,
-    model_id: String,
-    config_json: String,
-    summary_json: String,
-    metrics_json: String,
-    session_id: Option<String>,
-    status: Option<String>,
-    preset_id: Option<String>,
+/// Sort score per run type: pass rate (code), tokens/sec (text), real-time speed (audio)
+const SCORE_SQL: &str = "COALESCE(r.pass_rate, r.avg_tps, 1.0 / NULLIF(r.avg_rtf, 0), 0.0)";
+
+/// `runs` joined with its payload, in `run_row` column order
+fn run_select() -> String {
+    format!(
+        "SELECT r.id, r.timestamp, r.benchmark_type, r.model_id, {} AS score,
+                r.language, r.audio_mode, r.session_id, r.status, r.preset_id,
+                h.config_json, h.summary_json, h.metrics_json, h.environment_json, h.origin_json
+         FROM runs r JOIN benchmark_history h ON h.id = r.id",
+        SCORE_SQL
+    )
+}
+
+fn run_row(row: &rusqlite::Row) -> rusqlite::Result<RunRow> {
+    Ok(RunRow {
+        id: row.get(0)?,
+        timestamp: row.get(1)?,
+        benchmark_type: row.get(2)?,
+        model_id: row.get(3)?,
+        score: row.get(4)?,
+        language: row.get(5)?,
+        audio_mode: row.get(6)?,
+        session_id: row.get(7)?,
+        status: row.get(8)?,
+        preset_id: row.get(9)?,
+        config_json: row.get(10)?,
+        summary_json: row.get(11)?,
+        metrics_json: row.get(12)?,
+        environment_json: row.get(13)?,
+        origin_json: row.get(14)?,
+    })
+}
+
+/// `%term%` with LIKE wildcards in the term escaped (pairs with `ESCAPE '\'`)
+fn like_pattern(term: &str) -> String {
+    let escaped = term.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+/// Parse a page of rows, keeping the ids of rows that don't parse so callers can report them
+fn map_page<T>(page: HistoryPage<RunRow>, f: fn(RunRow) -> Option<T>) -> HistoryPage<T> {
+    let mut entries = Vec::with_capacity(page.entries.len());
+    let mut unreadable = page.unreadable;
+    for row in page.entries {
+        let id = row.id.clone();
+        match f(row) {
+            Some(entry) => entries.push(entry),
+            None => unreadable.push(id),
+        }
+    }
+    HistoryPage { entries, next_cursor: page.next_cursor, unreadable }
+}
+
+fn history_record(row: RunRow) -> Option<HistoryRecord> {
+    match serde_json::from_str(&row.benchmark_type) {
+        Ok(BenchmarkType::Text) => text_entry(row).map(HistoryRecord::Text),
+        Ok(BenchmarkType::Audio) => audio_entry(row).map(HistoryRecord::Audio),
+        Ok(BenchmarkType::Code) => code_entry(row).map(HistoryRecord::Code),
+        _ => {
+            tracing::warn!("Skipping history entry {} of type {}", row.id, row.benchmark_type);
+            None
+        }
+    }
+}
+
+fn text_entry(row: RunRow) -> Option<BenchmarkHistoryEntry> {
+    let (config, summary, metrics) =
+        parse_payload(&row.id, &row.config_json, &row.summary_json, &row.metrics_json)?;
+    let environment = parse_json_column(&row.id, "environment", row.environment_json);
+    let origin = parse_json_column(&row.id, "origin", row.origin_json);
+    Some(BenchmarkHistoryEntry {
+        id: row.id,
+        timestamp: row.timestamp,
+        benchmark_type: BenchmarkType::Text,
+        model_id: row.model_id,
+        config,
+        summary,
+        metrics,
+        environment,
+        origin,
+    })
+}
+
+fn audio_entry(row: RunRow) -> Option<AudioHistoryEntry> {
+    let (config, summary, metrics) =
+        parse_payload(&row.id, &row.config_json, &row.summary_json, &row.metrics_json)?;
+    let environment = parse_json_column(&row.id, "environment", row.environment_json);
+    let origin = parse_json_column(&row.id, "origin", row.origin_json);
+    let audio_mode = row
+        .audio_mode
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(AudioMode::Stt);
+    Some(AudioHistoryEntry {
+        id: row.id,
+        timestamp: row.timestamp,
+        benchmark_type: BenchmarkType::Audio,
+        audio_mode,
+        model_id: row.model_id,
+        config,
+        summary,
+        metrics,
+        environment,
+        origin,
+    })
+}
+
+fn code_entry(row: RunRow) -> Option<CodeHistoryEntry> {
+    let (config, summary, metrics): (CodeBenchmarkConfig, _, _) =
+        parse_payload(&row.id, &row.config_json, &row.summary_json, &row.metrics_json)?;
+    let environment = parse_json_column(&row.id, "environment", row.environment_json);
+    let origin = parse_json_column(&row.id, "origin", row.origin_json);
+    // Runs saved before the language column existed have none; the config records it too
+    let language = row
+        .language
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or(config.language);
+    Some(CodeHistoryEntry {
+        id: row.id,
+        timestamp: row.timestamp,
+        benchmark_type: BenchmarkType::Code,
+        model_id: row.model_id,
+        language,
+        config,
+        summary,
+        metrics,
+        session_id: row.session_id,
+        status: RunStatus::from_str(&row.status),
+        preset_id: row.preset_id,
+        environment,
+        origin,
+    })
+}
+
+fn json_column<T: Serialize>(value: &Option<T>) -> Result<Option<String>> {
+    Ok(value.as_ref().map(serde_json::to_string).transpose()?)
+}
+
+/// Optional JSON column such as the environment; older runs (or unreadable JSON) have none
+fn parse_json_column<T: DeserializeOwned>(id: &str, column: &str, json: Option<String>) -> Option<T> {
+    match serde_json::from_str(&json?) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            tracing::warn!("Ignoring unreadable {} of history entry {}: {}", column, id, e);
+            None
+        }
+    }
+}
+
+/// Compare a newly saved run with the baseline pinned for its config, if any
+fn record_baseline_comparison(conn: &Connection, record: &HistoryRecord) -> Result<()> {
+    let Some(baseline) = pinned_baseline(conn, record)? else {
+        return Ok(());
+    };
+    let report = compare_runs(&baseline, record, RegressionThresholds::default(), unix_now());
+    if report.regressed {
+        tracing::warn!("Run {} regressed against baseline {}:\n{}", report.run_id, report.baseline_id, report.describe());
+    }
+    conn.execute(
+        "INSERT OR REPLACE INTO baseline_comparisons (run_id, baseline_id, regressed, report_json)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![report.run_id, report.baseline_id, report.regressed, serde_json::to_string(&report)?],
+    )?;
+    Ok(())
+}
+
+/// The baseline pinned for a run's config, unless it is that run
+fn pinned_baseline(conn: &Connection, record: &HistoryRecord) -> Result<Option<HistoryRecord>> {
+    let baseline_id: Option<String> = conn
+        .query_row(
+            "SELECT run_id FROM baselines WHERE config_key = ?1",
+            params![record.config_key()],
+            |row| row.get(0),
+        )
+        .optional()?;
+    match baseline_id {
+        Some(id) if id != record.id() => load_record(conn, &id),
+        _ => Ok(None),
+    }
+}
+
+fn load_record(conn: &Connection, id: &str) -> Result<Option<HistoryRecord>> {
+    let sql = format!("{} WHERE r.id = ?1", run_select());
+    let row = conn.query_row(&sql, params![id], run_row).optional()?;
+    Ok(row.and_then(history_record))
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64
 }
 
 fn default_db_path() -> PathBuf {
@@ -881,3 +1825,406 @@ fn default_db_path() -> PathBuf {
         .join("llamaburn")
         .join("history.db")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use llamaburn_core::{
+        AudioBenchmarkConfig, AudioBenchmarkMetrics, AudioBenchmarkSummary, BenchmarkMetrics,
+        CodeBenchmarkMetrics, DatasetClip, FailedClip, TextBenchmarkConfig, TextBenchmarkSummary,
+        WhisperModel,
+    };
+
+    fn service() -> (tempfile::TempDir, HistoryService) {
+        let dir = tempfile::tempdir().unwrap();
+        let service = HistoryService::new(Some(dir.path().join("history.db"))).unwrap();
+        (dir, service)
+    }
+
+    fn text_run(id: &str, timestamp: i64, model: &str) -> BenchmarkHistoryEntry {
+        BenchmarkHistoryEntry {
+            id: id.to_string(),
+            timestamp,
+            benchmark_type: BenchmarkType::Text,
+            model_id: model.to_string(),
+            config: TextBenchmarkConfig { model_id: model.to_string(), ..Default::default() },
+            summary: TextBenchmarkSummary {
+                avg_tps: 42.0,
+                min_tps: 40.0,
+                max_tps: 44.0,
+                avg_ttft_ms: 10.0,
+                avg_total_ms: 100.0,
+                iterations: 2,
+            },
+            metrics: vec![
+                BenchmarkMetrics { tokens_per_sec: 40.0, ..Default::default() },
+                BenchmarkMetrics { tokens_per_sec: 44.0, ..Default::default() },
+            ],
+            environment: None,
+            origin: None,
+        }
+    }
+
+    fn code_run(id: &str, timestamp: i64, model: &str, pass_rate: f64) -> CodeHistoryEntry {
+        CodeHistoryEntry {
+            id: id.to_string(),
+            timestamp,
+            benchmark_type: BenchmarkType::Code,
+            model_id: model.to_string(),
+            language: Language::Rust,
+            config: CodeBenchmarkConfig {
+                model_id: model.to_string(),
+                language: Language::Rust,
+                ..Default::default()
+            },
+            summary: CodeBenchmarkSummary { pass_rate, problems_solved: 1, problems_total: 2, ..Default::default() },
+            metrics: vec![
+                CodeBenchmarkMetrics {
+                    problem_id: "two-sum".to_string(),
+                    tags: vec!["arrays".to_string()],
+                    tests_passed: 3,
+                    tests_total: 3,
+                    generated_code: "fn two_sum() { let seen = HashMap::new(); }".to_string(),
+                    ..Default::default()
+                },
+                CodeBenchmarkMetrics {
+                    problem_id: "fizz".to_string(),
+                    tests_passed: 1,
+                    tests_total: 3,
+                    ..Default::default()
+                },
+            ],
+            session_id: Some("s1".to_string()),
+            status: RunStatus::Success,
+            preset_id: None,
+            environment: None,
+            origin: None,
+        }
+    }
+
+    fn audio_run(id: &str, timestamp: i64) -> AudioHistoryEntry {
+        AudioHistoryEntry {
+            id: id.to_string(),
+            timestamp,
+            benchmark_type: BenchmarkType::Audio,
+            audio_mode: AudioMode::Stt,
+            model_id: "whisper-tiny".to_string(),
+            config: AudioBenchmarkConfig { model_size: Some(WhisperModel::Tiny), ..Default::default() },
+            summary: AudioBenchmarkSummary {
+                avg_rtf: 0.1,
+                min_rtf: 0.1,
+                max_rtf: 0.1,
+                avg_processing_ms: 50.0,
+                iterations: 1,
+                accuracy: None,
+                failed_clips: Vec::new(),
+            },
+            metrics: vec![AudioBenchmarkMetrics {
+                real_time_factor: 0.1,
+                processing_time_ms: 50.0,
+                audio_duration_ms: 500.0,
+                transcription: "hello there".to_string(),
+                word_count: 2,
+                accuracy: None,
+                clip: None,
+            }],
+            environment: None,
+            origin: None,
+        }
+    }
+
+    fn record_ids(page: &HistoryPage<HistoryRecord>) -> Vec<String> {
+        page.entries.iter().map(|r| r.id().to_string()).collect()
+    }
+
+    fn count(service: &HistoryService, table: &str) -> i64 {
+        let conn = service.conn.lock().unwrap();
+        conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0)).unwrap()
+    }
+
+    fn corrupt_summary(service: &HistoryService, id: &str) {
+        let conn = service.conn.lock().unwrap();
+        conn.execute("UPDATE benchmark_history SET summary_json = '{' WHERE id = ?1", [id]).unwrap();
+    }
+
+    /// Rows as V9 stored them: JSON payloads in benchmark_history, nothing in the run tables
+    fn insert_legacy_row(
+        conn: &Connection,
+        id: &str,
+        benchmark_type: &str,
+        language: Option<&str>,
+        payload: (String, String, String),
+    ) {
+        conn.execute(
+            "INSERT INTO benchmark_history
+             (id, timestamp, benchmark_type, model_id, language, config_json, summary_json, metrics_json, status)
+             VALUES (?1, 1, ?2, 'legacy', ?3, ?4, ?5, ?6, 'success')",
+            params![id, benchmark_type, language, payload.0, payload.1, payload.2],
+        )
+        .unwrap();
+    }
+
+    fn payload<C: Serialize, S: Serialize, M: Serialize>(config: &C, summary: &S, metrics: &M) -> (String, String, String) {
+        (
+            serde_json::to_string(config).unwrap(),
+            serde_json::to_string(summary).unwrap(),
+            serde_json::to_string(metrics).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_migrations_backfill_legacy_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.db");
+        {
+            let mut conn = Connection::open(&path).unwrap();
+            embedded::migrations::runner()
+                .set_target(refinery::Target::Version(9))
+                .run(&mut conn)
+                .unwrap();
+
+            let t = text_run("t1", 1, "legacy");
+            insert_legacy_row(&conn, "t1", "\"Text\"", None, payload(&t.config, &t.summary, &t.metrics));
+            let c = code_run("c1", 1, "legacy", 0.5);
+            insert_legacy_row(&conn, "c1", "\"Code\"", Some("\"rust\""), payload(&c.config, &c.summary, &c.metrics));
+            // Saved before the language column; the config still names it
+            insert_legacy_row(&conn, "c0", "\"Code\"", None, payload(&c.config, &c.summary, &c.metrics));
+            let a = audio_run("a1", 1);
+            insert_legacy_row(&conn, "a1", "\"Audio\"", None, payload(&a.config, &a.summary, &a.metrics));
+            insert_legacy_row(&conn, "bad", "\"Text\"", None, ("not json".into(), "{".into(), "[".into()));
+            conn.execute(
+                "INSERT INTO batch_state (session_id, created_at, updated_at, status, selected_models,
+                 selected_languages, selected_temperatures, selected_max_tokens, selected_problem_ids,
+                 auto_run_tests, skip_on_error, pending_combos, queue_total, queue_completed)
+                 VALUES ('b1', 1, 1, 'paused', '[]', '[]', '[]', '[]', '[]', 1, 0, '[]', 2, 1)",
+                [],
+            )
+            .unwrap();
+        }
+
+        let service = HistoryService::new(Some(path)).unwrap();
+
+        // V10: batches from before it were code batches
+        let batches = service.list_batches().unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].benchmark_type, BenchmarkType::Code);
+
+        // V11: every row, readable or not, gets a run and its normalized metrics
+        assert_eq!(count(&service, "runs"), 5);
+        assert_eq!(count(&service, "iterations"), 2);
+        assert_eq!(count(&service, "code_problem_results"), 4);
+        assert_eq!(count(&service, "audio_iterations"), 1);
+
+        let page = service.list_page(&HistoryFilter::default()).unwrap();
+        assert_eq!(page.entries.len(), 4);
+        assert_eq!(page.unreadable, ["bad"]);
+        let code = service.list_code(&HistoryFilter::default()).unwrap().entries;
+        assert!(code.iter().all(|e| e.language == Language::Rust));
+        assert_eq!(service.get_best_code_for_model("legacy", Language::Rust).unwrap(), Some(0.5));
+
+        // V12: tags; V16: generated code and transcripts are searchable
+        assert_eq!(service.history_tags().unwrap(), ["arrays"]);
+        assert_eq!(service.search_text("HashMap", 10).unwrap().len(), 2);
+        assert_eq!(service.search_text("there", 10).unwrap().len(), 1);
+
+        // V13, V15, V17, V18: new columns stay empty for old runs
+        let audio = service.list_audio(&HistoryFilter::default()).unwrap().entries;
+        assert!(audio[0].environment.is_none() && audio[0].origin.is_none());
+        assert!(audio[0].summary.accuracy.is_none() && audio[0].metrics[0].clip.is_none());
+
+        // V14: old runs can be pinned
+        service.pin_baseline("t1").unwrap();
+        assert!(service.baseline_ids().unwrap().contains("t1"));
+    }
+
+    #[test]
+    fn test_keyset_pages_match_unpaged_order() {
+        let (_dir, service) = service();
+        for i in 0..7 {
+            let model = match i % 2 {
+                0 => "llama",
+                _ => "qwen",
+            };
+            service.insert_code(&code_run(&format!("c{}", i), 100 + i, model, i as f64 / 10.0)).unwrap();
+        }
+        // Shares a timestamp and score with c3, so only the id breaks the tie
+        service.insert_code(&code_run("c3b", 103, "qwen", 0.3)).unwrap();
+        service.insert(&text_run("t1", 50, "llama")).unwrap();
+        service.insert_audio(&audio_run("a1", 60)).unwrap();
+        corrupt_summary(&service, "c5");
+
+        for sort in HistorySort::all() {
+            let all = service.list_page(&HistoryFilter { sort: *sort, ..Default::default() }).unwrap();
+            assert_eq!(all.unreadable, ["c5"]);
+
+            let mut paged = Vec::new();
+            let mut unreadable = Vec::new();
+            let mut cursor = None;
+            loop {
+                let filter = HistoryFilter { sort: *sort, limit: Some(2), cursor, ..Default::default() };
+                let page = service.list_page(&filter).unwrap();
+                assert!(page.entries.len() + page.unreadable.len() <= 2);
+                paged.extend(record_ids(&page));
+                unreadable.extend(page.unreadable);
+                cursor = page.next_cursor;
+                if cursor.is_none() {
+                    break;
+                }
+            }
+            assert_eq!(paged, record_ids(&all), "{:?}", sort);
+            assert_eq!(unreadable, ["c5"]);
+        }
+
+        let newest = service.list_page(&HistoryFilter::default()).unwrap();
+        assert_eq!(record_ids(&newest)[..3], ["c6", "c4", "c3b"]);
+
+        // A page ending exactly on the last row has no cursor
+        let page = service.list_code(&HistoryFilter { limit: Some(8), ..Default::default() }).unwrap();
+        assert_eq!(page.entries.len() + page.unreadable.len(), 8);
+        assert!(page.next_cursor.is_none());
+    }
+
+    #[test]
+    fn test_import_skips_runs_already_present() {
+        let (_a_dir, a) = service();
+        a.insert(&text_run("t1", 1, "llama")).unwrap();
+        a.insert_code(&code_run("c1", 2, "llama", 0.5)).unwrap();
+        a.insert_audio(&audio_run("a1", 3)).unwrap();
+        let bundle = a.export_bundle(&BundleContents::default()).unwrap();
+
+        let (_b_dir, b) = service();
+        b.insert_code(&code_run("c1", 2, "llama", 0.5)).unwrap();
+        let first = b.import_bundle(&bundle).unwrap();
+        assert_eq!((first.runs_imported, first.runs_skipped), (2, 1));
+        let again = b.import_bundle(&bundle).unwrap();
+        assert_eq!((again.runs_imported, again.runs_skipped), (0, 3));
+
+        assert_eq!(count(&b, "runs"), 3);
+        assert_eq!(count(&b, "code_problem_results"), 2);
+        assert_eq!(b.search_text("HashMap", 10).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_bundle_round_trip_reports_unreadable_runs() {
+        let (dir, a) = service();
+        a.insert(&text_run("t1", 1, "llama")).unwrap();
+        a.insert_code(&code_run("c1", 2, "llama", 0.5)).unwrap();
+        a.insert_code(&code_run("c2", 3, "qwen", 0.5)).unwrap();
+        a.insert_audio(&audio_run("a1", 4)).unwrap();
+        corrupt_summary(&a, "c2");
+
+        let path = dir.path().join("bundle.json");
+        let exported = a.export_bundle_to(&path, &BundleContents::default()).unwrap();
+        assert_eq!(exported.runs_exported, 3);
+        assert_eq!(exported.unreadable, ["c2"]);
+
+        // Picking runs by id reports them the same way
+        let picked = BundleContents { run_ids: Some(vec!["c2".into(), "a1".into()]), ..Default::default() };
+        let bundle = a.export_bundle(&picked).unwrap();
+        assert_eq!(bundle.runs.len(), 1);
+        assert_eq!(bundle.unreadable, ["c2"]);
+
+        let (_b_dir, b) = service();
+        let summary = b.import_bundle_from(&path).unwrap();
+        assert_eq!((summary.runs_imported, summary.runs_skipped), (3, 0));
+
+        let original = a.export_bundle(&BundleContents::default()).unwrap();
+        let imported = b.export_bundle(&BundleContents::default()).unwrap();
+        assert!(imported.unreadable.is_empty());
+        assert_eq!(imported.runs.len(), original.runs.len());
+        for (before, after) in original.runs.iter().zip(&imported.runs) {
+            let mut after = after.clone();
+            let origin = after.origin_mut().take().unwrap();
+            assert_eq!(origin.host, original.host);
+            assert_eq!(serde_json::to_value(before).unwrap(), serde_json::to_value(&after).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_bundle_import_leaves_out_unfinished_batches() {
+        let (_a_dir, a) = service();
+        let statuses = [
+            ("done", BatchStatus::Completed),
+            ("paused", BatchStatus::Paused),
+            ("running", BatchStatus::Running),
+        ];
+        for (id, status) in statuses {
+            a.insert_batch(&BatchState {
+                session_id: id.to_string(),
+                benchmark_type: BenchmarkType::Code,
+                created_at: 1,
+                updated_at: 2,
+                status,
+                selected_models: vec!["llama".to_string()],
+                selected_languages: Vec::new(),
+                selected_temperatures: Vec::new(),
+                selected_max_tokens: Vec::new(),
+                selected_problem_ids: Vec::new(),
+                auto_run_tests: true,
+                skip_on_error: false,
+                pending_combos: Vec::new(),
+                queue_total: 1,
+                queue_completed: 0,
+                failed_combo: None,
+                error_message: None,
+            })
+            .unwrap();
+        }
+        let bundle = a.export_bundle(&BundleContents { batches: true, ..Default::default() }).unwrap();
+        assert_eq!(bundle.batches.len(), 3);
+
+        let (_b_dir, b) = service();
+        let summary = b.import_bundle(&bundle).unwrap();
+        assert_eq!((summary.batches_imported, summary.batches_unfinished), (1, 2));
+        assert!(summary.describe().ends_with("2 unfinished batches left out"));
+        assert!(b.get_incomplete_batches().unwrap().is_empty());
+        let imported = b.list_batches().unwrap();
+        assert_eq!(imported.iter().map(|batch| batch.session_id.as_str()).collect::<Vec<_>>(), ["done"]);
+    }
+
+    #[test]
+    fn test_problems_without_tests_are_not_solved() {
+        let (_dir, service) = service();
+        let mut run = code_run("c1", 1, "llama", 0.5);
+        run.metrics.push(CodeBenchmarkMetrics { problem_id: "empty".to_string(), ..Default::default() });
+        service.insert_code(&run).unwrap();
+
+        for counts in service.problem_solutions().unwrap() {
+            let solutions = service.solutions_for_problem(&counts.problem_id).unwrap();
+            let passing = solutions.iter().filter(|s| s.passed()).count() as u32;
+            assert_eq!((counts.solutions, counts.passing), (solutions.len() as u32, passing), "{}", counts.problem_id);
+        }
+        let empty = service.problem_solutions().unwrap().into_iter().find(|c| c.problem_id == "empty").unwrap();
+        assert_eq!(empty.passing, 0);
+    }
+
+    #[test]
+    fn test_dataset_runs_keep_failed_clips() {
+        let (_dir, service) = service();
+        let mut run = audio_run("a1", 1);
+        run.summary.failed_clips = vec![FailedClip {
+            clip: DatasetClip { audio_path: PathBuf::from("clips/2.flac"), ..Default::default() },
+            error: "Empty audio".to_string(),
+        }];
+        service.insert_audio(&run).unwrap();
+
+        let Some(HistoryRecord::Audio(stored)) = service.get_record("a1").unwrap() else {
+            panic!("audio run not stored");
+        };
+        assert_eq!(stored.summary.failed_clips, run.summary.failed_clips);
+        assert_eq!(stored.metrics.len(), 1);
+    }
+
+    #[test]
+    fn test_leaderboard_skips_unreadable_summaries() {
+        let (_dir, service) = service();
+        service.insert_code(&code_run("a", 1, "alpha", 0.9)).unwrap();
+        service.insert_code(&code_run("b", 2, "beta", 0.5)).unwrap();
+        corrupt_summary(&service, "a");
+
+        let leaderboard = service.get_code_tag_leaderboard(Language::Rust, 10).unwrap();
+        assert_eq!(leaderboard.skipped, 1);
+        assert_eq!(leaderboard.entries.len(), 1);
+        assert_eq!(leaderboard.entries[0].model_id, "beta");
+    }
+}
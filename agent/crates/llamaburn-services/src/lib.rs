@@ -2,34 +2,49 @@ mod audio_input;
 mod audio_output;
 pub mod audio_effects;
 mod benchmark;
+mod dataset;
+mod diff;
 mod effect_detection;
 mod gpu_monitor;
 mod history;
 mod io_services;
 mod ollama;
 mod problem_loader;
+mod regression;
+mod report;
 pub mod runners;
 mod settings;
+mod trends;
 mod whisper;
 
 pub use audio_input::{AudioInputError, AudioInputService, StreamHandle};
 pub use audio_output::{AudioOutputError, AudioOutputService, MonitorHandle, PlaybackHandle};
 pub use benchmark::BenchmarkService;
+pub use dataset::{
+    dataset_stats, load_dataset, ClipOutlier, DatasetError, DatasetLayout, DatasetStats,
+    OutlierKind, RtfDistribution, SttDataset,
+};
+pub use diff::{line_diff, DiffLine};
 pub use io_services::IoServices;
-pub use effect_detection::{EffectDetectionError, EffectDetectionService, get_llm_blind_analysis, build_llm_analysis_prompt};
+pub use effect_detection::{
+    build_llm_analysis_prompt, get_llm_blind_analysis, EffectDetectionError,
+    EffectDetectionService,
+};
 pub use gpu_monitor::{GpuMonitor, GpuMonitorError};
 pub use history::{HistoryError, HistoryService};
 pub use ollama::{OllamaClient, OllamaError, OllamaModelDetails, OllamaShowResponse};
 pub use settings::{keys as settings_keys, SettingsError, SettingsService};
-pub use whisper::{get_audio_duration_ms, WhisperError, WhisperService};
+pub use whisper::{get_audio_duration_ms, load_reference_transcript, WhisperError, WhisperService};
 pub use problem_loader::{load_all_problem_sets, load_problem_set, ProblemLoaderError};
+pub use report::{format_utc, render_report, render_session_report, ReportFormat};
+pub use trends::trend_series;
 
 // Re-export benchmark runner types
 pub use runners::{
     BenchmarkEvent, BenchmarkResult, BenchmarkRunner, BenchmarkSummary,
     CodeBenchmarkEvent, CodeBenchmarkResult, CodeBenchmarkRunner,
     CodeExecutor, CodeExecutorError, TestResult,
-    run_tests_only, code_output_schema, StructuredCodeResponse,
+    run_tests_only, code_output_schema, StructuredCodeResponse, capture_host_environment, host_name,
 };
 
 // Re-export core types for GUI (GUI should only import from services)
@@ -41,17 +56,24 @@ pub use llamaburn_core::{
     AudioBenchmark, CodeBenchmark, BenchmarkCombo,
     // Audio types
     AudioBenchmarkConfig, AudioBenchmarkMetrics, AudioBenchmarkResult, AudioCaptureConfig,
-    AudioDevice, AudioMode, AudioSampleFormat, AudioSource, AudioSourceMode, DeviceType,
-    EffectDetectionConfig, EffectDetectionResult, EffectDetectionTool, Segment,
-    TranscriptionResult, WhisperEvent, WhisperModel,
+    AudioDevice, AudioMode, AudioSampleFormat, AudioSource, AudioSourceMode, DatasetClip,
+    DeviceType, FailedClip, EffectDetectionConfig, EffectDetectionResult, EffectDetectionTool,
+    Segment, TranscriptAccuracy, TranscriptionResult, WhisperEvent, WhisperModel,
+    normalize_transcript, score_transcript, word_error_rate,
     // Code types
-    CodeBenchmarkConfig, CodeBenchmarkMetrics, CodeBenchmarkSummary, Language,
-    CodeProblem, ProblemSet, Difficulty,
+    all_tests_passed, CodeBenchmarkConfig, CodeBenchmarkMetrics, CodeBenchmarkSummary, Language,
+    CodeProblem, ProblemSet, Difficulty, Comparator, TestCase, RepairAttempt, GenerationMode,
+    ProblemKind, TagStats, TestCaseResult, CodeQuality, PatchStats, RepoFixture, CodeTask,
     // History types
     AudioHistoryEntry, BatchCombo, BatchState, BatchStatus, BenchmarkHistoryEntry,
-    CodeHistoryEntry, EffectDetectionHistoryEntry, HistoryFilter, Preset, RunStatus,
+    CodeBatchCombo, CodeHistoryEntry, CodeLeaderboard, CodeLeaderboardEntry,
+    EffectDetectionHistoryEntry, HistoryCursor, HistoryFilter, HistoryPage, HistoryRecord,
+    HistorySort, Preset, RunStatus, SttBatchCombo, TextBatchCombo, MetricComparison,
+    RegressionReport, RegressionThresholds, SignificanceTest, BundleContents, BundleExportSummary,
+    BundleImportSummary, HistoryBundle, RunOrigin, SearchHit, SearchSource, ProblemSolutions,
+    StoredSolution, TrendMetric, TrendPoint, TrendSeries,
     // System types
-    GpuMetrics,
+    EnvironmentFingerprint, GpuMetrics,
     // Model types
     ModelConfig, ModelInfo,
 };
@@ -1,6 +1,6 @@
 use std::path::Path;
 
-use llamaburn_core::ProblemSet;
+use llamaburn_core::{Comparator, ProblemSet};
 
 #[derive(Debug, thiserror::Error)]
 pub enum ProblemLoaderError {
@@ -12,7 +12,8 @@ pub enum ProblemLoaderError {
 
 pub fn load_problem_set(path: &Path) -> Result<ProblemSet, ProblemLoaderError> {
     let content = std::fs::read_to_string(path)?;
-    let problem_set: ProblemSet = serde_json::from_str(&content)?;
+    let mut problem_set: ProblemSet = serde_json::from_str(&content)?;
+    resolve_checker_paths(&mut problem_set, path.parent().unwrap_or(Path::new("")));
     Ok(problem_set)
 }
 
@@ -31,3 +32,47 @@ pub fn load_all_problem_sets(dir: &Path) -> Result<Vec<ProblemSet>, ProblemLoade
 
     Ok(sets)
 }
+
+/// Make relative custom checker paths relative to the problem-set file's directory
+fn resolve_checker_paths(problem_set: &mut ProblemSet, dir: &Path) {
+    let comparators = problem_set.problems.iter_mut().flat_map(|p| {
+        std::iter::once(&mut p.comparator).chain(p.test_cases.iter_mut().filter_map(|t| t.comparator.as_mut()))
+    });
+    for comparator in comparators {
+        let Comparator::Custom { script } = comparator else {
+            continue;
+        };
+        if Path::new(script.as_str()).is_relative() {
+            *script = dir.join(script.as_str()).to_string_lossy().to_string();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checker_paths_resolve_against_set_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let set = r#"{"name": "s", "problems": [{
+            "id": "p", "title": "p", "description": "", "difficulty": "easy", "signatures": {},
+            "comparator": {"type": "custom", "script": "checkers/check.sh"},
+            "test_cases": [
+                {"input": "1", "expected": "1", "comparator": {"type": "custom", "script": "/usr/bin/check"}},
+                {"input": "2", "expected": "2"}
+            ]
+        }]}"#;
+        let path = dir.path().join("set.json");
+        std::fs::write(&path, set).unwrap();
+
+        let loaded = load_problem_set(&path).unwrap();
+        let problem = &loaded.problems[0];
+        let expected = dir.path().join("checkers/check.sh").to_string_lossy().to_string();
+        assert_eq!(problem.comparator, Comparator::Custom { script: expected });
+        assert_eq!(
+            problem.test_cases[0].comparator,
+            Some(Comparator::Custom { script: "/usr/bin/check".to_string() })
+        );
+    }
+}
@@ -0,0 +1,428 @@
+//! Significance tests comparing a run with its pinned baseline
+
+use llamaburn_core::{
+    HistoryRecord, MetricComparison, RegressionReport, RegressionThresholds, SignificanceTest,
+};
+
+/// Compare every metric the two runs share. Runs of different types compare nothing.
+pub fn compare_runs(
+    baseline: &HistoryRecord,
+    candidate: &HistoryRecord,
+    thresholds: RegressionThresholds,
+    compared_at: i64,
+) -> RegressionReport {
+    let metrics = match (baseline, candidate) {
+        (HistoryRecord::Text(b), HistoryRecord::Text(c)) => vec![
+            welch_metric(
+                "tokens_per_sec",
+                true,
+                &b.metrics.iter().map(|m| m.tokens_per_sec).collect::<Vec<_>>(),
+                &c.metrics.iter().map(|m| m.tokens_per_sec).collect::<Vec<_>>(),
+                &thresholds,
+            ),
+            welch_metric(
+                "ttft_ms",
+                false,
+                &b.metrics.iter().map(|m| m.time_to_first_token_ms).collect::<Vec<_>>(),
+                &c.metrics.iter().map(|m| m.time_to_first_token_ms).collect::<Vec<_>>(),
+                &thresholds,
+            ),
+            welch_metric(
+                "total_generation_ms",
+                false,
+                &b.metrics.iter().map(|m| m.total_generation_ms).collect::<Vec<_>>(),
+                &c.metrics.iter().map(|m| m.total_generation_ms).collect::<Vec<_>>(),
+                &thresholds,
+            ),
+        ],
+        (HistoryRecord::Code(b), HistoryRecord::Code(c)) => vec![
+            fisher_metric(
+                "pass_rate",
+                (b.summary.problems_solved, b.summary.problems_total),
+                (c.summary.problems_solved, c.summary.problems_total),
+                &thresholds,
+            ),
+            welch_metric(
+                "tokens_per_sec",
+                true,
+                &b.metrics.iter().map(|m| m.tokens_per_sec).collect::<Vec<_>>(),
+                &c.metrics.iter().map(|m| m.tokens_per_sec).collect::<Vec<_>>(),
+                &thresholds,
+            ),
+            welch_metric(
+                "ttft_ms",
+                false,
+                &b.metrics.iter().map(|m| m.ttft_ms).collect::<Vec<_>>(),
+                &c.metrics.iter().map(|m| m.ttft_ms).collect::<Vec<_>>(),
+                &thresholds,
+            ),
+        ],
+        (HistoryRecord::Audio(b), HistoryRecord::Audio(c)) => vec![
+            welch_metric(
+                "real_time_factor",
+                false,
+                &b.metrics.iter().map(|m| m.real_time_factor).collect::<Vec<_>>(),
+                &c.metrics.iter().map(|m| m.real_time_factor).collect::<Vec<_>>(),
+                &thresholds,
+            ),
+            welch_metric(
+                "processing_time_ms",
+                false,
+                &b.metrics.iter().map(|m| m.processing_time_ms).collect::<Vec<_>>(),
+                &c.metrics.iter().map(|m| m.processing_time_ms).collect::<Vec<_>>(),
+                &thresholds,
+            ),
+        ],
+        _ => Vec::new(),
+    };
+
+    RegressionReport {
+        run_id: candidate.id().to_string(),
+        baseline_id: baseline.id().to_string(),
+        config_key: candidate.config_key(),
+        compared_at,
+        thresholds,
+        regressed: metrics.iter().any(|m| m.regressed),
+        metrics,
+    }
+}
+
+/// Welch's t-test on per-iteration values; needs two samples on each side
+fn welch_metric(
+    metric: &str,
+    higher_is_better: bool,
+    baseline: &[f64],
+    candidate: &[f64],
+    thresholds: &RegressionThresholds,
+) -> MetricComparison {
+    let baseline_mean = mean(baseline);
+    let candidate_mean = mean(candidate);
+    let p_value = welch_p_value(baseline, candidate, higher_is_better);
+    finish(
+        MetricComparison {
+            metric: metric.to_string(),
+            test: SignificanceTest::WelchT,
+            higher_is_better,
+            baseline_mean,
+            candidate_mean,
+            baseline_samples: baseline.len(),
+            candidate_samples: candidate.len(),
+            change_pct: change_pct(baseline_mean, candidate_mean),
+            p_value,
+            regressed: false,
+        },
+        thresholds,
+    )
+}
+
+/// Fisher's exact test on the two runs' solved/failed counts, so the baseline's
+/// pass rate is treated as a sample rather than the true rate
+fn fisher_metric(
+    metric: &str,
+    (baseline_solved, baseline_total): (u32, u32),
+    (solved, total): (u32, u32),
+    thresholds: &RegressionThresholds,
+) -> MetricComparison {
+    let rate = |solved: u32, total: u32| match total {
+        0 => 0.0,
+        _ => solved as f64 / total as f64,
+    };
+    let baseline_mean = rate(baseline_solved, baseline_total);
+    let candidate_mean = rate(solved, total);
+    let p_value = match baseline_total > 0 && total > 0 {
+        true => Some(hypergeometric_cdf(
+            solved,
+            baseline_total + total,
+            baseline_solved + solved,
+            total,
+        )),
+        false => None,
+    };
+    finish(
+        MetricComparison {
+            metric: metric.to_string(),
+            test: SignificanceTest::FisherExact,
+            higher_is_better: true,
+            baseline_mean,
+            candidate_mean,
+            baseline_samples: baseline_total as usize,
+            candidate_samples: total as usize,
+            change_pct: change_pct(baseline_mean, candidate_mean),
+            p_value,
+            regressed: false,
+        },
+        thresholds,
+    )
+}
+
+/// Flag a significant change in the worse direction that crosses the size threshold
+fn finish(mut m: MetricComparison, thresholds: &RegressionThresholds) -> MetricComparison {
+    let worse = match m.higher_is_better {
+        true => m.change_pct <= -thresholds.min_change_pct,
+        false => m.change_pct >= thresholds.min_change_pct,
+    };
+    m.regressed = worse && m.p_value.is_some_and(|p| p <= thresholds.max_p_value);
+    m
+}
+
+fn mean(values: &[f64]) -> f64 {
+    match values.is_empty() {
+        true => 0.0,
+        false => values.iter().sum::<f64>() / values.len() as f64,
+    }
+}
+
+/// Unbiased sample variance
+fn variance(values: &[f64]) -> f64 {
+    let m = mean(values);
+    values.iter().map(|v| (v - m).powi(2)).sum::<f64>() / (values.len() as f64 - 1.0)
+}
+
+/// Percent change of the mean; a zero baseline only reports whether the value moved
+fn change_pct(baseline: f64, candidate: f64) -> f64 {
+    match baseline == 0.0 {
+        true if candidate == 0.0 => 0.0,
+        true => 100.0 * candidate.signum(),
+        false => (candidate - baseline) / baseline.abs() * 100.0,
+    }
+}
+
+/// One-sided p-value that `candidate` is worse than `baseline`
+fn welch_p_value(baseline: &[f64], candidate: &[f64], higher_is_better: bool) -> Option<f64> {
+    if baseline.len() < 2 || candidate.len() < 2 {
+        return None;
+    }
+    let (nb, nc) = (baseline.len() as f64, candidate.len() as f64);
+    let (vb, vc) = (variance(baseline) / nb, variance(candidate) / nc);
+    let diff = mean(candidate) - mean(baseline);
+    // Worse means lower when higher is better, so flip the sign to test one tail
+    let worse_by = match higher_is_better {
+        true => -diff,
+        false => diff,
+    };
+
+    let se = (vb + vc).sqrt();
+    if se == 0.0 {
+        // No spread on either side leaves nothing to judge the difference against
+        return None;
+    }
+
+    let t = worse_by / se;
+    let df = (vb + vc).powi(2) / (vb.powi(2) / (nb - 1.0) + vc.powi(2) / (nc - 1.0));
+    Some(1.0 - student_t_cdf(t, df))
+}
+
+/// P(T <= t) for Student's t with `df` degrees of freedom
+fn student_t_cdf(t: f64, df: f64) -> f64 {
+    let tail = 0.5 * incomplete_beta(df / 2.0, 0.5, df / (df + t * t));
+    match t > 0.0 {
+        true => 1.0 - tail,
+        false => tail,
+    }
+}
+
+/// P(X <= k) where X counts successes in `draws` taken without replacement from
+/// `population` items of which `successes` are successes (one tail of Fisher's test)
+fn hypergeometric_cdf(k: u32, population: u32, successes: u32, draws: u32) -> f64 {
+    let ln_choose = |n: u32, r: u32| {
+        ln_gamma(n as f64 + 1.0) - ln_gamma(r as f64 + 1.0) - ln_gamma((n - r) as f64 + 1.0)
+    };
+    let lowest = (draws + successes).saturating_sub(population);
+    let highest = k.min(draws).min(successes);
+    if highest < lowest {
+        return 0.0;
+    }
+    let ln_total = ln_choose(population, draws);
+    let sum: f64 = (lowest..=highest)
+        .map(|i| (ln_choose(successes, i) + ln_choose(population - successes, draws - i) - ln_total).exp())
+        .sum();
+    sum.min(1.0)
+}
+
+/// Regularized incomplete beta I_x(a, b), via its continued fraction
+fn incomplete_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let front = (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+    // The fraction converges fastest below the mean; use the symmetry relation above it
+    match x < (a + 1.0) / (a + b + 2.0) {
+        true => front * beta_fraction(a, b, x) / a,
+        false => 1.0 - front * beta_fraction(b, a, 1.0 - x) / b,
+    }
+}
+
+/// Lentz's method for the incomplete beta continued fraction
+fn beta_fraction(a: f64, b: f64, x: f64) -> f64 {
+    const TINY: f64 = 1e-300;
+    const EPS: f64 = 1e-14;
+
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    if d.abs() < TINY {
+        d = TINY;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+
+    for m in 1..300 {
+        let m = m as f64;
+        let m2 = 2.0 * m;
+
+        let aa = m * (b - m) * x / ((a + m2 - 1.0) * (a + m2));
+        d = 1.0 + aa * d;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = 1.0 + aa / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        h *= d * c;
+
+        let aa = -(a + m) * (a + b + m) * x / ((a + m2) * (a + m2 + 1.0));
+        d = 1.0 + aa * d;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = 1.0 + aa / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+
+        if (delta - 1.0).abs() < EPS {
+            break;
+        }
+    }
+    h
+}
+
+/// ln Γ(x) for x > 0 (Lanczos approximation)
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFS: [f64; 6] = [
+        76.180_091_729_471_46,
+        -86.505_320_329_416_77,
+        24.014_098_240_830_91,
+        -1.231_739_572_450_155,
+        0.001_208_650_973_866_179,
+        -0.000_005_395_239_384_953,
+    ];
+    let tmp = x + 5.5 - (x + 0.5) * (x + 5.5).ln();
+    let mut series = 1.000_000_000_190_015;
+    for (i, c) in COEFFS.iter().enumerate() {
+        series += c / (x + 1.0 + i as f64);
+    }
+    -tmp + (2.506_628_274_631_000_5 * series / x).ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distribution_functions() {
+        assert!((ln_gamma(5.0) - 24f64.ln()).abs() < 1e-9);
+        // Two-sided 5% critical value of t with 10 df is 2.228
+        assert!((student_t_cdf(2.228, 10.0) - 0.975).abs() < 1e-3);
+        assert!((student_t_cdf(0.0, 4.0) - 0.5).abs() < 1e-12);
+        // 126 of the 252 ways to draw 5 of 10 (5 successes) have at most 2 successes
+        assert!((hypergeometric_cdf(2, 10, 5, 5) - 0.5).abs() < 1e-9);
+        assert!((hypergeometric_cdf(5, 10, 5, 5) - 1.0).abs() < 1e-9);
+        assert_eq!(hypergeometric_cdf(0, 10, 8, 5), 0.0);
+    }
+
+    #[test]
+    fn test_welch_significant_drops() {
+        let thresholds = RegressionThresholds::default();
+        let baseline = [50.0, 51.0, 49.5, 50.5, 50.2];
+
+        let slower = welch_metric("tps", true, &baseline, &[40.0, 41.0, 39.5, 40.5, 40.1], &thresholds);
+        assert!(slower.regressed);
+        assert!(slower.p_value.unwrap() < 0.001);
+        assert!((slower.change_pct + 19.8).abs() < 0.5);
+
+        // Faster is never a regression, however significant
+        let faster = welch_metric("tps", true, &baseline, &[60.0, 61.0, 59.5, 60.5, 60.2], &thresholds);
+        assert!(!faster.regressed);
+        assert!(faster.p_value.unwrap() > 0.999);
+
+        // A large but noisy drop isn't significant
+        let noisy = welch_metric("tps", true, &baseline, &[20.0, 80.0, 15.0, 70.0, 30.0], &thresholds);
+        assert!(!noisy.regressed);
+
+        // Lower-is-better metrics regress upwards
+        let latency = welch_metric("ttft", false, &[100.0, 101.0, 99.0], &[130.0, 131.0, 129.0], &thresholds);
+        assert!(latency.p_value.unwrap() < 0.001);
+        assert!(latency.regressed);
+
+        let single = welch_metric("tps", true, &[50.0], &[10.0], &thresholds);
+        assert_eq!(single.p_value, None);
+        assert!(!single.regressed);
+    }
+
+    #[test]
+    fn test_welch_without_spread() {
+        let thresholds = RegressionThresholds::default();
+
+        let constant = welch_metric("ttft", false, &[100.0, 100.0], &[130.0, 130.0], &thresholds);
+        assert_eq!(constant.p_value, None);
+        assert!(!constant.regressed);
+
+        let unchanged = welch_metric("tps", true, &[50.0, 50.0, 50.0], &[50.0, 50.0], &thresholds);
+        assert_eq!(unchanged.p_value, None);
+        assert!(!unchanged.regressed);
+
+        // Spread on one side is enough to test
+        let one_sided = welch_metric("tps", true, &[50.0, 50.0, 50.0], &[40.0, 41.0, 39.0], &thresholds);
+        assert!(one_sided.p_value.unwrap() < 0.01);
+        assert!(one_sided.regressed);
+    }
+
+    #[test]
+    fn test_fisher_pass_rate_drops() {
+        let thresholds = RegressionThresholds::default();
+        let dropped = fisher_metric("pass_rate", (18, 20), (11, 20), &thresholds);
+        assert_eq!(dropped.test, SignificanceTest::FisherExact);
+        assert!(dropped.regressed);
+        assert!(dropped.p_value.unwrap() < 0.05);
+
+        let same = fisher_metric("pass_rate", (18, 20), (17, 20), &thresholds);
+        assert!(!same.regressed);
+
+        let empty = fisher_metric("pass_rate", (0, 0), (5, 10), &thresholds);
+        assert_eq!(empty.p_value, None);
+    }
+
+    #[test]
+    fn test_fisher_all_or_nothing() {
+        let thresholds = RegressionThresholds::default();
+
+        // A perfect baseline is a sample too: one miss in 20 is a coin flip
+        let one_miss = fisher_metric("pass_rate", (20, 20), (19, 20), &thresholds);
+        assert!((one_miss.p_value.unwrap() - 0.5).abs() < 1e-9);
+        assert!(!one_miss.regressed);
+
+        let collapsed = fisher_metric("pass_rate", (20, 20), (0, 20), &thresholds);
+        assert!(collapsed.p_value.unwrap() < 1e-9);
+        assert!(collapsed.regressed);
+
+        let still_perfect = fisher_metric("pass_rate", (20, 20), (20, 20), &thresholds);
+        assert!((still_perfect.p_value.unwrap() - 1.0).abs() < 1e-9);
+        assert!(!still_perfect.regressed);
+
+        let still_zero = fisher_metric("pass_rate", (0, 20), (0, 20), &thresholds);
+        assert!((still_zero.p_value.unwrap() - 1.0).abs() < 1e-9);
+        assert!(!still_zero.regressed);
+
+        let improved = fisher_metric("pass_rate", (0, 20), (20, 20), &thresholds);
+        assert!((improved.p_value.unwrap() - 1.0).abs() < 1e-9);
+        assert!(!improved.regressed);
+    }
+}
@@ -0,0 +1,584 @@
+//! Self-contained Markdown and HTML reports over history entries, with inline SVG charts
+
+use std::collections::BTreeSet;
+
+use llamaburn_core::{
+    EnvironmentFingerprint, HistoryFilter, HistoryRecord, HistorySort, TagStats,
+};
+
+use crate::history::{HistoryService, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReportFormat {
+    #[default]
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReportFormat::Markdown => "Markdown",
+            ReportFormat::Html => "HTML",
+        }
+    }
+
+    pub fn all() -> &'static [ReportFormat] {
+        &[ReportFormat::Markdown, ReportFormat::Html]
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ReportFormat::Markdown => "md",
+            ReportFormat::Html => "html",
+        }
+    }
+}
+
+/// Report over the given runs: summary tables and charts per benchmark type, code
+/// breakdowns by difficulty and tag, failed problems, environments and full configs
+pub fn render_report(title: &str, runs: &[HistoryRecord], format: ReportFormat, generated_at: i64) -> String {
+    render_blocks(title, &build_report(title, runs, &[], generated_at), format)
+}
+
+fn render_blocks(title: &str, blocks: &[Block], format: ReportFormat) -> String {
+    match format {
+        ReportFormat::Markdown => to_markdown(blocks),
+        ReportFormat::Html => to_html(title, blocks),
+    }
+}
+
+/// Report over every run of a batch session, oldest first; runs whose stored data
+/// can't be read are listed by id
+pub fn render_session_report(
+    history: &HistoryService,
+    session_id: &str,
+    format: ReportFormat,
+    generated_at: i64,
+) -> Result<String> {
+    let filter = HistoryFilter {
+        session_id: Some(session_id.to_string()),
+        sort: HistorySort::Oldest,
+        ..Default::default()
+    };
+    let page = history.list_page(&filter)?;
+    let title = format!("Batch session {}", session_id);
+    let blocks = build_report(&title, &page.entries, &page.unreadable, generated_at);
+    Ok(render_blocks(&title, &blocks, format))
+}
+
+/// Format-neutral report content
+enum Block {
+    Heading(usize, String),
+    Text(String),
+    Table { headers: Vec<String>, rows: Vec<Vec<String>> },
+    List(Vec<String>),
+    Code(String),
+    Svg(String),
+}
+
+fn build_report(title: &str, runs: &[HistoryRecord], unreadable: &[String], generated_at: i64) -> Vec<Block> {
+    let mut blocks = vec![
+        Block::Heading(1, title.to_string()),
+        Block::Text(format!("Generated {} · {} runs", format_utc(generated_at), runs.len())),
+    ];
+    if !unreadable.is_empty() {
+        blocks.push(Block::Text(format!(
+            "⚠ {} runs left out because their stored data can't be read: {}",
+            unreadable.len(),
+            unreadable.join(", ")
+        )));
+    }
+
+    let text: Vec<_> = runs
+        .iter()
+        .filter_map(|r| match r {
+            HistoryRecord::Text(e) => Some(e),
+            _ => None,
+        })
+        .collect();
+    let code: Vec<_> = runs
+        .iter()
+        .filter_map(|r| match r {
+            HistoryRecord::Code(e) => Some(e),
+            _ => None,
+        })
+        .collect();
+    let audio: Vec<_> = runs
+        .iter()
+        .filter_map(|r| match r {
+            HistoryRecord::Audio(e) => Some(e),
+            _ => None,
+        })
+        .collect();
+
+    if !text.is_empty() {
+        blocks.push(Block::Heading(2, "Text Benchmarks".to_string()));
+        blocks.push(table(
+            &["Run", "Prompt Set", "Temp", "Max Tokens", "Iterations", "Avg TPS", "Min/Max TPS", "TTFT", "Total", "Date"],
+            text.iter()
+                .map(|e| {
+                    vec![
+                        run_label(&e.model_id, &e.id),
+                        e.config.prompt_set.clone(),
+                        format!("{:.1}", e.config.temperature),
+                        e.config.max_tokens.map_or("—".to_string(), |n| n.to_string()),
+                        e.summary.iterations.to_string(),
+                        format!("{:.1}", e.summary.avg_tps),
+                        format!("{:.1}/{:.1}", e.summary.min_tps, e.summary.max_tps),
+                        format!("{:.0} ms", e.summary.avg_ttft_ms),
+                        format!("{:.0} ms", e.summary.avg_total_ms),
+                        format_utc(e.timestamp),
+                    ]
+                })
+                .collect(),
+        ));
+        blocks.push(Block::Svg(bar_chart(
+            "Average tokens/sec (higher is better)",
+            &text
+                .iter()
+                .map(|e| (run_label(&e.model_id, &e.id), e.summary.avg_tps))
+                .collect::<Vec<_>>(),
+            "",
+        )));
+    }
+
+    if !code.is_empty() {
+        blocks.push(Block::Heading(2, "Code Benchmarks".to_string()));
+        blocks.push(table(
+            &["Run", "Language", "Task", "Temp", "Pass Rate", "Solved", "Avg TPS", "TTFT", "Execution", "Status", "Date"],
+            code.iter()
+                .map(|e| {
+                    vec![
+                        run_label(&e.model_id, &e.id),
+                        e.language.label().to_string(),
+                        e.config.task.label(),
+                        format!("{:.1}", e.config.temperature),
+                        format!("{:.1}%", e.summary.pass_rate * 100.0),
+                        e.summary.solved_label(),
+                        format!("{:.1}", e.summary.avg_tps),
+                        format!("{:.0} ms", e.summary.avg_ttft_ms),
+                        format!("{:.0} ms", e.summary.avg_execution_time_ms),
+                        e.status.as_str().to_string(),
+                        format_utc(e.timestamp),
+                    ]
+                })
+                .collect(),
+        ));
+        blocks.push(Block::Svg(bar_chart(
+            "Pass rate (higher is better)",
+            &code
+                .iter()
+                .map(|e| (run_label(&e.model_id, &e.id), e.summary.pass_rate * 100.0))
+                .collect::<Vec<_>>(),
+            "%",
+        )));
+
+        blocks.push(Block::Heading(3, "By Difficulty".to_string()));
+        blocks.push(table(
+            &["Run", "Easy", "Medium", "Hard"],
+            code.iter()
+                .map(|e| {
+                    let s = &e.summary;
+                    vec![
+                        run_label(&e.model_id, &e.id),
+                        solved_cell(TagStats { solved: s.easy_solved, total: s.easy_total }),
+                        solved_cell(TagStats { solved: s.medium_solved, total: s.medium_total }),
+                        solved_cell(TagStats { solved: s.hard_solved, total: s.hard_total }),
+                    ]
+                })
+                .collect(),
+        ));
+
+        let tags: BTreeSet<&String> = code.iter().flat_map(|e| e.summary.tag_stats.keys()).collect();
+        if !tags.is_empty() {
+            blocks.push(Block::Heading(3, "By Tag".to_string()));
+            let mut headers = vec!["Tag".to_string()];
+            headers.extend(code.iter().map(|e| run_label(&e.model_id, &e.id)));
+            let rows = tags
+                .iter()
+                .map(|tag| {
+                    let mut row = vec![tag.to_string()];
+                    row.extend(code.iter().map(|e| {
+                        e.summary.tag_stats.get(*tag).map_or("—".to_string(), |s| solved_cell(*s))
+                    }));
+                    row
+                })
+                .collect();
+            blocks.push(Block::Table { headers, rows });
+        }
+
+        let failed: Vec<_> = code
+            .iter()
+            .filter(|e| e.metrics.iter().any(|m| !m.solved()))
+            .collect();
+        if !failed.is_empty() {
+            blocks.push(Block::Heading(3, "Failed Problems".to_string()));
+            for e in failed {
+                blocks.push(Block::Text(run_label(&e.model_id, &e.id)));
+                blocks.push(Block::List(
+                    e.metrics
+                        .iter()
+                        .filter(|m| !m.solved())
+                        .map(|m| {
+                            let error = m
+                                .compilation_error
+                                .as_deref()
+                                .or(m.runtime_error.as_deref())
+                                .and_then(|err| err.lines().find(|l| !l.trim().is_empty()))
+                                .map(|line| format!(": {}", line.trim()))
+                                .unwrap_or_default();
+                            format!(
+                                "{} ({}) — {}/{} tests{}",
+                                m.problem_id,
+                                m.difficulty.label(),
+                                m.tests_passed,
+                                m.tests_total,
+                                error
+                            )
+                        })
+                        .collect(),
+                ));
+            }
+        }
+    }
+
+    if !audio.is_empty() {
+        blocks.push(Block::Heading(2, "Audio Benchmarks".to_string()));
+        blocks.push(table(
+            &["Run", "Mode", "Whisper Model", "Audio", "Iterations", "Avg RTF", "Min/Max RTF", "Processing", "WER", "CER", "Date"],
+            audio
+                .iter()
+                .map(|e| {
+                    vec![
+                        run_label(&e.model_id, &e.id),
+                        e.audio_mode.label().to_string(),
+                        e.config.model_size.map_or("—".to_string(), |m| m.label().to_string()),
+                        e.config
+                            .audio_path
+                            .file_name()
+                            .map_or("—".to_string(), |n| n.to_string_lossy().to_string()),
+                        e.summary.iterations.to_string(),
+                        format!("{:.3}x", e.summary.avg_rtf),
+                        format!("{:.3}/{:.3}", e.summary.min_rtf, e.summary.max_rtf),
+                        format!("{:.0} ms", e.summary.avg_processing_ms),
+                        e.summary.accuracy.map_or("—".to_string(), |a| format!("{:.1}%", a.wer() * 100.0)),
+                        e.summary.accuracy.map_or("—".to_string(), |a| format!("{:.1}%", a.cer() * 100.0)),
+                        format_utc(e.timestamp),
+                    ]
+                })
+                .collect(),
+        ));
+        blocks.push(Block::Svg(bar_chart(
+            "Average real-time factor (lower is better)",
+            &audio
+                .iter()
+                .map(|e| (run_label(&e.model_id, &e.id), e.summary.avg_rtf))
+                .collect::<Vec<_>>(),
+            "x",
+        )));
+    }
+
+    blocks.extend(environment_blocks(runs));
+
+    blocks.push(Block::Heading(2, "Configurations".to_string()));
+    for run in runs {
+        let (label, config) = match run {
+            HistoryRecord::Text(e) => (run_label(&e.model_id, &e.id), serde_json::to_string_pretty(&e.config)),
+            HistoryRecord::Code(e) => (run_label(&e.model_id, &e.id), serde_json::to_string_pretty(&e.config)),
+            HistoryRecord::Audio(e) => (run_label(&e.model_id, &e.id), serde_json::to_string_pretty(&e.config)),
+        };
+        blocks.push(Block::Heading(3, label));
+        blocks.push(Block::Code(config.unwrap_or_default()));
+    }
+
+    blocks
+}
+
+/// Fingerprint table plus the differences between runs, relative to the first one recorded
+fn environment_blocks(runs: &[HistoryRecord]) -> Vec<Block> {
+    let envs: Vec<(String, &str, Option<&EnvironmentFingerprint>)> = runs
+        .iter()
+        .map(|r| match r {
+            HistoryRecord::Text(e) => (run_label(&e.model_id, &e.id), e.model_id.as_str(), e.environment.as_ref()),
+            HistoryRecord::Code(e) => (run_label(&e.model_id, &e.id), e.model_id.as_str(), e.environment.as_ref()),
+            HistoryRecord::Audio(e) => (run_label(&e.model_id, &e.id), e.model_id.as_str(), e.environment.as_ref()),
+        })
+        .collect();
+
+    let or_dash = |v: Option<&String>| v.cloned().unwrap_or_else(|| "—".to_string());
+    let rows = envs
+        .iter()
+        .filter_map(|(label, _, env)| {
+            let env = (*env)?;
+            Some(vec![
+                label.clone(),
+                or_dash(env.ollama_version.as_ref()),
+                or_dash(env.gpu_name.as_ref()),
+                or_dash(env.gpu_driver.as_ref()),
+                or_dash(env.quantization.as_ref()),
+                or_dash(env.kernel.as_ref()),
+                or_dash(env.cpu_model.as_ref()),
+                env.ram_mb.map_or("—".to_string(), |mb| format!("{:.1} GB", mb as f64 / 1024.0)),
+                env.llamaburn_version.clone(),
+            ])
+        })
+        .collect::<Vec<_>>();
+    if rows.is_empty() {
+        return Vec::new();
+    }
+
+    let mut blocks = vec![
+        Block::Heading(2, "Environment".to_string()),
+        table(
+            &["Run", "Ollama", "GPU", "Driver", "Quantization", "Kernel", "CPU", "RAM", "llamaburn"],
+            rows,
+        ),
+    ];
+
+    let missing = envs.iter().filter(|(_, _, env)| env.is_none()).count();
+    let mut notes = Vec::new();
+    if missing > 0 {
+        notes.push(format!("{} of {} runs have no environment recorded", missing, envs.len()));
+    }
+    let mut recorded = envs.iter().filter_map(|(label, model, env)| Some((label, *model, (*env)?)));
+    if let Some((first_label, first_model, first)) = recorded.next() {
+        for (label, model, env) in recorded {
+            for diff in first.differences(env, first_model == model) {
+                notes.push(format!("{} vs {}: {}", first_label, label, diff));
+            }
+        }
+    }
+    if !notes.is_empty() {
+        blocks.push(Block::Text("Differences between runs:".to_string()));
+        blocks.push(Block::List(notes));
+    }
+    blocks
+}
+
+fn table(headers: &[&str], rows: Vec<Vec<String>>) -> Block {
+    Block::Table {
+        headers: headers.iter().map(|h| h.to_string()).collect(),
+        rows,
+    }
+}
+
+/// "llama3.1:8b (1a2b3c4d)"
+fn run_label(model_id: &str, id: &str) -> String {
+    format!("{} ({})", model_id, &id[..8.min(id.len())])
+}
+
+fn solved_cell(stats: TagStats) -> String {
+    match stats.total {
+        0 => "—".to_string(),
+        _ => format!("{}/{} ({:.0}%)", stats.solved, stats.total, stats.pass_rate() * 100.0),
+    }
+}
+
+/// Horizontal bar chart, one bar per run
+fn bar_chart(title: &str, bars: &[(String, f64)], unit: &str) -> String {
+    const WIDTH: f64 = 720.0;
+    const LABEL_WIDTH: f64 = 260.0;
+    const VALUE_WIDTH: f64 = 80.0;
+    const BAR_HEIGHT: f64 = 20.0;
+    const GAP: f64 = 6.0;
+    const TOP: f64 = 30.0;
+
+    let max = bars.iter().map(|(_, v)| *v).fold(0.0, f64::max);
+    let height = TOP + bars.len() as f64 * (BAR_HEIGHT + GAP) + GAP;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" \
+         font-family=\"sans-serif\" font-size=\"12\">\n\
+         <text x=\"0\" y=\"18\" font-size=\"14\" font-weight=\"bold\">{t}</text>\n",
+        w = WIDTH,
+        h = height,
+        t = escape_html(title)
+    );
+    for (i, (label, value)) in bars.iter().enumerate() {
+        let y = TOP + i as f64 * (BAR_HEIGHT + GAP);
+        let width = match max > 0.0 {
+            true => (value / max) * (WIDTH - LABEL_WIDTH - VALUE_WIDTH),
+            false => 0.0,
+        };
+        svg.push_str(&format!(
+            "<text x=\"{lx}\" y=\"{ty}\" text-anchor=\"end\">{label}</text>\n\
+             <rect x=\"{x}\" y=\"{y}\" width=\"{width:.1}\" height=\"{bh}\" fill=\"#e4572e\"/>\n\
+             <text x=\"{vx:.1}\" y=\"{ty}\">{value:.2}{unit}</text>\n",
+            lx = LABEL_WIDTH - 8.0,
+            ty = y + BAR_HEIGHT - 5.0,
+            label = escape_html(label),
+            x = LABEL_WIDTH,
+            y = y,
+            width = width,
+            bh = BAR_HEIGHT,
+            vx = LABEL_WIDTH + width + 6.0,
+            value = value,
+            unit = escape_html(unit),
+        ));
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+fn to_markdown(blocks: &[Block]) -> String {
+    let cell = |s: &str| s.replace('|', "\\|").replace('\n', " ");
+    let mut out = String::new();
+    for block in blocks {
+        match block {
+            Block::Heading(level, text) => out.push_str(&format!("{} {}\n\n", "#".repeat(*level), text)),
+            Block::Text(text) => out.push_str(&format!("{}\n\n", text)),
+            Block::Table { headers, rows } => {
+                let headers: Vec<String> = headers.iter().map(|h| cell(h)).collect();
+                out.push_str(&format!("| {} |\n", headers.join(" | ")));
+                out.push_str(&format!("|{}\n", " --- |".repeat(headers.len())));
+                for row in rows {
+                    let row: Vec<String> = row.iter().map(|c| cell(c)).collect();
+                    out.push_str(&format!("| {} |\n", row.join(" | ")));
+                }
+                out.push('\n');
+            }
+            Block::List(items) => {
+                for item in items {
+                    out.push_str(&format!("- {}\n", item));
+                }
+                out.push('\n');
+            }
+            Block::Code(code) => out.push_str(&format!("```json\n{}\n```\n\n", code)),
+            // Raw HTML; renders in most Markdown viewers
+            Block::Svg(svg) => out.push_str(&format!("{}\n\n", svg)),
+        }
+    }
+    out
+}
+
+fn to_html(title: &str, blocks: &[Block]) -> String {
+    let mut body = String::new();
+    for block in blocks {
+        match block {
+            Block::Heading(level, text) => {
+                body.push_str(&format!("<h{l}>{}</h{l}>\n", escape_html(text), l = level))
+            }
+            Block::Text(text) => body.push_str(&format!("<p>{}</p>\n", escape_html(text))),
+            Block::Table { headers, rows } => {
+                body.push_str("<table>\n<tr>");
+                for h in headers {
+                    body.push_str(&format!("<th>{}</th>", escape_html(h)));
+                }
+                body.push_str("</tr>\n");
+                for row in rows {
+                    body.push_str("<tr>");
+                    for c in row {
+                        body.push_str(&format!("<td>{}</td>", escape_html(c)));
+                    }
+                    body.push_str("</tr>\n");
+                }
+                body.push_str("</table>\n");
+            }
+            Block::List(items) => {
+                body.push_str("<ul>\n");
+                for item in items {
+                    body.push_str(&format!("<li>{}</li>\n", escape_html(item)));
+                }
+                body.push_str("</ul>\n");
+            }
+            Block::Code(code) => body.push_str(&format!("<pre>{}</pre>\n", escape_html(code))),
+            Block::Svg(svg) => body.push_str(&format!("<figure>{}</figure>\n", svg)),
+        }
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n\
+         body {{ font-family: sans-serif; max-width: 1100px; margin: 2em auto; color: #222; }}\n\
+         table {{ border-collapse: collapse; margin: 1em 0; }}\n\
+         th, td {{ border: 1px solid #ccc; padding: 4px 8px; text-align: left; }}\n\
+         th {{ background: #f3f3f3; }}\n\
+         pre {{ background: #f6f6f6; padding: 8px; overflow-x: auto; }}\n\
+         </style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape_html(title),
+        body
+    )
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// "2025-01-31 14:05 UTC" from unix seconds
+pub fn format_utc(ts: i64) -> String {
+    let days = ts.div_euclid(86_400);
+    let secs = ts.rem_euclid(86_400);
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = match mp < 10 {
+        true => mp + 3,
+        false => mp - 9,
+    };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        secs / 3_600,
+        secs % 3_600 / 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_utc() {
+        assert_eq!(format_utc(0), "1970-01-01 00:00 UTC");
+        assert_eq!(format_utc(951_782_400), "2000-02-29 00:00 UTC");
+        assert_eq!(format_utc(1_738_332_300), "2025-01-31 14:05 UTC");
+    }
+
+    #[test]
+    fn test_render_escaping() {
+        let blocks = vec![
+            Block::Heading(2, "A <b> & c".to_string()),
+            table(&["Run", "Note"], vec![vec!["x|y".to_string(), "1 < 2".to_string()]]),
+        ];
+        let md = to_markdown(&blocks);
+        assert!(md.contains("## A <b> & c"));
+        assert!(md.contains("| Run | Note |\n| --- | --- |\n| x\\|y | 1 < 2 |"));
+
+        let html = to_html("t", &blocks);
+        assert!(html.contains("<h2>A &lt;b&gt; &amp; c</h2>"));
+        assert!(html.contains("<td>x|y</td><td>1 &lt; 2</td>"));
+    }
+
+    #[test]
+    fn test_unreadable_runs_listed() {
+        let blocks = build_report("Batch session s1", &[], &["c2".to_string(), "c<3".to_string()], 0);
+        let md = render_blocks("Batch session s1", &blocks, ReportFormat::Markdown);
+        assert!(md.contains("⚠ 2 runs left out because their stored data can't be read: c2, c<3"));
+        let html = render_blocks("Batch session s1", &blocks, ReportFormat::Html);
+        assert!(html.contains("c2, c&lt;3"));
+
+        let blocks = build_report("All", &[], &[], 0);
+        assert!(!to_markdown(&blocks).contains("left out"));
+    }
+
+    #[test]
+    fn test_bar_chart() {
+        let svg = bar_chart("TPS", &[("a".to_string(), 50.0), ("b".to_string(), 25.0)], "");
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("width=\"380.0\""));
+        assert!(svg.contains("width=\"190.0\""));
+        assert!(bar_chart("empty", &[("a".to_string(), 0.0)], "").contains("width=\"0.0\""));
+    }
+}
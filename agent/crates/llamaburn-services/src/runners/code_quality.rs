@@ -0,0 +1,407 @@
+//! Static analysis of generated code.
+//!
+//! Runs whichever local linters and formatters are installed (ruff/pyflakes,
+//! node --check/eslint/prettier, clippy/rustfmt, go vet/gofmt) and computes
+//! lines of code plus an approximate cyclomatic complexity. Missing tools are
+//! skipped rather than counted as failures, so results are only comparable
+//! between runs on machines with the same toolchain.
+
+use std::process::Stdio;
+use std::time::Duration;
+
+use llamaburn_core::{CodeQuality, Language};
+use tempfile::TempDir;
+use tokio::fs;
+use tokio::process::Command;
+
+/// Linter messages kept per problem (the count covers all of them)
+const MAX_LINT_MESSAGES: usize = 10;
+
+const LINT_TIMEOUT_MS: u64 = 60_000;
+/// Clippy has to build the crate first
+const CLIPPY_TIMEOUT_MS: u64 = 180_000;
+
+/// Output of a tool that was found and ran to completion
+struct ToolOutput {
+    success: bool,
+    stdout: String,
+    stderr: String,
+}
+
+impl ToolOutput {
+    fn combined(&self) -> String {
+        format!("{}\n{}", self.stdout, self.stderr)
+    }
+}
+
+/// Collected linter diagnostics before they are folded into `CodeQuality`
+#[derive(Default)]
+struct LintReport {
+    tools: Vec<String>,
+    messages: Vec<String>,
+    formatted: Option<bool>,
+}
+
+impl LintReport {
+    fn add(&mut self, tool: &str, messages: Vec<String>) {
+        self.tools.push(tool.to_string());
+        self.messages.extend(messages);
+    }
+}
+
+/// Analyze `code` with the locally installed tools for `language`
+pub async fn analyze_code(code: &str, language: Language) -> CodeQuality {
+    let lint = match TempDir::new() {
+        Ok(dir) => lint(code, language, &dir).await,
+        Err(e) => {
+            tracing::warn!("Code analysis skipped, no temp dir: {}", e);
+            LintReport::default()
+        }
+    };
+
+    let lint_warnings = lint.messages.len() as u32;
+    let mut lint_messages = lint.messages;
+    lint_messages.truncate(MAX_LINT_MESSAGES);
+
+    CodeQuality {
+        tools: lint.tools,
+        lint_warnings,
+        lint_messages,
+        formatted: lint.formatted,
+        lines_of_code: lines_of_code(code, language),
+        cyclomatic_complexity: cyclomatic_complexity(code, language),
+    }
+}
+
+async fn lint(code: &str, language: Language, dir: &TempDir) -> LintReport {
+    let result = match language {
+        Language::Python => lint_python(code, dir).await,
+        Language::JavaScript => lint_javascript(code, dir).await,
+        Language::Rust => lint_rust(code, dir).await,
+        Language::Go => lint_go(code, dir).await,
+    };
+    result.unwrap_or_else(|e| {
+        tracing::warn!("Code analysis failed: {}", e);
+        LintReport::default()
+    })
+}
+
+async fn lint_python(code: &str, dir: &TempDir) -> std::io::Result<LintReport> {
+    fs::write(dir.path().join("solution.py"), code).await?;
+    let mut report = LintReport::default();
+
+    let ruff = run_tool(
+        dir,
+        "ruff",
+        &["check", "--no-cache", "--output-format", "concise", "solution.py"],
+        LINT_TIMEOUT_MS,
+    )
+    .await;
+    match ruff {
+        Some(out) => {
+            report.add("ruff", diagnostic_lines(&out.stdout, "solution.py:"));
+            report.formatted = run_tool(dir, "ruff", &["format", "--check", "solution.py"], LINT_TIMEOUT_MS)
+                .await
+                .map(|out| out.success);
+        }
+        None => {
+            let pyflakes =
+                run_tool(dir, "python3", &["-m", "pyflakes", "solution.py"], LINT_TIMEOUT_MS).await;
+            // python3 without the module reports "No module named pyflakes"
+            if let Some(out) = pyflakes.filter(|o| !o.stderr.contains("No module named")) {
+                report.add("pyflakes", diagnostic_lines(&out.combined(), "solution.py:"));
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+async fn lint_javascript(code: &str, dir: &TempDir) -> std::io::Result<LintReport> {
+    fs::write(dir.path().join("solution.js"), code).await?;
+    let mut report = LintReport::default();
+
+    if let Some(out) = run_tool(dir, "node", &["--check", "solution.js"], LINT_TIMEOUT_MS).await {
+        let messages = match out.success {
+            true => Vec::new(),
+            false => out.stderr.lines().take(1).map(|l| l.to_string()).collect(),
+        };
+        report.add("node --check", messages);
+    }
+
+    // No project config in the temp dir, so pass a small fixed rule set
+    let eslint = run_tool(
+        dir,
+        "eslint",
+        &[
+            "--no-config-lookup",
+            "--format",
+            "unix",
+            "--rule",
+            "no-unused-vars: warn",
+            "--rule",
+            "no-unreachable: warn",
+            "--rule",
+            "no-dupe-keys: warn",
+            "--rule",
+            "eqeqeq: warn",
+            "--rule",
+            "no-var: warn",
+            "--rule",
+            "prefer-const: warn",
+            "solution.js",
+        ],
+        LINT_TIMEOUT_MS,
+    )
+    .await;
+    if let Some(out) = eslint {
+        report.add("eslint", diagnostic_lines(&out.stdout, "solution.js:"));
+    }
+
+    report.formatted = run_tool(dir, "prettier", &["--check", "solution.js"], LINT_TIMEOUT_MS)
+        .await
+        .map(|out| out.success);
+
+    Ok(report)
+}
+
+async fn lint_rust(code: &str, dir: &TempDir) -> std::io::Result<LintReport> {
+    let src_dir = dir.path().join("src");
+    fs::create_dir_all(&src_dir).await?;
+    fs::write(
+        dir.path().join("Cargo.toml"),
+        "[package]\nname = \"solution\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[workspace]\n",
+    )
+    .await?;
+    // Solutions are free functions in a lib crate; don't count them as dead code
+    let source = format!("#![allow(dead_code)]\n{}", code);
+    fs::write(src_dir.join("lib.rs"), &source).await?;
+
+    let mut report = LintReport::default();
+
+    let clippy = run_tool(
+        dir,
+        "cargo",
+        &["clippy", "--quiet", "--offline", "--message-format", "short"],
+        CLIPPY_TIMEOUT_MS,
+    )
+    .await;
+    // cargo without the clippy component reports "no such command"
+    if let Some(out) = clippy.filter(|o| !o.stderr.contains("no such command")) {
+        report.add("clippy", diagnostic_lines(&out.stderr, "src/lib.rs:"));
+    }
+
+    report.formatted = run_tool(dir, "rustfmt", &["--check", "src/lib.rs"], LINT_TIMEOUT_MS)
+        .await
+        .map(|out| out.success);
+
+    Ok(report)
+}
+
+async fn lint_go(code: &str, dir: &TempDir) -> std::io::Result<LintReport> {
+    let source = match code.lines().any(|l| l.trim_start().starts_with("package ")) {
+        true => code.to_string(),
+        false => format!("package solution\n\n{}", code),
+    };
+    fs::write(dir.path().join("go.mod"), "module solution\n\ngo 1.21\n").await?;
+    fs::write(dir.path().join("solution.go"), &source).await?;
+
+    let mut report = LintReport::default();
+
+    if let Some(out) = run_tool(dir, "go", &["vet", "."], LINT_TIMEOUT_MS).await {
+        report.add("go vet", diagnostic_lines(&out.stderr, "solution.go:"));
+    }
+
+    report.formatted = run_tool(dir, "gofmt", &["-l", "solution.go"], LINT_TIMEOUT_MS)
+        .await
+        .map(|out| out.success && out.stdout.trim().is_empty());
+
+    Ok(report)
+}
+
+/// Run a tool in `dir`. None when it isn't installed or didn't finish in time.
+async fn run_tool(dir: &TempDir, program: &str, args: &[&str], timeout_ms: u64) -> Option<ToolOutput> {
+    let child = Command::new(program)
+        .args(args)
+        .current_dir(dir.path())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .ok()?;
+
+    let timeout = Duration::from_millis(timeout_ms);
+    let Ok(Ok(output)) = tokio::time::timeout(timeout, child.wait_with_output()).await else {
+        tracing::warn!("{} did not finish within {}ms", program, timeout_ms);
+        return None;
+    };
+
+    Some(ToolOutput {
+        success: output.status.success(),
+        stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+        stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+    })
+}
+
+/// Diagnostic lines that point into the analyzed file
+fn diagnostic_lines(output: &str, file_marker: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter(|l| l.contains(file_marker))
+        .map(|l| l.trim().to_string())
+        .collect()
+}
+
+// =============================================================================
+// Size and complexity metrics
+// =============================================================================
+
+/// Non-blank lines once comments are removed
+pub fn lines_of_code(code: &str, language: Language) -> u32 {
+    strip_comments_and_strings(code, language)
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .count() as u32
+}
+
+/// Approximate McCabe complexity for the whole file: 1 + decision points.
+/// Decision points are branch keywords, short-circuit operators, ternaries and
+/// extra match arms; nested functions are not counted separately.
+pub fn cyclomatic_complexity(code: &str, language: Language) -> u32 {
+    let stripped = strip_comments_and_strings(code, language);
+
+    let keywords: &[&str] = match language {
+        Language::Python => &["if", "elif", "for", "while", "except", "and", "or", "case"],
+        Language::JavaScript => &["if", "for", "while", "case", "catch"],
+        Language::Rust => &["if", "for", "while"],
+        Language::Go => &["if", "for", "case"],
+    };
+
+    let words: Vec<&str> = stripped
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|w| !w.is_empty())
+        .collect();
+    let mut decisions = words.iter().filter(|w| keywords.contains(w)).count();
+
+    if language != Language::Python {
+        decisions += stripped.matches("&&").count() + stripped.matches("||").count();
+    }
+
+    match language {
+        Language::JavaScript => {
+            decisions += stripped.matches("??").count();
+            decisions += ternary_count(&stripped);
+        }
+        // A match with n arms adds n - 1 paths
+        Language::Rust => {
+            let arms = stripped.matches("=>").count();
+            let matches = words.iter().filter(|w| **w == "match").count();
+            decisions += arms.saturating_sub(matches);
+        }
+        _ => {}
+    }
+
+    1 + decisions as u32
+}
+
+/// `?` used as a ternary, excluding `?.` and `??`
+fn ternary_count(code: &str) -> usize {
+    let chars: Vec<char> = code.chars().collect();
+    (0..chars.len())
+        .filter(|&i| chars[i] == '?')
+        .filter(|&i| {
+            let prev = i.checked_sub(1).map(|p| chars[p]);
+            let next = chars.get(i + 1).copied();
+            prev != Some('?') && !matches!(next, Some('?') | Some('.'))
+        })
+        .count()
+}
+
+/// Blank out comments and string literal contents, keeping newlines so line
+/// counts survive
+fn strip_comments_and_strings(code: &str, language: Language) -> String {
+    let chars: Vec<char> = code.chars().collect();
+    let mut out = String::with_capacity(code.len());
+    let line_comment: &[char] = match language {
+        Language::Python => &['#'],
+        _ => &['/', '/'],
+    };
+    // Rust uses ' for lifetimes as well as chars, so only " delimits strings there
+    let quotes: &[char] = match language {
+        Language::Python => &['"', '\''],
+        Language::JavaScript => &['"', '\'', '`'],
+        Language::Rust => &['"'],
+        Language::Go => &['"', '`'],
+    };
+
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+
+        if chars[i..].starts_with(line_comment) {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            continue;
+        }
+
+        if language != Language::Python && chars[i..].starts_with(&['/', '*']) {
+            i += 2;
+            while i < chars.len() && !chars[i..].starts_with(&['*', '/']) {
+                if chars[i] == '\n' {
+                    out.push('\n');
+                }
+                i += 1;
+            }
+            i += 2;
+            continue;
+        }
+
+        if !quotes.contains(&c) {
+            out.push(c);
+            i += 1;
+            continue;
+        }
+
+        // Python triple-quoted strings end at the matching triple
+        let delim: Vec<char> = match language == Language::Python && chars[i..].starts_with(&[c, c, c]) {
+            true => vec![c, c, c],
+            false => vec![c],
+        };
+        out.push_str("\"\"");
+        i += delim.len();
+        while i < chars.len() && !chars[i..].starts_with(&delim) {
+            match chars[i] {
+                '\\' if c != '`' => i += 1,
+                '\n' => out.push('\n'),
+                _ => {}
+            }
+            i += 1;
+        }
+        i += delim.len();
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_complexity_and_loc() {
+        let python = "def f(x):\n    # if in a comment\n    if x > 0 and x < 10:\n        return 'if'\n    for i in range(x):\n        pass\n    return x\n";
+        assert_eq!(lines_of_code(python, Language::Python), 6);
+        assert_eq!(cyclomatic_complexity(python, Language::Python), 4);
+
+        let js = "function f(a) {\n  /* while */\n  const b = a?.x ?? 0;\n  return a > b ? a : b;\n}\n";
+        assert_eq!(lines_of_code(js, Language::JavaScript), 4);
+        assert_eq!(cyclomatic_complexity(js, Language::JavaScript), 3);
+
+        let rust = "fn f<'a>(s: &'a str) -> u32 {\n    match s {\n        \"a\" => 1,\n        \"b\" | \"c\" => 2,\n        _ => 3,\n    }\n}\n";
+        assert_eq!(cyclomatic_complexity(rust, Language::Rust), 3);
+
+        let go = "func f(n int) int {\n\tswitch {\n\tcase n < 0 && n > -5:\n\t\treturn -1\n\tcase n == 0:\n\t\treturn 0\n\t}\n\treturn 1\n}\n";
+        assert_eq!(cyclomatic_complexity(go, Language::Go), 4);
+    }
+}
@@ -1,10 +1,17 @@
-use super::code_executor::{CodeExecutor, TestResult};
-use super::ollama_client::{code_output_schema, OllamaClient, StructuredCodeResponse};
+use super::code_executor::{extract_code_block, extract_diff_block, CodeExecutor, TestResult};
+use super::code_quality::analyze_code;
+use super::environment::capture_environment;
+use super::ollama_client::{
+    code_output_schema, ChatMessage, GenerationStats, OllamaClient, StructuredCodeResponse,
+};
+use super::repo_patch::{diff_stats, repo_listing, PATCH_APPLY_FAILED};
 use llamaburn_core::{
-    CodeBenchmarkConfig, CodeBenchmarkMetrics, CodeBenchmarkSummary, CodeProblem, Language,
-    LlamaBurnError, Result,
+    all_tests_passed, CodeBenchmarkConfig, CodeBenchmarkMetrics, CodeBenchmarkSummary, CodeProblem,
+    CodeQuality, CodeTask, EnvironmentFingerprint, GenerationMode, Language, LlamaBurnError,
+    PatchStats, ProblemKind, RepairAttempt, RepoFixture, Result, TagStats, TestCaseResult,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::time::Instant;
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
@@ -12,11 +19,18 @@ use tokio_util::sync::CancellationToken;
 #[derive(Debug, Clone, Serialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum CodeBenchmarkEvent {
+    /// Sent first, before any warmup
+    Environment { fingerprint: EnvironmentFingerprint },
     Warmup { current: u32, total: u32 },
     Problem { current: u32, total: u32, title: String },
+    /// The task can't be posed for this problem; it is left out of the run and its totals
+    ProblemSkipped { problem_id: String, reason: String },
     GeneratingCode,
     Token { content: String },
+    RepairAttempt { attempt: u32, total: u32 },
     ExecutingTests { total: u32 },
+    AnalyzingCode,
+    TimingReference,
     TestResult { test_num: u32, test_total: u32, passed: bool, expected: String, actual: String, error: Option<String> },
     ProblemComplete { metrics: CodeBenchmarkMetrics },
     Done { summary: CodeBenchmarkSummary },
@@ -24,11 +38,115 @@ pub enum CodeBenchmarkEvent {
     Error { message: String },
 }
 
+/// Failing test cases quoted back to the model per repair turn
+const MAX_REPAIR_FEEDBACK_CASES: usize = 3;
+
+/// Cap on stored actual output/error per test case so runaway prints don't bloat history
+const MAX_STORED_OUTPUT_CHARS: usize = 2000;
+
 pub struct CodeBenchmarkRunner {
     client: OllamaClient,
     executor: CodeExecutor,
 }
 
+/// Code produced by one generation call, in either mode
+struct GeneratedCode {
+    /// Code that is displayed and tested
+    code: String,
+    /// Raw assistant reply, replayed in repair turns
+    reply: String,
+    /// Structured components (structured mode only)
+    structured: Option<StructuredCodeResponse>,
+}
+
+/// Aggregated test results for one generation attempt
+#[derive(Default)]
+struct TestOutcome {
+    tests_passed: u32,
+    tests_total: u32,
+    compilation_error: Option<String>,
+    runtime_error: Option<String>,
+    harness_error: Option<String>,
+    failures: Vec<(usize, TestResult)>,
+    cases: Vec<TestCaseResult>,
+}
+
+impl TestOutcome {
+    fn from_results(
+        results: std::result::Result<Vec<TestResult>, String>,
+        problem: &CodeProblem,
+    ) -> Self {
+        let r = match results {
+            Ok(r) => r,
+            Err(e) => {
+                return Self {
+                    tests_total: problem.test_cases.len() as u32,
+                    compilation_error: Some(e.clone()),
+                    harness_error: Some(e),
+                    ..Default::default()
+                }
+            }
+        };
+
+        let passed = r.iter().filter(|t| t.passed).count() as u32;
+        let total = r.len() as u32;
+        // A diff that doesn't apply is the repo-patch equivalent of a compile error
+        let is_build_error = |e: &&String| e.contains("Compilation") || e.starts_with(PATCH_APPLY_FAILED);
+        let comp_err = r.iter()
+            .filter_map(|t| t.error.as_ref())
+            .find(is_build_error)
+            .cloned();
+        let run_err = r.iter()
+            .filter_map(|t| t.error.as_ref())
+            .find(|e| !is_build_error(e))
+            .cloned();
+        let cases = r
+            .iter()
+            .enumerate()
+            .map(|(idx, t)| TestCaseResult {
+                input: problem
+                    .test_cases
+                    .get(idx)
+                    .map(|tc| truncate_chars(&tc.input, MAX_STORED_OUTPUT_CHARS))
+                    .unwrap_or_default(),
+                expected: t.expected_output.clone(),
+                actual: truncate_chars(&t.actual_output, MAX_STORED_OUTPUT_CHARS),
+                passed: t.passed,
+                error: t.error.as_deref().map(|e| truncate_chars(e, MAX_STORED_OUTPUT_CHARS)),
+                execution_time_ms: t.execution_time_ms,
+                cpu_time_ms: t.cpu_time_ms,
+                peak_rss_kb: t.peak_rss_kb,
+                performance: problem.test_cases.get(idx).is_some_and(|tc| tc.performance),
+            })
+            .collect();
+        let failures = r.into_iter().enumerate().filter(|(_, t)| !t.passed).collect();
+
+        Self {
+            tests_passed: passed,
+            tests_total: total,
+            compilation_error: comp_err,
+            runtime_error: run_err,
+            harness_error: None,
+            failures,
+            cases,
+        }
+    }
+}
+
+/// Truncate to at most `max` characters, marking the cut
+fn truncate_chars(s: &str, max: usize) -> String {
+    match s.char_indices().nth(max) {
+        Some((end, _)) => format!("{}… [truncated]", &s[..end]),
+        None => s.to_string(),
+    }
+}
+
+/// Temperature and token limit sent with every generation request; the
+/// environment fingerprint records the same pair
+fn sampling_options(config: &CodeBenchmarkConfig) -> (Option<f32>, Option<u32>) {
+    (Some(config.temperature), config.max_tokens)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CodeBenchmarkResult {
     pub config: CodeBenchmarkConfig,
@@ -51,6 +169,26 @@ impl CodeBenchmarkRunner {
         cancel_token: CancellationToken,
         tx: mpsc::Sender<CodeBenchmarkEvent>,
     ) {
+        // Bug-fix and translation need a buggy or source solution to start from,
+        // and repo patches can only be generated
+        let (problems, skipped): (Vec<&CodeProblem>, Vec<&CodeProblem>) = problems
+            .iter()
+            .partition(|p| config.task.supports(p, config.language));
+
+        let (temperature, max_tokens) = sampling_options(config);
+        let fingerprint =
+            capture_environment(&self.client, &config.model_id, temperature, max_tokens).await;
+        let _ = tx.send(CodeBenchmarkEvent::Environment { fingerprint }).await;
+
+        for problem in &skipped {
+            let _ = tx
+                .send(CodeBenchmarkEvent::ProblemSkipped {
+                    problem_id: problem.id.clone(),
+                    reason: config.task.unsupported_reason(problem, config.language),
+                })
+                .await;
+        }
+
         // Warmup
         for i in 0..config.warmup_runs {
             if cancel_token.is_cancelled() {
@@ -110,6 +248,7 @@ impl CodeBenchmarkRunner {
                     CodeBenchmarkMetrics {
                         problem_id: problem.id.clone(),
                         difficulty: problem.difficulty,
+                        tags: problem.tags.clone(),
                         ttft_ms: 0.0,
                         tokens_per_sec: 0.0,
                         tests_passed: 0,
@@ -118,6 +257,17 @@ impl CodeBenchmarkRunner {
                         generated_code: String::new(),
                         compilation_error: Some(e.to_string()),
                         runtime_error: None,
+                        output_tokens: 0,
+                        prompt_tokens: 0,
+                        load_duration_ms: 0.0,
+                        test_execution_time_ms: 0.0,
+                        attempts: Vec::new(),
+                        test_results: Vec::new(),
+                        quality: None,
+                        cpu_time_ms: 0.0,
+                        peak_rss_kb: 0,
+                        slowdown: None,
+                        patch: None,
                     }
                 }
             };
@@ -130,7 +280,8 @@ impl CodeBenchmarkRunner {
             all_metrics.push(metrics);
         }
 
-        let summary = Self::calculate_summary(&all_metrics);
+        let mut summary = Self::calculate_summary(&all_metrics);
+        summary.skipped_problems = skipped.iter().map(|p| p.id.clone()).collect();
         let _ = tx.send(CodeBenchmarkEvent::Done { summary }).await;
     }
 
@@ -145,87 +296,391 @@ impl CodeBenchmarkRunner {
             return Err(LlamaBurnError::Cancelled);
         }
 
-        let _ = tx.send(CodeBenchmarkEvent::GeneratingCode).await;
-        let start = Instant::now();
+        // Repair turns only make sense when tests provide feedback
+        let max_attempts = match config.run_tests {
+            true => config.max_repair_attempts + 1,
+            false => 1,
+        };
 
-        // SINGLE CALL: Get structured output (single source of truth)
-        let structured = self
-            .get_structured_code(config, problem)
-            .await
-            .map_err(|e| LlamaBurnError::OllamaError(format!("Structured output failed: {}", e)))?;
+        let prompt = match config.generation_mode {
+            GenerationMode::Structured => {
+                self.build_structured_prompt(problem, config.language, config.task)
+            }
+            GenerationMode::FreeForm => {
+                Self::build_free_form_prompt(problem, config.language, config.task)
+            }
+        };
+        let mut messages = vec![ChatMessage::user(prompt)];
+        let mut attempts: Vec<RepairAttempt> = Vec::new();
+        let mut first_ttft_ms = 0.0;
+        let mut total_generation_ms = 0.0;
+        let mut total_eval_ms = 0.0;
+        let mut output_tokens = 0;
+        let mut prompt_tokens = 0;
+        let mut load_duration_ms = 0.0;
 
-        let generation_time_ms = start.elapsed().as_secs_f64() * 1000.0;
+        let mut attempt = 1;
+        let (generated, outcome, test_execution_time_ms) = loop {
+            if attempt > 1 {
+                if cancel_token.is_cancelled() {
+                    return Err(LlamaBurnError::Cancelled);
+                }
+                let _ = tx
+                    .send(CodeBenchmarkEvent::RepairAttempt {
+                        attempt,
+                        total: max_attempts,
+                    })
+                    .await;
+            }
 
-        // Estimate tokens from code length (~4 chars per token)
-        let estimated_tokens = (structured.code.len() as f64 / 4.0).max(1.0);
-        let tokens_per_sec = match generation_time_ms > 0.0 {
-            true => estimated_tokens / (generation_time_ms / 1000.0),
-            false => 0.0,
-        };
+            let _ = tx.send(CodeBenchmarkEvent::GeneratingCode).await;
+
+            let (generated, stats) = self.generate_code(config, problem, &messages).await?;
+
+            if attempt == 1 {
+                first_ttft_ms = stats.ttft_ms;
+                load_duration_ms = stats.load_duration_ms;
+            }
+            total_generation_ms += stats.total_ms;
+            total_eval_ms += stats.eval_duration_ms;
+            output_tokens += stats.eval_count;
+            prompt_tokens += stats.prompt_eval_count;
 
-        // Display the code to Live Output (same code that will be tested)
-        let _ = tx.send(CodeBenchmarkEvent::Token {
-            content: structured.code.clone(),
-        }).await;
+            // Display the code to Live Output (same code that will be tested)
+            let _ = tx.send(CodeBenchmarkEvent::Token {
+                content: generated.code.clone(),
+            }).await;
 
-        // Run tests if enabled - uses same structured response
-        let (tests_passed, tests_total, compilation_error, runtime_error) =
-            match config.run_tests {
-                false => (0, 0, None, None),
+            // Run tests if enabled - uses the same code that was displayed
+            let test_start = Instant::now();
+            let outcome = match config.run_tests {
+                false => TestOutcome::default(),
                 true => {
                     let results = self
-                        .run_tests_structured(&structured, config.language, problem, tx)
+                        .run_generated_tests(&generated, config.language, problem, tx)
                         .await;
-
-                    match results {
-                        Err(e) => (0, problem.test_cases.len() as u32, Some(e), None),
-                        Ok(r) => {
-                            let passed = r.iter().filter(|t| t.passed).count() as u32;
-                            let total = r.len() as u32;
-                            let comp_err = r.iter()
-                                .filter_map(|t| t.error.as_ref())
-                                .find(|e| e.contains("Compilation"))
-                                .cloned();
-                            let run_err = r.iter()
-                                .filter_map(|t| t.error.as_ref())
-                                .find(|e| !e.contains("Compilation"))
-                                .cloned();
-                            (passed, total, comp_err, run_err)
-                        }
-                    }
+                    TestOutcome::from_results(results, problem)
                 }
             };
+            let test_execution_time_ms = test_start.elapsed().as_secs_f64() * 1000.0;
+
+            attempts.push(RepairAttempt {
+                attempt,
+                tests_passed: outcome.tests_passed,
+                tests_total: outcome.tests_total,
+                tokens: stats.eval_count,
+                ttft_ms: stats.ttft_ms,
+                generation_time_ms: stats.total_ms,
+                error: outcome.compilation_error.clone().or_else(|| outcome.runtime_error.clone()),
+            });
+
+            let solved = all_tests_passed(outcome.tests_passed, outcome.tests_total);
+            if solved || attempt >= max_attempts {
+                break (generated, outcome, test_execution_time_ms);
+            }
+
+            // Feed the failure back as a follow-up turn
+            messages.push(ChatMessage::assistant(generated.reply.clone()));
+            messages.push(ChatMessage::user(Self::build_repair_prompt(
+                problem,
+                &outcome,
+                config.generation_mode,
+            )));
+            attempt += 1;
+        };
+
+        // Decode throughput from Ollama's eval counters across all attempts
+        let tokens_per_sec = match total_eval_ms > 0.0 {
+            true => output_tokens as f64 / (total_eval_ms / 1000.0),
+            false => 0.0,
+        };
+
+        let analyze = config.analyze_code && problem.problem_kind != ProblemKind::RepoPatch;
+        let quality = match analyze && !generated.code.trim().is_empty() {
+            true => {
+                let _ = tx.send(CodeBenchmarkEvent::AnalyzingCode).await;
+                Some(analyze_code(&generated.code, config.language).await)
+            }
+            false => None,
+        };
+
+        let cpu_time_ms = outcome.cases.iter().map(|c| c.cpu_time_ms).sum();
+        let peak_rss_kb = outcome.cases.iter().map(|c| c.peak_rss_kb).max().unwrap_or(0);
+        let slowdown = match problem.reference_solutions.get(&config.language) {
+            Some(reference) if !outcome.cases.is_empty() => {
+                let _ = tx.send(CodeBenchmarkEvent::TimingReference).await;
+                self.reference_slowdown(reference, config.language, problem, &outcome.cases)
+                    .await
+            }
+            _ => None,
+        };
+
+        let patch = match problem.problem_kind {
+            ProblemKind::RepoPatch => Some(PatchStats {
+                applied: config.run_tests.then(|| {
+                    outcome
+                        .compilation_error
+                        .as_ref()
+                        .is_none_or(|e| !e.starts_with(PATCH_APPLY_FAILED))
+                }),
+                ..diff_stats(&generated.code)
+            }),
+            _ => None,
+        };
+
+        // Only keep the per-attempt breakdown when repair was enabled
+        let attempts = match max_attempts > 1 {
+            true => attempts,
+            false => Vec::new(),
+        };
 
         Ok(CodeBenchmarkMetrics {
             problem_id: problem.id.clone(),
             difficulty: problem.difficulty,
-            ttft_ms: generation_time_ms,
+            tags: problem.tags.clone(),
+            ttft_ms: first_ttft_ms,
             tokens_per_sec,
-            tests_passed,
-            tests_total,
-            execution_time_ms: generation_time_ms,  // LLM generation time
-            generated_code: structured.code,
-            compilation_error,
-            runtime_error,
+            tests_passed: outcome.tests_passed,
+            tests_total: outcome.tests_total,
+            execution_time_ms: total_generation_ms,  // LLM generation time
+            generated_code: generated.code,
+            compilation_error: outcome.compilation_error,
+            runtime_error: outcome.runtime_error,
+            output_tokens,
+            prompt_tokens,
+            load_duration_ms,
+            test_execution_time_ms,
+            attempts,
+            test_results: outcome.cases,
+            quality,
+            cpu_time_ms,
+            peak_rss_kb,
+            slowdown,
+            patch,
         })
     }
 
-    /// Get structured code output for reliable test execution (CALL 2)
-    async fn get_structured_code(
+    /// CPU time of `cases` relative to the reference solution on the same tests.
+    /// Uses only the performance cases when the problem has any, since startup
+    /// cost dominates the small ones.
+    async fn reference_slowdown(
+        &self,
+        reference: &str,
+        language: Language,
+        problem: &CodeProblem,
+        cases: &[TestCaseResult],
+    ) -> Option<f64> {
+        let reference_results = match self.executor.run_tests(reference, language, problem).await {
+            Ok(results) => results,
+            Err(e) => {
+                tracing::warn!("Reference solution for '{}' failed: {}", problem.id, e);
+                return None;
+            }
+        };
+
+        let has_performance = problem.test_cases.iter().any(|tc| tc.performance);
+        let (mut candidate_ms, mut reference_ms) = (0.0, 0.0);
+        for ((tc, case), reference) in problem.test_cases.iter().zip(cases).zip(&reference_results) {
+            if has_performance && !tc.performance {
+                continue;
+            }
+            candidate_ms += case.cpu_time_ms;
+            reference_ms += reference.cpu_time_ms;
+        }
+
+        match reference_ms > 0.0 {
+            true => Some(candidate_ms / reference_ms),
+            false => None,
+        }
+    }
+
+    /// Request code in the configured generation mode
+    async fn generate_code(
         &self,
         config: &CodeBenchmarkConfig,
         problem: &CodeProblem,
-    ) -> Result<StructuredCodeResponse> {
-        let prompt = self.build_structured_prompt(problem, config.language);
+        messages: &[ChatMessage],
+    ) -> Result<(GeneratedCode, GenerationStats)> {
+        match config.generation_mode {
+            GenerationMode::Structured => {
+                // SINGLE CALL: Get structured output (single source of truth)
+                let (structured, stats) = self
+                    .get_structured_code(config, messages)
+                    .await
+                    .map_err(|e| {
+                        LlamaBurnError::OllamaError(format!("Structured output failed: {}", e))
+                    })?;
+                let generated = GeneratedCode {
+                    code: structured.code.clone(),
+                    reply: serde_json::to_string(&structured)
+                        .unwrap_or_else(|_| structured.code.clone()),
+                    structured: Some(structured),
+                };
+                Ok((generated, stats))
+            }
+            GenerationMode::FreeForm => {
+                let (temperature, max_tokens) = sampling_options(config);
+                let (reply, stats) = self
+                    .client
+                    .chat_messages(&config.model_id, messages, temperature, max_tokens)
+                    .await
+                    .map_err(|e| LlamaBurnError::OllamaError(format!("Chat failed: {}", e)))?;
+                let code = match problem.problem_kind {
+                    ProblemKind::RepoPatch => extract_diff_block(&reply),
+                    _ => extract_code_block(&reply, config.language),
+                };
+                let generated = GeneratedCode {
+                    code,
+                    reply,
+                    structured: None,
+                };
+                Ok((generated, stats))
+            }
+        }
+    }
+
+    /// Get structured code output for reliable test execution (CALL 2), with the same
+    /// sampling options as free-form mode so the two are comparable
+    async fn get_structured_code(
+        &self,
+        config: &CodeBenchmarkConfig,
+        messages: &[ChatMessage],
+    ) -> Result<(StructuredCodeResponse, GenerationStats)> {
         let schema = code_output_schema();
+        let (temperature, max_tokens) = sampling_options(config);
 
         self.client
-            .chat_structured(&config.model_id, &prompt, schema, Some(0.0))
+            .chat_structured_messages(&config.model_id, messages, schema, temperature, max_tokens)
             .await
     }
 
+    /// Build the follow-up prompt describing why the previous attempt failed
+    fn build_repair_prompt(
+        problem: &CodeProblem,
+        outcome: &TestOutcome,
+        mode: GenerationMode,
+    ) -> String {
+        let mut feedback = match (&outcome.harness_error, &outcome.compilation_error) {
+            (Some(e), _) => format!("Your code could not be executed:\n{}\n", e),
+            (None, Some(e)) if e.starts_with(PATCH_APPLY_FAILED) => {
+                format!("Your diff could not be applied:\n{}\n", e)
+            }
+            (None, Some(e)) => format!("Your code failed to compile:\n{}\n", e),
+            (None, None) => format!(
+                "Your code passed {}/{} tests. Failing cases:\n",
+                outcome.tests_passed, outcome.tests_total
+            ),
+        };
+
+        let failures = outcome
+            .failures
+            .iter()
+            .take(MAX_REPAIR_FEEDBACK_CASES)
+            .map(|(idx, result)| {
+                let input = problem
+                    .test_cases
+                    .get(*idx)
+                    .map(|tc| tc.input.as_str())
+                    .unwrap_or("");
+                let error = result
+                    .error
+                    .as_ref()
+                    .map(|e| format!("\nError: {}", e))
+                    .unwrap_or_default();
+                format!(
+                    "Input: {}\nExpected: {}\nActual: {}{}",
+                    input, result.expected_output, result.actual_output, error
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        if outcome.compilation_error.is_none() && outcome.harness_error.is_none() {
+            feedback.push_str(&failures);
+            feedback.push('\n');
+        }
+
+        let solution = match problem.problem_kind {
+            ProblemKind::RepoPatch => "a corrected diff against the original files",
+            _ => "the complete corrected solution",
+        };
+        feedback.push_str(&match mode {
+            GenerationMode::Structured => {
+                format!("\nFix the code and return {} in the same JSON format.", solution)
+            }
+            GenerationMode::FreeForm => {
+                format!("\nFix the code and return {} in a single fenced code block.", solution)
+            }
+        });
+        feedback
+    }
+
     /// Build prompt for structured output - requests clean JSON response
-    fn build_structured_prompt(&self, problem: &CodeProblem, language: Language) -> String {
+    fn build_structured_prompt(
+        &self,
+        problem: &CodeProblem,
+        language: Language,
+        task: CodeTask,
+    ) -> String {
+        let (name_field, code_field) = match problem.problem_kind {
+            ProblemKind::Function => (
+                "the name of your solution function",
+                "the complete function code only - NO package declaration, NO main function, NO example usage",
+            ),
+            ProblemKind::Stdio => (
+                "the name of your entry point, e.g. \"main\"",
+                "a complete program that reads from stdin and prints the answer to stdout, including the main function",
+            ),
+            ProblemKind::ClassOperations => (
+                "the name of your class",
+                "the complete class definition only - NO package declaration, NO main function, NO example usage",
+            ),
+            ProblemKind::RepoPatch => (
+                "the path of the main file you changed",
+                "a unified diff (git diff format, paths prefixed with a/ and b/) against the files above",
+            ),
+        };
+
+        format!(
+            r#"{}
+
+Return a JSON object with exactly these fields:
+- "function_name": {} (string)
+- "imports": array of required imports/packages, names only without 'import' keyword (array of strings)
+- "code": {} (string)"#,
+            Self::problem_statement(problem, language, task),
+            name_field,
+            code_field
+        )
+    }
+
+    /// Build prompt for free-form output - a normal chat answer with a fenced code block
+    fn build_free_form_prompt(problem: &CodeProblem, language: Language, task: CodeTask) -> String {
+        format!(
+            r#"{}
+
+Respond with the complete solution in a single ```{} fenced code block, including any imports it needs.
+{}"#,
+            Self::problem_statement(problem, language, task),
+            language.label().to_lowercase(),
+            match problem.problem_kind {
+                ProblemKind::Function => "Do NOT include a main function or example usage.",
+                ProblemKind::Stdio => "Write a complete program that reads from stdin and prints the answer to stdout.",
+                ProblemKind::ClassOperations => "Define the class only; do NOT include a main function or example usage.",
+                ProblemKind::RepoPatch => "The block must be a unified diff (git diff format, paths prefixed with a/ and b/) against the files above.",
+            }
+        )
+    }
+
+    /// Problem description, signature and examples shared by both prompt styles
+    fn problem_statement(problem: &CodeProblem, language: Language, task: CodeTask) -> String {
+        if let (ProblemKind::RepoPatch, Some(repo)) = (problem.problem_kind, &problem.repo) {
+            return Self::repo_statement(problem, repo);
+        }
+        if let Some(source) = task.source_code(problem, language) {
+            return Self::task_statement(problem, language, task, source);
+        }
+
         let signature = problem
             .signatures
             .get(&language)
@@ -245,40 +700,149 @@ impl CodeBenchmarkRunner {
 
 {}
 
-{}
+{}{}
 
 Examples:
-{}
-
-Return a JSON object with exactly these fields:
-- "function_name": the name of your solution function (string)
-- "imports": array of required imports/packages, names only without 'import' keyword (array of strings)
-- "code": the complete function code only - NO package declaration, NO main function, NO example usage (string)"#,
+{}"#,
             language.label(),
             signature,
             problem.description,
+            Self::kind_instructions(problem.problem_kind, language),
             examples
         )
     }
 
-    /// Run tests using structured output - clean code components
-    async fn run_tests_structured(
+    /// Buggy or source solution to fix or translate, in place of writing from scratch
+    fn task_statement(
+        problem: &CodeProblem,
+        language: Language,
+        task: CodeTask,
+        source: &str,
+    ) -> String {
+        let signature = problem
+            .signatures
+            .get(&language)
+            .cloned()
+            .unwrap_or_else(|| format!("// Implement {} solution", problem.id));
+        let fence = |lang: Language| lang.label().to_lowercase();
+
+        match task {
+            CodeTask::Translate { from } => format!(
+                r#"Translate this working {} solution to {}. Keep its behavior identical.
+
+{}{}
+
+```{}
+{}
+```
+
+The {} solution must use this signature:
+{}"#,
+                from.label(),
+                language.label(),
+                problem.description,
+                Self::kind_instructions(problem.problem_kind, language),
+                fence(from),
+                source.trim_end(),
+                language.label(),
+                signature
+            ),
+            _ => {
+                // The bug must be found from the tests, so all of them are shown
+                let tests = problem
+                    .test_cases
+                    .iter()
+                    .map(|tc| format!("Input: {}\nOutput: {}", tc.input, tc.expected))
+                    .collect::<Vec<_>>()
+                    .join("\n\n");
+
+                format!(
+                    r#"The following {} solution has a bug. Find and fix it.
+
+{}{}
+
+```{}
+{}
+```
+
+It must pass these test cases:
+{}"#,
+                    language.label(),
+                    problem.description,
+                    Self::kind_instructions(problem.problem_kind, language),
+                    fence(language),
+                    source.trim_end(),
+                    tests
+                )
+            }
+        }
+    }
+
+    /// Issue, fixture files and test commands for a repo-patch problem
+    fn repo_statement(problem: &CodeProblem, repo: &RepoFixture) -> String {
+        let commands = problem
+            .test_cases
+            .iter()
+            .map(|tc| format!("  {}", tc.input))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        format!(
+            r#"Resolve this issue in the repository below.
+
+Issue:
+{}
+
+Repository files:
+
+{}
+
+After your diff is applied, these commands must succeed from the repository root:
+{}"#,
+            problem.description,
+            repo_listing(repo),
+            commands
+        )
+    }
+
+    /// How the harness will drive the solution, for non-function problem kinds
+    fn kind_instructions(kind: ProblemKind, language: Language) -> String {
+        let class_convention = match language {
+            Language::Go => " Provide a `Constructor` function returning the struct and pointer-receiver methods named after the operations with the first letter capitalized.",
+            Language::Rust => " Provide a `new` associated function and methods named after the operations in snake_case.",
+            Language::Python | Language::JavaScript => " Methods must be named exactly like the operations.",
+        };
+
+        match kind {
+            ProblemKind::Function | ProblemKind::RepoPatch => String::new(),
+            ProblemKind::Stdio => {
+                "\n\nThe input is given on stdin exactly as shown in the examples; print the output to stdout.".to_string()
+            }
+            ProblemKind::ClassOperations => format!(
+                "\n\nThe input is a list of operations and a list of their arguments. The first operation constructs the class; each following one calls a method on that instance. The output lists every return value, with null for the constructor and methods that return nothing.{}",
+                class_convention
+            ),
+        }
+    }
+
+    /// Run tests on generated code - structured components when available,
+    /// otherwise the extracted free-form code with entry point detection
+    async fn run_generated_tests(
         &self,
-        structured: &StructuredCodeResponse,
+        generated: &GeneratedCode,
         language: Language,
         problem: &CodeProblem,
         tx: &mpsc::Sender<CodeBenchmarkEvent>,
     ) -> std::result::Result<Vec<TestResult>, String> {
-        let test_cases = &problem.test_cases;
-        let total = test_cases.len() as u32;
+        let total = problem.test_cases.len() as u32;
 
         let _ = tx.send(CodeBenchmarkEvent::ExecutingTests { total }).await;
 
-        let test_results = self
-            .executor
-            .run_tests_structured(structured, language, test_cases, problem.time_limit_ms)
-            .await
-            .map_err(|e| e.to_string())?;
+        let test_results = match &generated.structured {
+            Some(structured) => self.executor.run_tests_structured(structured, language, problem).await,
+            None => self.executor.run_tests(&generated.code, language, problem).await,
+        }
+        .map_err(|e| e.to_string())?;
 
         for (idx, result) in test_results.iter().enumerate() {
             let _ = tx
@@ -300,7 +864,7 @@ Return a JSON object with exactly these fields:
         use llamaburn_core::Difficulty::{self, *};
 
         let problems_total = metrics.len() as u32;
-        let problems_solved = metrics.iter().filter(|m| m.tests_passed == m.tests_total).count() as u32;
+        let problems_solved = metrics.iter().filter(|m| m.solved()).count() as u32;
         let pass_rate = match problems_total {
             0 => 0.0,
             _ => problems_solved as f64 / problems_total as f64,
@@ -316,11 +880,25 @@ Return a JSON object with exactly these fields:
             false => metrics.iter().map(|m| m.execution_time_ms).sum::<f64>() / metrics.len() as f64,
         };
 
+        let avg_ttft_ms = match metrics.is_empty() {
+            true => 0.0,
+            false => metrics.iter().map(|m| m.ttft_ms).sum::<f64>() / metrics.len() as f64,
+        };
+
+        let avg_test_execution_time_ms = match metrics.is_empty() {
+            true => 0.0,
+            false => {
+                metrics.iter().map(|m| m.test_execution_time_ms).sum::<f64>() / metrics.len() as f64
+            }
+        };
+
+        let total_output_tokens = metrics.iter().map(|m| m.output_tokens).sum();
+
         // Calculate difficulty breakdown
         let count_by_difficulty = |diff: Difficulty| -> (u32, u32) {
             let matching: Vec<_> = metrics.iter().filter(|m| m.difficulty == diff).collect();
             let total = matching.len() as u32;
-            let solved = matching.iter().filter(|m| m.tests_passed == m.tests_total).count() as u32;
+            let solved = matching.iter().filter(|m| m.solved()).count() as u32;
             (solved, total)
         };
 
@@ -328,18 +906,90 @@ Return a JSON object with exactly these fields:
         let (medium_solved, medium_total) = count_by_difficulty(Medium);
         let (hard_solved, hard_total) = count_by_difficulty(Hard);
 
+        // Tag breakdown: a problem counts toward every tag it carries
+        let mut tag_stats: BTreeMap<String, TagStats> = BTreeMap::new();
+        for m in metrics {
+            let solved = m.solved();
+            for tag in &m.tags {
+                let stats = tag_stats.entry(tag.clone()).or_default();
+                stats.total += 1;
+                stats.solved += solved as u32;
+            }
+        }
+
+        // Cumulative pass rate by attempt (empty unless self-repair ran)
+        let max_attempts = metrics.iter().map(|m| m.attempts.len()).max().unwrap_or(0);
+        let pass_at_attempt = (1..=max_attempts)
+            .map(|k| {
+                let solved = metrics
+                    .iter()
+                    .filter(|m| m.attempts.iter().take(k).any(|a| a.solved()))
+                    .count();
+                solved as f64 / problems_total as f64
+            })
+            .collect();
+
+        // Code-quality averages over the problems that were analyzed
+        let analyzed: Vec<_> = metrics.iter().filter_map(|m| m.quality.as_ref()).collect();
+        let problems_analyzed = analyzed.len() as u32;
+        let avg_quality = |f: fn(&CodeQuality) -> u32| -> f64 {
+            match analyzed.is_empty() {
+                true => 0.0,
+                false => analyzed.iter().map(|q| f(q) as f64).sum::<f64>() / analyzed.len() as f64,
+            }
+        };
+        let avg_lint_warnings = avg_quality(|q| q.lint_warnings);
+        let avg_lines_of_code = avg_quality(|q| q.lines_of_code);
+        let avg_cyclomatic_complexity = avg_quality(|q| q.cyclomatic_complexity);
+
+        // Geometric mean, so one 100x outlier doesn't swamp several 1x results
+        let slowdowns: Vec<f64> = metrics.iter().filter_map(|m| m.slowdown).collect();
+        let avg_slowdown = match slowdowns.is_empty() {
+            true => None,
+            false => {
+                let log_sum: f64 = slowdowns.iter().map(|s| s.max(f64::MIN_POSITIVE).ln()).sum();
+                Some((log_sum / slowdowns.len() as f64).exp())
+            }
+        };
+        let problems_inefficient = metrics.iter().filter(|m| m.is_inefficient()).count() as u32;
+        let max_peak_rss_kb = metrics.iter().map(|m| m.peak_rss_kb).max().unwrap_or(0);
+
+        let patches: Vec<_> = metrics.iter().filter_map(|m| m.patch.as_ref()).collect();
+        let patch_problems = patches.len() as u32;
+        let patches_applied = patches.iter().filter(|p| p.applied == Some(true)).count() as u32;
+        let avg_diff_lines = match patches.is_empty() {
+            true => 0.0,
+            false => patches.iter().map(|p| p.diff_lines() as f64).sum::<f64>() / patches.len() as f64,
+        };
+
         CodeBenchmarkSummary {
             pass_rate,
             problems_solved,
             problems_total,
             avg_tps,
             avg_execution_time_ms,
+            avg_ttft_ms,
+            avg_test_execution_time_ms,
+            total_output_tokens,
             easy_solved,
             easy_total,
             medium_solved,
             medium_total,
             hard_solved,
             hard_total,
+            pass_at_attempt,
+            tag_stats,
+            problems_analyzed,
+            avg_lint_warnings,
+            avg_lines_of_code,
+            avg_cyclomatic_complexity,
+            avg_slowdown,
+            problems_inefficient,
+            max_peak_rss_kb,
+            patch_problems,
+            patches_applied,
+            avg_diff_lines,
+            skipped_problems: Vec::new(),
         }
     }
 }
@@ -353,13 +1003,12 @@ pub async fn run_tests_only(
     tx: mpsc::Sender<CodeBenchmarkEvent>,
 ) -> std::result::Result<(u32, u32, f64), String> {
     let executor = CodeExecutor::default();
-    let test_cases = &problem.test_cases;
-    let total = test_cases.len() as u32;
+    let total = problem.test_cases.len() as u32;
 
     let _ = tx.send(CodeBenchmarkEvent::ExecutingTests { total }).await;
 
     let test_results = executor
-        .run_tests(code, language, test_cases, problem.time_limit_ms)
+        .run_tests(code, language, problem)
         .await
         .map_err(|e| e.to_string())?;
 
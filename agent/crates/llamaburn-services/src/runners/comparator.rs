@@ -0,0 +1,171 @@
+use llamaburn_core::Comparator;
+use serde_json::Value;
+
+/// Compare actual program output against the expected output.
+/// `Comparator::Custom` needs a process and is handled by the executor;
+/// here it falls back to normalized comparison.
+pub fn outputs_match(actual: &str, expected: &str, comparator: &Comparator) -> bool {
+    match comparator {
+        Comparator::Exact => trim_newlines(actual) == trim_newlines(expected),
+        Comparator::Normalized | Comparator::Custom { .. } => normalized_match(actual, expected),
+        Comparator::Json => json_match(actual, expected, |a, b| canonical(a) == canonical(b)),
+        Comparator::Float { abs_tol, rel_tol } => {
+            json_match(actual, expected, |a, b| values_close(a, b, *abs_tol, *rel_tol))
+        }
+        Comparator::UnorderedList => json_match(actual, expected, |a, b| {
+            sorted_elements(a, false) == sorted_elements(b, false)
+        }),
+        Comparator::SetOfLists => json_match(actual, expected, |a, b| {
+            let dedup = |mut v: Vec<String>| {
+                v.dedup();
+                v
+            };
+            sorted_elements(a, true).map(dedup) == sorted_elements(b, true).map(dedup)
+        }),
+    }
+}
+
+fn trim_newlines(s: &str) -> &str {
+    s.trim_end_matches(['\n', '\r'])
+}
+
+fn normalized_match(actual: &str, expected: &str) -> bool {
+    normalize_output(actual) == normalize_output(expected)
+}
+
+/// Parse both sides as JSON and apply `cmp`; unparseable output falls back to normalized match
+fn json_match(actual: &str, expected: &str, cmp: impl Fn(&Value, &Value) -> bool) -> bool {
+    let parsed = (
+        serde_json::from_str::<Value>(actual.trim()),
+        serde_json::from_str::<Value>(expected.trim()),
+    );
+    match parsed {
+        (Ok(a), Ok(b)) => cmp(&a, &b),
+        _ => normalized_match(actual, expected),
+    }
+}
+
+/// Canonical string form of a JSON value: sorted object keys, integral floats as ints
+fn canonical(value: &Value) -> String {
+    match value {
+        Value::Number(n) => match n.as_f64() {
+            Some(f) if f.fract() == 0.0 && f.abs() < 1e15 => format!("{}", f as i64),
+            Some(f) => format!("{}", f),
+            None => n.to_string(),
+        },
+        Value::Array(items) => {
+            let inner: Vec<String> = items.iter().map(canonical).collect();
+            format!("[{}]", inner.join(","))
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            let inner: Vec<String> = entries
+                .into_iter()
+                .map(|(k, v)| format!("{}:{}", Value::String(k.clone()), canonical(v)))
+                .collect();
+            format!("{{{}}}", inner.join(","))
+        }
+        other => other.to_string(),
+    }
+}
+
+/// Canonical forms of a top-level array's elements, sorted.
+/// With `sort_inner`, nested arrays are sorted before canonicalizing.
+fn sorted_elements(value: &Value, sort_inner: bool) -> Option<Vec<String>> {
+    let Value::Array(items) = value else {
+        return None;
+    };
+
+    let mut elements: Vec<String> = items
+        .iter()
+        .map(|item| match (sort_inner, item) {
+            (true, Value::Array(_)) => {
+                let inner = sorted_elements(item, false).unwrap_or_default();
+                format!("[{}]", inner.join(","))
+            }
+            _ => canonical(item),
+        })
+        .collect();
+    elements.sort();
+    Some(elements)
+}
+
+fn values_close(a: &Value, b: &Value, abs_tol: f64, rel_tol: f64) -> bool {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => {
+            let (Some(x), Some(y)) = (x.as_f64(), y.as_f64()) else {
+                return false;
+            };
+            let diff = (x - y).abs();
+            diff <= abs_tol || diff <= rel_tol * x.abs().max(y.abs())
+        }
+        (Value::Array(xs), Value::Array(ys)) => {
+            xs.len() == ys.len()
+                && xs.iter().zip(ys).all(|(x, y)| values_close(x, y, abs_tol, rel_tol))
+        }
+        (Value::Object(xs), Value::Object(ys)) => {
+            xs.len() == ys.len()
+                && xs.iter().all(|(k, x)| {
+                    ys.get(k)
+                        .map(|y| values_close(x, y, abs_tol, rel_tol))
+                        .unwrap_or(false)
+                })
+        }
+        _ => a == b,
+    }
+}
+
+fn normalize_output(s: &str) -> String {
+    let trimmed = s.trim().replace(' ', "").replace('\n', "").replace('\r', "");
+
+    // Try to parse as a number and normalize (2 == 2.0)
+    if let Ok(n) = trimmed.parse::<f64>() {
+        let formatted = format!("{:.10}", n);
+        return formatted.trim_end_matches('0').trim_end_matches('.').to_string();
+    }
+
+    trimmed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_keeps_whitespace() {
+        assert!(outputs_match("a b\n", "a b", &Comparator::Exact));
+        assert!(outputs_match("a b\r\n\n", "a b\n", &Comparator::Exact));
+        assert!(!outputs_match("a b", "ab", &Comparator::Exact));
+        assert!(!outputs_match("a  b", "a b", &Comparator::Exact));
+        assert!(!outputs_match("a\nb", "a b", &Comparator::Exact));
+        assert!(!outputs_match("a\nb", "ab", &Comparator::Exact));
+        assert!(!outputs_match(" a", "a", &Comparator::Exact));
+        assert!(!outputs_match("2.0", "2", &Comparator::Exact));
+
+        assert!(outputs_match("a b", "ab", &Comparator::Normalized));
+        assert!(outputs_match("a\nb", "a b", &Comparator::Normalized));
+    }
+
+    #[test]
+    fn test_comparators() {
+        assert!(outputs_match("2.0", "2", &Comparator::Normalized));
+        assert!(outputs_match("[0, 1]", "[0,1]", &Comparator::default()));
+        assert!(outputs_match(r#"{"b": 1, "a": [1, 2.0]}"#, r#"{"a":[1,2],"b":1}"#, &Comparator::Json));
+        assert!(!outputs_match("[1,2]", "[2,1]", &Comparator::Json));
+
+        let float = Comparator::Float { abs_tol: 1e-6, rel_tol: 0.0 };
+        assert!(outputs_match("[0.3333333]", "[0.33333333]", &float));
+        assert!(!outputs_match("0.34", "0.33", &float));
+
+        assert!(outputs_match("[1,0]", "[0,1]", &Comparator::UnorderedList));
+        assert!(!outputs_match("[1,1]", "[0,1]", &Comparator::UnorderedList));
+
+        assert!(outputs_match(
+            r#"[["tan","nat"],["bat"],["ate","eat","tea"]]"#,
+            r#"[["eat","tea","ate"],["tan","nat"],["bat"]]"#,
+            &Comparator::SetOfLists
+        ));
+        assert!(!outputs_match("[[1,2]]", "[[1,3]]", &Comparator::SetOfLists));
+    }
+}
@@ -0,0 +1,179 @@
+//! Environment fingerprint captured at the start of every run
+
+use std::collections::BTreeMap;
+use std::process::Command;
+
+use llamaburn_core::EnvironmentFingerprint;
+
+use super::ollama_client::OllamaClient;
+
+/// Host details plus Ollama version, model metadata and the effective sampling options.
+/// Lookups that fail are left unset so capture never fails a run.
+pub async fn capture_environment(
+    client: &OllamaClient,
+    model_id: &str,
+    temperature: Option<f32>,
+    max_tokens: Option<u32>,
+) -> EnvironmentFingerprint {
+    // The host probes shell out to rocm-smi, nvidia-smi and uname
+    let mut env = match tokio::task::spawn_blocking(capture_host_environment).await {
+        Ok(env) => env,
+        Err(e) => {
+            tracing::warn!("Host environment probe failed: {}", e);
+            EnvironmentFingerprint {
+                llamaburn_version: env!("CARGO_PKG_VERSION").to_string(),
+                ..Default::default()
+            }
+        }
+    };
+
+    match client.version().await {
+        Ok(version) => env.ollama_version = Some(version),
+        Err(e) => tracing::warn!("Failed to read Ollama version: {}", e),
+    }
+
+    match client.model_metadata(model_id).await {
+        Ok(meta) => {
+            env.model_digest = meta.digest;
+            env.model_family = meta.family;
+            env.parameter_size = meta.parameter_size;
+            env.quantization = meta.quantization;
+            env.sampling = parse_parameters(&meta.parameters);
+        }
+        Err(e) => tracing::warn!("Failed to read metadata for {}: {}", model_id, e),
+    }
+
+    // Request options override the model's defaults
+    if let Some(t) = temperature {
+        env.sampling.insert("temperature".to_string(), t.to_string());
+    }
+    if let Some(n) = max_tokens {
+        env.sampling.insert("num_predict".to_string(), n.to_string());
+    }
+
+    env
+}
+
+/// llamaburn version, GPU, kernel, CPU and RAM (for runs that don't go through Ollama)
+pub fn capture_host_environment() -> EnvironmentFingerprint {
+    let (gpu_name, gpu_driver) = detect_gpu();
+    let ram_mb = std::fs::read_to_string("/proc/meminfo")
+        .ok()
+        .and_then(|s| field_value(&s, "MemTotal"))
+        .and_then(|v| v.trim_end_matches("kB").trim().parse::<u64>().ok())
+        .map(|kb| kb / 1024);
+
+    EnvironmentFingerprint {
+        llamaburn_version: env!("CARGO_PKG_VERSION").to_string(),
+        gpu_name,
+        gpu_driver,
+        kernel: std::fs::read_to_string("/proc/sys/kernel/osrelease")
+            .ok()
+            .map(|s| s.trim().to_string())
+            .or_else(|| command_output("uname", &["-r"])),
+        cpu_model: std::fs::read_to_string("/proc/cpuinfo")
+            .ok()
+            .and_then(|s| field_value(&s, "model name")),
+        ram_mb,
+        ..Default::default()
+    }
+}
+
+/// This machine's host name, recorded as the origin of exported runs
+pub fn host_name() -> String {
+    std::fs::read_to_string("/proc/sys/kernel/hostname")
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .or_else(|| command_output("hostname", &[]))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// GPU name and driver version from rocm-smi, falling back to nvidia-smi
+fn detect_gpu() -> (Option<String>, Option<String>) {
+    let rocm_name = command_output("rocm-smi", &["--showproductname"])
+        .and_then(|s| field_value(&s, "Card Series").or_else(|| field_value(&s, "Card series")));
+    if let Some(name) = rocm_name {
+        let driver = command_output("rocm-smi", &["--showdriverversion"])
+            .and_then(|s| field_value(&s, "Driver version"));
+        return (Some(name), driver);
+    }
+
+    let Some(csv) = command_output(
+        "nvidia-smi",
+        &["--query-gpu=name,driver_version", "--format=csv,noheader"],
+    ) else {
+        return (None, None);
+    };
+    let first = csv.lines().next().unwrap_or_default();
+    let mut fields = first
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    (fields.next(), fields.next())
+}
+
+/// Trimmed stdout of a command that exited successfully
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Value after `key` on the first line containing it, e.g. "model name\t: Ryzen 9" -> "Ryzen 9"
+fn field_value(text: &str, key: &str) -> Option<String> {
+    let line = text.lines().find(|l| l.contains(key))?;
+    let (_, rest) = line.split_once(key)?;
+    let value = rest
+        .trim_start_matches(|c: char| c == ':' || c.is_whitespace())
+        .trim();
+    match value.is_empty() {
+        true => None,
+        false => Some(value.to_string()),
+    }
+}
+
+/// Modelfile parameters ("temperature 0.6", "stop \"<|eot|>\""); repeated keys are joined
+fn parse_parameters(parameters: &str) -> BTreeMap<String, String> {
+    let mut map: BTreeMap<String, String> = BTreeMap::new();
+    for line in parameters.lines() {
+        let Some((key, value)) = line.trim().split_once(char::is_whitespace) else {
+            continue;
+        };
+        let value = value.trim();
+        map.entry(key.to_string())
+            .and_modify(|v| {
+                v.push_str(", ");
+                v.push_str(value);
+            })
+            .or_insert_with(|| value.to_string());
+    }
+    map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_parameters() {
+        let params = "stop                           \"<|start_header_id|>\"\n\
+                      stop                           \"<|eot_id|>\"\n\
+                      temperature                    0.6\n";
+        let map = parse_parameters(params);
+        assert_eq!(map["temperature"], "0.6");
+        assert_eq!(map["stop"], "\"<|start_header_id|>\", \"<|eot_id|>\"");
+        assert!(parse_parameters("").is_empty());
+    }
+
+    #[test]
+    fn test_field_value() {
+        let rocm = "GPU[0]\t\t: Card Series: \t\tRadeon RX 7900 XTX\nGPU[0]\t\t: Card Model: \t\t0x744c";
+        assert_eq!(field_value(rocm, "Card Series").as_deref(), Some("Radeon RX 7900 XTX"));
+        assert_eq!(field_value("Driver version: 6.8.0", "Driver version").as_deref(), Some("6.8.0"));
+        assert_eq!(field_value("MemTotal:       32795388 kB", "MemTotal").as_deref(), Some("32795388 kB"));
+        assert_eq!(field_value("model name\t:", "model name"), None);
+    }
+}
@@ -0,0 +1,1865 @@
+//! Type-driven test harnesses for Rust and Go, and canonical output for JavaScript.
+//!
+//! The function signature is parsed into parameter types, and the generated
+//! program deserializes each JSON argument read from stdin into exactly that type
+//! (a bundled std-only JSON reader for Rust, encoding/json for Go). The harness is
+//! compiled once per solution and run once per test case. Return values are printed
+//! as canonical JSON — compact, sorted object keys, Python float repr — so they
+//! compare byte-for-byte with the Python harness output. Class-operation problems
+//! get one program per class that dispatches each operation to a typed method call.
+
+use llamaburn_core::Language;
+
+/// A parsed function signature; types are kept as source text
+#[derive(Debug, Clone, PartialEq)]
+pub struct Signature {
+    pub name: String,
+    pub params: Vec<Param>,
+    /// Empty for no return value; Go may declare several
+    pub returns: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Param {
+    pub name: String,
+    pub ty: String,
+}
+
+/// A class for operation-sequence problems: how it is constructed and the methods
+/// an operation can name
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassSignature {
+    pub name: String,
+    pub constructor: Signature,
+    pub methods: Vec<Signature>,
+}
+
+/// Packages the typed Go harness imports itself
+pub const GO_TYPED_HARNESS_IMPORTS: [&str; 7] =
+    ["encoding/json", "fmt", "os", "reflect", "sort", "strconv", "strings"];
+
+// =============================================================================
+// Signature parsing
+// =============================================================================
+
+/// Parse the first function declared in `text` (a bare signature or full code)
+pub fn parse_signature(text: &str, language: Language) -> Option<Signature> {
+    let keyword = match language {
+        Language::Rust => "fn ",
+        Language::Go => "func ",
+        Language::Python | Language::JavaScript => return None,
+    };
+    let start = find_keyword(text, keyword, None)?;
+    parse_at(&text[start..], language)
+}
+
+/// Find the declaration of function `name` in `code`
+pub fn find_signature(code: &str, name: &str, language: Language) -> Option<Signature> {
+    let keyword = match language {
+        Language::Rust => "fn ",
+        Language::Go => "func ",
+        Language::Python | Language::JavaScript => return None,
+    };
+    let start = find_keyword(code, keyword, Some(name))?;
+    parse_at(&code[start..], language)
+}
+
+/// Find class `name` in `code`. Rust: `new` and the `self` methods of its inherent
+/// `impl` blocks. Go (LeetCode convention): `Constructor` and the methods with a `name`
+/// or `*name` receiver.
+pub fn find_class(code: &str, name: &str, language: Language) -> Option<ClassSignature> {
+    let (constructor, methods) = match language {
+        Language::Rust => {
+            let decls: Vec<&str> = rust_impl_bodies(code, name).into_iter().flat_map(top_level_fns).collect();
+            let constructor = decls
+                .iter()
+                .filter(|decl| !takes_self(decl))
+                .filter_map(|decl| parse_at(decl, Language::Rust))
+                .find(|signature| signature.name == "new")?;
+            let methods = decls
+                .iter()
+                .filter(|decl| takes_self(decl))
+                .filter_map(|decl| parse_at(decl, Language::Rust))
+                .collect();
+            (constructor, methods)
+        }
+        Language::Go => {
+            let constructor = find_signature(code, "Constructor", Language::Go)?;
+            let methods = code
+                .match_indices("func (")
+                .filter_map(|(idx, _)| {
+                    let receiver = &code[idx + "func (".len()..];
+                    let end = matching_close(receiver, '(', ')')?;
+                    let ty = receiver[..end].split_whitespace().last()?.trim_start_matches('*');
+                    match ty == name {
+                        true => parse_at(&format!("func {}", receiver[end + 1..].trim_start()), Language::Go),
+                        false => None,
+                    }
+                })
+                .collect();
+            (constructor, methods)
+        }
+        Language::Python | Language::JavaScript => return None,
+    };
+    Some(ClassSignature { name: name.to_string(), constructor, methods })
+}
+
+/// Bodies of the inherent `impl name { .. }` blocks; trait impls are skipped
+fn rust_impl_bodies<'a>(code: &'a str, name: &str) -> Vec<&'a str> {
+    code.match_indices("impl ")
+        .map(|(idx, _)| idx)
+        .filter(|&idx| idx == 0 || !code[..idx].ends_with(|c: char| c.is_alphanumeric() || c == '_'))
+        .filter_map(|idx| {
+            let after = code[idx + "impl ".len()..].trim_start().strip_prefix(name)?;
+            let inner = after.trim_start().strip_prefix('{')?;
+            Some(&inner[..matching_close(inner, '{', '}')?])
+        })
+        .collect()
+}
+
+/// Declarations starting at each `fn` directly inside `body`, skipping nested items
+fn top_level_fns(body: &str) -> Vec<&str> {
+    let mut depth = 0;
+    let mut decls = Vec::new();
+    for (idx, c) in body.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            'f' if depth == 0
+                && body[idx..].starts_with("fn ")
+                && !body[..idx].ends_with(|c: char| c.is_alphanumeric() || c == '_') =>
+            {
+                decls.push(&body[idx..])
+            }
+            _ => {}
+        }
+    }
+    decls
+}
+
+/// Whether the declaration's first parameter is a `self` receiver
+fn takes_self(decl: &str) -> bool {
+    decl.split_once('(')
+        .and_then(|(_, params)| split_top_level(&params[..matching_close(params, '(', ')')?]).into_iter().next())
+        .map(|first| first.ends_with("self"))
+        .unwrap_or(false)
+}
+
+/// Byte offset of `keyword` at a word boundary, optionally followed by `name`
+fn find_keyword(text: &str, keyword: &str, name: Option<&str>) -> Option<usize> {
+    text.match_indices(keyword)
+        .map(|(idx, _)| idx)
+        .filter(|&idx| {
+            idx == 0 || !text[..idx].ends_with(|c: char| c.is_alphanumeric() || c == '_')
+        })
+        .find(|&idx| {
+            let rest = text[idx + keyword.len()..].trim_start();
+            match name {
+                Some(name) => rest
+                    .strip_prefix(name)
+                    .map(|after| after.trim_start().starts_with(['(', '<']))
+                    .unwrap_or(false),
+                // Skip Go methods: `func (r *T) name(`
+                None => !rest.starts_with('('),
+            }
+        })
+}
+
+/// Parse a declaration starting at its `fn`/`func` keyword
+fn parse_at(decl: &str, language: Language) -> Option<Signature> {
+    let after_keyword = decl.split_once(' ')?.1.trim_start();
+    let name_end = after_keyword.find(|c: char| !(c.is_alphanumeric() || c == '_'))?;
+    let name = after_keyword[..name_end].to_string();
+    let rest = after_keyword[name_end..].trim_start();
+
+    // Generic functions can't be instantiated from JSON
+    let inner_start = rest.strip_prefix('(')?;
+    let params_end = matching_close(inner_start, '(', ')')?;
+    let params_text = &inner_start[..params_end];
+    let tail = &inner_start[params_end + 1..];
+
+    let (params, returns) = match language {
+        Language::Rust => (parse_rust_params(params_text)?, parse_rust_return(tail)),
+        Language::Go => (parse_go_params(params_text)?, parse_go_returns(tail)?),
+        Language::Python | Language::JavaScript => return None,
+    };
+
+    Some(Signature { name, params, returns })
+}
+
+/// Index of the bracket closing an already-opened `open`
+fn matching_close(text: &str, open: char, close: char) -> Option<usize> {
+    let mut depth = 1;
+    for (idx, c) in text.char_indices() {
+        match c {
+            c if c == open => depth += 1,
+            c if c == close => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(idx);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Split on commas that are not nested inside brackets
+fn split_top_level(text: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut current = String::new();
+
+    for c in text.chars() {
+        match c {
+            '<' | '(' | '[' | '{' => depth += 1,
+            // `->` in fn-pointer types is not a closing bracket
+            '>' if current.ends_with('-') => {}
+            '>' | ')' | ']' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(current.trim().to_string());
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    parts.push(current.trim().to_string());
+    parts.into_iter().filter(|p| !p.is_empty()).collect()
+}
+
+fn parse_rust_params(text: &str) -> Option<Vec<Param>> {
+    split_top_level(text)
+        .into_iter()
+        .filter(|p| !p.ends_with("self"))
+        .map(|p| {
+            let (name, ty) = p.split_once(':')?;
+            let name = name.trim().trim_start_matches("mut ").trim();
+            Some(Param { name: name.to_string(), ty: ty.trim().to_string() })
+        })
+        .collect()
+}
+
+fn parse_rust_return(tail: &str) -> Vec<String> {
+    let Some(ret) = tail.trim_start().strip_prefix("->") else {
+        return Vec::new();
+    };
+    let end = [ret.find('{'), ret.find(" where "), ret.find('\n')]
+        .into_iter()
+        .flatten()
+        .min()
+        .unwrap_or(ret.len());
+    let ty = ret[..end].trim().trim_end_matches(';').trim();
+    match ty.is_empty() || ty == "()" {
+        true => Vec::new(),
+        false => vec![ty.to_string()],
+    }
+}
+
+/// Go params may share a type: `a, b int`
+fn parse_go_params(text: &str) -> Option<Vec<Param>> {
+    let mut params: Vec<Param> = Vec::new();
+    let mut pending: Vec<String> = Vec::new();
+
+    for part in split_top_level(text) {
+        match part.split_once(char::is_whitespace) {
+            Some((name, ty)) => {
+                let ty = ty.trim().to_string();
+                params.extend(pending.drain(..).map(|name| Param { name, ty: ty.clone() }));
+                params.push(Param { name: name.to_string(), ty });
+            }
+            None => pending.push(part),
+        }
+    }
+
+    // Unnamed params (`func f(int, string)`) can't be told apart from grouped names
+    match pending.is_empty() {
+        true => Some(params),
+        false => None,
+    }
+}
+
+fn parse_go_returns(tail: &str) -> Option<Vec<String>> {
+    let end = [tail.find('{'), tail.find('\n')]
+        .into_iter()
+        .flatten()
+        .min()
+        .unwrap_or(tail.len());
+    let ret = tail[..end].trim();
+
+    let Some(inner) = ret.strip_prefix('(') else {
+        return Some(match ret.is_empty() {
+            true => Vec::new(),
+            false => vec![ret.to_string()],
+        });
+    };
+    let inner = &inner[..matching_close(inner, '(', ')')?];
+
+    // Named results `(sum int, ok bool)` keep only the types
+    let parts = split_top_level(inner);
+    let named = parts.iter().all(|p| p.contains(char::is_whitespace));
+    Some(
+        parts
+            .into_iter()
+            .map(|p| match named {
+                true => p.split_once(char::is_whitespace).map(|(_, t)| t.trim().to_string()).unwrap_or(p),
+                false => p,
+            })
+            .collect(),
+    )
+}
+
+// =============================================================================
+// Rust harness
+// =============================================================================
+
+/// Owned storage type for a parameter and the expression that passes it
+fn rust_binding(ty: &str, var: &str) -> (String, String) {
+    let strip_lifetime = |t: &str| -> String {
+        let t = t.trim();
+        match t.strip_prefix('\'') {
+            Some(rest) => rest.split_once(char::is_whitespace).map(|(_, t)| t.trim().to_string()).unwrap_or_default(),
+            None => t.to_string(),
+        }
+    };
+    let owned = |t: &str| -> String {
+        match (t, t.strip_prefix('[').and_then(|t| t.strip_suffix(']'))) {
+            ("str", _) => "String".to_string(),
+            (_, Some(elem)) => format!("Vec<{}>", elem),
+            _ => t.to_string(),
+        }
+    };
+
+    match (ty.strip_prefix("&mut "), ty.strip_prefix('&')) {
+        (Some(inner), _) => (owned(&strip_lifetime(inner)), format!("&mut {}", var)),
+        (None, Some(inner)) => {
+            let inner = strip_lifetime(inner);
+            let inner = inner.strip_prefix("mut ").unwrap_or(&inner).to_string();
+            (owned(&inner), format!("&{}", var))
+        }
+        (None, None) => (ty.trim().to_string(), var.to_string()),
+    }
+}
+
+/// Drop serde imports, derives and attributes; the harness has its own JSON traits
+fn strip_serde(code: &str) -> String {
+    code.lines()
+        .filter(|line| {
+            let trimmed = line.trim_start();
+            !trimmed.starts_with("use serde") && !trimmed.starts_with("#[serde(")
+        })
+        .map(|line| {
+            let trimmed = line.trim_start();
+            let Some(derives) = trimmed.strip_prefix("#[derive(").and_then(|d| d.strip_suffix(")]")) else {
+                return line.to_string();
+            };
+            let kept: Vec<&str> = derives
+                .split(',')
+                .map(str::trim)
+                .filter(|d| !d.is_empty() && !d.ends_with("Serialize") && !d.ends_with("Deserialize"))
+                .collect();
+            match kept.is_empty() {
+                true => String::new(),
+                false => format!("{}#[derive({})]", &line[..line.len() - trimmed.len()], kept.join(", ")),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Struct and enum names in the signature that aren't std types
+fn custom_type_names(signature: &Signature) -> Vec<String> {
+    let known = [
+        "Vec", "Option", "HashMap", "HashSet", "BTreeMap", "BTreeSet", "VecDeque", "String",
+        "Box", "Rc", "RefCell", "Self", "Result",
+    ];
+    let type_text = signature
+        .params
+        .iter()
+        .map(|p| p.ty.as_str())
+        .chain(signature.returns.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut names: Vec<String> = type_text
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|w| w.starts_with(|c: char| c.is_ascii_uppercase()) && !known.contains(w))
+        .map(str::to_string)
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Keyword and the text following the name of the `struct`/`enum` declaring `name`
+fn find_type_decl<'a>(code: &'a str, name: &str) -> Option<(&'a str, &'a str)> {
+    ["struct ", "enum "].into_iter().find_map(|keyword| {
+        code.match_indices(keyword)
+            .map(|(idx, _)| idx)
+            .filter(|&idx| idx == 0 || !code[..idx].ends_with(|c: char| c.is_alphanumeric() || c == '_'))
+            .find_map(|idx| {
+                let after = code[idx + keyword.len()..].trim_start().strip_prefix(name)?;
+                match after.starts_with(|c: char| c.is_alphanumeric() || c == '_') {
+                    true => None,
+                    false => Some((keyword.trim(), after.trim_start())),
+                }
+            })
+    })
+}
+
+/// Strip attributes, comments and visibility so only `name: Type` or `Type` items remain
+fn clean_items(body: &str) -> Vec<String> {
+    let body = body
+        .lines()
+        .map(|line| line.split("//").next().unwrap_or(""))
+        .filter(|line| !line.trim_start().starts_with("#["))
+        .collect::<Vec<_>>()
+        .join("\n");
+    split_top_level(&body)
+        .into_iter()
+        .map(|item| {
+            let item = item.trim();
+            let item = match item.strip_prefix("pub(") {
+                Some(rest) => rest.split_once(')').map(|(_, t)| t).unwrap_or(rest),
+                None => item.strip_prefix("pub ").unwrap_or(item),
+            };
+            item.trim().to_string()
+        })
+        .collect()
+}
+
+/// `HarnessFrom`/`HarnessTo` impls for the custom types in the signature, shaped like
+/// serde's defaults: structs as objects, newtypes as their field, tuple structs as
+/// arrays and unit-only enums as variant names. Generic types and enums with data get
+/// no impl, so using one fails to compile.
+fn rust_type_impls(code: &str, signature: &Signature) -> String {
+    let mut impls = Vec::new();
+    for name in custom_type_names(signature) {
+        let Some((keyword, rest)) = find_type_decl(code, &name) else {
+            continue;
+        };
+        let body = |open: char, close: char| {
+            let inner = rest.strip_prefix(open)?;
+            Some(clean_items(&inner[..matching_close(inner, open, close)?]))
+        };
+
+        let (from, to) = match (keyword, rest.chars().next()) {
+            ("struct", Some('{')) => {
+                let Some(fields) = body('{', '}') else {
+                    continue;
+                };
+                let fields: Vec<(String, String)> = fields
+                    .iter()
+                    .filter_map(|f| f.split_once(':'))
+                    .map(|(field, _)| {
+                        let field = field.trim().to_string();
+                        let key = field.trim_start_matches("r#").to_string();
+                        (field, key)
+                    })
+                    .collect();
+                let reads = fields
+                    .iter()
+                    .map(|(field, key)| format!("            {}: harness_field(&mut fields, {:?})?,", field, key))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                let writes = fields
+                    .iter()
+                    .map(|(field, key)| format!("            ({:?}.to_string(), self.{}.harness_to()),", key, field))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                (
+                    format!(
+                        "        let mut fields = harness_object(value, {name:?})?;\n        Ok({name} {{\n{reads}\n        }})",
+                        name = name,
+                        reads = reads
+                    ),
+                    format!("        HarnessJson::Object(vec![\n{}\n        ])", writes),
+                )
+            }
+            ("struct", Some('(')) => {
+                let Some(fields) = body('(', ')') else {
+                    continue;
+                };
+                match fields.len() {
+                    1 => (
+                        format!("        Ok({}(HarnessFrom::harness_from(value)?))", name),
+                        "        self.0.harness_to()".to_string(),
+                    ),
+                    n => {
+                        let reads = (0..n)
+                            .map(|_| "HarnessFrom::harness_from(items.next().unwrap_or(HarnessJson::Null))?")
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        let writes = (0..n)
+                            .map(|i| format!("self.{}.harness_to()", i))
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        (
+                            format!(
+                                "        let mut items = harness_items(value, {n}, {name:?})?.into_iter();\n        Ok({name}({reads}))",
+                                n = n,
+                                name = name,
+                                reads = reads
+                            ),
+                            format!("        HarnessJson::Array(vec![{}])", writes),
+                        )
+                    }
+                }
+            }
+            ("struct", Some(';')) => (format!("        let _ = value;\n        Ok({})", name), "        HarnessJson::Null".to_string()),
+            ("enum", Some('{')) => {
+                let Some(variants) = body('{', '}') else {
+                    continue;
+                };
+                let variants: Vec<String> = variants
+                    .iter()
+                    .map(|v| v.split('=').next().unwrap_or(v).trim().to_string())
+                    .collect();
+                if variants.iter().any(|v| !v.chars().all(|c| c.is_alphanumeric() || c == '_')) {
+                    continue;
+                }
+                let reads = variants
+                    .iter()
+                    .map(|v| format!("            Some({:?}) => Ok({}::{}),", v, name, v))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                let writes = variants
+                    .iter()
+                    .map(|v| format!("            {}::{} => {:?},", name, v, v))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                (
+                    format!(
+                        "        match harness_variant(&value) {{\n{reads}\n            _ => Err(format!(\"expected a {name} variant, got {{}}\", value.kind())),\n        }}",
+                        reads = reads,
+                        name = name
+                    ),
+                    format!(
+                        "        let variant = match self {{\n{}\n        }};\n        HarnessJson::String(variant.to_string())",
+                        writes
+                    ),
+                )
+            }
+            _ => continue,
+        };
+
+        impls.push(format!(
+            "impl HarnessFrom for {name} {{\n    fn harness_from(value: HarnessJson) -> Result<Self, String> {{\n{from}\n    }}\n}}\n\n\
+             impl HarnessTo for {name} {{\n    fn harness_to(&self) -> HarnessJson {{\n{to}\n    }}\n}}",
+            name = name,
+            from = from,
+            to = to,
+        ));
+    }
+    impls.join("\n\n")
+}
+
+/// Declarations reading each parameter from `args`, and the call passing them to `target`
+fn rust_call(signature: &Signature, target: &str, indent: &str) -> (String, String) {
+    let bindings: Vec<(String, String)> = signature
+        .params
+        .iter()
+        .enumerate()
+        .map(|(i, p)| rust_binding(&p.ty, &format!("arg{}", i)))
+        .collect();
+
+    let decls = bindings
+        .iter()
+        .enumerate()
+        .map(|(i, (ty, _))| format!("{}let mut arg{i}: {ty} = harness_arg(&mut args, {i});", indent, i = i, ty = ty))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let call = format!(
+        "{}({})",
+        target,
+        bindings.iter().map(|(_, pass)| pass.as_str()).collect::<Vec<_>>().join(", ")
+    );
+    (decls, call)
+}
+
+/// `main.rs` for the Rust harness, compiled with plain rustc
+pub fn rust_harness(code: &str, signature: &Signature) -> String {
+    let (decls, call) = rust_call(signature, &signature.name, "    ");
+
+    // In-place solutions return nothing; report the first `&mut` argument instead
+    let mutated = signature.params.iter().position(|p| p.ty.starts_with("&mut "));
+    let result = match (signature.returns.is_empty(), mutated) {
+        (true, Some(i)) => format!("    {};\n    let result = &arg{};", call, i),
+        _ => format!("    let result = {};", call),
+    };
+
+    let code = strip_serde(code);
+    format!(
+        r##"#![allow(unused)]
+use std::collections::{{HashMap, HashSet, BTreeMap, BTreeSet, VecDeque}};
+use std::cmp::{{min, max, Ordering}};
+
+{code}
+
+{impls}
+
+fn main() {{
+    let mut input = String::new();
+    std::io::Read::read_to_string(&mut std::io::stdin(), &mut input).expect("read stdin");
+    let mut args = match HarnessParser::parse(&input) {{
+        Ok(HarnessJson::Array(items)) => items,
+        Ok(other) => harness_fail(format!("input must be a JSON array, got {{}}", other.kind())),
+        Err(e) => harness_fail(format!("input is not JSON: {{}}", e)),
+    }};
+    args.resize({arity}, HarnessJson::Null);
+{decls}
+{result}
+    println!("{{}}", harness_canonical(&result.harness_to()));
+}}
+
+{support}
+"##,
+        code = code,
+        impls = rust_type_impls(&code, signature),
+        arity = signature.params.len(),
+        decls = decls,
+        result = result,
+        support = RUST_HARNESS_SUPPORT,
+    )
+}
+
+/// `main.rs` replaying class operations read from stdin as `[operations, arguments]`.
+/// The first operation constructs the class with `new`; the rest call its methods,
+/// named in camelCase or snake_case. Prints every return value as a canonical JSON array.
+pub fn rust_class_harness(code: &str, class: &ClassSignature) -> String {
+    let (ctor_decls, ctor_call) = rust_call(&class.constructor, &format!("{}::new", class.name), "    ");
+    let arms = class
+        .methods
+        .iter()
+        .map(|method| {
+            let (decls, call) = rust_call(method, &format!("obj.{}", method.name), "                ");
+            let camel = to_camel_case(&method.name);
+            let pattern = match camel == method.name {
+                true => format!("{:?}", method.name),
+                false => format!("{:?} | {:?}", camel, method.name),
+            };
+            format!(
+                "            {pattern} => {{\n                args.resize({arity}, HarnessJson::Null);\n{decls}\n                {call}.harness_to()\n            }}",
+                pattern = pattern,
+                arity = method.params.len(),
+                decls = decls,
+                call = call,
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    // Custom types only cross the JSON boundary as arguments and method results
+    let types = Signature {
+        name: class.name.clone(),
+        params: class.methods.iter().flat_map(|m| m.params.clone()).chain(class.constructor.params.clone()).collect(),
+        returns: class.methods.iter().flat_map(|m| m.returns.clone()).collect(),
+    };
+
+    let code = strip_serde(code);
+    format!(
+        r##"#![allow(unused)]
+use std::collections::{{HashMap, HashSet, BTreeMap, BTreeSet, VecDeque}};
+use std::cmp::{{min, max, Ordering}};
+
+{code}
+
+{impls}
+
+fn main() {{
+    let mut input = String::new();
+    std::io::Read::read_to_string(&mut std::io::stdin(), &mut input).expect("read stdin");
+    let (ops, calls): (Vec<String>, Vec<Vec<HarnessJson>>) =
+        match HarnessParser::parse(&input).and_then(HarnessFrom::harness_from) {{
+            Ok(parsed) => parsed,
+            Err(e) => harness_fail(format!("input must be [operations, arguments]: {{}}", e)),
+        }};
+    let mut calls = calls.into_iter();
+    let mut args = calls.next().unwrap_or_default();
+    args.resize({arity}, HarnessJson::Null);
+{ctor_decls}
+    let mut obj = {ctor_call};
+    let mut out = vec![HarnessJson::Null];
+    for (op, mut args) in ops.iter().skip(1).zip(calls) {{
+        let result = match op.as_str() {{
+{arms}
+            other => harness_fail(format!("{class} has no method {{}}", other)),
+        }};
+        out.push(result);
+    }}
+    println!("{{}}", harness_canonical(&HarnessJson::Array(out)));
+}}
+
+{support}
+"##,
+        code = code,
+        impls = rust_type_impls(&code, &types),
+        arity = class.constructor.params.len(),
+        ctor_decls = ctor_decls,
+        ctor_call = ctor_call,
+        arms = arms,
+        class = class.name,
+        support = RUST_HARNESS_SUPPORT,
+    )
+}
+
+/// `get_random` -> `getRandom`, the spelling LeetCode uses for operation names
+fn to_camel_case(name: &str) -> String {
+    let mut parts = name.split('_').filter(|p| !p.is_empty());
+    let mut camel = parts.next().unwrap_or_default().to_string();
+    for part in parts {
+        let mut chars = part.chars();
+        camel.extend(chars.next().map(|c| c.to_ascii_uppercase()));
+        camel.push_str(chars.as_str());
+    }
+    camel
+}
+
+/// JSON reader, conversion traits and canonical printer compiled into every Rust harness
+const RUST_HARNESS_SUPPORT: &str = r##"fn harness_fail(message: String) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(2)
+}
+
+fn harness_arg<T: HarnessFrom>(args: &mut [HarnessJson], idx: usize) -> T {
+    let value = std::mem::replace(&mut args[idx], HarnessJson::Null);
+    T::harness_from(value).unwrap_or_else(|e| harness_fail(format!("arg {}: {}", idx, e)))
+}
+
+#[derive(Debug, Clone)]
+enum HarnessJson {
+    Null,
+    Bool(bool),
+    // Kept as written so integers don't lose precision
+    Number(String),
+    String(String),
+    Array(Vec<HarnessJson>),
+    Object(Vec<(String, HarnessJson)>),
+}
+
+impl HarnessJson {
+    fn kind(&self) -> &'static str {
+        match self {
+            HarnessJson::Null => "null",
+            HarnessJson::Bool(_) => "a boolean",
+            HarnessJson::Number(_) => "a number",
+            HarnessJson::String(_) => "a string",
+            HarnessJson::Array(_) => "an array",
+            HarnessJson::Object(_) => "an object",
+        }
+    }
+}
+
+struct HarnessParser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> HarnessParser<'a> {
+    fn parse(text: &'a str) -> Result<HarnessJson, String> {
+        let mut parser = HarnessParser { bytes: text.as_bytes(), pos: 0 };
+        let value = parser.value()?;
+        parser.skip_ws();
+        match parser.pos == parser.bytes.len() {
+            true => Ok(value),
+            false => Err(format!("trailing characters at {}", parser.pos)),
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn skip_ws(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn literal(&mut self, word: &str, value: HarnessJson) -> Result<HarnessJson, String> {
+        match self.bytes[self.pos..].starts_with(word.as_bytes()) {
+            true => {
+                self.pos += word.len();
+                Ok(value)
+            }
+            false => Err(format!("unexpected input at {}", self.pos)),
+        }
+    }
+
+    fn value(&mut self) -> Result<HarnessJson, String> {
+        self.skip_ws();
+        match self.peek() {
+            Some(b'n') => self.literal("null", HarnessJson::Null),
+            Some(b't') => self.literal("true", HarnessJson::Bool(true)),
+            Some(b'f') => self.literal("false", HarnessJson::Bool(false)),
+            Some(b'"') => self.string().map(HarnessJson::String),
+            Some(b'[') => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                self.skip_ws();
+                if self.peek() == Some(b']') {
+                    self.pos += 1;
+                    return Ok(HarnessJson::Array(items));
+                }
+                loop {
+                    items.push(self.value()?);
+                    self.skip_ws();
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(b']') => {
+                            self.pos += 1;
+                            return Ok(HarnessJson::Array(items));
+                        }
+                        _ => return Err(format!("expected ',' or ']' at {}", self.pos)),
+                    }
+                }
+            }
+            Some(b'{') => {
+                self.pos += 1;
+                let mut entries = Vec::new();
+                self.skip_ws();
+                if self.peek() == Some(b'}') {
+                    self.pos += 1;
+                    return Ok(HarnessJson::Object(entries));
+                }
+                loop {
+                    self.skip_ws();
+                    let key = self.string()?;
+                    self.skip_ws();
+                    if self.peek() != Some(b':') {
+                        return Err(format!("expected ':' at {}", self.pos));
+                    }
+                    self.pos += 1;
+                    entries.push((key, self.value()?));
+                    self.skip_ws();
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(b'}') => {
+                            self.pos += 1;
+                            return Ok(HarnessJson::Object(entries));
+                        }
+                        _ => return Err(format!("expected ',' or '}}' at {}", self.pos)),
+                    }
+                }
+            }
+            Some(b'-' | b'0'..=b'9') => {
+                let start = self.pos;
+                while matches!(self.peek(), Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9')) {
+                    self.pos += 1;
+                }
+                let text = std::str::from_utf8(&self.bytes[start..self.pos]).unwrap_or_default();
+                match text.parse::<f64>() {
+                    Ok(_) => Ok(HarnessJson::Number(text.to_string())),
+                    Err(_) => Err(format!("invalid number {:?}", text)),
+                }
+            }
+            _ => Err(format!("unexpected input at {}", self.pos)),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        if self.peek() != Some(b'"') {
+            return Err(format!("expected a string at {}", self.pos));
+        }
+        self.pos += 1;
+        let mut out = String::new();
+        loop {
+            // Runs end at an ASCII quote or backslash, so they are whole UTF-8
+            let start = self.pos;
+            while !matches!(self.peek(), Some(b'"' | b'\\') | None) {
+                self.pos += 1;
+            }
+            out.push_str(std::str::from_utf8(&self.bytes[start..self.pos]).map_err(|e| e.to_string())?);
+            match self.peek() {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(out);
+                }
+                Some(b'\\') => {
+                    let escape = self.bytes.get(self.pos + 1).copied();
+                    self.pos += 2;
+                    match escape {
+                        Some(b'"') => out.push('"'),
+                        Some(b'\\') => out.push('\\'),
+                        Some(b'/') => out.push('/'),
+                        Some(b'n') => out.push('\n'),
+                        Some(b'r') => out.push('\r'),
+                        Some(b't') => out.push('\t'),
+                        Some(b'b') => out.push('\u{8}'),
+                        Some(b'f') => out.push('\u{c}'),
+                        Some(b'u') => {
+                            let unit = self.hex4()?;
+                            let code = match (0xD800..0xDC00).contains(&unit) && self.bytes[self.pos..].starts_with(b"\\u") {
+                                true => {
+                                    self.pos += 2;
+                                    let low = self.hex4()?;
+                                    0x10000 + ((unit - 0xD800) << 10) + (low.wrapping_sub(0xDC00) & 0x3FF)
+                                }
+                                false => unit,
+                            };
+                            out.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+                        }
+                        _ => return Err(format!("invalid escape at {}", self.pos)),
+                    }
+                }
+                _ => return Err("unterminated string".to_string()),
+            }
+        }
+    }
+
+    fn hex4(&mut self) -> Result<u32, String> {
+        let digits = self
+            .bytes
+            .get(self.pos..self.pos + 4)
+            .and_then(|b| std::str::from_utf8(b).ok())
+            .ok_or_else(|| format!("invalid \\u escape at {}", self.pos))?;
+        self.pos += 4;
+        u32::from_str_radix(digits, 16).map_err(|e| e.to_string())
+    }
+}
+
+trait HarnessFrom: Sized {
+    fn harness_from(value: HarnessJson) -> Result<Self, String>;
+}
+
+// Class-operation arguments stay raw until the called method's parameter types are known
+impl HarnessFrom for HarnessJson {
+    fn harness_from(value: HarnessJson) -> Result<Self, String> {
+        Ok(value)
+    }
+}
+
+trait HarnessTo {
+    fn harness_to(&self) -> HarnessJson;
+}
+
+// Object keys are strings; numeric keys are written as their digits
+trait HarnessKey: Sized {
+    fn harness_key(key: &str) -> Result<Self, String>;
+    fn harness_key_string(&self) -> String;
+}
+
+fn harness_object(value: HarnessJson, name: &str) -> Result<Vec<(String, HarnessJson)>, String> {
+    match value {
+        HarnessJson::Object(entries) => Ok(entries),
+        other => Err(format!("expected a {} object, got {}", name, other.kind())),
+    }
+}
+
+fn harness_field<T: HarnessFrom>(fields: &mut Vec<(String, HarnessJson)>, key: &str) -> Result<T, String> {
+    let value = match fields.iter().position(|(k, _)| k == key) {
+        Some(idx) => fields.swap_remove(idx).1,
+        None => HarnessJson::Null,
+    };
+    T::harness_from(value).map_err(|e| format!("field {}: {}", key, e))
+}
+
+fn harness_items(value: HarnessJson, len: usize, name: &str) -> Result<Vec<HarnessJson>, String> {
+    match value {
+        HarnessJson::Array(items) if items.len() == len => Ok(items),
+        HarnessJson::Array(items) => Err(format!("expected {} items for {}, got {}", len, name, items.len())),
+        other => Err(format!("expected an array for {}, got {}", name, other.kind())),
+    }
+}
+
+fn harness_variant(value: &HarnessJson) -> Option<&str> {
+    match value {
+        HarnessJson::String(s) => Some(s),
+        _ => None,
+    }
+}
+
+macro_rules! harness_integers {
+    ($($t:ty),*) => {$(
+        impl HarnessFrom for $t {
+            fn harness_from(value: HarnessJson) -> Result<Self, String> {
+                match value {
+                    HarnessJson::Number(n) => n.parse().map_err(|_| format!("{} is not a valid {}", n, stringify!($t))),
+                    other => Err(format!("expected {}, got {}", stringify!($t), other.kind())),
+                }
+            }
+        }
+        impl HarnessTo for $t {
+            fn harness_to(&self) -> HarnessJson {
+                HarnessJson::Number(self.to_string())
+            }
+        }
+        impl HarnessKey for $t {
+            fn harness_key(key: &str) -> Result<Self, String> {
+                key.parse().map_err(|_| format!("key {:?} is not a valid {}", key, stringify!($t)))
+            }
+            fn harness_key_string(&self) -> String {
+                self.to_string()
+            }
+        }
+    )*};
+}
+harness_integers!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+macro_rules! harness_floats {
+    ($($t:ty),*) => {$(
+        impl HarnessFrom for $t {
+            fn harness_from(value: HarnessJson) -> Result<Self, String> {
+                match value {
+                    HarnessJson::Number(n) => n.parse().map_err(|_| format!("{} is not a valid {}", n, stringify!($t))),
+                    other => Err(format!("expected {}, got {}", stringify!($t), other.kind())),
+                }
+            }
+        }
+        impl HarnessTo for $t {
+            fn harness_to(&self) -> HarnessJson {
+                HarnessJson::Number(harness_float(*self as f64))
+            }
+        }
+    )*};
+}
+harness_floats!(f32, f64);
+
+impl HarnessFrom for bool {
+    fn harness_from(value: HarnessJson) -> Result<Self, String> {
+        match value {
+            HarnessJson::Bool(b) => Ok(b),
+            other => Err(format!("expected a boolean, got {}", other.kind())),
+        }
+    }
+}
+
+impl HarnessTo for bool {
+    fn harness_to(&self) -> HarnessJson {
+        HarnessJson::Bool(*self)
+    }
+}
+
+impl HarnessKey for bool {
+    fn harness_key(key: &str) -> Result<Self, String> {
+        key.parse().map_err(|_| format!("key {:?} is not a boolean", key))
+    }
+    fn harness_key_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl HarnessFrom for String {
+    fn harness_from(value: HarnessJson) -> Result<Self, String> {
+        match value {
+            HarnessJson::String(s) => Ok(s),
+            other => Err(format!("expected a string, got {}", other.kind())),
+        }
+    }
+}
+
+impl HarnessTo for String {
+    fn harness_to(&self) -> HarnessJson {
+        HarnessJson::String(self.clone())
+    }
+}
+
+// Borrowed strings (`Vec<&str>`) live for the rest of the run
+impl HarnessFrom for &'static str {
+    fn harness_from(value: HarnessJson) -> Result<Self, String> {
+        String::harness_from(value).map(|s| &*Box::leak(s.into_boxed_str()))
+    }
+}
+
+impl HarnessTo for str {
+    fn harness_to(&self) -> HarnessJson {
+        HarnessJson::String(self.to_string())
+    }
+}
+
+impl HarnessKey for String {
+    fn harness_key(key: &str) -> Result<Self, String> {
+        Ok(key.to_string())
+    }
+    fn harness_key_string(&self) -> String {
+        self.clone()
+    }
+}
+
+impl HarnessFrom for char {
+    fn harness_from(value: HarnessJson) -> Result<Self, String> {
+        let s = String::harness_from(value)?;
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(format!("expected a single character, got {:?}", s)),
+        }
+    }
+}
+
+impl HarnessTo for char {
+    fn harness_to(&self) -> HarnessJson {
+        HarnessJson::String(self.to_string())
+    }
+}
+
+impl HarnessKey for char {
+    fn harness_key(key: &str) -> Result<Self, String> {
+        char::harness_from(HarnessJson::String(key.to_string()))
+    }
+    fn harness_key_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl HarnessFrom for () {
+    fn harness_from(_: HarnessJson) -> Result<Self, String> {
+        Ok(())
+    }
+}
+
+impl HarnessTo for () {
+    fn harness_to(&self) -> HarnessJson {
+        HarnessJson::Null
+    }
+}
+
+impl<T: HarnessTo + ?Sized> HarnessTo for &T {
+    fn harness_to(&self) -> HarnessJson {
+        (**self).harness_to()
+    }
+}
+
+impl<T: HarnessTo + ?Sized> HarnessTo for &mut T {
+    fn harness_to(&self) -> HarnessJson {
+        (**self).harness_to()
+    }
+}
+
+impl<T: HarnessFrom> HarnessFrom for Option<T> {
+    fn harness_from(value: HarnessJson) -> Result<Self, String> {
+        match value {
+            HarnessJson::Null => Ok(None),
+            value => T::harness_from(value).map(Some),
+        }
+    }
+}
+
+impl<T: HarnessTo> HarnessTo for Option<T> {
+    fn harness_to(&self) -> HarnessJson {
+        match self {
+            Some(value) => value.harness_to(),
+            None => HarnessJson::Null,
+        }
+    }
+}
+
+impl<T: HarnessFrom> HarnessFrom for Box<T> {
+    fn harness_from(value: HarnessJson) -> Result<Self, String> {
+        T::harness_from(value).map(Box::new)
+    }
+}
+
+impl<T: HarnessTo + ?Sized> HarnessTo for Box<T> {
+    fn harness_to(&self) -> HarnessJson {
+        (**self).harness_to()
+    }
+}
+
+impl<T: HarnessFrom> HarnessFrom for std::rc::Rc<T> {
+    fn harness_from(value: HarnessJson) -> Result<Self, String> {
+        T::harness_from(value).map(std::rc::Rc::new)
+    }
+}
+
+impl<T: HarnessTo + ?Sized> HarnessTo for std::rc::Rc<T> {
+    fn harness_to(&self) -> HarnessJson {
+        (**self).harness_to()
+    }
+}
+
+impl<T: HarnessFrom> HarnessFrom for std::cell::RefCell<T> {
+    fn harness_from(value: HarnessJson) -> Result<Self, String> {
+        T::harness_from(value).map(std::cell::RefCell::new)
+    }
+}
+
+impl<T: HarnessTo> HarnessTo for std::cell::RefCell<T> {
+    fn harness_to(&self) -> HarnessJson {
+        self.borrow().harness_to()
+    }
+}
+
+fn harness_array<T: HarnessFrom>(value: HarnessJson) -> Result<Vec<T>, String> {
+    match value {
+        HarnessJson::Array(items) => items
+            .into_iter()
+            .enumerate()
+            .map(|(i, item)| T::harness_from(item).map_err(|e| format!("[{}]: {}", i, e)))
+            .collect(),
+        other => Err(format!("expected an array, got {}", other.kind())),
+    }
+}
+
+impl<T: HarnessFrom> HarnessFrom for Vec<T> {
+    fn harness_from(value: HarnessJson) -> Result<Self, String> {
+        harness_array(value)
+    }
+}
+
+impl<T: HarnessTo> HarnessTo for Vec<T> {
+    fn harness_to(&self) -> HarnessJson {
+        self.as_slice().harness_to()
+    }
+}
+
+impl<T: HarnessTo> HarnessTo for [T] {
+    fn harness_to(&self) -> HarnessJson {
+        HarnessJson::Array(self.iter().map(HarnessTo::harness_to).collect())
+    }
+}
+
+impl<T: HarnessFrom, const N: usize> HarnessFrom for [T; N] {
+    fn harness_from(value: HarnessJson) -> Result<Self, String> {
+        let items: Vec<T> = harness_array(value)?;
+        let len = items.len();
+        items.try_into().map_err(|_| format!("expected {} items, got {}", N, len))
+    }
+}
+
+impl<T: HarnessTo, const N: usize> HarnessTo for [T; N] {
+    fn harness_to(&self) -> HarnessJson {
+        self.as_slice().harness_to()
+    }
+}
+
+impl<T: HarnessFrom> HarnessFrom for VecDeque<T> {
+    fn harness_from(value: HarnessJson) -> Result<Self, String> {
+        harness_array(value).map(VecDeque::from)
+    }
+}
+
+impl<T: HarnessTo> HarnessTo for VecDeque<T> {
+    fn harness_to(&self) -> HarnessJson {
+        HarnessJson::Array(self.iter().map(HarnessTo::harness_to).collect())
+    }
+}
+
+impl<T: HarnessFrom + Eq + std::hash::Hash> HarnessFrom for HashSet<T> {
+    fn harness_from(value: HarnessJson) -> Result<Self, String> {
+        harness_array::<T>(value).map(|items| items.into_iter().collect())
+    }
+}
+
+// Iteration order is random, so elements are sorted by their canonical JSON
+impl<T: HarnessTo> HarnessTo for HashSet<T> {
+    fn harness_to(&self) -> HarnessJson {
+        let mut items: Vec<HarnessJson> = self.iter().map(HarnessTo::harness_to).collect();
+        items.sort_by_cached_key(harness_canonical);
+        HarnessJson::Array(items)
+    }
+}
+
+impl<T: HarnessFrom + Ord> HarnessFrom for BTreeSet<T> {
+    fn harness_from(value: HarnessJson) -> Result<Self, String> {
+        harness_array::<T>(value).map(|items| items.into_iter().collect())
+    }
+}
+
+impl<T: HarnessTo> HarnessTo for BTreeSet<T> {
+    fn harness_to(&self) -> HarnessJson {
+        HarnessJson::Array(self.iter().map(HarnessTo::harness_to).collect())
+    }
+}
+
+fn harness_entries<K: HarnessKey, V: HarnessFrom>(value: HarnessJson) -> Result<Vec<(K, V)>, String> {
+    match value {
+        HarnessJson::Object(entries) => entries
+            .into_iter()
+            .map(|(k, v)| {
+                let value = V::harness_from(v).map_err(|e| format!("{:?}: {}", k, e))?;
+                Ok((K::harness_key(&k)?, value))
+            })
+            .collect(),
+        other => Err(format!("expected an object, got {}", other.kind())),
+    }
+}
+
+impl<K: HarnessKey + Eq + std::hash::Hash, V: HarnessFrom> HarnessFrom for HashMap<K, V> {
+    fn harness_from(value: HarnessJson) -> Result<Self, String> {
+        harness_entries(value).map(|entries| entries.into_iter().collect())
+    }
+}
+
+impl<K: HarnessKey, V: HarnessTo> HarnessTo for HashMap<K, V> {
+    fn harness_to(&self) -> HarnessJson {
+        HarnessJson::Object(self.iter().map(|(k, v)| (k.harness_key_string(), v.harness_to())).collect())
+    }
+}
+
+impl<K: HarnessKey + Ord, V: HarnessFrom> HarnessFrom for BTreeMap<K, V> {
+    fn harness_from(value: HarnessJson) -> Result<Self, String> {
+        harness_entries(value).map(|entries| entries.into_iter().collect())
+    }
+}
+
+impl<K: HarnessKey, V: HarnessTo> HarnessTo for BTreeMap<K, V> {
+    fn harness_to(&self) -> HarnessJson {
+        HarnessJson::Object(self.iter().map(|(k, v)| (k.harness_key_string(), v.harness_to())).collect())
+    }
+}
+
+macro_rules! harness_tuples {
+    ($(($len:expr; $($t:ident $i:tt),+)),*) => {$(
+        impl<$($t: HarnessFrom),+> HarnessFrom for ($($t,)+) {
+            fn harness_from(value: HarnessJson) -> Result<Self, String> {
+                let mut items = harness_items(value, $len, "tuple")?.into_iter();
+                Ok(($($t::harness_from(items.next().unwrap_or(HarnessJson::Null))?,)+))
+            }
+        }
+        impl<$($t: HarnessTo),+> HarnessTo for ($($t,)+) {
+            fn harness_to(&self) -> HarnessJson {
+                HarnessJson::Array(vec![$(self.$i.harness_to()),+])
+            }
+        }
+    )*};
+}
+harness_tuples!(
+    (1; A 0),
+    (2; A 0, B 1),
+    (3; A 0, B 1, C 2),
+    (4; A 0, B 1, C 2, D 3),
+    (5; A 0, B 1, C 2, D 3, E 4),
+    (6; A 0, B 1, C 2, D 3, E 4, F 5)
+);
+
+fn harness_canonical(value: &HarnessJson) -> String {
+    match value {
+        HarnessJson::Null => "null".to_string(),
+        HarnessJson::Bool(b) => b.to_string(),
+        HarnessJson::Number(n) => n.clone(),
+        HarnessJson::String(s) => harness_quote(s),
+        HarnessJson::Array(items) => {
+            let inner: Vec<String> = items.iter().map(harness_canonical).collect();
+            format!("[{}]", inner.join(","))
+        }
+        HarnessJson::Object(entries) => {
+            let mut entries: Vec<&(String, HarnessJson)> = entries.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            let inner: Vec<String> = entries
+                .into_iter()
+                .map(|(k, v)| format!("{}:{}", harness_quote(k), harness_canonical(v)))
+                .collect();
+            format!("{{{}}}", inner.join(","))
+        }
+    }
+}
+
+// Python float repr: shortest round-trip digits, exponent form outside [1e-4, 1e16)
+fn harness_float(f: f64) -> String {
+    if f.is_nan() {
+        return "NaN".to_string();
+    }
+    if f.is_infinite() {
+        return match f > 0.0 {
+            true => "Infinity".to_string(),
+            false => "-Infinity".to_string(),
+        };
+    }
+    let sci = format!("{:e}", f);
+    let (mantissa, exp) = sci.split_once('e').unwrap_or((&sci, "0"));
+    let exp: i32 = exp.parse().unwrap_or(0);
+    match (-4..16).contains(&exp) {
+        true => {
+            let s = format!("{}", f);
+            match s.contains('.') {
+                true => s,
+                false => format!("{}.0", s),
+            }
+        }
+        false => format!("{}e{}{:02}", mantissa, if exp < 0 { '-' } else { '+' }, exp.abs()),
+    }
+}
+
+fn harness_quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}"##;
+
+// =============================================================================
+// Go harness
+// =============================================================================
+
+/// Declarations decoding each parameter from `args`, and the call passing them to `target`
+fn go_call(signature: &Signature, target: &str, indent: &str) -> (String, String) {
+    let decls = signature
+        .params
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let ty = p.ty.strip_prefix("...").map(|t| format!("[]{}", t)).unwrap_or_else(|| p.ty.clone());
+            match ty.as_str() {
+                // encoding/json treats []byte as base64; tests pass plain strings
+                "[]byte" => format!(
+                    "{indent}var arg{i}Str string\n{indent}harnessDecode(args[{i}], &arg{i}Str, {i})\n{indent}arg{i} := []byte(arg{i}Str)",
+                    indent = indent,
+                    i = i
+                ),
+                _ => format!(
+                    "{indent}var arg{i} {ty}\n{indent}harnessDecode(args[{i}], &arg{i}, {i})",
+                    indent = indent,
+                    i = i,
+                    ty = ty
+                ),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let call_args = signature
+        .params
+        .iter()
+        .enumerate()
+        .map(|(i, p)| match p.ty.starts_with("...") {
+            true => format!("arg{}...", i),
+            false => format!("arg{}", i),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    (decls, format!("{}({})", target, call_args))
+}
+
+/// Full `main.go` for the Go harness; `code` must already have package/imports stripped
+pub fn go_harness(code: &str, user_imports: &str, signature: &Signature) -> String {
+    let (decls, call) = go_call(signature, &signature.name, "    ");
+
+    // In-place solutions return nothing; report the first slice/map/pointer argument instead
+    let mutated = signature
+        .params
+        .iter()
+        .position(|p| p.ty.starts_with("[]") || p.ty.starts_with("map[") || p.ty.starts_with('*'));
+    let result = match (signature.returns.len(), mutated) {
+        (0, Some(i)) => format!("    {}\n    result := interface{{}}(arg{})", call, i),
+        (0, None) => format!("    {}\n    var result interface{{}}", call),
+        (1, _) => format!("    result := {}", call),
+        (n, _) => {
+            let names = (0..n).map(|i| format!("r{}", i)).collect::<Vec<_>>().join(", ");
+            format!("    {names} := {call}\n    result := []interface{{}}{{{names}}}", names = names, call = call)
+        }
+    };
+
+    format!(
+        r#"package main
+
+import (
+    "encoding/json"
+    "fmt"
+    "os"
+    "reflect"
+    "sort"
+    "strconv"
+    "strings"
+{user_imports}
+)
+
+{code}
+
+func main() {{
+    var args []json.RawMessage
+    if err := json.NewDecoder(os.Stdin).Decode(&args); err != nil {{
+        fmt.Fprintln(os.Stderr, "input must be a JSON array:", err)
+        os.Exit(2)
+    }}
+    for len(args) < {arity} {{
+        args = append(args, json.RawMessage("null"))
+    }}
+{decls}
+{result}
+    fmt.Println(harnessCanonical(reflect.ValueOf(result)))
+}}
+
+func harnessDecode(raw json.RawMessage, target interface{{}}, idx int) {{
+    if err := json.Unmarshal(raw, target); err != nil {{
+        fmt.Fprintf(os.Stderr, "arg %d: %v\n", idx, err)
+        os.Exit(2)
+    }}
+}}
+
+{canonical}
+"#,
+        user_imports = user_imports,
+        code = code,
+        arity = signature.params.len(),
+        decls = decls,
+        result = result,
+        canonical = GO_CANONICAL_JSON,
+    )
+}
+
+/// Full `main.go` replaying class operations read from stdin as `[operations, arguments]`:
+/// `Constructor` builds the object, then each operation calls the method of that name
+/// with its first letter upper-cased. Prints every return value as a canonical JSON array.
+pub fn go_class_harness(code: &str, user_imports: &str, class: &ClassSignature) -> String {
+    let (ctor_decls, ctor_call) = go_call(&class.constructor, "Constructor", "    ");
+    let ctor_args = match class.constructor.params.len() {
+        0 => String::new(),
+        n => format!("    args := harnessArgs(calls, 0, {})\n{}\n", n, ctor_decls),
+    };
+    let cases = class
+        .methods
+        .iter()
+        .map(|method| {
+            let (decls, call) = go_call(method, &format!("obj.{}", method.name), "            ");
+            let args = match method.params.len() {
+                0 => String::new(),
+                n => format!("            args := harnessArgs(calls, i, {})\n{}\n", n, decls),
+            };
+            let result = match method.returns.len() {
+                0 => format!("            {}\n            out = append(out, nil)", call),
+                1 => format!("            out = append(out, {})", call),
+                n => {
+                    let names = (0..n).map(|i| format!("r{}", i)).collect::<Vec<_>>().join(", ");
+                    format!(
+                        "            {names} := {call}\n            out = append(out, []interface{{}}{{{names}}})",
+                        names = names,
+                        call = call
+                    )
+                }
+            };
+            let op = match method.name.split_at_checked(1) {
+                Some((first, rest)) => first.to_ascii_lowercase() + rest,
+                None => method.name.clone(),
+            };
+            format!("        case {:?}:\n{}{}", op, args, result)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        r#"package main
+
+import (
+    "encoding/json"
+    "fmt"
+    "os"
+    "reflect"
+    "sort"
+    "strconv"
+    "strings"
+{user_imports}
+)
+
+{code}
+
+func main() {{
+    var input [2]json.RawMessage
+    var ops []string
+    var calls [][]json.RawMessage
+    err := json.NewDecoder(os.Stdin).Decode(&input)
+    if err == nil {{
+        err = json.Unmarshal(input[0], &ops)
+    }}
+    if err == nil {{
+        err = json.Unmarshal(input[1], &calls)
+    }}
+    if err != nil || len(ops) == 0 || len(calls) < len(ops) {{
+        fmt.Fprintln(os.Stderr, "input must be [operations, arguments]:", err)
+        os.Exit(2)
+    }}
+{ctor_args}    obj := {ctor_call}
+    _ = obj
+    out := []interface{{}}{{nil}}
+    for i := 1; i < len(ops); i++ {{
+        switch ops[i] {{
+{cases}
+        default:
+            fmt.Fprintf(os.Stderr, "{class} has no method %s\n", ops[i])
+            os.Exit(2)
+        }}
+    }}
+    fmt.Println(harnessCanonical(reflect.ValueOf(out)))
+}}
+
+func harnessArgs(calls [][]json.RawMessage, i, arity int) []json.RawMessage {{
+    args := calls[i]
+    for len(args) < arity {{
+        args = append(args, json.RawMessage("null"))
+    }}
+    return args
+}}
+
+func harnessDecode(raw json.RawMessage, target interface{{}}, idx int) {{
+    if err := json.Unmarshal(raw, target); err != nil {{
+        fmt.Fprintf(os.Stderr, "arg %d: %v\n", idx, err)
+        os.Exit(2)
+    }}
+}}
+
+{canonical}
+"#,
+        user_imports = user_imports,
+        code = code,
+        ctor_args = ctor_args,
+        ctor_call = ctor_call,
+        cases = cases,
+        class = class.name,
+        canonical = GO_CANONICAL_JSON,
+    )
+}
+
+const GO_CANONICAL_JSON: &str = r#"func harnessCanonical(v reflect.Value) string {
+    switch v.Kind() {
+    case reflect.Invalid:
+        return "null"
+    case reflect.Ptr, reflect.Interface:
+        if v.IsNil() {
+            return "null"
+        }
+        return harnessCanonical(v.Elem())
+    case reflect.Bool:
+        return strconv.FormatBool(v.Bool())
+    case reflect.Int, reflect.Int8, reflect.Int16, reflect.Int32, reflect.Int64:
+        return strconv.FormatInt(v.Int(), 10)
+    case reflect.Uint, reflect.Uint8, reflect.Uint16, reflect.Uint32, reflect.Uint64:
+        return strconv.FormatUint(v.Uint(), 10)
+    case reflect.Float32, reflect.Float64:
+        return harnessFloat(v.Float())
+    case reflect.String:
+        return harnessQuote(v.String())
+    case reflect.Slice, reflect.Array:
+        if v.Kind() == reflect.Slice && v.Type().Elem().Kind() == reflect.Uint8 {
+            return harnessQuote(string(v.Bytes()))
+        }
+        parts := make([]string, v.Len())
+        for i := range parts {
+            parts[i] = harnessCanonical(v.Index(i))
+        }
+        return "[" + strings.Join(parts, ",") + "]"
+    case reflect.Map:
+        entries := make([]string, 0, v.Len())
+        keys := make(map[string]reflect.Value, v.Len())
+        for _, k := range v.MapKeys() {
+            key := fmt.Sprint(k.Interface())
+            keys[key] = v.MapIndex(k)
+            entries = append(entries, key)
+        }
+        sort.Strings(entries)
+        for i, key := range entries {
+            entries[i] = harnessQuote(key) + ":" + harnessCanonical(keys[key])
+        }
+        return "{" + strings.Join(entries, ",") + "}"
+    case reflect.Struct:
+        fields := make([]string, 0, v.NumField())
+        names := make(map[string]reflect.Value, v.NumField())
+        for i := 0; i < v.NumField(); i++ {
+            field := v.Type().Field(i)
+            if field.PkgPath != "" {
+                continue
+            }
+            name := field.Name
+            if tag := strings.Split(field.Tag.Get("json"), ",")[0]; tag == "-" {
+                continue
+            } else if tag != "" {
+                name = tag
+            }
+            names[name] = v.Field(i)
+            fields = append(fields, name)
+        }
+        sort.Strings(fields)
+        for i, name := range fields {
+            fields[i] = harnessQuote(name) + ":" + harnessCanonical(names[name])
+        }
+        return "{" + strings.Join(fields, ",") + "}"
+    }
+    out, _ := json.Marshal(v.Interface())
+    return string(out)
+}
+
+// Python float repr: shortest round-trip digits, exponent form outside [1e-4, 1e16)
+func harnessFloat(f float64) string {
+    sci := strconv.FormatFloat(f, 'e', -1, 64)
+    switch sci {
+    case "NaN":
+        return "NaN"
+    case "+Inf":
+        return "Infinity"
+    case "-Inf":
+        return "-Infinity"
+    }
+    parts := strings.SplitN(sci, "e", 2)
+    exp, _ := strconv.Atoi(parts[1])
+    if exp >= -4 && exp < 16 {
+        s := strconv.FormatFloat(f, 'f', -1, 64)
+        if !strings.Contains(s, ".") {
+            s += ".0"
+        }
+        return s
+    }
+    sign := "+"
+    if exp < 0 {
+        sign, exp = "-", -exp
+    }
+    return fmt.Sprintf("%se%s%02d", parts[0], sign, exp)
+}
+
+func harnessQuote(s string) string {
+    var b strings.Builder
+    b.WriteByte('"')
+    for _, r := range s {
+        switch r {
+        case '"':
+            b.WriteString(`\"`)
+        case '\\':
+            b.WriteString(`\\`)
+        case '\n':
+            b.WriteString(`\n`)
+        case '\r':
+            b.WriteString(`\r`)
+        case '\t':
+            b.WriteString(`\t`)
+        case '\b':
+            b.WriteString(`\b`)
+        case '\f':
+            b.WriteString(`\f`)
+        default:
+            if r < 0x20 {
+                fmt.Fprintf(&b, `\u%04x`, r)
+            } else {
+                b.WriteRune(r)
+            }
+        }
+    }
+    b.WriteByte('"')
+    return b.String()
+}"#;
+
+// =============================================================================
+// JavaScript output
+// =============================================================================
+
+/// Canonical printer appended to JavaScript programs; `JSON.stringify` keeps insertion
+/// order, drops Maps and Sets and prints `1e-7` where Python prints `1e-07`
+pub const JS_CANONICAL_JSON: &str = r#"function harnessCanonical(value) {
+    if (value === undefined || value === null || typeof value === "function") {
+        return "null";
+    }
+    if (typeof value === "number") {
+        return harnessNumber(value);
+    }
+    if (typeof value === "bigint") {
+        return value.toString();
+    }
+    if (typeof value === "string") {
+        return JSON.stringify(value);
+    }
+    if (typeof value === "boolean") {
+        return String(value);
+    }
+    if (Array.isArray(value) || ArrayBuffer.isView(value)) {
+        return "[" + Array.from(value, harnessCanonical).join(",") + "]";
+    }
+    if (value instanceof Set) {
+        return "[" + Array.from(value, harnessCanonical).sort().join(",") + "]";
+    }
+    const entries = value instanceof Map
+        ? Array.from(value, ([k, v]) => [String(k), v])
+        : Object.entries(value);
+    entries.sort((a, b) => (a[0] < b[0] ? -1 : a[0] > b[0] ? 1 : 0));
+    return "{" + entries.map(([k, v]) => JSON.stringify(k) + ":" + harnessCanonical(v)).join(",") + "}";
+}
+
+// Python float repr: shortest round-trip digits, exponent form outside [1e-4, 1e16)
+function harnessNumber(n) {
+    if (Number.isNaN(n)) {
+        return "NaN";
+    }
+    if (!Number.isFinite(n)) {
+        return n > 0 ? "Infinity" : "-Infinity";
+    }
+    if (Number.isInteger(n) && Math.abs(n) < 1e16) {
+        return String(n);
+    }
+    const [mantissa, exp] = n.toExponential().split("e");
+    const e = Number(exp);
+    if (e >= -4 && e < 16) {
+        return String(n);
+    }
+    return mantissa + "e" + (e < 0 ? "-" : "+") + String(Math.abs(e)).padStart(2, "0");
+}"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_signatures() {
+        let rust = parse_signature(
+            "fn two_sum(mut nums: Vec<i32>, target: i32, seen: &mut HashMap<i32, (usize, bool)>) -> Option<Vec<i32>> {",
+            Language::Rust,
+        )
+        .unwrap();
+        assert_eq!(rust.name, "two_sum");
+        assert_eq!(rust.params.len(), 3);
+        assert_eq!(rust.params[0], Param { name: "nums".into(), ty: "Vec<i32>".into() });
+        assert_eq!(rust.params[2].ty, "&mut HashMap<i32, (usize, bool)>");
+        assert_eq!(rust.returns, vec!["Option<Vec<i32>>".to_string()]);
+
+        let code = "struct S;\nimpl S { fn helper(&self) {} }\npub fn rotate(matrix: &mut Vec<Vec<i32>>) {\n}";
+        let found = find_signature(code, "rotate", Language::Rust).unwrap();
+        assert!(found.returns.is_empty());
+        assert_eq!(rust_binding(&found.params[0].ty, "arg0"), ("Vec<Vec<i32>>".into(), "&mut arg0".into()));
+        assert_eq!(rust_binding("&'a [char]", "arg1"), ("Vec<char>".into(), "&arg1".into()));
+        assert_eq!(rust_binding("&str", "arg2"), ("String".into(), "&arg2".into()));
+
+        let go = parse_signature("func merge(a, b []int, m map[string][]int) (sum int, ok bool)", Language::Go).unwrap();
+        assert_eq!(go.params.iter().map(|p| p.ty.as_str()).collect::<Vec<_>>(), ["[]int", "[]int", "map[string][]int"]);
+        assert_eq!(go.returns, vec!["int".to_string(), "bool".to_string()]);
+
+        let code = "func (s *Solver) solve(x int) int { return x }\nfunc solve(x int) int { return x }";
+        assert_eq!(parse_signature(code, Language::Go).unwrap().params[0].ty, "int");
+        assert!(find_signature(code, "missing", Language::Go).is_none());
+    }
+
+    #[test]
+    fn test_find_class() {
+        let code = "struct MinStack { items: Vec<i32> }\n\
+            impl Default for MinStack { fn default() -> Self { Self::new() } }\n\
+            impl MinStack {\n    pub fn new() -> Self { fn helper(x: u8) {} MinStack { items: vec![] } }\n    \
+            pub fn push(&mut self, val: i32) {}\n    fn get_min(&self) -> i32 { 0 }\n    fn from_vec(items: Vec<i32>) -> Self { MinStack { items } }\n}";
+        let class = find_class(code, "MinStack", Language::Rust).unwrap();
+        assert!(class.constructor.params.is_empty());
+        assert_eq!(class.methods.iter().map(|m| m.name.as_str()).collect::<Vec<_>>(), ["push", "get_min"]);
+        let main = rust_class_harness(code, &class);
+        assert!(main.contains("\"getMin\" | \"get_min\" =>"));
+        assert!(main.contains("let mut arg0: i32 = harness_arg(&mut args, 0);"));
+        assert!(find_class(code, "Missing", Language::Rust).is_none());
+
+        let code = "type LRU struct{}\nfunc Constructor(capacity int) LRU { return LRU{} }\n\
+            func (this *LRU) Get(key int) int { return -1 }\nfunc (o Other) Put(k int) {}";
+        let class = find_class(code, "LRU", Language::Go).unwrap();
+        assert_eq!(class.constructor.params[0].ty, "int");
+        assert_eq!(class.methods.len(), 1);
+        assert!(go_class_harness(code, "", &class).contains("case \"get\":"));
+    }
+
+    #[test]
+    fn test_harness_generation() {
+        let code = "use serde::Deserialize;\n#[derive(Debug, serde::Deserialize, Clone)]\npub struct Pair(i32, i32);\n\
+            struct Wrap<T>(T);\nfn f(p: Pair, w: Wrap<i32>) -> Pair { p }";
+        let sig = find_signature(code, "f", Language::Rust).unwrap();
+        let main = rust_harness(code, &sig);
+        assert!(!main.contains("serde"));
+        assert!(main.contains("#[derive(Debug, Clone)]"));
+        assert!(main.contains("impl HarnessFrom for Pair"));
+        assert!(!main.contains("impl HarnessFrom for Wrap"));
+
+        let sig = parse_signature("func fill(n int, out []int)", Language::Go).unwrap();
+        assert!(go_harness("", "", &sig).contains("result := interface{}(arg1)"));
+    }
+}
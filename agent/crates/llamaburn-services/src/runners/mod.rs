@@ -1,9 +1,17 @@
 mod code_executor;
+mod code_quality;
 mod code_runner;
+mod comparator;
+mod environment;
+mod harness;
 mod ollama_client;
+mod repo_patch;
+#[cfg(unix)]
+mod rusage;
 mod text_runner;
 
 pub use code_executor::{CodeExecutor, CodeExecutorError, TestResult};
 pub use code_runner::{run_tests_only, CodeBenchmarkEvent, CodeBenchmarkResult, CodeBenchmarkRunner};
-pub use ollama_client::{code_output_schema, StructuredCodeResponse};
+pub use environment::{capture_host_environment, host_name};
+pub use ollama_client::{code_output_schema, GenerationStats, StructuredCodeResponse};
 pub use text_runner::{BenchmarkEvent, BenchmarkResult, BenchmarkRunner, BenchmarkSummary};
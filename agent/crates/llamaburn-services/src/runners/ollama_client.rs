@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use futures::stream::{BoxStream, StreamExt};
 use llamaburn_core::{LlamaBurnError, ModelConfig, Result};
@@ -24,17 +24,44 @@ struct OllamaModel {
     #[allow(dead_code)]
     size: u64,
     #[serde(default)]
+    digest: String,
+    #[serde(default)]
     details: Option<ModelDetails>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 struct ModelDetails {
-    #[allow(dead_code)]
+    #[serde(default)]
+    family: Option<String>,
     parameter_size: Option<String>,
-    #[allow(dead_code)]
     quantization_level: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+struct VersionResponse {
+    version: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ShowResponse {
+    /// Modelfile PARAMETER lines, e.g. "temperature 0.7"
+    #[serde(default)]
+    parameters: String,
+    #[serde(default)]
+    details: Option<ModelDetails>,
+}
+
+/// Digest, details and default parameters of an installed model
+#[derive(Debug, Clone, Default)]
+pub struct ModelMetadata {
+    pub digest: Option<String>,
+    pub family: Option<String>,
+    pub parameter_size: Option<String>,
+    pub quantization: Option<String>,
+    /// Modelfile PARAMETER lines, e.g. "temperature 0.7"
+    pub parameters: String,
+}
+
 #[derive(Debug, Serialize)]
 struct ChatRequest {
     model: String,
@@ -44,10 +71,26 @@ struct ChatRequest {
     options: Option<ChatOptions>,
 }
 
-#[derive(Debug, Serialize)]
-struct ChatMessage {
-    role: String,
-    content: String,
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn user(content: impl Into<String>) -> Self {
+        Self {
+            role: "user".to_string(),
+            content: content.into(),
+        }
+    }
+
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self {
+            role: "assistant".to_string(),
+            content: content.into(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -60,7 +103,6 @@ struct ChatOptions {
 
 #[derive(Debug, Deserialize)]
 pub struct ChatResponse {
-    pub message: ResponseMessage,
     #[serde(default)]
     pub eval_count: Option<u64>,
     #[serde(default)]
@@ -89,6 +131,29 @@ pub struct StreamChunk {
     pub eval_duration: Option<i64>,
 }
 
+/// Token counts and timings for a single generation.
+/// `ttft_ms` and `total_ms` are wall-clock; the rest come from Ollama.
+#[derive(Debug, Clone, Default)]
+pub struct GenerationStats {
+    pub ttft_ms: f64,
+    pub total_ms: f64,
+    pub eval_count: u64,
+    pub eval_duration_ms: f64,
+    pub prompt_eval_count: u64,
+    pub prompt_eval_duration_ms: f64,
+    pub load_duration_ms: f64,
+}
+
+impl GenerationStats {
+    /// Decode throughput from Ollama's eval counters
+    pub fn tokens_per_sec(&self) -> f64 {
+        match self.eval_duration_ms > 0.0 {
+            true => self.eval_count as f64 / (self.eval_duration_ms / 1000.0),
+            false => 0.0,
+        }
+    }
+}
+
 /// Structured output for code generation - clean components for test harness
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StructuredCodeResponse {
@@ -231,7 +296,7 @@ impl OllamaClient {
             LlamaBurnError::Http(format!(
                 "Failed to parse response: {} - Body: {}",
                 e,
-                &body[..body.len().min(500)]
+                truncate(&body, 500)
             ))
         })?;
 
@@ -303,31 +368,77 @@ impl OllamaClient {
         Ok(Box::pin(chunk_stream))
     }
 
-    /// Chat with structured output - returns JSON matching the provided schema
-    pub async fn chat_structured<T: DeserializeOwned>(
+    /// Structured chat over a full conversation (used for follow-up repair turns).
+    /// Streams the response so time-to-first-token can be measured, and returns
+    /// the token counts and durations Ollama reports on the final chunk.
+    pub async fn chat_structured_messages<T: DeserializeOwned>(
         &self,
         model: &str,
-        prompt: &str,
+        messages: &[ChatMessage],
         schema: serde_json::Value,
         temperature: Option<f32>,
-    ) -> Result<T> {
-        let url = format!("{}/api/chat", self.host);
-
+        max_tokens: Option<u32>,
+    ) -> Result<(T, GenerationStats)> {
         let request = serde_json::json!({
             "model": model,
-            "messages": [{"role": "user", "content": prompt}],
-            "stream": false,
+            "messages": messages,
+            "stream": true,
             "format": schema,
-            "options": {
-                "temperature": temperature.unwrap_or(0.0)
+            "options": ChatOptions {
+                temperature: Some(temperature.unwrap_or(0.0)),
+                num_predict: max_tokens,
             }
         });
 
+        let (content, stats) = self.stream_chat_request(&request, "Structured chat").await?;
+
+        // Parse the message content as the structured type
+        let value = serde_json::from_str(&content).map_err(|e| {
+            LlamaBurnError::Http(format!(
+                "Failed to parse structured content: {} - Content: {}",
+                e, truncate(&content, 500)
+            ))
+        })?;
+
+        Ok((value, stats))
+    }
+
+    /// Plain (free-form) chat over a full conversation, streamed for TTFT.
+    /// Returns the complete response text with generation stats.
+    pub async fn chat_messages(
+        &self,
+        model: &str,
+        messages: &[ChatMessage],
+        temperature: Option<f32>,
+        max_tokens: Option<u32>,
+    ) -> Result<(String, GenerationStats)> {
+        let request = ChatRequest {
+            model: model.to_string(),
+            messages: messages.to_vec(),
+            stream: true,
+            options: Some(ChatOptions {
+                temperature: Some(temperature.unwrap_or(0.0)),
+                num_predict: max_tokens,
+            }),
+        };
+
+        self.stream_chat_request(&request, "Chat").await
+    }
+
+    /// POST a streaming /api/chat request and accumulate the full response
+    async fn stream_chat_request<R: Serialize>(
+        &self,
+        request: &R,
+        label: &str,
+    ) -> Result<(String, GenerationStats)> {
+        let url = format!("{}/api/chat", self.host);
+
+        let start = Instant::now();
         let resp = self
             .client
             .post(&url)
-            .json(&request)
-            .timeout(Duration::from_secs(300)) // Longer timeout for structured output
+            .json(request)
+            .timeout(Duration::from_secs(300)) // Longer timeout for full code generation
             .send()
             .await
             .map_err(|e| LlamaBurnError::Http(e.to_string()))?;
@@ -336,27 +447,106 @@ impl OllamaClient {
             let status = resp.status();
             let body = resp.text().await.unwrap_or_default();
             return Err(LlamaBurnError::OllamaError(format!(
-                "Structured chat failed: {} - {}",
-                status, body
+                "{} failed: {} - {}",
+                label, status, body
             )));
         }
 
-        let body = resp
-            .text()
+        let mut stats = GenerationStats::default();
+        let mut content = String::new();
+        let mut pending: Vec<u8> = Vec::new();
+        let mut byte_stream = resp.bytes_stream();
+
+        while let Some(bytes) = byte_stream.next().await {
+            let bytes = bytes.map_err(|e| LlamaBurnError::Http(e.to_string()))?;
+            pending.extend_from_slice(&bytes);
+
+            // Chunks are newline-delimited JSON and may split across reads, even inside a
+            // multibyte character, so only whole lines are decoded
+            while let Some(line) = take_line(&mut pending) {
+                apply_chat_chunk(&line, start, &mut content, &mut stats)?;
+            }
+        }
+        apply_chat_chunk(&String::from_utf8_lossy(&pending), start, &mut content, &mut stats)?;
+
+        stats.total_ms = start.elapsed().as_secs_f64() * 1000.0;
+        Ok((content, stats))
+    }
+
+    /// Ollama server version from `/api/version`
+    pub async fn version(&self) -> Result<String> {
+        let url = format!("{}/api/version", self.host);
+        let resp = self
+            .client
+            .get(&url)
+            .send()
             .await
             .map_err(|e| LlamaBurnError::Http(e.to_string()))?;
 
-        // Parse the outer response to get message content
-        let chat_resp: ChatResponse = serde_json::from_str(&body).map_err(|e| {
-            LlamaBurnError::Http(format!("Failed to parse chat response: {} - {}", e, &body[..body.len().min(500)]))
-        })?;
+        if !resp.status().is_success() {
+            return Err(LlamaBurnError::OllamaError(format!(
+                "Failed to get version: {}",
+                resp.status()
+            )));
+        }
 
-        // Parse the message content as the structured type
-        serde_json::from_str(&chat_resp.message.content).map_err(|e| {
-            LlamaBurnError::Http(format!(
-                "Failed to parse structured content: {} - Content: {}",
-                e, &chat_resp.message.content[..chat_resp.message.content.len().min(500)]
-            ))
+        let body: VersionResponse = resp
+            .json()
+            .await
+            .map_err(|e| LlamaBurnError::Http(e.to_string()))?;
+        Ok(body.version)
+    }
+
+    /// Digest from `/api/tags` plus details and default parameters from `/api/show`
+    pub async fn model_metadata(&self, model: &str) -> Result<ModelMetadata> {
+        let url = format!("{}/api/tags", self.host);
+        let tags: TagsResponse = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| LlamaBurnError::Http(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| LlamaBurnError::Http(e.to_string()))?;
+
+        // Untagged names resolve to ":latest"
+        let latest = format!("{}:latest", model);
+        let digest = tags
+            .models
+            .into_iter()
+            .find(|m| m.name == model || m.name == latest)
+            .map(|m| m.digest)
+            .filter(|d| !d.is_empty());
+
+        let url = format!("{}/api/show", self.host);
+        let resp = self
+            .client
+            .post(&url)
+            .json(&serde_json::json!({ "model": model }))
+            .send()
+            .await
+            .map_err(|e| LlamaBurnError::Http(e.to_string()))?;
+
+        if !resp.status().is_success() {
+            return Err(LlamaBurnError::OllamaError(format!(
+                "Failed to show model: {}",
+                resp.status()
+            )));
+        }
+
+        let show: ShowResponse = resp
+            .json()
+            .await
+            .map_err(|e| LlamaBurnError::Http(e.to_string()))?;
+        let details = show.details.unwrap_or_default();
+
+        Ok(ModelMetadata {
+            digest,
+            family: details.family,
+            parameter_size: details.parameter_size,
+            quantization: details.quantization_level,
+            parameters: show.parameters,
         })
     }
 
@@ -395,6 +585,69 @@ struct OllamaStreamResponse {
     eval_count: Option<u64>,
     #[serde(default)]
     eval_duration: Option<i64>,
+    #[serde(default)]
+    prompt_eval_count: Option<u64>,
+    #[serde(default)]
+    prompt_eval_duration: Option<i64>,
+    #[serde(default)]
+    load_duration: Option<i64>,
+}
+
+/// Fold one streamed chat line into the accumulated content and stats
+fn apply_chat_chunk(
+    line: &str,
+    start: Instant,
+    content: &mut String,
+    stats: &mut GenerationStats,
+) -> Result<()> {
+    let line = line.trim();
+    if line.is_empty() {
+        return Ok(());
+    }
+
+    let chunk: OllamaStreamResponse = serde_json::from_str(line).map_err(|e| {
+        LlamaBurnError::Http(format!(
+            "Failed to parse chat response: {} - {}",
+            e,
+            truncate(line, 500)
+        ))
+    })?;
+
+    let text = chunk.message.map(|m| m.content).unwrap_or_default();
+    if !text.is_empty() && content.is_empty() {
+        stats.ttft_ms = start.elapsed().as_secs_f64() * 1000.0;
+    }
+    content.push_str(&text);
+
+    if chunk.done {
+        stats.eval_count = chunk.eval_count.unwrap_or(0);
+        stats.eval_duration_ms = ns_to_ms(chunk.eval_duration);
+        stats.prompt_eval_count = chunk.prompt_eval_count.unwrap_or(0);
+        stats.prompt_eval_duration_ms = ns_to_ms(chunk.prompt_eval_duration);
+        stats.load_duration_ms = ns_to_ms(chunk.load_duration);
+    }
+
+    Ok(())
+}
+
+/// Remove and decode the first complete line of `pending`, if there is one
+fn take_line(pending: &mut Vec<u8>) -> Option<String> {
+    let newline = pending.iter().position(|&b| b == b'\n')?;
+    let line: Vec<u8> = pending.drain(..=newline).collect();
+    Some(String::from_utf8_lossy(&line).into_owned())
+}
+
+/// At most `max` bytes of `text`, cut at a char boundary, for error messages
+fn truncate(text: &str, max: usize) -> &str {
+    let end = (0..=max.min(text.len()))
+        .rev()
+        .find(|&i| text.is_char_boundary(i))
+        .unwrap_or(0);
+    &text[..end]
+}
+
+fn ns_to_ms(ns: Option<i64>) -> f64 {
+    ns.unwrap_or(0).max(0) as f64 / 1_000_000.0
 }
 
 fn parse_stream_chunk(text: &str) -> Result<StreamChunk> {
@@ -402,7 +655,7 @@ fn parse_stream_chunk(text: &str) -> Result<StreamChunk> {
         LlamaBurnError::Http(format!(
             "Failed to parse stream chunk: {} - {}",
             e,
-            &text[..text.len().min(200)]
+            truncate(text, 200)
         ))
     })?;
 
@@ -413,3 +666,33 @@ fn parse_stream_chunk(text: &str) -> Result<StreamChunk> {
         eval_duration: resp.eval_duration,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines_split_inside_a_character() {
+        let line = "{\"message\":{\"content\":\"héllo 日本\"}}\n".as_bytes();
+        let mut pending = Vec::new();
+        let mut lines = Vec::new();
+        // Feed one byte at a time so every multibyte character is split across reads
+        for byte in line.iter().chain(b"tail") {
+            pending.push(*byte);
+            while let Some(line) = take_line(&mut pending) {
+                lines.push(line);
+            }
+        }
+        assert_eq!(lines, vec!["{\"message\":{\"content\":\"héllo 日本\"}}\n".to_string()]);
+        assert_eq!(pending, b"tail");
+    }
+
+    #[test]
+    fn test_truncate_at_char_boundary() {
+        assert_eq!(truncate("abc", 500), "abc");
+        assert_eq!(truncate("héllo", 2), "h");
+        assert_eq!(truncate("héllo", 3), "hé");
+        assert_eq!(truncate("日本", 4), "日");
+        assert_eq!(truncate("", 0), "");
+    }
+}
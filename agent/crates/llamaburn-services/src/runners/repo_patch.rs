@@ -0,0 +1,89 @@
+//! Helpers for repository-level problems.
+//!
+//! The model sees every fixture file and answers with a unified diff. The
+//! executor writes the fixture into a fresh git checkout, applies the diff with
+//! `git apply` and runs each test command inside the checkout.
+
+use std::path::{Component, Path};
+
+use llamaburn_core::{PatchStats, RepoFixture};
+
+/// Directory under the executor's temp dir that holds the patched checkout
+pub const REPO_CHECKOUT_DIR: &str = "repo";
+
+/// Error prefix for diffs that could not be applied, the compile step of a repo problem
+pub const PATCH_APPLY_FAILED: &str = "Patch failed to apply";
+
+/// Count touched files and added/removed lines in a unified diff. `---`/`+++` lines are
+/// file headers only before a file's first hunk: after `diff --git`, at the start of a
+/// plain diff, or as a `---`/`+++`/`@@` run between the files of a plain diff. Inside a
+/// hunk they are removed/added lines that happen to start with `--`/`++`.
+pub fn diff_stats(diff: &str) -> PatchStats {
+    let lines: Vec<&str> = diff.lines().collect();
+    let mut stats = PatchStats::default();
+    let mut in_header = true;
+    for (idx, line) in lines.iter().enumerate() {
+        let next_file = line.starts_with("--- ")
+            && lines.get(idx + 1).is_some_and(|l| l.starts_with("+++ "))
+            && lines.get(idx + 2).is_some_and(|l| l.starts_with("@@"));
+        match *line {
+            l if l.starts_with("diff --git ") || next_file => in_header = true,
+            l if l.starts_with("@@") => in_header = false,
+            l if in_header && l.starts_with("+++ ") => stats.files_changed += 1,
+            _ if in_header => {}
+            l if l.starts_with('+') => stats.lines_added += 1,
+            l if l.starts_with('-') => stats.lines_removed += 1,
+            _ => {}
+        }
+    }
+    stats
+}
+
+/// Trim the surrounding blank lines and end with a newline; git rejects a diff
+/// whose last hunk line is unterminated as corrupt
+pub fn normalize_diff(diff: &str) -> String {
+    let body = diff.trim_matches('\n');
+    format!("{}\n", body)
+}
+
+/// Fixture paths must stay inside the checkout
+pub fn is_safe_path(path: &str) -> bool {
+    let path = Path::new(path);
+    !path.as_os_str().is_empty()
+        && path.components().all(|c| matches!(c, Component::Normal(_)))
+}
+
+/// Every fixture file as a path header and fenced block, for the prompt
+pub fn repo_listing(fixture: &RepoFixture) -> String {
+    fixture
+        .files
+        .iter()
+        .map(|(path, contents)| format!("### {}\n```\n{}\n```", path, contents.trim_end()))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_stats() {
+        let diff = "diff --git a/calc.py b/calc.py\n--- a/calc.py\n+++ b/calc.py\n@@ -1,3 +1,3 @@\n def add(a, b):\n-    return a - b\n+    return a + b\n+\n--- /dev/null\n+++ b/new.py\n@@ -0,0 +1 @@\n+x = 1";
+        let stats = diff_stats(diff);
+        assert_eq!((stats.files_changed, stats.lines_added, stats.lines_removed), (2, 3, 1));
+        assert_eq!(stats.applied, None);
+
+        // A removed SQL comment and an added `++` line are hunk lines, not file headers
+        let diff = "diff --git a/q.sql b/q.sql\n--- a/q.sql\n+++ b/q.sql\n@@ -1,2 +1,2 @@\n--- old note\n+++ counter\n select 1;\n\
+            --- a/r.sql\n+++ b/r.sql\n@@ -1 +1 @@\n-x\n+y";
+        let stats = diff_stats(diff);
+        assert_eq!((stats.files_changed, stats.lines_added, stats.lines_removed), (2, 2, 2));
+
+        assert_eq!(normalize_diff("\n\n--- a\n+++ b\n"), "--- a\n+++ b\n");
+        assert!(is_safe_path("src/lib.rs"));
+        assert!(!is_safe_path("../escape.py"));
+        assert!(!is_safe_path("/etc/passwd"));
+        assert!(!is_safe_path(""));
+    }
+}
@@ -0,0 +1,172 @@
+//! Child process execution with resource accounting via wait4.
+//!
+//! The child runs in its own process group so a timeout also kills anything it
+//! spawned (`go run` starts the compiled binary as a grandchild). The exit is
+//! observed with `waitid(WNOWAIT)` before the child is reaped, so the timeout
+//! watchdog can never signal a pid that has already been recycled. The rusage
+//! returned by wait4 covers the child and every descendant it waited for.
+
+use std::io::{Read, Write};
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use super::code_executor::RunStats;
+
+/// Run `cmd` to completion or until `timeout`, feeding `stdin` if given.
+/// Output follows `CodeExecutor::execute_command`: trimmed stdout on success,
+/// stderr + stdout on failure.
+pub fn run(
+    mut cmd: Command,
+    stdin: Option<String>,
+    timeout: Duration,
+) -> (Result<String, String>, RunStats) {
+    cmd.stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .stdin(match stdin.is_some() {
+            true => Stdio::piped(),
+            false => Stdio::null(),
+        })
+        .process_group(0);
+
+    let start = Instant::now();
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => return (Err(e.to_string()), RunStats::default()),
+    };
+    let pid = child.id() as libc::pid_t;
+
+    // Writer and readers on their own threads so full pipes can't deadlock the child
+    if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        std::thread::spawn(move || {
+            let _ = pipe.write_all(input.as_bytes());
+        });
+    }
+    let stdout = child.stdout.take().map(read_to_end);
+    let stderr = child.stderr.take().map(read_to_end);
+
+    let exited = Arc::new((Mutex::new(false), Condvar::new()));
+    let watchdog = {
+        let exited = exited.clone();
+        std::thread::spawn(move || {
+            let (lock, cvar) = &*exited;
+            let guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+            let (guard, _) = cvar
+                .wait_timeout_while(guard, timeout, |done| !*done)
+                .unwrap_or_else(|e| e.into_inner());
+            let timed_out = !*guard;
+            if timed_out {
+                // SAFETY: the child is not reaped until `exited` is set, which needs this lock
+                unsafe { libc::kill(-pid, libc::SIGKILL) };
+            }
+            timed_out
+        })
+    };
+
+    wait_for_exit(pid);
+    let wall_time_ms = start.elapsed().as_secs_f64() * 1000.0;
+    {
+        let (lock, cvar) = &*exited;
+        *lock.lock().unwrap_or_else(|e| e.into_inner()) = true;
+        cvar.notify_one();
+    }
+    let timed_out = watchdog.join().unwrap_or(false);
+
+    // Kill stragglers still holding the pipes. The unreaped leader keeps the
+    // group id from being reused, so this can't hit an unrelated group.
+    unsafe { libc::kill(-pid, libc::SIGKILL) };
+
+    let (status, usage) = reap(pid);
+    let stats = RunStats {
+        wall_time_ms,
+        cpu_time_ms: timeval_ms(usage.ru_utime) + timeval_ms(usage.ru_stime),
+        peak_rss_kb: peak_rss_kb(usage.ru_maxrss),
+    };
+
+    let stdout = join_output(stdout);
+    let stderr = join_output(stderr);
+
+    if timed_out {
+        return (
+            Err(format!("Timeout after {}ms", timeout.as_millis())),
+            stats,
+        );
+    }
+
+    let output = match status {
+        Some(0) => Ok(stdout.trim().to_string()),
+        _ => Err(format!("{}\n{}", stderr, stdout).trim().to_string()),
+    };
+    (output, stats)
+}
+
+fn read_to_end<R: Read + Send + 'static>(mut pipe: R) -> JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = pipe.read_to_end(&mut buf);
+        buf
+    })
+}
+
+fn join_output(handle: Option<JoinHandle<Vec<u8>>>) -> String {
+    handle
+        .and_then(|h| h.join().ok())
+        .map(|buf| String::from_utf8_lossy(&buf).to_string())
+        .unwrap_or_default()
+}
+
+/// Block until the child exits, leaving it waitable
+fn wait_for_exit(pid: libc::pid_t) {
+    loop {
+        // SAFETY: siginfo_t is plain data and waitid only writes into it
+        let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+        let rc = unsafe {
+            libc::waitid(
+                libc::P_PID,
+                pid as libc::id_t,
+                &mut info,
+                libc::WEXITED | libc::WNOWAIT,
+            )
+        };
+        if rc == 0 || std::io::Error::last_os_error().kind() != std::io::ErrorKind::Interrupted {
+            return;
+        }
+    }
+}
+
+/// Reap the child, returning its exit code (None if killed by a signal) and rusage
+fn reap(pid: libc::pid_t) -> (Option<i32>, libc::rusage) {
+    let mut status = 0;
+    // SAFETY: rusage is plain data and wait4 only writes into it
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    loop {
+        let rc = unsafe { libc::wait4(pid, &mut status, 0, &mut usage) };
+        if rc == pid {
+            break;
+        }
+        if rc == -1 && std::io::Error::last_os_error().kind() == std::io::ErrorKind::Interrupted {
+            continue;
+        }
+        return (None, usage);
+    }
+
+    let code = match libc::WIFEXITED(status) {
+        true => Some(libc::WEXITSTATUS(status)),
+        false => None,
+    };
+    (code, usage)
+}
+
+fn timeval_ms(tv: libc::timeval) -> f64 {
+    tv.tv_sec as f64 * 1000.0 + tv.tv_usec as f64 / 1000.0
+}
+
+/// ru_maxrss is kilobytes on Linux but bytes on macOS
+fn peak_rss_kb(maxrss: libc::c_long) -> u64 {
+    match cfg!(target_os = "macos") {
+        true => maxrss as u64 / 1024,
+        false => maxrss as u64,
+    }
+}
@@ -1,6 +1,10 @@
+use super::environment::capture_environment;
 use super::ollama_client::OllamaClient;
 use futures::StreamExt;
-use llamaburn_core::{BenchmarkMetrics, LlamaBurnError, Result, TextBenchmarkConfig, TextBenchmarkSummary};
+use llamaburn_core::{
+    BenchmarkMetrics, EnvironmentFingerprint, LlamaBurnError, Result, TextBenchmarkConfig,
+    TextBenchmarkSummary,
+};
 use serde::{Deserialize, Serialize};
 use std::time::Instant;
 use tokio::sync::mpsc;
@@ -12,6 +16,8 @@ pub type BenchmarkSummary = TextBenchmarkSummary;
 #[derive(Debug, Clone, Serialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum BenchmarkEvent {
+    /// Sent first, before any warmup
+    Environment { fingerprint: EnvironmentFingerprint },
     Warmup { current: u32, total: u32 },
     Iteration { current: u32, total: u32, prompt: String },
     Token { content: String },
@@ -117,6 +123,15 @@ impl BenchmarkRunner {
         cancel_token: CancellationToken,
         tx: mpsc::Sender<BenchmarkEvent>,
     ) {
+        let fingerprint = capture_environment(
+            &self.client,
+            &config.model_id,
+            Some(config.temperature),
+            config.max_tokens,
+        )
+        .await;
+        let _ = tx.send(BenchmarkEvent::Environment { fingerprint }).await;
+
         // Warmup runs
         for i in 0..config.warmup_runs {
             if cancel_token.is_cancelled() {
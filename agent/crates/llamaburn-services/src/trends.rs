@@ -0,0 +1,130 @@
+//! Per-model time series of run metrics for the History trends view
+
+use std::collections::{BTreeMap, HashSet};
+
+use llamaburn_core::{
+    BenchmarkType, EnvironmentFingerprint, HistoryRecord, TrendMetric, TrendPoint, TrendSeries,
+};
+
+/// A series' runs with their metric values
+type ValuedRuns<'a> = Vec<(&'a HistoryRecord, f64)>;
+
+/// Group runs into one series per model and benchmark type, oldest point first. Runs
+/// without a value for `metric` are skipped; `baseline_ids` marks the pinned baselines.
+pub fn trend_series(
+    records: &[HistoryRecord],
+    metric: TrendMetric,
+    baseline_ids: &HashSet<String>,
+) -> Vec<TrendSeries> {
+    // Keyed by model and type label, so series come out sorted
+    let mut grouped: BTreeMap<(String, &str), (BenchmarkType, ValuedRuns)> = BTreeMap::new();
+    for record in records {
+        let Some(value) = metric.value(record).filter(|v| v.is_finite()) else {
+            continue;
+        };
+        let (model_id, _, _) = run_parts(record);
+        let benchmark_type = record.benchmark_type();
+        grouped
+            .entry((model_id.to_string(), benchmark_type.label()))
+            .or_insert_with(|| (benchmark_type, Vec::new()))
+            .1
+            .push((record, value));
+    }
+
+    grouped
+        .into_iter()
+        .map(|((model_id, _), (benchmark_type, mut runs))| {
+            runs.sort_by_key(|(record, _)| run_parts(record).1);
+            let mut previous: Option<&EnvironmentFingerprint> = None;
+            let points = runs
+                .into_iter()
+                .map(|(record, value)| {
+                    let (_, timestamp, environment) = run_parts(record);
+                    // Only runs that both recorded an environment can show a change
+                    let environment_changes = match (previous, environment) {
+                        (Some(prev), Some(env)) => prev.differences(env, true),
+                        _ => Vec::new(),
+                    };
+                    previous = environment.or(previous);
+                    TrendPoint {
+                        run_id: record.id().to_string(),
+                        timestamp,
+                        value,
+                        baseline: baseline_ids.contains(record.id()),
+                        environment_changes,
+                    }
+                })
+                .collect();
+            TrendSeries {
+                model_id,
+                benchmark_type,
+                points,
+            }
+        })
+        .collect()
+}
+
+/// Model, timestamp and environment of a run
+fn run_parts(record: &HistoryRecord) -> (&str, i64, Option<&EnvironmentFingerprint>) {
+    match record {
+        HistoryRecord::Text(e) => (&e.model_id, e.timestamp, e.environment.as_ref()),
+        HistoryRecord::Audio(e) => (&e.model_id, e.timestamp, e.environment.as_ref()),
+        HistoryRecord::Code(e) => (&e.model_id, e.timestamp, e.environment.as_ref()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_run(id: &str, model: &str, timestamp: i64, tps: f64, ollama: Option<&str>) -> HistoryRecord {
+        let environment = ollama.map(|v| {
+            serde_json::json!({ "llamaburn_version": "0.1.0", "ollama_version": v })
+        });
+        serde_json::from_value(serde_json::json!({
+            "type": "text",
+            "entry": {
+                "id": id,
+                "timestamp": timestamp,
+                "benchmark_type": "Text",
+                "model_id": model,
+                "config": {
+                    "model_id": model, "iterations": 1, "warmup_runs": 0,
+                    "prompt_set": "default", "temperature": 0.0
+                },
+                "summary": {
+                    "avg_tps": tps, "min_tps": tps, "max_tps": tps,
+                    "avg_ttft_ms": 100.0, "avg_total_ms": 1000.0, "iterations": 1
+                },
+                "metrics": [],
+                "environment": environment
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_trend_series() {
+        let records = vec![
+            text_run("c", "llama", 300, 42.0, Some("0.6.0")),
+            text_run("a", "llama", 100, 50.0, Some("0.5.1")),
+            text_run("x", "qwen", 150, 80.0, None),
+            text_run("b", "llama", 200, 51.0, None),
+        ];
+        let baselines = HashSet::from(["a".to_string()]);
+        let series = trend_series(&records, TrendMetric::Tps, &baselines);
+
+        assert_eq!(series.len(), 2);
+        assert_eq!(series[0].model_id, "llama");
+        let ids: Vec<&str> = series[0].points.iter().map(|p| p.run_id.as_str()).collect();
+        assert_eq!(ids, ["a", "b", "c"]);
+        assert!(series[0].points[0].baseline);
+        assert!(!series[0].points[1].baseline);
+        // "b" recorded no environment, so "c" is compared with "a"
+        assert!(series[0].points[1].environment_changes.is_empty());
+        assert_eq!(series[0].points[2].environment_changes, ["Ollama: 0.5.1 vs 0.6.0"]);
+        assert_eq!(series[1].points.len(), 1);
+
+        assert!(trend_series(&records, TrendMetric::Rtf, &baselines).is_empty());
+    }
+}
@@ -11,7 +11,8 @@ use std::time::Duration;
 use std::time::Instant;
 
 use llamaburn_core::{
-    AudioBenchmarkMetrics, Segment, TranscriptionResult, WhisperEvent, WhisperModel,
+    score_transcript, AudioBenchmarkMetrics, DatasetClip, FailedClip, Segment, TranscriptionResult,
+    WhisperEvent, WhisperModel,
 };
 use thiserror::Error;
 use tracing::{debug, info, warn};
@@ -33,7 +34,7 @@ pub enum WhisperError {
 }
 
 /// Create default FullParams for whisper transcription
-fn default_params(streaming: bool) -> whisper_rs::FullParams<'static, 'static> {
+fn default_params<'a>(streaming: bool) -> whisper_rs::FullParams<'a, 'static> {
     let mut params = whisper_rs::FullParams::new(whisper_rs::SamplingStrategy::Greedy { best_of: 1 });
     params.set_language(Some("en"));
     params.set_print_progress(streaming);
@@ -452,6 +453,7 @@ impl WhisperService {
         audio_path: &Path,
         iterations: u32,
         warmup: u32,
+        reference: Option<&str>,
         tx: Option<Sender<WhisperEvent>>,
     ) -> Result<Vec<AudioBenchmarkMetrics>, WhisperError> {
         let send = |event: WhisperEvent| {
@@ -500,12 +502,15 @@ impl WhisperService {
                 processing_ms: processing_ms as u64,
             });
 
+            let accuracy = reference.and_then(|r| score_transcript(r, &result.text));
             metrics.push(AudioBenchmarkMetrics {
                 real_time_factor: rtf,
                 processing_time_ms: processing_ms,
                 audio_duration_ms,
                 transcription: result.text,
                 word_count: result.segments.len() as u32,
+                accuracy,
+                clip: None,
             });
 
             debug!(
@@ -516,6 +521,91 @@ impl WhisperService {
 
         Ok(metrics)
     }
+
+    /// Transcribe every clip of a dataset once, scoring the ones with a reference.
+    /// `on_clip` gets each clip's outcome in order and returns `false` to stop early.
+    /// Returns the metrics of the transcribed clips and the clips that failed.
+    pub fn run_dataset(
+        &mut self,
+        model: WhisperModel,
+        clips: &[DatasetClip],
+        warmup: u32,
+        mut on_clip: impl FnMut(usize, &Result<AudioBenchmarkMetrics, WhisperError>) -> bool,
+    ) -> Result<(Vec<AudioBenchmarkMetrics>, Vec<FailedClip>), WhisperError> {
+        if self.current_model != Some(model) {
+            self.load_model(model)?;
+        }
+
+        // Warm up on the first clip
+        if let Some(first) = clips.first() {
+            for i in 0..warmup {
+                debug!("Warmup run {}/{}", i + 1, warmup);
+                let _ = self.transcribe_clip(first);
+            }
+        }
+
+        let mut metrics = Vec::with_capacity(clips.len());
+        let mut failed = Vec::new();
+        for (i, clip) in clips.iter().enumerate() {
+            let result = self.transcribe_clip(clip);
+            if let Err(e) = &result {
+                warn!("Clip {} failed: {}", clip.audio_path.display(), e);
+            }
+            let keep_going = on_clip(i, &result);
+            match result {
+                Ok(m) => metrics.push(m),
+                Err(e) => failed.push(FailedClip { clip: clip.clone(), error: e.to_string() }),
+            }
+            if !keep_going {
+                info!("Dataset run stopped after {}/{} clips", i + 1, clips.len());
+                break;
+            }
+        }
+
+        Ok((metrics, failed))
+    }
+
+    /// Transcribe one dataset clip in its own language, if it names one
+    fn transcribe_clip(&self, clip: &DatasetClip) -> Result<AudioBenchmarkMetrics, WhisperError> {
+        let ctx = self
+            .context
+            .as_ref()
+            .ok_or_else(|| WhisperError::ModelLoadError("No model loaded".into()))?;
+
+        let audio_data = self.load_audio(&clip.audio_path)?;
+        if audio_data.is_empty() {
+            return Err(WhisperError::AudioLoadError("Empty audio".into()));
+        }
+        let audio_duration_ms = audio_data.len() as f64 / 16.0; // 16kHz = 16 samples/ms
+
+        // Locales like "pt-BR" map to whisper's base language code
+        let language = clip.language.as_deref().and_then(|l| l.split(['-', '_']).next());
+        let mut params = default_params(false);
+        if let Some(language) = language.filter(|l| !l.is_empty()) {
+            params.set_language(Some(language));
+        }
+
+        let start = Instant::now();
+        let mut state = ctx
+            .create_state()
+            .map_err(|e| WhisperError::TranscriptionError(e.to_string()))?;
+        state
+            .full(params, &audio_data)
+            .map_err(|e| WhisperError::TranscriptionError(e.to_string()))?;
+        let processing_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+        let (text, _) = extract_segments(&state)?;
+        let transcription = text.trim().to_string();
+        Ok(AudioBenchmarkMetrics {
+            real_time_factor: processing_ms / audio_duration_ms,
+            processing_time_ms: processing_ms,
+            audio_duration_ms,
+            word_count: transcription.split_whitespace().count() as u32,
+            accuracy: score_transcript(&clip.reference, &transcription),
+            transcription,
+            clip: Some(clip.clone()),
+        })
+    }
 }
 
 impl Default for WhisperService {
@@ -524,6 +614,18 @@ impl Default for WhisperService {
     }
 }
 
+/// Reference transcript stored next to an audio file: `clip.wav.txt`, else `clip.txt`
+pub fn load_reference_transcript(audio_path: &Path) -> Option<String> {
+    let mut sidecar = audio_path.as_os_str().to_owned();
+    sidecar.push(".txt");
+    [PathBuf::from(sidecar), audio_path.with_extension("txt")]
+        .iter()
+        .filter(|p| p.as_path() != audio_path)
+        .find_map(|p| std::fs::read_to_string(p).ok())
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty())
+}
+
 pub fn get_audio_duration_ms(path: &Path) -> Result<f64, WhisperError> {
     let ext = path
         .extension()